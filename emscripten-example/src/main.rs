@@ -1,6 +1,12 @@
 fn main() {
-    let system = unsafe { fmod::studio::System::new() }.unwrap();
+    let mut builder = unsafe { fmod::studio::SystemBuilder::new() }.unwrap();
+    builder.web_defaults().unwrap();
+    let system = builder
+        .build(32, fmod::studio::InitFlags::NORMAL, fmod::InitFlags::NORMAL)
+        .unwrap();
     let core_system = system.get_core_system().unwrap();
+    // browsers keep audio suspended until the user interacts with the page
+    core_system.resume_audio_context_on_gesture().unwrap();
     let driver = core_system.get_driver().unwrap();
     let driver_info = core_system.get_driver_info(driver).unwrap();
     println!("{driver_info:?}");
@@ -0,0 +1,104 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+// Measures System::set_many_3d_attributes against calling set_3d_attributes on each channel.
+// Run with `cargo run --release --example batch_3d`.
+
+use fmod_examples::media_path_for;
+use std::time::{Duration, Instant};
+
+const EMITTER_COUNTS: [usize; 4] = [10, 100, 500, 1000];
+const FRAMES: u32 = 200;
+
+fn attributes(index: usize, frame: u32) -> fmod::Attributes3D {
+    let angle = index as f32 * 0.1 + frame as f32 * 0.01;
+    fmod::Attributes3D {
+        position: fmod::Vector {
+            x: angle.cos() * 20.0,
+            y: 0.0,
+            z: angle.sin() * 20.0,
+        },
+        velocity: fmod::Vector {
+            x: -angle.sin(),
+            y: 0.0,
+            z: angle.cos(),
+        },
+        forward: fmod::Vector {
+            x: 0.0,
+            y: 0.0,
+            z: 1.0,
+        },
+        up: fmod::Vector {
+            x: 0.0,
+            y: 1.0,
+            z: 0.0,
+        },
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let builder = unsafe {
+        // Safety: we call this before calling any other functions and only in main, so this is safe
+        fmod::SystemBuilder::new()?
+    };
+    let max_emitters = EMITTER_COUNTS[EMITTER_COUNTS.len() - 1];
+    let system = builder.build(max_emitters as _, fmod::InitFlags::NORMAL)?;
+
+    let sound = fmod::SoundBuilder::open(&media_path_for("drumloop.wav"))
+        .with_mode(fmod::Mode::D3 | fmod::Mode::LOOP_NORMAL)
+        .build(system)?;
+
+    println!("emitters   per call (avg/frame)   batched (avg/frame)");
+    for count in EMITTER_COUNTS {
+        let channels = (0..count)
+            .map(|_| system.play_sound(sound, None, false))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut batch = Vec::with_capacity(count);
+
+        let mut per_call = Duration::ZERO;
+        let mut batched = Duration::ZERO;
+        for frame in 0..FRAMES {
+            let start = Instant::now();
+            for (index, channel) in channels.iter().enumerate() {
+                let attributes = attributes(index, frame);
+                channel.set_3d_attributes(Some(attributes.position), Some(attributes.velocity))?;
+            }
+            per_call += start.elapsed();
+
+            batch.clear();
+            batch.extend(
+                channels
+                    .iter()
+                    .enumerate()
+                    .map(|(index, channel)| (**channel, attributes(index, frame))),
+            );
+            let start = Instant::now();
+            system.set_many_3d_attributes(&batch)?;
+            batched += start.elapsed();
+
+            system.update()?;
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        println!(
+            "{count:>8}   {:>20.1?}   {:>19.1?}",
+            per_call / FRAMES,
+            batched / FRAMES
+        );
+        for channel in channels {
+            channel.stop()?;
+        }
+    }
+
+    sound.release()?;
+
+    // Safety: we don't use any fmod api calls after this, so this is ok
+    unsafe {
+        system.close()?;
+        system.release()?;
+    }
+    Ok(())
+}
@@ -0,0 +1,327 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Derive macros for [fmod-oxide](https://github.com/melody-rs/fmod-oxide).
+//!
+//! These are re-exported by fmod-oxide when its `derive` feature is enabled, use them from there.
+
+use std::ffi::CString;
+
+use proc_macro2::{Literal, Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{
+    Data, DeriveInput, Expr, Fields, Ident, Lit, LitCStr, LitStr, Type, UnOp, parse_macro_input,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Float,
+    Int,
+    Bool,
+}
+
+struct Parameter {
+    field: Ident,
+    kind: Kind,
+    name: LitCStr,
+    label: LitCStr,
+    description: LitCStr,
+    min: Option<Expr>,
+    max: Option<Expr>,
+    default: Expr,
+}
+
+fn kind_of(ty: &Type) -> Option<Kind> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let ident = &path.path.segments.last()?.ident;
+    match ident.to_string().as_str() {
+        "f32" | "c_float" => Some(Kind::Float),
+        "i32" | "c_int" => Some(Kind::Int),
+        "bool" => Some(Kind::Bool),
+        _ => None,
+    }
+}
+
+// the value of a (possibly negated) number literal, so ranges can be checked while expanding
+fn literal_value(expr: &Expr) -> Option<f64> {
+    match expr {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Int(int) => int.base10_parse().ok(),
+            Lit::Float(float) => float.base10_parse().ok(),
+            _ => None,
+        },
+        Expr::Unary(unary) if matches!(unary.op, UnOp::Neg(_)) => {
+            literal_value(&unary.expr).map(|value| -value)
+        }
+        Expr::Paren(paren) => literal_value(&paren.expr),
+        _ => None,
+    }
+}
+
+fn c_str(lit: &LitStr) -> syn::Result<LitCStr> {
+    let value = CString::new(lit.value())
+        .map_err(|_| syn::Error::new(lit.span(), "strings can't contain nul bytes"))?;
+    Ok(LitCStr::new(&value, lit.span()))
+}
+
+fn parse_parameter(field: &syn::Field) -> syn::Result<Option<Parameter>> {
+    let Some(attr) = field.attrs.iter().find(|a| a.path().is_ident("dsp")) else {
+        return Ok(None);
+    };
+    let ident = field.ident.clone().expect("only named fields are parsed");
+    let kind = kind_of(&field.ty).ok_or_else(|| {
+        syn::Error::new_spanned(&field.ty, "DSP parameters must be f32, i32 or bool")
+    })?;
+
+    let mut name = None;
+    let mut label = None;
+    let mut description = None;
+    let mut min = None;
+    let mut max = None;
+    let mut default = None;
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("name") {
+            name = Some(c_str(&meta.value()?.parse()?)?);
+        } else if meta.path.is_ident("label") {
+            label = Some(c_str(&meta.value()?.parse()?)?);
+        } else if meta.path.is_ident("description") {
+            description = Some(c_str(&meta.value()?.parse()?)?);
+        } else if meta.path.is_ident("min") {
+            min = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("max") {
+            max = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("default") {
+            default = Some(meta.value()?.parse()?);
+        } else {
+            return Err(
+                meta.error("expected `name`, `label`, `description`, `min`, `max` or `default`")
+            );
+        }
+        Ok(())
+    })?;
+
+    if kind == Kind::Bool && (min.is_some() || max.is_some()) {
+        return Err(syn::Error::new_spanned(
+            attr,
+            "bool parameters have no `min` or `max`",
+        ));
+    }
+    if kind != Kind::Bool && (min.is_none() || max.is_none()) {
+        return Err(syn::Error::new_spanned(
+            attr,
+            "`min` and `max` are required",
+        ));
+    }
+    if let (Some(min), Some(max)) = (&min, &max) {
+        // ranges that aren't plain literals are checked at compile time by the generated code instead
+        let valid = match (literal_value(min), literal_value(max)) {
+            (Some(min), Some(max)) => min <= max,
+            _ => true,
+        };
+        if !valid {
+            return Err(syn::Error::new_spanned(
+                max,
+                "`min` must not be greater than `max`",
+            ));
+        }
+    }
+    let default = default.ok_or_else(|| syn::Error::new_spanned(attr, "`default` is required"))?;
+    let empty = || LitCStr::new(c"", Span::call_site());
+    Ok(Some(Parameter {
+        name: name.unwrap_or_else(|| {
+            LitCStr::new(
+                &CString::new(ident.to_string()).expect("identifiers have no nul bytes"),
+                ident.span(),
+            )
+        }),
+        field: ident,
+        kind,
+        label: label.unwrap_or_else(empty),
+        description: description.unwrap_or_else(empty),
+        min,
+        max,
+        default,
+    }))
+}
+
+fn accessors(
+    parameters: &[(Literal, Parameter)],
+    kind: Kind,
+    set: &str,
+    get: &str,
+    ty: &TokenStream,
+) -> TokenStream {
+    let set = format_ident!("{set}");
+    let get = format_ident!("{get}");
+    let matching = || parameters.iter().filter(|(_, p)| p.kind == kind);
+
+    // the arms match on plain integers, since associated constants can't be used as patterns in generic impls
+    let set_arms = matching().map(|(index, p)| {
+        let field = &p.field;
+        let value = match (&p.min, &p.max) {
+            (Some(min), Some(max)) => quote!(value.clamp(#min, #max)),
+            _ => quote!(value),
+        };
+        quote! {
+            #index => {
+                self.#field = #value;
+                ::std::result::Result::Ok(())
+            }
+        }
+    });
+    let get_arms = matching().map(|(index, p)| {
+        let field = &p.field;
+        quote!(#index => ::std::result::Result::Ok(self.#field),)
+    });
+
+    quote! {
+        fn #set(&mut self, index: ::std::ffi::c_int, value: #ty) -> ::fmod::Result<()> {
+            match index {
+                #(#set_arms)*
+                _ => ::std::result::Result::Err(::fmod::Error::InvalidParam),
+            }
+        }
+
+        fn #get(&self, index: ::std::ffi::c_int) -> ::fmod::Result<#ty> {
+            match index {
+                #(#get_arms)*
+                _ => ::std::result::Result::Err(::fmod::Error::InvalidParam),
+            }
+        }
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            input,
+            "DspParameters can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            &data.fields,
+            "DspParameters needs named fields",
+        ));
+    };
+
+    let mut parameters = Vec::new();
+    for field in &fields.named {
+        if let Some(parameter) = parse_parameter(field)? {
+            let index = Literal::i32_unsuffixed(parameters.len() as i32);
+            parameters.push((index, parameter));
+        }
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let count = parameters.len() as i32;
+
+    let constants = parameters.iter().map(|(index, p)| {
+        let constant = format_ident!("{}", p.field.to_string().to_uppercase());
+        let doc = format!("Index of the `{}` parameter.", p.field);
+        quote! {
+            #[doc = #doc]
+            pub const #constant: ::std::ffi::c_int = #index;
+        }
+    });
+    let range_checks = parameters.iter().filter_map(|(_, p)| {
+        let (min, max) = (p.min.as_ref()?, p.max.as_ref()?);
+        if literal_value(min).is_some() && literal_value(max).is_some() {
+            return None;
+        }
+        let ty = match p.kind {
+            Kind::Float => quote!(::std::ffi::c_float),
+            _ => quote!(::std::ffi::c_int),
+        };
+        let message = format!(
+            "`min` must not be greater than `max` for the `{}` parameter",
+            p.field
+        );
+        Some(quote! {
+            const {
+                let min: #ty = #min;
+                let max: #ty = #max;
+                ::std::assert!(min <= max, #message);
+            }
+        })
+    });
+    let descriptions = parameters.iter().map(|(_, p)| {
+        let Parameter { name, label, description, min, max, default, .. } = p;
+        match p.kind {
+            Kind::Float => quote!(::fmod::DspParameterTable::float_desc(#name, #label, #description, #min, #max, #default)),
+            Kind::Int => quote!(::fmod::DspParameterTable::int_desc(#name, #label, #description, #min, #max, #default)),
+            Kind::Bool => quote!(::fmod::DspParameterTable::bool_desc(#name, #label, #description, #default)),
+        }
+    });
+    let floats = accessors(
+        &parameters,
+        Kind::Float,
+        "set_float",
+        "get_float",
+        &quote!(::std::ffi::c_float),
+    );
+    let ints = accessors(
+        &parameters,
+        Kind::Int,
+        "set_int",
+        "get_int",
+        &quote!(::std::ffi::c_int),
+    );
+    let bools = accessors(
+        &parameters,
+        Kind::Bool,
+        "set_bool",
+        "get_bool",
+        &quote!(bool),
+    );
+
+    Ok(quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #(#constants)*
+        }
+
+        impl #impl_generics ::fmod::DspParameters for #ident #ty_generics #where_clause {
+            const COUNT: ::std::ffi::c_int = #count;
+
+            fn descriptions() -> ::std::vec::Vec<::fmod::sys::FMOD_DSP_PARAMETER_DESC> {
+                // `set_*` clamps to the range, which panics if it is backwards
+                #(#range_checks)*
+                ::std::vec![#(#descriptions),*]
+            }
+
+            #floats
+            #ints
+            #bools
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests;
+
+/// Implements `fmod::DspParameters` for a struct, and adds an index constant for each parameter.
+///
+/// Fields marked with `#[dsp(...)]` become parameters, in declaration order.
+/// Their type picks the parameter type: `f32` is a float parameter, `i32` an int parameter, and `bool` a bool parameter.
+///
+/// - `name = "..."`: shown in tools, cut off at 15 bytes (without splitting a character). Defaults to the field name.
+/// - `label = "..."`: the unit, such as `"dB"`, cut off the same way.
+/// - `description = "..."`: a longer description.
+/// - `min = ..` and `max = ..`: the range, required for float and int parameters. Set values are clamped to it.
+///   A backwards range is a compile error.
+/// - `default = ..`: the initial value, required.
+///
+/// The index constant is the field name in upper case, so a `cutoff` field gets `Self::CUTOFF`.
+#[proc_macro_derive(DspParameters, attributes(dsp))]
+pub fn derive_dsp_parameters(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,61 @@
+use syn::parse_quote;
+
+use crate::expand;
+
+fn expand_to_string(input: &syn::DeriveInput) -> String {
+    expand(input).expect("expansion failed").to_string()
+}
+
+fn expand_error(input: &syn::DeriveInput) -> String {
+    expand(input).expect_err("expansion succeeded").to_string()
+}
+
+#[test]
+fn generic_structs_match_on_plain_indices() {
+    let output = expand_to_string(&parse_quote! {
+        struct Filter<T> {
+            #[dsp(min = 20.0, max = 20000.0, default = 1000.0)]
+            cutoff: f32,
+            #[dsp(default = false)]
+            bypass: bool,
+            state: T,
+        }
+    });
+    assert!(output.contains("pub const CUTOFF : :: std :: ffi :: c_int = 0"));
+    assert!(output.contains("pub const BYPASS : :: std :: ffi :: c_int = 1"));
+    assert!(output.contains("0 => {"));
+    assert!(!output.contains("Self :: CUTOFF =>"));
+}
+
+#[test]
+fn backwards_literal_ranges_are_rejected() {
+    let error = expand_error(&parse_quote! {
+        struct Gain {
+            #[dsp(min = 10.0, max = -10.0, default = 0.0)]
+            gain: f32,
+        }
+    });
+    assert_eq!(error, "`min` must not be greater than `max`");
+}
+
+#[test]
+fn non_literal_ranges_are_checked_in_a_const_block() {
+    let output = expand_to_string(&parse_quote! {
+        struct Gain {
+            #[dsp(min = MIN_GAIN, max = MAX_GAIN, default = 0.0)]
+            gain: f32,
+        }
+    });
+    assert!(output.contains("const { let min : :: std :: ffi :: c_float = MIN_GAIN ;"));
+}
+
+#[test]
+fn bool_parameters_reject_ranges() {
+    let error = expand_error(&parse_quote! {
+        struct Toggle {
+            #[dsp(min = 0, max = 1, default = false)]
+            enabled: bool,
+        }
+    });
+    assert_eq!(error, "bool parameters have no `min` or `max`");
+}
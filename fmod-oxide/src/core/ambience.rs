@@ -0,0 +1,333 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::c_float;
+use std::time::Duration;
+
+use super::emitter::Playing;
+use crate::{AutomationCurve, ChannelGroup, Error, Result, Sound, System};
+
+#[cfg(feature = "studio")]
+use crate::studio;
+
+/// What an [`AmbienceLayer`] plays.
+///
+/// Layers are meant to loop, such as rain, wind or birdsong.
+/// If a layer stops anyway it is started again on the next [`AmbienceMixer::update`] in which it should be heard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AmbienceSource {
+    /// A Studio event.
+    #[cfg(feature = "studio")]
+    Event(studio::EventDescription),
+    /// A core sound, played on a [`crate::Channel`].
+    Sound {
+        /// The system the sound is played on.
+        system: System,
+        /// The sound to play.
+        sound: Sound,
+        /// The group to play the sound on, or the master channel group.
+        channel_group: Option<ChannelGroup>,
+    },
+}
+
+impl AmbienceSource {
+    // starts the source silent, so the mixer can fade it in
+    fn play(self) -> Result<Playing> {
+        match self {
+            #[cfg(feature = "studio")]
+            AmbienceSource::Event(description) => {
+                let instance = description.create_instance()?;
+                let result = instance
+                    .set_volume(0.0)
+                    .and_then(|()| instance.start())
+                    // the instance is destroyed once it stops, which is how a stopped layer is noticed
+                    .and_then(|()| instance.release());
+                if let Err(e) = result {
+                    let _ = instance.stop(studio::StopMode::Immediate);
+                    let _ = instance.release();
+                    return Err(e);
+                }
+                Ok(Playing::Event(instance))
+            }
+            AmbienceSource::Sound {
+                system,
+                sound,
+                channel_group,
+            } => {
+                let channel = system.play_sound(sound, channel_group, true)?;
+                let result = channel
+                    .set_volume(0.0)
+                    .and_then(|()| channel.set_paused(false));
+                if let Err(e) = result {
+                    let _ = channel.stop();
+                    return Err(e);
+                }
+                Ok(Playing::Sound(channel))
+            }
+        }
+    }
+}
+
+impl Playing {
+    fn set_volume(self, volume: c_float) -> Result<()> {
+        match self {
+            #[cfg(feature = "studio")]
+            Playing::Event(instance) => instance.set_volume(volume),
+            Playing::Sound(channel) => channel.set_volume(volume),
+        }
+    }
+
+    fn set_paused(self, paused: bool) -> Result<()> {
+        match self {
+            #[cfg(feature = "studio")]
+            Playing::Event(instance) => instance.set_paused(paused),
+            Playing::Sound(channel) => channel.set_paused(paused),
+        }
+    }
+}
+
+/// One looping layer of an [`AmbienceMixer`], whose volume follows the mixer's parameters.
+///
+/// Each curve added with [`AmbienceLayer::with_curve`] maps one parameter to a volume between 0 and 1,
+/// and the layer's target volume is those volumes multiplied together.
+/// A rain layer might follow a `"rain"` parameter directly, while a night insects layer follows `"time_of_day"` and fades out with `"rain"`.
+#[derive(Debug)]
+pub struct AmbienceLayer {
+    source: AmbienceSource,
+    volume: c_float,
+    curves: Vec<(String, AutomationCurve)>,
+    playing: Option<Playing>,
+    paused: bool,
+    current: c_float,
+    target: c_float,
+}
+
+impl AmbienceLayer {
+    /// Creates a layer playing `source`, at full volume until curves are added.
+    pub fn new(source: AmbienceSource) -> Self {
+        Self {
+            source,
+            volume: 1.0,
+            curves: Vec::new(),
+            playing: None,
+            paused: false,
+            current: 0.0,
+            target: 0.0,
+        }
+    }
+
+    /// Scales the layer's volume by `volume`, on top of its curves.
+    #[must_use]
+    pub fn with_volume(mut self, volume: c_float) -> Self {
+        self.volume = volume;
+        self
+    }
+
+    /// Makes the layer's volume follow `curve`, evaluated at the value of the mixer parameter `parameter`.
+    ///
+    /// Parameters that haven't been set with [`AmbienceMixer::set_parameter`] count as 0.
+    #[must_use]
+    pub fn with_curve(mut self, parameter: impl Into<String>, curve: AutomationCurve) -> Self {
+        self.curves.push((parameter.into(), curve));
+        self
+    }
+
+    /// What the layer plays.
+    pub fn source(&self) -> AmbienceSource {
+        self.source
+    }
+
+    /// The volume the layer is fading towards.
+    pub fn target_volume(&self) -> c_float {
+        self.target
+    }
+
+    /// The volume the layer is playing at.
+    pub fn current_volume(&self) -> c_float {
+        self.current
+    }
+
+    /// Whether the layer has an instance or channel that isn't paused.
+    pub fn is_audible(&self) -> bool {
+        self.playing.is_some() && !self.paused
+    }
+
+    fn update(&mut self, parameters: &[(String, c_float)], max_step: c_float) -> Result<()> {
+        self.target = self
+            .curves
+            .iter()
+            .map(|(name, curve)| {
+                let value = parameters
+                    .iter()
+                    .find(|(n, _)| n == name)
+                    .map_or(0.0, |(_, value)| *value);
+                curve.evaluate(value)
+            })
+            .product::<c_float>()
+            * self.volume;
+
+        let delta = self.target - self.current;
+        self.current = if delta.abs() <= max_step {
+            self.target
+        } else {
+            self.current + max_step.copysign(delta)
+        };
+
+        if self.current <= 0.0 {
+            // silent layers are paused rather than stopped, so they carry on from the same place in the loop
+            if let Some(playing) = self.playing.filter(|_| !self.paused) {
+                playing.set_paused(true)?;
+                self.paused = true;
+            }
+            return Ok(());
+        }
+
+        let playing = match self.playing {
+            Some(playing) if playing.is_playing()? => playing,
+            _ => {
+                let playing = self.source.play()?;
+                self.playing = Some(playing);
+                self.paused = false;
+                playing
+            }
+        };
+        playing.set_volume(self.current)?;
+        if self.paused {
+            playing.set_paused(false)?;
+            self.paused = false;
+        }
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        self.current = 0.0;
+        self.paused = false;
+        self.playing.take().map_or(Ok(()), Playing::stop)
+    }
+}
+
+/// Mixes looping ambience layers (weather, time of day, room tone) from a handful of game parameters.
+///
+/// Add layers with [`AmbienceMixer::add_layer`], feed the game's state in with [`AmbienceMixer::set_parameter`],
+/// and call [`AmbienceMixer::update`] once per frame.
+/// Layers crossfade towards their target volumes at a fixed rate, so sudden parameter changes still fade smoothly.
+///
+/// Layers that fade out completely are paused, which keeps their place in the loop and costs nothing to mix,
+/// and layers that have never been heard aren't started at all.
+/// Dropping the mixer stops every layer.
+#[derive(Debug)]
+pub struct AmbienceMixer {
+    layers: Vec<AmbienceLayer>,
+    parameters: Vec<(String, c_float)>,
+    fade_time: Duration,
+}
+
+impl Default for AmbienceMixer {
+    fn default() -> Self {
+        Self {
+            layers: Vec::new(),
+            parameters: Vec::new(),
+            fade_time: Duration::from_secs(2),
+        }
+    }
+}
+
+impl AmbienceMixer {
+    /// Creates a mixer without layers, which takes 2 seconds to fade a layer all the way in or out.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how long fading a layer from silent to full volume (or back) takes.
+    ///
+    /// Smaller changes take proportionally less time.
+    #[must_use]
+    pub fn with_fade_time(mut self, fade_time: Duration) -> Self {
+        self.fade_time = fade_time;
+        self
+    }
+
+    /// Adds a layer and returns its index.
+    ///
+    /// It starts silent and fades in with the next updates.
+    pub fn add_layer(&mut self, layer: AmbienceLayer) -> usize {
+        self.layers.push(layer);
+        self.layers.len() - 1
+    }
+
+    /// Removes the layer at `index`, stopping it.
+    ///
+    /// Later layers move down an index. Returns [`Error::InvalidParam`] if there is no such layer.
+    pub fn remove_layer(&mut self, index: usize) -> Result<()> {
+        if index >= self.layers.len() {
+            return Err(Error::InvalidParam);
+        }
+        self.layers.remove(index).stop()
+    }
+
+    /// The layer at `index`.
+    pub fn layer(&self, index: usize) -> Option<&AmbienceLayer> {
+        self.layers.get(index)
+    }
+
+    /// Every layer, in the order they were added.
+    pub fn layers(&self) -> &[AmbienceLayer] {
+        &self.layers
+    }
+
+    /// Sets the parameter `name`, which the layers' curves are evaluated at on the next update.
+    pub fn set_parameter(&mut self, name: &str, value: c_float) {
+        match self.parameters.iter_mut().find(|(n, _)| n == name) {
+            Some((_, existing)) => *existing = value,
+            None => self.parameters.push((name.to_string(), value)),
+        }
+    }
+
+    /// The value of the parameter `name`, or [`None`] if it hasn't been set.
+    pub fn parameter(&self, name: &str) -> Option<c_float> {
+        self.parameters
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| *value)
+    }
+
+    /// Moves every layer `dt` further towards its target volume, starting, pausing and resuming layers as needed.
+    ///
+    /// Every layer is updated even if one fails, the first error is returned.
+    pub fn update(&mut self, dt: Duration) -> Result<()> {
+        let max_step = if self.fade_time.is_zero() {
+            c_float::INFINITY
+        } else {
+            dt.as_secs_f32() / self.fade_time.as_secs_f32()
+        };
+        let mut result = Ok(());
+        for layer in &mut self.layers {
+            let layer_result = layer.update(&self.parameters, max_step);
+            if result.is_ok() {
+                result = layer_result;
+            }
+        }
+        result
+    }
+
+    /// Stops every layer right away. They start again, fading in, with the next update in which they should be heard.
+    pub fn stop(&mut self) -> Result<()> {
+        let mut result = Ok(());
+        for layer in &mut self.layers {
+            let layer_result = layer.stop();
+            if result.is_ok() {
+                result = layer_result;
+            }
+        }
+        result
+    }
+}
+
+impl Drop for AmbienceMixer {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
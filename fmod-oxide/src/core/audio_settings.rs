@@ -0,0 +1,311 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use lanyard::Utf8CString;
+use std::collections::BTreeMap;
+use std::ffi::c_int;
+
+#[cfg(feature = "studio")]
+use crate::studio;
+use crate::{ChannelGroup, Decibels, Error, Guid, Result, SpeakerMode, System, SystemBuilder};
+
+/// Where the volume and mute toggle of an [`AudioSettings`] category are applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VolumeTarget {
+    /// A core [`ChannelGroup`], such as the master channel group.
+    ChannelGroup(ChannelGroup),
+    /// A Studio bus.
+    #[cfg(feature = "studio")]
+    Bus(studio::Bus),
+    /// A Studio VCA.
+    ///
+    /// VCAs have no mute state of their own, so muting one sets its volume to [`Decibels::SILENCE`].
+    #[cfg(feature = "studio")]
+    Vca(studio::Vca),
+}
+
+impl VolumeTarget {
+    fn apply(self, setting: CategorySetting) -> Result<()> {
+        match self {
+            VolumeTarget::ChannelGroup(group) => {
+                group.set_volume_db(setting.volume)?;
+                group.set_mute(setting.muted)
+            }
+            #[cfg(feature = "studio")]
+            VolumeTarget::Bus(bus) => {
+                bus.set_volume_db(setting.volume)?;
+                bus.set_mute(setting.muted)
+            }
+            #[cfg(feature = "studio")]
+            VolumeTarget::Vca(vca) if setting.muted => vca.set_volume_db(Decibels::SILENCE),
+            #[cfg(feature = "studio")]
+            VolumeTarget::Vca(vca) => vca.set_volume_db(setting.volume),
+        }
+    }
+}
+
+/// The volume slider and mute toggle of one [`AudioSettings`] category.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CategorySetting {
+    /// The slider's value.
+    pub volume: Decibels,
+    /// Whether the category is muted, independently of the slider.
+    pub muted: bool,
+}
+
+impl Default for CategorySetting {
+    fn default() -> Self {
+        Self {
+            volume: Decibels::UNITY,
+            muted: false,
+        }
+    }
+}
+
+/// The plain data behind [`AudioSettings`], for saving alongside the rest of a game's options.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AudioPreferences {
+    /// The setting of each category, by name.
+    ///
+    /// Categories the game doesn't register are kept, so settings survive a category being removed and later added back.
+    pub categories: BTreeMap<String, CategorySetting>,
+    /// The [`Guid`] of the chosen output device, or [`None`] for the system default.
+    ///
+    /// Driver indices change as devices come and go, the [`Guid`] doesn't.
+    pub output_device: Option<Guid>,
+    /// The speaker mode to mix in, or [`None`] for the one the output device prefers.
+    pub speaker_mode: Option<SpeakerMode>,
+}
+
+/// An output device, as listed by [`AudioSettings::output_devices`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OutputDevice {
+    /// The driver index, for [`System::set_driver`].
+    pub driver: c_int,
+    /// The name to show in the options menu.
+    pub name: Utf8CString,
+    /// The id to store in [`AudioPreferences::output_device`].
+    pub guid: Guid,
+}
+
+/// The audio page of an options menu: volume sliders, mute toggles, output device and speaker mode.
+///
+/// Each named category (conventionally [`AudioSettings::MASTER`], [`AudioSettings::MUSIC`], [`AudioSettings::SFX`] and [`AudioSettings::VOICE`])
+/// drives a [`VolumeTarget`], and everything set here is recorded in [`AudioPreferences`] for saving.
+/// On the next launch, pass the saved preferences to [`SystemBuilder::audio_preferences`] and then [`AudioSettings::set_preferences`].
+///
+/// The speaker mode can only be changed when the system is created, so [`AudioSettings::set_speaker_mode`] only records it.
+#[derive(Debug, Clone)]
+pub struct AudioSettings {
+    system: System,
+    targets: Vec<(String, VolumeTarget)>,
+    preferences: AudioPreferences,
+}
+
+impl AudioSettings {
+    /// The conventional name of the category for everything.
+    pub const MASTER: &str = "master";
+    /// The conventional name of the category for music.
+    pub const MUSIC: &str = "music";
+    /// The conventional name of the category for sound effects.
+    pub const SFX: &str = "sfx";
+    /// The conventional name of the category for dialogue.
+    pub const VOICE: &str = "voice";
+
+    /// Creates settings for `system`, without any categories.
+    pub fn new(system: System) -> Self {
+        Self {
+            system,
+            targets: Vec::new(),
+            preferences: AudioPreferences::default(),
+        }
+    }
+
+    /// Registers the category `name` as controlling `target`, and applies its current setting to it.
+    ///
+    /// Registering a name again replaces its target.
+    pub fn add_category(&mut self, name: impl Into<String>, target: VolumeTarget) -> Result<()> {
+        let name = name.into();
+        target.apply(self.category(&name))?;
+        match self.targets.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, existing)) => *existing = target,
+            None => self.targets.push((name, target)),
+        }
+        Ok(())
+    }
+
+    /// The names of the registered categories, in the order they were added.
+    pub fn categories(&self) -> impl Iterator<Item = &str> {
+        self.targets.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// The [`VolumeTarget`] of the category `name`.
+    pub fn target(&self, name: &str) -> Option<VolumeTarget> {
+        self.targets
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, target)| *target)
+    }
+
+    /// The setting of the category `name`, the default if it has never been changed.
+    pub fn category(&self, name: &str) -> CategorySetting {
+        self.preferences
+            .categories
+            .get(name)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Moves the volume slider of the category `name`.
+    ///
+    /// Returns [`Error::InvalidParam`] if no category called `name` is registered.
+    pub fn set_volume(&mut self, name: &str, volume: Decibels) -> Result<()> {
+        self.modify(name, |setting| setting.volume = volume)
+    }
+
+    /// The volume slider of the category `name`.
+    pub fn volume(&self, name: &str) -> Decibels {
+        self.category(name).volume
+    }
+
+    /// Sets the mute toggle of the category `name`.
+    ///
+    /// Returns [`Error::InvalidParam`] if no category called `name` is registered.
+    pub fn set_muted(&mut self, name: &str, muted: bool) -> Result<()> {
+        self.modify(name, |setting| setting.muted = muted)
+    }
+
+    /// The mute toggle of the category `name`.
+    pub fn is_muted(&self, name: &str) -> bool {
+        self.category(name).muted
+    }
+
+    fn modify(&mut self, name: &str, f: impl FnOnce(&mut CategorySetting)) -> Result<()> {
+        let target = self.target(name).ok_or(Error::InvalidParam)?;
+        let mut setting = self.category(name);
+        f(&mut setting);
+        target.apply(setting)?;
+        self.preferences
+            .categories
+            .insert(name.to_string(), setting);
+        Ok(())
+    }
+
+    /// The output devices to choose from.
+    pub fn output_devices(&self) -> Result<Vec<OutputDevice>> {
+        (0..self.system.get_driver_count()?)
+            .map(|driver| {
+                let (name, guid, ..) = self.system.get_driver_info(driver)?;
+                Ok(OutputDevice { driver, name, guid })
+            })
+            .collect()
+    }
+
+    /// Switches to the output device with `guid`, or the system default with [`None`].
+    ///
+    /// Returns [`Error::InvalidParam`] if no device has that [`Guid`], which can happen if it was unplugged.
+    /// See [`System::switch_output_device`] for what survives the switch.
+    pub fn set_output_device(&mut self, guid: Option<Guid>) -> Result<()> {
+        let driver = match guid {
+            Some(guid) => find_driver(self.system, guid)?.ok_or(Error::InvalidParam)?,
+            None => 0,
+        };
+        self.system.switch_output_device(driver)?;
+        self.preferences.output_device = guid;
+        Ok(())
+    }
+
+    /// The chosen output device, or [`None`] for the system default.
+    pub fn output_device(&self) -> Option<Guid> {
+        self.preferences.output_device
+    }
+
+    /// Records the speaker mode to mix in from the next launch, or [`None`] for the one the output device prefers.
+    ///
+    /// Use [`AudioSettings::needs_restart`] to tell the player that the change only applies after a restart.
+    pub fn set_speaker_mode(&mut self, speaker_mode: Option<SpeakerMode>) {
+        self.preferences.speaker_mode = speaker_mode;
+    }
+
+    /// The speaker mode to mix in from the next launch.
+    pub fn speaker_mode(&self) -> Option<SpeakerMode> {
+        self.preferences.speaker_mode
+    }
+
+    /// Whether the recorded speaker mode differs from the one the system is mixing in.
+    pub fn needs_restart(&self) -> Result<bool> {
+        let Some(speaker_mode) = self.preferences.speaker_mode else {
+            return Ok(false);
+        };
+        let (_, current, _) = self.system.get_software_format()?;
+        Ok(current != speaker_mode)
+    }
+
+    /// Everything set so far, for saving.
+    pub fn preferences(&self) -> &AudioPreferences {
+        &self.preferences
+    }
+
+    /// Replaces the settings with saved `preferences`, applying them to the registered categories and switching output device.
+    ///
+    /// An output device that is no longer present is skipped (leaving the current device), but stays in the preferences,
+    /// so it is picked again once it is plugged back in and the preferences are applied again.
+    pub fn set_preferences(&mut self, preferences: AudioPreferences) -> Result<()> {
+        for (name, target) in &self.targets {
+            let setting = preferences
+                .categories
+                .get(name)
+                .copied()
+                .unwrap_or_default();
+            target.apply(setting)?;
+        }
+        let driver = match preferences.output_device {
+            Some(guid) => find_driver(self.system, guid)?,
+            None => Some(0),
+        };
+        let current = self.system.get_driver()?;
+        if let Some(driver) = driver.filter(|&driver| driver != current) {
+            self.system.switch_output_device(driver)?;
+        }
+        self.preferences = preferences;
+        Ok(())
+    }
+}
+
+fn find_driver(system: System, guid: Guid) -> Result<Option<c_int>> {
+    for driver in 0..system.get_driver_count()? {
+        let (_, driver_guid, ..) = system.get_driver_info(driver)?;
+        if driver_guid == guid {
+            return Ok(Some(driver));
+        }
+    }
+    Ok(None)
+}
+
+impl SystemBuilder {
+    /// Applies the parts of saved [`AudioPreferences`] that have to be set before the system is initialized.
+    ///
+    /// That is the speaker mode, keeping the sample rate, and the output device if it is present.
+    /// Category settings are applied afterwards, with [`AudioSettings::set_preferences`].
+    pub fn audio_preferences(&mut self, preferences: &AudioPreferences) -> Result<&mut Self> {
+        // the system already exists at this point, so these can be set right away
+        let system = unsafe { System::from_ffi(self.system) };
+        let driver = match preferences.output_device {
+            Some(guid) => find_driver(system, guid)?,
+            None => None,
+        };
+        if let Some(driver) = driver {
+            system.set_driver(driver)?;
+        }
+        if let Some(speaker_mode) = preferences.speaker_mode {
+            let (sample_rate, _, raw_speakers) = system.get_software_format()?;
+            self.software_format(sample_rate, speaker_mode, raw_speakers)?;
+        }
+        Ok(self)
+    }
+}
@@ -0,0 +1,292 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::{c_float, c_int};
+use std::time::Duration;
+
+use crate::{ChannelControl, Dsp, Error, Result};
+
+/// How an [`AutomationCurve`] moves from one [`Keyframe`] to the next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Interpolation {
+    /// Hold the value until the next keyframe.
+    Step,
+    /// Move at a constant rate.
+    #[default]
+    Linear,
+    /// Ease out of this keyframe and into the next one.
+    Smooth,
+}
+
+/// A point on an [`AutomationCurve`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Keyframe {
+    /// When the curve reaches this keyframe, in seconds from its start.
+    pub time: c_float,
+    /// The value at this keyframe.
+    pub value: c_float,
+    /// How the curve moves from this keyframe to the next one.
+    pub interpolation: Interpolation,
+}
+
+impl Keyframe {
+    /// A keyframe with [`Interpolation::Linear`] to the next one.
+    pub const fn new(time: c_float, value: c_float) -> Self {
+        Self {
+            time,
+            value,
+            interpolation: Interpolation::Linear,
+        }
+    }
+
+    /// Sets how the curve moves from this keyframe to the next one.
+    #[must_use]
+    pub const fn with_interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+}
+
+/// A keyframed curve, for an [`AutomationLane`] to play back.
+///
+/// Curves are plain data, so with the `serde` feature they can be authored in a data file next to the level they belong to.
+/// Deserializing goes through [`AutomationCurve::new`], so curves from a data file are checked and sorted the same way.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "RawCurve")
+)]
+pub struct AutomationCurve {
+    keyframes: Vec<Keyframe>,
+    looping: bool,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawCurve {
+    keyframes: Vec<Keyframe>,
+    #[serde(default)]
+    looping: bool,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawCurve> for AutomationCurve {
+    type Error = Error;
+
+    fn try_from(raw: RawCurve) -> Result<Self> {
+        let curve = Self::new(raw.keyframes)?;
+        Ok(if raw.looping { curve.looping() } else { curve })
+    }
+}
+
+impl AutomationCurve {
+    /// Creates a one-shot curve through `keyframes`, which are sorted by time.
+    ///
+    /// Returns [`Error::InvalidParam`] if there are no keyframes, or a keyframe has a negative or non-finite time.
+    pub fn new(keyframes: impl IntoIterator<Item = Keyframe>) -> Result<Self> {
+        let mut keyframes: Vec<Keyframe> = keyframes.into_iter().collect();
+        let valid = !keyframes.is_empty()
+            && keyframes
+                .iter()
+                .all(|k| k.time.is_finite() && k.time >= 0.0);
+        if !valid {
+            return Err(Error::InvalidParam);
+        }
+        keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Ok(Self {
+            keyframes,
+            looping: false,
+        })
+    }
+
+    /// Makes the curve start over from its first keyframe once it reaches the last one.
+    ///
+    /// For a seamless loop, give the last keyframe the same value as the first.
+    #[must_use]
+    pub fn looping(mut self) -> Self {
+        self.looping = true;
+        self
+    }
+
+    /// The keyframes, sorted by time.
+    pub fn keyframes(&self) -> &[Keyframe] {
+        &self.keyframes
+    }
+
+    /// Whether the curve starts over once it reaches the end.
+    pub fn is_looping(&self) -> bool {
+        self.looping
+    }
+
+    /// The time of the last keyframe, in seconds.
+    pub fn duration(&self) -> c_float {
+        self.keyframes.last().map_or(0.0, |k| k.time)
+    }
+
+    /// The value of the curve `time` seconds from its start.
+    ///
+    /// Before the first keyframe this is the first keyframe's value, and after the last (for one-shot curves) the last keyframe's value.
+    pub fn evaluate(&self, time: c_float) -> c_float {
+        let next = self.keyframes.partition_point(|k| k.time <= time);
+        if next == 0 {
+            return self.keyframes[0].value;
+        }
+        let from = self.keyframes[next - 1];
+        let Some(&to) = self.keyframes.get(next) else {
+            return from.value;
+        };
+        let t = (time - from.time) / (to.time - from.time);
+        let t = match from.interpolation {
+            Interpolation::Step => 0.0,
+            Interpolation::Linear => t,
+            Interpolation::Smooth => t * t * (3.0 - 2.0 * t),
+        };
+        from.value + (to.value - from.value) * t
+    }
+}
+
+/// The property an [`AutomationLane`] writes to.
+pub enum AutomationTarget {
+    /// [`ChannelControl::set_volume`].
+    Volume(ChannelControl),
+    /// [`ChannelControl::set_pitch`].
+    Pitch(ChannelControl),
+    /// [`ChannelControl::set_pan`].
+    Pan(ChannelControl),
+    /// [`ChannelControl::set_low_pass_gain`].
+    LowPassGain(ChannelControl),
+    /// [`ChannelControl::set_reverb_properties`], for the wet level of one reverb instance.
+    ReverbWet(ChannelControl, c_int),
+    /// A float parameter of a [`Dsp`], such as the cutoff of a lowpass filter.
+    DspParameter(Dsp, c_int),
+    /// Anything else, the closure is called with each new value.
+    Custom(Box<dyn FnMut(c_float) -> Result<()> + Send>),
+}
+
+impl AutomationTarget {
+    fn apply(&mut self, value: c_float) -> Result<()> {
+        match self {
+            AutomationTarget::Volume(control) => control.set_volume(value),
+            AutomationTarget::Pitch(control) => control.set_pitch(value),
+            AutomationTarget::Pan(control) => control.set_pan(value),
+            AutomationTarget::LowPassGain(control) => control.set_low_pass_gain(value),
+            AutomationTarget::ReverbWet(control, instance) => {
+                control.set_reverb_properties(*instance, value)
+            }
+            AutomationTarget::DspParameter(dsp, index) => dsp.set_parameter(*index, value),
+            AutomationTarget::Custom(apply) => apply(value),
+        }
+    }
+}
+
+impl std::fmt::Debug for AutomationTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AutomationTarget::Volume(control) => f.debug_tuple("Volume").field(control).finish(),
+            AutomationTarget::Pitch(control) => f.debug_tuple("Pitch").field(control).finish(),
+            AutomationTarget::Pan(control) => f.debug_tuple("Pan").field(control).finish(),
+            AutomationTarget::LowPassGain(control) => {
+                f.debug_tuple("LowPassGain").field(control).finish()
+            }
+            AutomationTarget::ReverbWet(control, instance) => f
+                .debug_tuple("ReverbWet")
+                .field(control)
+                .field(instance)
+                .finish(),
+            AutomationTarget::DspParameter(dsp, index) => f
+                .debug_tuple("DspParameter")
+                .field(dsp)
+                .field(index)
+                .finish(),
+            AutomationTarget::Custom(_) => f.debug_tuple("Custom").finish_non_exhaustive(),
+        }
+    }
+}
+
+/// Plays an [`AutomationCurve`] back on a channel or DSP property, such as a slow filter sweep or volume drift.
+///
+/// This covers what Studio automation does for events, for sounds played through the core API.
+/// Call [`AutomationLane::update`] once per frame with the time since the last frame.
+#[derive(Debug)]
+pub struct AutomationLane {
+    target: AutomationTarget,
+    curve: AutomationCurve,
+    position: c_float,
+    finished: bool,
+}
+
+impl AutomationLane {
+    /// Creates a lane playing `curve` on `target`, from the start.
+    ///
+    /// Nothing is written to the target until the first [`AutomationLane::update`].
+    pub fn new(target: AutomationTarget, curve: AutomationCurve) -> Self {
+        Self {
+            target,
+            curve,
+            position: 0.0,
+            finished: false,
+        }
+    }
+
+    /// The property being automated.
+    pub fn target(&self) -> &AutomationTarget {
+        &self.target
+    }
+
+    /// The curve being played.
+    pub fn curve(&self) -> &AutomationCurve {
+        &self.curve
+    }
+
+    /// Replaces the curve, keeping the position.
+    pub fn set_curve(&mut self, curve: AutomationCurve) {
+        self.curve = curve;
+        self.finished = false;
+    }
+
+    /// How far into the curve playback is, in seconds.
+    pub fn position(&self) -> c_float {
+        self.position
+    }
+
+    /// Moves playback to `position` seconds into the curve.
+    ///
+    /// The new value is written with the next [`AutomationLane::update`].
+    pub fn seek(&mut self, position: c_float) {
+        self.position = position.max(0.0);
+        self.finished = false;
+    }
+
+    /// Whether a one-shot curve has played to the end and written its last value.
+    ///
+    /// Looping curves never finish.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Advances playback by `dt` and writes the curve's value to the target.
+    ///
+    /// Once a one-shot curve has finished this does nothing, so finished lanes can be left in place.
+    pub fn update(&mut self, dt: Duration) -> Result<()> {
+        if self.finished {
+            return Ok(());
+        }
+        let duration = self.curve.duration();
+        self.position += dt.as_secs_f32();
+        if self.position >= duration {
+            if self.curve.looping && duration > 0.0 {
+                self.position %= duration;
+            } else {
+                self.position = duration;
+                self.finished = true;
+            }
+        }
+        self.target.apply(self.curve.evaluate(self.position))
+    }
+}
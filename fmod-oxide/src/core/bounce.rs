@@ -0,0 +1,241 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use fmod_sys::*;
+use std::ffi::{c_float, c_int, c_uint};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, TryLockError};
+use std::time::Duration;
+
+use super::wav;
+use crate::{ChannelControl, ChannelGroup, Dsp, Error, Result, panic_wrapper};
+
+#[derive(Debug)]
+struct Capture {
+    channels: usize,
+    wanted_frames: usize,
+    // written by the mixer after every block, so progress can be read without touching the samples
+    frames: AtomicUsize,
+    // allocated up front and only locked by the mixer until the dsp is released, so the mixer never allocates or waits
+    samples: Mutex<Vec<c_float>>,
+}
+
+impl Capture {
+    fn frames(&self) -> usize {
+        self.frames.load(Ordering::Acquire)
+    }
+}
+
+fn lock(samples: &Mutex<Vec<c_float>>) -> MutexGuard<'_, Vec<c_float>> {
+    // the samples are only ever appended to, so a panic can't leave them inconsistent
+    samples.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Captures the output of a [`ChannelGroup`] to a WAV file while the game keeps running.
+///
+/// Created with [`bounce`]. Call [`Bounce::update`] once per frame, after [`crate::System::update`],
+/// and the file is written once enough audio has been captured.
+/// Useful for sound designers capturing the in-game mix of a single bus without external tools.
+///
+/// The capture [`Dsp`] is removed when the bounce finishes or is dropped, dropping it before it finishes writes nothing.
+#[derive(Debug)]
+pub struct Bounce {
+    channel_group: ChannelGroup,
+    dsp: Option<Dsp>,
+    capture: Arc<Capture>,
+    sample_rate: c_int,
+    path: PathBuf,
+}
+
+/// Starts capturing `duration` of the output of `channel_group` to a WAV file at `path`. See [`Bounce`].
+///
+/// Audio is captured after the group's fader and effects, at the mixer's sample rate and speaker mode,
+/// and saved as 16 bit PCM. The whole capture is allocated up front, so the mixer thread never allocates.
+pub fn bounce(
+    channel_group: ChannelGroup,
+    duration: Duration,
+    path: impl AsRef<Path>,
+) -> Result<Bounce> {
+    let system = channel_group.get_system()?;
+    let (sample_rate, speaker_mode, _) = system.get_software_format()?;
+    let channels = system.get_speaker_mode_channels(speaker_mode)?.max(1) as usize;
+    let wanted_frames = (duration.as_secs_f64() * f64::from(sample_rate)) as usize;
+    let capture = Arc::new(Capture {
+        channels,
+        wanted_frames,
+        frames: AtomicUsize::new(0),
+        samples: Mutex::new(Vec::with_capacity(wanted_frames * channels)),
+    });
+
+    // every field is either a number, a nullable pointer or an optional callback, so zeroed is a valid starting point
+    let mut description: FMOD_DSP_DESCRIPTION = unsafe { std::mem::zeroed() };
+    description.pluginsdkversion = FMOD_PLUGIN_SDK_VERSION;
+    for (dst, &src) in description.name.iter_mut().zip(b"fmod-oxide bounce") {
+        *dst = src as _;
+    }
+    description.numinputbuffers = 1;
+    description.numoutputbuffers = 1;
+    description.read = Some(read_callback);
+    let dsp = unsafe { system.create_dsp(&raw const description)? };
+
+    let result = dsp
+        .set_userdata(Arc::as_ptr(&capture).cast_mut().cast())
+        .and_then(|()| channel_group.add_dsp(ChannelControl::DSP_HEAD, dsp));
+    if let Err(e) = result {
+        dsp.release()?;
+        return Err(e);
+    }
+
+    Ok(Bounce {
+        channel_group,
+        dsp: Some(dsp),
+        capture,
+        sample_rate,
+        path: path.as_ref().to_path_buf(),
+    })
+}
+
+impl Bounce {
+    /// Checks whether enough audio has been captured, and if so removes the capture [`Dsp`] and writes the file.
+    ///
+    /// Returns whether the bounce has finished. Returns [`Error::FileBad`] if writing the file fails.
+    pub fn update(&mut self) -> Result<bool> {
+        if self.dsp.is_none() {
+            return Ok(true);
+        }
+        let done = self.capture.frames() >= self.capture.wanted_frames;
+        if done {
+            self.finish_now()?;
+        }
+        Ok(done)
+    }
+
+    /// How much audio has been captured so far.
+    pub fn captured(&self) -> Duration {
+        let frames = self.capture.frames();
+        Duration::from_secs_f64(frames as f64 / f64::from(self.sample_rate))
+    }
+
+    /// Whether the file has been written.
+    pub fn is_finished(&self) -> bool {
+        self.dsp.is_none()
+    }
+
+    /// Stops capturing and writes what has been captured so far, even if that's shorter than requested.
+    ///
+    /// Does nothing if the bounce has already finished.
+    pub fn finish(mut self) -> Result<()> {
+        self.finish_now()
+    }
+
+    fn finish_now(&mut self) -> Result<()> {
+        if self.dsp.is_none() {
+            return Ok(());
+        }
+        self.detach()?;
+        let samples: Vec<i16> = lock(&self.capture.samples)
+            .iter()
+            .map(|&s| (s.clamp(-1.0, 1.0) * c_float::from(i16::MAX)) as i16)
+            .collect();
+        let file = std::fs::File::create(&self.path).map_err(|e| wav::io_error(&e))?;
+        wav::write_pcm16(
+            std::io::BufWriter::new(file),
+            self.sample_rate,
+            self.capture.channels as c_int,
+            &samples,
+        )
+    }
+
+    fn detach(&mut self) -> Result<()> {
+        let Some(dsp) = self.dsp else {
+            return Ok(());
+        };
+        match self.channel_group.remove_dsp(dsp) {
+            // the group was released, which already disconnected the dsp
+            Ok(()) | Err(Error::InvalidHandle) => {}
+            Err(e) => return Err(e),
+        }
+        self.dsp = None;
+        if let Err(e) = dsp.release() {
+            // the mixer may still read through the userdata, so the capture has to outlive the dsp
+            std::mem::forget(Arc::clone(&self.capture));
+            return Err(e);
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Bounce {
+    fn drop(&mut self) {
+        let _ = self.detach();
+    }
+}
+
+unsafe extern "C" fn read_callback(
+    state: *mut FMOD_DSP_STATE,
+    in_buffer: *mut c_float,
+    out_buffer: *mut c_float,
+    length: c_uint,
+    in_channels: c_int,
+    out_channels: *mut c_int,
+) -> FMOD_RESULT {
+    panic_wrapper(|| {
+        let in_channels = in_channels.max(0) as usize;
+        // SAFETY: FMOD passes buffers of `length` frames with `in_channels` and `out_channels` channels
+        let (input, output) = unsafe {
+            let out_channels = (*out_channels).max(0) as usize;
+            (
+                std::slice::from_raw_parts(in_buffer, length as usize * in_channels),
+                std::slice::from_raw_parts_mut(out_buffer, length as usize * out_channels),
+            )
+        };
+        pass_through(input, in_channels, output);
+
+        let dsp = unsafe { Dsp::from_ffi((*state).instance.cast()) };
+        let Ok(userdata) = dsp.get_userdata() else {
+            return FMOD_RESULT::FMOD_OK;
+        };
+        if userdata.is_null() {
+            return FMOD_RESULT::FMOD_OK;
+        }
+        // SAFETY: the userdata is set to the capture by `bounce`, which keeps it alive until the dsp is released
+        let capture = unsafe { &*userdata.cast::<Capture>() };
+        // `Bounce` only locks the samples once the dsp is gone, so this doesn't fail in practice;
+        // if it ever does, dropping the block beats stalling the mixer
+        let mut samples = match capture.samples.try_lock() {
+            Ok(samples) => samples,
+            Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+            Err(TryLockError::WouldBlock) => return FMOD_RESULT::FMOD_OK,
+        };
+        // the group's channel count can differ from the file's, map its layout onto the file's
+        let channels = capture.channels;
+        let remaining = capture.wanted_frames - samples.len() / channels;
+        for frame in input.chunks_exact(in_channels.max(1)).take(remaining) {
+            // stays within the capacity allocated by `bounce`
+            samples.extend((0..channels).map(|c| frame[c % frame.len()]));
+        }
+        capture
+            .frames
+            .store(samples.len() / channels, Ordering::Release);
+        FMOD_RESULT::FMOD_OK
+    })
+}
+
+// copies the input to the output unchanged, repeating channels if the output has more
+fn pass_through(input: &[c_float], in_channels: usize, output: &mut [c_float]) {
+    if input.len() == output.len() {
+        output.copy_from_slice(input);
+        return;
+    }
+    let frames = input.chunks_exact(in_channels.max(1));
+    let out_channels = output.len() / frames.len().max(1);
+    for (out, frame) in output.chunks_exact_mut(out_channels.max(1)).zip(frames) {
+        for (c, sample) in out.iter_mut().enumerate() {
+            *sample = frame[c % frame.len()];
+        }
+    }
+}
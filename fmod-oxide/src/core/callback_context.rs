@@ -0,0 +1,108 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::cell::Cell;
+use std::fmt::{Display, Formatter};
+
+#[cfg(doc)]
+use crate::{ChannelControlCallback, NonBlockCallback, PcmCallback, Sound, System, studio};
+
+/// The FMOD thread a crate callback was invoked on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CallbackThread {
+    /// The thread calling [`System::update`], for [`ChannelControlCallback`]s.
+    Update,
+    /// The Studio update thread, or the thread calling [`studio::System::update`] with deferred callbacks,
+    /// for Studio event and system callbacks.
+    Studio,
+    /// One of FMOD's non blocking loading threads, for [`NonBlockCallback`]s.
+    NonBlocking,
+    /// The stream thread, for [`PcmCallback`]s on streams.
+    Stream,
+}
+
+impl Display for CallbackThread {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            CallbackThread::Update => "update",
+            CallbackThread::Studio => "studio",
+            CallbackThread::NonBlocking => "non blocking",
+            CallbackThread::Stream => "stream",
+        })
+    }
+}
+
+thread_local! {
+    static CURRENT: Cell<Option<CallbackThread>> = const { Cell::new(None) };
+}
+
+/// Which crate callback, if any, is running on the current thread.
+///
+/// The crate's callback trampolines record the [`CallbackThread`] they run on for the duration of the callback,
+/// so code deep inside a callback can ask where it is without the information being threaded through every trait method.
+///
+/// In debug builds, functions that FMOD documents as forbidden from a callback check the context and panic when misused,
+/// such as [`Sound::release`] from a [`NonBlockCallback`], which deadlocks.
+/// The panic is caught at the callback boundary and printed, like any other panic in a callback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CallbackContext {
+    thread: CallbackThread,
+}
+
+impl CallbackContext {
+    /// The context of the callback running on this thread, or [`None`] outside of a callback.
+    pub fn current() -> Option<Self> {
+        CURRENT.with(Cell::get).map(|thread| Self { thread })
+    }
+
+    /// The thread the callback was invoked on.
+    pub fn thread(self) -> CallbackThread {
+        self.thread
+    }
+
+    /// Records that a callback on `thread` is running until the guard is dropped.
+    pub(crate) fn enter(thread: CallbackThread) -> CallbackGuard {
+        CallbackGuard {
+            previous: CURRENT.with(|current| current.replace(Some(thread))),
+        }
+    }
+
+    /// Panics in debug builds if called from a callback on any of `forbidden`.
+    #[track_caller]
+    pub(crate) fn debug_assert_not_in(forbidden: &[CallbackThread], function: &str) {
+        let Some(context) = Self::current().filter(|_| cfg!(debug_assertions)) else {
+            return;
+        };
+        assert!(
+            !forbidden.contains(&context.thread),
+            "{function} must not be called from a {} thread callback",
+            context.thread
+        );
+    }
+
+    /// Panics in debug builds if called from any callback.
+    #[track_caller]
+    pub(crate) fn debug_assert_outside(function: &str) {
+        let Some(context) = Self::current().filter(|_| cfg!(debug_assertions)) else {
+            return;
+        };
+        panic!(
+            "{function} must not be called from a callback (called from a {} thread callback)",
+            context.thread
+        );
+    }
+}
+
+/// Restores the previous [`CallbackContext`] when dropped, so nested callbacks unwind correctly.
+pub(crate) struct CallbackGuard {
+    previous: Option<CallbackThread>,
+}
+
+impl Drop for CallbackGuard {
+    fn drop(&mut self) {
+        CURRENT.with(|current| current.set(self.previous));
+    }
+}
@@ -0,0 +1,102 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::c_float;
+use std::time::{Duration, Instant};
+
+use crate::{Channel, ChannelGroup, Error, Result, Sound, System};
+
+#[cfg(doc)]
+use crate::ChannelControl;
+
+/// Stops fire-and-forget [`Channel`]s that have been inaudible for too long.
+///
+/// Looping sounds that are played and forgotten about keep a virtual voice forever, even once they can no longer be heard
+/// (for example after the listener has walked away from them).
+/// Channels played through [`AudibilityCuller::play`], or handed over with [`AudibilityCuller::track`],
+/// are stopped once their [`ChannelControl::get_audibility`] has stayed below a threshold for a grace period.
+///
+/// Call [`AudibilityCuller::update`] once per frame.
+#[derive(Debug)]
+pub struct AudibilityCuller {
+    threshold: c_float,
+    grace: Duration,
+    channels: Vec<(Channel, Option<Instant>)>,
+}
+
+impl AudibilityCuller {
+    /// Creates a culler that stops channels whose audibility stays below `threshold` for `grace`.
+    pub fn new(threshold: c_float, grace: Duration) -> Self {
+        Self {
+            threshold,
+            grace,
+            channels: Vec::new(),
+        }
+    }
+
+    /// Plays `sound` on `channel_group` (or the master channel group) and tracks the [`Channel`].
+    pub fn play(
+        &mut self,
+        system: System,
+        sound: Sound,
+        channel_group: Option<ChannelGroup>,
+    ) -> Result<Channel> {
+        let channel = system.play_sound(sound, channel_group, false)?;
+        self.track(channel);
+        Ok(channel)
+    }
+
+    /// Tracks an already playing `channel`.
+    pub fn track(&mut self, channel: Channel) {
+        if !self.channels.iter().any(|(c, _)| *c == channel) {
+            self.channels.push((channel, None));
+        }
+    }
+
+    /// Number of channels being tracked.
+    pub fn len(&self) -> usize {
+        self.channels.len()
+    }
+
+    /// Whether no channels are being tracked.
+    pub fn is_empty(&self) -> bool {
+        self.channels.is_empty()
+    }
+
+    /// Checks the audibility of every tracked channel, stopping those that have been inaudible for the grace period.
+    ///
+    /// Channels that have finished playing on their own are forgotten. Returns the number of channels stopped.
+    pub fn update(&mut self) -> Result<usize> {
+        let now = Instant::now();
+        let mut stopped = 0;
+        let mut index = 0;
+        while index < self.channels.len() {
+            let (channel, quiet_since) = &mut self.channels[index];
+            let audibility = match channel.get_audibility() {
+                Ok(audibility) => audibility,
+                Err(Error::InvalidHandle | Error::ChannelStolen) => {
+                    self.channels.swap_remove(index);
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+
+            if audibility >= self.threshold {
+                *quiet_since = None;
+            } else {
+                let since = *quiet_since.get_or_insert(now);
+                if now.duration_since(since) >= self.grace {
+                    channel.stop()?;
+                    self.channels.swap_remove(index);
+                    stopped += 1;
+                    continue;
+                }
+            }
+            index += 1;
+        }
+        Ok(stopped)
+    }
+}
@@ -7,7 +7,7 @@
 use fmod_sys::*;
 use std::ffi::c_int;
 
-use crate::{Channel, Sound};
+use crate::{Channel, Sound, StreamHealth};
 use crate::{FmodResultExt, Result};
 
 impl Channel {
@@ -37,6 +37,17 @@ impl Channel {
         })
     }
 
+    /// Retrieves the loading and buffering state of the currently playing [`Sound`].
+    ///
+    /// This is [`Sound::get_stream_health`] on [`Channel::get_current_sound`],
+    /// so streaming code can check on a stream through the [`Channel`] playing it.
+    /// Returns [`None`] if no [`Sound`] is playing.
+    pub fn stream_health(&self) -> Result<Option<StreamHealth>> {
+        self.get_current_sound()?
+            .map(|sound| sound.get_stream_health())
+            .transpose()
+    }
+
     /// Retrieves the index of this object in the System Channel pool.
     pub fn get_index(&self) -> Result<c_int> {
         let mut index = 0;
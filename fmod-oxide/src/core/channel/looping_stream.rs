@@ -0,0 +1,178 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::{c_int, c_uint};
+
+use crate::{Channel, ChannelGroup, Error, Mode, Result, Sound, System, TimeUnit};
+
+/// Plays a stream with loop settings that take effect when they are changed.
+///
+/// Streams decode ahead into a buffer, and run their loop logic while doing so.
+/// Loop counts and loop points changed after the buffer has been filled are silently ignored until the next flush,
+/// which is easy to miss when the loop region is shorter than the decode buffer.
+///
+/// This sets the loop count and loop points on the [`Channel`] while it is still paused,
+/// flushes the stream with [`Channel::set_position`] before starting it,
+/// and flushes again whenever they are changed mid-play.
+#[derive(Debug)]
+pub struct LoopingStream {
+    system: System,
+    sound: Sound,
+    channel: Option<Channel>,
+    loop_count: c_int,
+    loop_points: Option<(c_uint, c_uint)>,
+}
+
+impl LoopingStream {
+    /// Wraps `sound`, which should have been created with [`Mode::CREATE_STREAM`].
+    ///
+    /// [`Mode::LOOP_NORMAL`] is set on the sound if it isn't already looping, since that can only be done before it is buffered.
+    /// The loop count starts out at -1 (loop forever).
+    pub fn new(system: System, sound: Sound) -> Result<Self> {
+        let mode = sound.get_mode()?;
+        if !mode.intersects(Mode::LOOP_NORMAL | Mode::LOOP_BIDI) {
+            sound.set_mode(mode.difference(Mode::LOOP_OFF) | Mode::LOOP_NORMAL)?;
+        }
+        Ok(Self {
+            system,
+            sound,
+            channel: None,
+            loop_count: -1,
+            loop_points: None,
+        })
+    }
+
+    /// The stream being played.
+    pub fn sound(&self) -> Sound {
+        self.sound
+    }
+
+    /// The channel the stream is playing on, if [`LoopingStream::play`] has been called.
+    pub fn channel(&self) -> Option<Channel> {
+        self.channel
+    }
+
+    /// Starts the stream on `channel_group` (or the master channel group), stopping any previous playback.
+    ///
+    /// The loop settings are applied while the channel is paused, and the stream is flushed before it starts.
+    pub fn play(&mut self, channel_group: Option<ChannelGroup>) -> Result<Channel> {
+        self.stop()?;
+        let channel = self.system.play_sound(self.sound, channel_group, true)?;
+        self.channel = Some(channel);
+        if let Err(e) = self.apply(channel, 0) {
+            let _ = channel.stop();
+            self.channel = None;
+            return Err(e);
+        }
+        channel.set_paused(false)?;
+        Ok(channel)
+    }
+
+    /// Sets the number of times to loop before stopping, -1 to loop forever.
+    ///
+    /// If the stream is playing, it is flushed at its current position so the new count takes effect right away.
+    pub fn set_loop_count(&mut self, loop_count: c_int) -> Result<()> {
+        self.loop_count = loop_count;
+        self.reflush()
+    }
+
+    /// The loop count set with [`LoopingStream::set_loop_count`].
+    ///
+    /// See [`Channel::get_loop_count`] for the remaining loops of the current playback.
+    pub fn loop_count(&self) -> c_int {
+        self.loop_count
+    }
+
+    /// Sets the inclusive loop start and end points, or [`None`] to loop the whole stream.
+    ///
+    /// Returns [`Error::InvalidParam`] if `end` is not after `start`, or is past the end of the stream.
+    /// If the stream is playing, it is flushed at its current position so the new points take effect right away.
+    pub fn set_loop_points(
+        &mut self,
+        points: Option<(c_uint, c_uint)>,
+        unit: TimeUnit,
+    ) -> Result<()> {
+        self.loop_points = match points {
+            Some((start, end)) => {
+                let start = self.to_pcm(start, unit)?;
+                let end = self.to_pcm(end, unit)?;
+                if end <= start || end >= self.sound.get_length(TimeUnit::PCM)? {
+                    return Err(Error::InvalidParam);
+                }
+                Some((start, end))
+            }
+            None => None,
+        };
+        self.reflush()
+    }
+
+    /// The loop points set with [`LoopingStream::set_loop_points`], in PCM samples.
+    pub fn loop_points(&self) -> Option<(c_uint, c_uint)> {
+        self.loop_points
+    }
+
+    /// Whether the stream is still playing. A stolen or finished channel counts as stopped.
+    pub fn is_playing(&self) -> Result<bool> {
+        let Some(channel) = self.channel else {
+            return Ok(false);
+        };
+        match channel.is_playing() {
+            Ok(playing) => Ok(playing),
+            Err(Error::InvalidHandle | Error::ChannelStolen) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Stops playback, if the stream is playing.
+    pub fn stop(&mut self) -> Result<()> {
+        let Some(channel) = self.channel.take() else {
+            return Ok(());
+        };
+        match channel.stop() {
+            Ok(()) | Err(Error::InvalidHandle | Error::ChannelStolen) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn to_pcm(&self, position: c_uint, unit: TimeUnit) -> Result<c_uint> {
+        match unit {
+            TimeUnit::PCM => Ok(position),
+            TimeUnit::MS => {
+                let (frequency, _) = self.sound.get_defaults()?;
+                Ok((f64::from(position) * f64::from(frequency) / 1000.0) as c_uint)
+            }
+            _ => Err(Error::Format),
+        }
+    }
+
+    fn apply(&self, channel: Channel, position: c_uint) -> Result<()> {
+        let _lock = self.system.lock_dsp()?;
+        channel.set_loop_count(self.loop_count)?;
+        let (start, end) = match self.loop_points {
+            Some(points) => points,
+            None => (0, self.sound.get_length(TimeUnit::PCM)?.saturating_sub(1)),
+        };
+        channel.set_loop_points(start, TimeUnit::PCM, end, TimeUnit::PCM)?;
+        // setting the position throws away anything decoded with the old loop settings
+        channel.set_position(position, TimeUnit::PCM)
+    }
+
+    fn reflush(&mut self) -> Result<()> {
+        let Some(channel) = self.channel else {
+            return Ok(());
+        };
+        let result = channel
+            .get_position(TimeUnit::PCM)
+            .and_then(|position| self.apply(channel, position));
+        match result {
+            Err(Error::InvalidHandle | Error::ChannelStolen) => {
+                self.channel = None;
+                Ok(())
+            }
+            result => result,
+        }
+    }
+}
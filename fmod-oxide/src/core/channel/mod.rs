@@ -9,8 +9,17 @@ use std::{ops::Deref, ptr::NonNull};
 
 use crate::ChannelControl;
 
+mod audibility_cull;
+pub use audibility_cull::AudibilityCuller;
 mod information;
+mod looping_stream;
+pub use looping_stream::LoopingStream;
 mod playback_control;
+mod speed;
+mod tuning;
+pub use tuning::ChannelTuning;
+mod virtual_voice;
+pub use virtual_voice::VirtualVoiceNotifier;
 
 #[cfg(doc)]
 use crate::{ChannelGroup, System};
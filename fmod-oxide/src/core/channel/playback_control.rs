@@ -7,13 +7,22 @@
 use fmod_sys::*;
 use std::ffi::{c_float, c_int, c_uint};
 
-use crate::{Channel, ChannelGroup, TimeUnit};
+use crate::{Channel, ChannelGroup, TimeUnit, check_same_system};
 use crate::{FmodResultExt, Result};
 
 #[cfg(doc)]
 use crate::{ChannelControl, Mode, System};
 
 impl Channel {
+    /// Most important priority, the last to be stolen by the virtual voice system.
+    ///
+    /// Note that priority values are inverted: lower values are more important.
+    pub const PRIORITY_MAX: c_int = 0;
+    /// Least important priority, the first to be stolen by the virtual voice system.
+    pub const PRIORITY_MIN: c_int = 256;
+    /// Default priority of a [`Channel`].
+    pub const PRIORITY_DEFAULT: c_int = 128;
+
     /// Sets the frequency or playback rate.
     ///
     /// Default frequency is determined by the audio format of the Sound or DSP.
@@ -32,6 +41,7 @@ impl Channel {
     /// Sets the priority used for virtual voice ordering.
     ///
     /// Priority is used as a coarse grain control for the virtual voice system, lower priority [`Channel`]s will always be stolen before higher ones.
+    /// Priority ranges from [`Channel::PRIORITY_MAX`] to [`Channel::PRIORITY_MIN`], and defaults to [`Channel::PRIORITY_DEFAULT`].
     /// For [`Channel`]s of equal priority, those with the quietest [`ChannelControl::get_audibility`] value will be stolen first.
     ///
     /// See the Virtual Voices guide for more information.
@@ -91,6 +101,7 @@ impl Channel {
     ///
     /// [`Channel`]s may only output to a single [`ChannelGroup`]. This operation will remove it from the previous group first.
     pub fn set_channel_group(&self, channel_group: ChannelGroup) -> Result<()> {
+        check_same_system(|| self.get_system(), || channel_group.get_system())?;
         unsafe {
             FMOD_Channel_SetChannelGroup(self.inner.as_ptr(), channel_group.into()).to_result()
         }
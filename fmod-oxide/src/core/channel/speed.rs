@@ -0,0 +1,58 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::c_float;
+
+use crate::{Channel, Error, Result};
+
+#[cfg(doc)]
+use crate::{ChannelControl, DspType, Sound};
+
+impl Channel {
+    /// Plays back at `speed` times the normal rate without changing the pitch, such as to follow a gameplay time scale.
+    ///
+    /// The frequency is set to `speed` times the default frequency of the current [`Sound`],
+    /// and a [`DspType::PitchShift`] is added to the head of the DSP chain to shift the pitch back down (or up) by the same ratio.
+    /// Setting `speed` back to 1 restores the default frequency and removes the DSP again.
+    /// Any frequency previously set with [`Channel::set_frequency`] is overwritten.
+    ///
+    /// `speed` must be between [`ChannelControl::MIN_PITCH_PRESERVING_SPEED`] and [`ChannelControl::MAX_PITCH_PRESERVING_SPEED`],
+    /// the range the PitchShift DSP can shift by, or [`Error::InvalidParam`] is returned.
+    /// [`Error::Unsupported`] is returned if the [`Channel`] is playing a DSP rather than a [`Sound`].
+    ///
+    /// The PitchShift DSP is an FFT based effect, so this is no substitute for offline time stretching:
+    /// - It adds latency of around one FFT window (1024 samples by default) and costs noticeably more CPU than playing the [`Channel`] normally.
+    /// - Transients get smeared and speech takes on a phasey, "underwater" quality, more so the further `speed` is from 1.
+    /// - It is fine for short scale changes like slow motion effects, but unsuited to long term playback of music at an altered speed.
+    ///
+    /// The DSP is not released when the [`Channel`] stops or ends on its own, since its handle is already invalid by then.
+    /// Call [`Channel::clear_speed`] before stopping the [`Channel`] (or set `speed` back to 1),
+    /// otherwise the DSP leaks until the [`System`](crate::System) is released.
+    pub fn set_speed_preserving_pitch(&self, speed: c_float) -> Result<()> {
+        let sound = self.get_current_sound()?.ok_or(Error::Unsupported)?;
+        let (default_frequency, _) = sound.get_defaults()?;
+
+        self.compensate_pitch(speed)?;
+        self.set_frequency(default_frequency * speed)
+    }
+
+    /// Undoes [`Channel::set_speed_preserving_pitch`], restoring the default frequency and releasing the PitchShift DSP.
+    ///
+    /// Call this before stopping a [`Channel`] that had its speed changed. Does nothing if the speed was never changed.
+    pub fn clear_speed(&self) -> Result<()> {
+        if self.find_pitch_shift()?.is_none() {
+            return Ok(());
+        }
+        self.set_speed_preserving_pitch(1.0)
+    }
+
+    /// The speed set with [`Channel::set_speed_preserving_pitch`], as the ratio of the current frequency to the [`Sound`]'s default.
+    pub fn get_speed(&self) -> Result<c_float> {
+        let sound = self.get_current_sound()?.ok_or(Error::Unsupported)?;
+        let (default_frequency, _) = sound.get_defaults()?;
+        Ok(self.get_frequency()? / default_frequency)
+    }
+}
@@ -0,0 +1,100 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::{c_float, c_int, c_uint};
+
+use crate::{Channel, Error, Result, TimeUnit};
+
+#[cfg(doc)]
+use crate::{Mode, System};
+
+/// The playback settings of a [`Channel`] that aren't part of its mix: frequency, priority, loop count and position.
+///
+/// Apply with [`Channel::apply_tuning`], which validates everything before changing anything.
+/// Fields left as [`None`] are left as they are.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ChannelTuning {
+    /// Playback rate in Hz, see [`Channel::set_frequency`].
+    pub frequency: Option<c_float>,
+    /// Virtual voice priority, see [`Channel::set_priority`].
+    pub priority: Option<c_int>,
+    /// Number of times to loop, -1 for forever, see [`Channel::set_loop_count`].
+    pub loop_count: Option<c_int>,
+    /// Playback position, see [`Channel::set_position`].
+    pub position: Option<(c_uint, TimeUnit)>,
+}
+
+impl ChannelTuning {
+    /// Captures the current settings of `channel`, with the position in milliseconds.
+    pub fn capture(channel: Channel) -> Result<Self> {
+        Ok(Self {
+            frequency: Some(channel.get_frequency()?),
+            priority: Some(channel.get_priority()?),
+            loop_count: Some(channel.get_loop_count()?),
+            position: Some((channel.get_position(TimeUnit::MS)?, TimeUnit::MS)),
+        })
+    }
+
+    /// Checks the settings that can be checked without a [`Channel`].
+    ///
+    /// Returns [`Error::InvalidParam`] for a non-finite frequency, a priority outside of
+    /// [`Channel::PRIORITY_MAX`]..=[`Channel::PRIORITY_MIN`], or a loop count below -1.
+    pub fn validate(&self) -> Result<()> {
+        if self
+            .frequency
+            .is_some_and(|frequency| !frequency.is_finite())
+            || self.priority.is_some_and(|priority| {
+                !(Channel::PRIORITY_MAX..=Channel::PRIORITY_MIN).contains(&priority)
+            })
+            || self.loop_count.is_some_and(|count| count < -1)
+        {
+            return Err(Error::InvalidParam);
+        }
+        Ok(())
+    }
+}
+
+impl Channel {
+    /// Applies `tuning`, changing either all of the given settings or none of them.
+    ///
+    /// Everything is validated first: [`ChannelTuning::validate`], and that the position is within the current sound.
+    /// The settings are then set one after another with the DSP engine locked (see [`System::lock_dsp`]),
+    /// so the mixer can't run with only some of them applied.
+    /// If FMOD still rejects a setting partway through, such as a negative frequency on a sound that isn't a [`Mode::CREATE_SAMPLE`],
+    /// the settings before it stay applied and the error is returned.
+    ///
+    /// Returns [`Error::InvalidPosition`] if the position is past the end of the current sound.
+    pub fn apply_tuning(&self, tuning: &ChannelTuning) -> Result<()> {
+        tuning.validate()?;
+        if let Some((position, unit)) = tuning.position {
+            let length = match self.get_current_sound()? {
+                Some(sound) => sound.get_length(unit)?,
+                None => c_uint::MAX,
+            };
+            if position >= length {
+                return Err(Error::InvalidPosition);
+            }
+        }
+
+        let system = self.get_system()?;
+        let _lock = system.lock_dsp()?;
+        if let Some(frequency) = tuning.frequency {
+            self.set_frequency(frequency)?;
+        }
+        if let Some(priority) = tuning.priority {
+            self.set_priority(priority)?;
+        }
+        if let Some(loop_count) = tuning.loop_count {
+            self.set_loop_count(loop_count)?;
+        }
+        if let Some((position, unit)) = tuning.position {
+            self.set_position(position, unit)?;
+        }
+        Ok(())
+    }
+}
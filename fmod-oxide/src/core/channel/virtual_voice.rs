@@ -0,0 +1,101 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use crate::{Channel, ChannelControlCallback, ChannelControlType, Result};
+
+#[cfg(doc)]
+use crate::{ChannelControl, System};
+
+type VirtualVoiceHandler = Box<dyn FnMut(Channel, bool) + Send>;
+
+struct Entry {
+    channel: usize,
+    handlers: Vec<VirtualVoiceHandler>,
+}
+
+static ENTRIES: Mutex<Vec<Entry>> = Mutex::new(Vec::new());
+
+fn entries() -> MutexGuard<'static, Vec<Entry>> {
+    // handlers are run outside the lock, so poisoning can't leave an entry half-updated
+    ENTRIES.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// A [`ChannelControlCallback`] that forwards virtual voice changes to [`Channel::on_virtual_voice`] handlers.
+///
+/// [`Channel::on_virtual_voice`] installs this for you.
+#[derive(Debug, Clone, Copy)]
+pub struct VirtualVoiceNotifier;
+
+impl ChannelControlCallback for VirtualVoiceNotifier {
+    fn end(channel_control: ChannelControlType) -> Result<()> {
+        if let ChannelControlType::Channel(channel) = channel_control {
+            channel.clear_virtual_voice_handlers();
+        }
+        Ok(())
+    }
+
+    fn virtual_voice(channel_control: ChannelControlType, is_virtual: bool) -> Result<()> {
+        let ChannelControlType::Channel(channel) = channel_control else {
+            return Ok(());
+        };
+        let key = channel.inner.as_ptr() as usize;
+        let Some(mut handlers) = entries()
+            .iter_mut()
+            .find(|e| e.channel == key)
+            .map(|e| std::mem::take(&mut e.handlers))
+        else {
+            return Ok(());
+        };
+
+        // run handlers without holding the lock, so they are free to register more
+        for handler in &mut handlers {
+            handler(channel, is_virtual);
+        }
+
+        let mut entries = entries();
+        // the channel may have ended (and been cleared) while the handlers ran
+        if let Some(entry) = entries.iter_mut().find(|e| e.channel == key) {
+            handlers.append(&mut entry.handlers);
+            entry.handlers = handlers;
+        }
+        Ok(())
+    }
+}
+
+impl Channel {
+    /// Calls `handler` whenever this channel goes virtual (`true`) or becomes real again (`false`).
+    ///
+    /// This is useful for restarting or repositioning sounds when they come back from virtual.
+    /// Handlers are dropped once the channel ends.
+    ///
+    /// This sets [`VirtualVoiceNotifier`] as the channel's callback, replacing any set with [`ChannelControl::set_callback`].
+    /// `handler` is called from [`System::update`].
+    pub fn on_virtual_voice<F>(&self, handler: F) -> Result<()>
+    where
+        F: FnMut(Channel, bool) + Send + 'static,
+    {
+        self.set_callback::<VirtualVoiceNotifier>()?;
+
+        let key = self.inner.as_ptr() as usize;
+        let mut entries = entries();
+        match entries.iter_mut().find(|e| e.channel == key) {
+            Some(entry) => entry.handlers.push(Box::new(handler)),
+            None => entries.push(Entry {
+                channel: key,
+                handlers: vec![Box::new(handler)],
+            }),
+        }
+        Ok(())
+    }
+
+    /// Removes every handler registered with [`Channel::on_virtual_voice`].
+    pub fn clear_virtual_voice_handlers(&self) {
+        let key = self.inner.as_ptr() as usize;
+        entries().retain(|e| e.channel != key);
+    }
+}
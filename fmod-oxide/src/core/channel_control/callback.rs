@@ -11,7 +11,9 @@ use std::{
     os::raw::c_void,
 };
 
-use crate::{Channel, ChannelControl, ChannelGroup, panic_wrapper};
+use crate::{
+    CallbackContext, CallbackThread, Channel, ChannelControl, ChannelGroup, panic_wrapper,
+};
 use crate::{FmodResultExt, Result};
 
 /// Enum used to distinguish between [`Channel`] and [`ChannelGroup`] in the [`ChannelControl`] callback.
@@ -77,6 +79,7 @@ unsafe extern "C" fn callback_impl<C: ChannelControlCallback>(
     commanddata2: *mut c_void,
 ) -> FMOD_RESULT {
     panic_wrapper(|| {
+        let _context = CallbackContext::enter(CallbackThread::Update);
         let channel_control = match control_type {
             FMOD_CHANNELCONTROL_CHANNEL => {
                 let channel = unsafe { Channel::from_ffi(channel_control.cast()) };
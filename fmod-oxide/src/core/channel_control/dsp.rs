@@ -8,7 +8,7 @@ use std::ffi::c_int;
 
 use fmod_sys::*;
 
-use crate::{ChannelControl, Dsp};
+use crate::{ChannelControl, Dsp, check_same_system};
 use crate::{FmodResultExt, Result};
 
 impl ChannelControl {
@@ -25,6 +25,7 @@ impl ChannelControl {
     ///
     /// For detailed information on FMOD's DSP network, read the DSP Architecture and Usage white paper.
     pub fn add_dsp(&self, index: c_int, dsp: Dsp) -> Result<()> {
+        check_same_system(|| self.get_system(), || dsp.get_system())?;
         unsafe {
             FMOD_ChannelControl_AddDSP(self.inner.as_ptr(), index, dsp.inner.as_ptr()).to_result()
         }
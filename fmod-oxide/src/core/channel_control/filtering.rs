@@ -8,10 +8,93 @@ use std::ffi::{c_float, c_int};
 
 use fmod_sys::*;
 
-use crate::ChannelControl;
+use crate::{ChannelControl, InitFlags, require_init_flags};
 use crate::{FmodResultExt, Result};
 
+#[cfg(doc)]
+use crate::{AdvancedSettings, Channel, ChannelGroup, Error, Mode, System};
+
+/// A manual setting for the 3D distance filter, see [`Filtering::set_distance_filter`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DistanceFilter {
+    /// The filter follows the 3D distance. This is the default.
+    Automatic,
+    /// The filter is held at a fixed level.
+    Custom {
+        /// Attenuation, from 0 (fully filtered) to 1 (unfiltered).
+        level: c_float,
+        /// Center frequency of the band-pass filter in Hz, or 0 for [`AdvancedSettings::distance_filter_center_freq`].
+        center_freq: c_float,
+    },
+}
+
+/// The built in lowpass and distance filter of a [`ChannelControl`], see [`ChannelControl::filtering`].
+///
+/// FMOD only creates these filters when the [`System`] is initialized with the right [`InitFlags`], and silently ignores them otherwise.
+/// These functions always check the flags first (with or without the `init-checks` feature), and return [`Error::Initialization`] if they are missing.
+/// The check is skipped for systems that weren't built by this crate, see [`System::get_init_flags`].
+///
+/// #### NOTE: Currently only supported for [`Channel`], not [`ChannelGroup`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Filtering {
+    control: ChannelControl,
+}
+
+impl Filtering {
+    fn require(&self, any_of: InitFlags) -> Result<()> {
+        require_init_flags(self.control.get_system()?, any_of)
+    }
+
+    /// Sets the gain of the dry signal when the built in lowpass or distance filter is applied.
+    ///
+    /// Requires [`InitFlags::CHANNEL_LOWPASS`] or [`InitFlags::CHANNEL_DISTANCE_FILTER`].
+    pub fn set_low_pass_gain(&self, gain: c_float) -> Result<()> {
+        self.require(InitFlags::CHANNEL_LOWPASS | InitFlags::CHANNEL_DISTANCE_FILTER)?;
+        self.control.set_low_pass_gain(gain)
+    }
+
+    /// Retrieves the gain of the dry signal when the built in lowpass or distance filter is applied.
+    ///
+    /// Requires [`InitFlags::CHANNEL_LOWPASS`] or [`InitFlags::CHANNEL_DISTANCE_FILTER`].
+    pub fn get_low_pass_gain(&self) -> Result<c_float> {
+        self.require(InitFlags::CHANNEL_LOWPASS | InitFlags::CHANNEL_DISTANCE_FILTER)?;
+        self.control.get_low_pass_gain()
+    }
+
+    /// Overrides the 3D distance filter, or sets it back to following the distance.
+    ///
+    /// Requires [`InitFlags::CHANNEL_DISTANCE_FILTER`], and [`Mode::D3`] on the [`ChannelControl`].
+    pub fn set_distance_filter(&self, filter: DistanceFilter) -> Result<()> {
+        self.require(InitFlags::CHANNEL_DISTANCE_FILTER)?;
+        match filter {
+            DistanceFilter::Automatic => self.control.set_3d_distance_filter(false, 1.0, 0.0),
+            DistanceFilter::Custom { level, center_freq } => {
+                self.control
+                    .set_3d_distance_filter(true, level, center_freq)
+            }
+        }
+    }
+
+    /// Retrieves the 3D distance filter setting.
+    ///
+    /// Requires [`InitFlags::CHANNEL_DISTANCE_FILTER`].
+    pub fn get_distance_filter(&self) -> Result<DistanceFilter> {
+        self.require(InitFlags::CHANNEL_DISTANCE_FILTER)?;
+        let (custom, level, center_freq) = self.control.get_3d_distance_filter()?;
+        Ok(if custom {
+            DistanceFilter::Custom { level, center_freq }
+        } else {
+            DistanceFilter::Automatic
+        })
+    }
+}
+
 impl ChannelControl {
+    /// The built in lowpass and distance filter, with checks that the [`System`] was initialized to support them.
+    pub fn filtering(&self) -> Filtering {
+        Filtering { control: *self }
+    }
+
     /// Sets the wet / send level for a particular reverb instance.
     ///
     /// Channels are automatically connected to all existing reverb instances due to the default wet level of 1.
@@ -40,7 +123,7 @@ impl ChannelControl {
 
     /// Sets the gain of the dry signal when built in lowpass / distance filtering is applied.
     ///
-    /// Requires the built in lowpass to be created with `FMOD_INIT_CHANNEL_LOWPASS` or `FMOD_INIT_CHANNEL_DISTANCEFILTER`.
+    /// Requires the built in lowpass to be created with `FMOD_INIT_CHANNEL_LOWPASS` or `FMOD_INIT_CHANNEL_DISTANCE_FILTER`.
     ///
     /// #### NOTE: Currently only supported for Channel, not `ChannelGroup`.
     pub fn set_low_pass_gain(&self, gain: c_float) -> Result<()> {
@@ -49,7 +132,7 @@ impl ChannelControl {
 
     /// Retrieves the gain of the dry signal when built in lowpass / distance filtering is applied.
     ///
-    /// Requires the built in lowpass to be created with `FMOD_INIT_CHANNEL_LOWPASS` or `FMOD_INIT_CHANNEL_DISTANCEFILTER`.
+    /// Requires the built in lowpass to be created with `FMOD_INIT_CHANNEL_LOWPASS` or `FMOD_INIT_CHANNEL_DISTANCE_FILTER`.
     ///
     /// #### NOTE: Currently only supported for Channel, not `ChannelGroup`.
     pub fn get_low_pass_gain(&self) -> Result<c_float> {
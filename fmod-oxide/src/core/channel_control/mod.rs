@@ -12,12 +12,20 @@ mod callback;
 mod dsp;
 mod filtering;
 mod general;
+mod panner;
 mod panning;
 mod playback;
 mod scheduling;
 mod spatialization;
+mod speed;
+mod state;
+mod sync;
 mod volume;
 pub use callback::{ChannelControlCallback, ChannelControlType};
+pub use filtering::{DistanceFilter, Filtering};
+pub use panner::Panner;
+pub use state::{ChannelControlState, DspParameterValue, DspState, MixMatrix};
+pub use sync::DspTime;
 
 #[cfg(doc)]
 use crate::{Channel, ChannelGroup};
@@ -0,0 +1,172 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::{c_float, c_int};
+
+use fmod_sys::*;
+
+use crate::{ChannelControl, MixMatrix, Speaker, SpeakerMode};
+use crate::{FmodResultExt, Result};
+
+/// Number of addressable speakers in [`Speaker`] (excluding [`Speaker::None`]).
+const SPEAKER_COUNT: usize = 12;
+
+/// A description of how a signal should be distributed across the output speakers.
+///
+/// Apply it with [`ChannelControl::apply_panner`], which picks the right FMOD API for the active [`SpeakerMode`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Panner {
+    /// Left/right pan, from -1.0 (left) to 1.0 (right).
+    Stereo(c_float),
+    /// Per speaker output levels, indexed by [`Speaker`].
+    Surround([c_float; SPEAKER_COUNT]),
+}
+
+impl Panner {
+    /// Creates a left/right panner, from -1.0 (left) to 1.0 (right).
+    pub const fn stereo(pan: c_float) -> Self {
+        Self::Stereo(pan)
+    }
+
+    /// Creates a panner with an explicit output level for each speaker.
+    ///
+    /// Speakers that are not specified are silent.
+    /// [`Speaker::None`] is ignored.
+    pub fn surround(levels: impl IntoIterator<Item = (Speaker, c_float)>) -> Self {
+        levels.into_iter().fold(
+            Self::Surround([0.0; SPEAKER_COUNT]),
+            |panner, (speaker, level)| panner.with_level(speaker, level),
+        )
+    }
+
+    /// Sets the level of a single speaker.
+    ///
+    /// A [`Panner::Stereo`] is converted into an equivalent [`Panner::Surround`] first.
+    #[must_use]
+    pub fn with_level(self, speaker: Speaker, level: c_float) -> Self {
+        let mut levels = self.speaker_levels();
+        if let Some(index) = speaker_index(speaker) {
+            levels[index] = level;
+        }
+        Self::Surround(levels)
+    }
+
+    /// Retrieves the level of a single speaker.
+    pub fn level(&self, speaker: Speaker) -> c_float {
+        speaker_index(speaker).map_or(0.0, |index| self.speaker_levels()[index])
+    }
+
+    /// Retrieves the output level of every speaker, indexed by [`Speaker`].
+    ///
+    /// A [`Panner::Stereo`] is resolved using constant power panning, the same way FMOD pans a mono input.
+    pub fn speaker_levels(&self) -> [c_float; SPEAKER_COUNT] {
+        match *self {
+            Self::Stereo(pan) => {
+                let angle = (pan.clamp(-1.0, 1.0) + 1.0) * std::f32::consts::FRAC_PI_4;
+                let mut levels = [0.0; SPEAKER_COUNT];
+                levels[Speaker::FrontLeft as usize] = angle.cos();
+                levels[Speaker::FrontRight as usize] = angle.sin();
+                levels
+            }
+            Self::Surround(levels) => levels,
+        }
+    }
+
+    /// Builds the mix matrix [`ChannelControl::apply_panner`] uses for height speakers and raw output.
+    ///
+    /// Each output speaker gets its level from [`Panner::speaker_levels`].
+    /// A [`Panner::Stereo`] with more than one input keeps the inputs isolated like [`ChannelControl::set_pan`] does, other panners feed every input to each speaker.
+    /// Both channel counts are clamped to `1..=MAX_CHANNEL_WIDTH`.
+    pub fn mix_matrix(&self, in_channels: c_int, out_channels: c_int) -> MixMatrix {
+        let in_channels = in_channels.clamp(1, FMOD_MAX_CHANNEL_WIDTH as c_int);
+        let out_channels = out_channels.clamp(1, FMOD_MAX_CHANNEL_WIDTH as c_int);
+
+        let levels = self.speaker_levels();
+        let mut data = vec![0.0; (in_channels * out_channels) as usize];
+        for (out, row) in data.chunks_exact_mut(in_channels as usize).enumerate() {
+            let level = levels.get(out).copied().unwrap_or_default();
+            match self {
+                // isolate the front left and right inputs, like set_pan does
+                Panner::Stereo(_) if in_channels > 1 => {
+                    if let Some(input) = row.get_mut(out) {
+                        *input = level;
+                    }
+                }
+                _ => row.fill(level),
+            }
+        }
+        MixMatrix {
+            in_channels,
+            out_channels,
+            data,
+        }
+    }
+}
+
+fn speaker_index(speaker: Speaker) -> Option<usize> {
+    match speaker {
+        Speaker::None => None,
+        speaker => Some(speaker as usize),
+    }
+}
+
+impl ChannelControl {
+    /// Applies a [`Panner`], dispatching to the API that is valid for the active [`SpeakerMode`].
+    ///
+    /// - [`Panner::Stereo`] uses [`ChannelControl::set_pan`].
+    /// - [`Panner::Surround`] uses [`ChannelControl::set_mix_levels_output`].
+    /// - [`SpeakerMode::SevenPointOneFour`] (height speakers) and [`SpeakerMode::Raw`] use a mix matrix instead,
+    ///   as the convenience functions cannot address height speakers and produce silence in raw mode.
+    ///
+    /// Like the functions it dispatches to, this overwrites any pan or mix levels previously set.
+    pub fn apply_panner(&self, panner: &Panner) -> Result<()> {
+        let (_, speaker_mode, raw_speakers) = self.get_system()?.get_software_format()?;
+        match (panner, speaker_mode) {
+            (_, SpeakerMode::Raw) => self.apply_panner_matrix(panner, raw_speakers),
+            (_, SpeakerMode::SevenPointOneFour) => {
+                self.apply_panner_matrix(panner, SPEAKER_COUNT as c_int)
+            }
+            (Panner::Stereo(pan), _) => self.set_pan(*pan),
+            (Panner::Surround(levels), _) => self.set_mix_levels_output(
+                levels[Speaker::FrontLeft as usize],
+                levels[Speaker::FrontRight as usize],
+                levels[Speaker::FrontCenter as usize],
+                levels[Speaker::LowFrequency as usize],
+                levels[Speaker::SurroundLeft as usize],
+                levels[Speaker::SurroundRight as usize],
+                levels[Speaker::BackLeft as usize],
+                levels[Speaker::BackRight as usize],
+            ),
+        }
+    }
+
+    fn apply_panner_matrix(&self, panner: &Panner, out_channels: c_int) -> Result<()> {
+        let mut current_out_channels = 0;
+        let mut in_channels = 0;
+        unsafe {
+            FMOD_ChannelControl_GetMixMatrix(
+                self.inner.as_ptr(),
+                std::ptr::null_mut(),
+                &raw mut current_out_channels,
+                &raw mut in_channels,
+                0,
+            )
+            .to_result()?;
+        }
+
+        let mut matrix = panner.mix_matrix(in_channels, out_channels);
+        unsafe {
+            FMOD_ChannelControl_SetMixMatrix(
+                self.inner.as_ptr(),
+                matrix.data.as_mut_ptr(),
+                matrix.out_channels,
+                matrix.in_channels,
+                matrix.in_channels,
+            )
+            .to_result()
+        }
+    }
+}
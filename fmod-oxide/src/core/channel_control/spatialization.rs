@@ -8,10 +8,10 @@ use std::{ffi::c_float, mem::MaybeUninit};
 
 use fmod_sys::*;
 
-use crate::{ChannelControl, Vector};
+use crate::{ChannelControl, InitFlags, Vector, check_init_flags};
 
 #[cfg(doc)]
-use crate::{Channel, ChannelGroup, Mode};
+use crate::{Channel, ChannelGroup, Error, Mode};
 use crate::{FmodResultExt, Result};
 
 impl ChannelControl {
@@ -194,7 +194,8 @@ impl ChannelControl {
     ///
     /// The `FMOD_3D` flag must be set on this object otherwise `FMOD_ERR_NEEDS3D` is returned.
     ///
-    /// The System must be initialized with `FMOD_INIT_CHANNEL_DISTANCEFILTER` for this feature to work.
+    /// The System must be initialized with [`InitFlags::CHANNEL_DISTANCE_FILTER`] for this feature to work.
+    /// With the `init-checks` feature, this returns [`Error::Initialization`] if it wasn't, since FMOD would otherwise ignore the call.
     ///
     /// #### NOTE: Currently only supported for [`Channel`], not [`ChannelGroup`].
     pub fn set_3d_distance_filter(
@@ -203,6 +204,7 @@ impl ChannelControl {
         custom_level: c_float,
         center_freq: c_float,
     ) -> Result<()> {
+        check_init_flags(|| self.get_system(), InitFlags::CHANNEL_DISTANCE_FILTER)?;
         unsafe {
             FMOD_ChannelControl_Set3DDistanceFilter(
                 self.inner.as_ptr(),
@@ -334,8 +336,8 @@ impl ChannelControl {
     ///
     /// There is a reverb path/send when `ChannelControl::setReverbProperties` has been used, reverbocclusion controls its attenuation.
     ///
-    /// If the System has been initialized with `FMOD_INIT_CHANNEL_DISTANCEFILTER` or
-    /// `FMOD_INIT_CHANNEL_LOWPASS` the directocclusion is applied as frequency filtering rather than volume attenuation.
+    /// If the System has been initialized with [`InitFlags::CHANNEL_DISTANCE_FILTER`] or
+    /// [`InitFlags::CHANNEL_LOWPASS`] the directocclusion is applied as frequency filtering rather than volume attenuation.
     pub fn set_3d_occlusion(&self, direct: c_float, reverb: c_float) -> Result<()> {
         unsafe {
             FMOD_ChannelControl_Set3DOcclusion(self.inner.as_ptr(), direct, reverb).to_result()
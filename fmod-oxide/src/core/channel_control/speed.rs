@@ -0,0 +1,60 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::{c_float, c_int};
+
+use crate::effects::pitch_shift;
+use crate::{ChannelControl, Dsp, DspType, Error, Result};
+
+impl ChannelControl {
+    /// Lowest speed the PitchShift DSP can fully compensate for.
+    pub const MIN_PITCH_PRESERVING_SPEED: c_float = 0.5;
+    /// Highest speed the PitchShift DSP can fully compensate for.
+    pub const MAX_PITCH_PRESERVING_SPEED: c_float = 2.0;
+
+    /// Finds the first [`DspType::PitchShift`] in the DSP chain.
+    pub(crate) fn find_pitch_shift(&self) -> Result<Option<(c_int, Dsp)>> {
+        for index in 0..self.get_dsp_count()? {
+            let dsp = self.get_dsp(index)?;
+            if dsp.get_type()? == DspType::PitchShift {
+                return Ok(Some((index, dsp)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Shifts the pitch by `1 / speed` to cancel out a playback rate change of `speed`.
+    ///
+    /// A PitchShift DSP is added to the head of the chain if there isn't one already,
+    /// and removed and released again once `speed` is back to 1.
+    pub(crate) fn compensate_pitch(&self, speed: c_float) -> Result<()> {
+        if !(Self::MIN_PITCH_PRESERVING_SPEED..=Self::MAX_PITCH_PRESERVING_SPEED).contains(&speed) {
+            return Err(Error::InvalidParam);
+        }
+
+        let existing = self.find_pitch_shift()?;
+        if (speed - 1.0).abs() < c_float::EPSILON {
+            if let Some((_, dsp)) = existing {
+                self.remove_dsp(dsp)?;
+                dsp.release()?;
+            }
+            return Ok(());
+        }
+
+        let dsp = match existing {
+            Some((_, dsp)) => dsp,
+            None => {
+                let dsp = self.get_system()?.create_dsp_by_type(DspType::PitchShift)?;
+                if let Err(e) = self.add_dsp(Self::DSP_HEAD, dsp) {
+                    let _ = dsp.release();
+                    return Err(e);
+                }
+                dsp
+            }
+        };
+        dsp.set_parameter(pitch_shift::Pitch, 1.0 / speed)
+    }
+}
@@ -0,0 +1,281 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::{c_float, c_int};
+
+use fmod_sys::*;
+
+use crate::{ChannelControl, Dsp, DspParameterType, DspType, Vector};
+use crate::{Error, FmodResultExt, Result};
+
+#[cfg(doc)]
+use crate::System;
+
+/// A snapshot of the mix state of a [`ChannelControl`], for persisting across save/load.
+///
+/// Capture with [`ChannelControlState::capture`] and restore with [`ChannelControlState::apply`].
+/// With the `serde` feature enabled this can be serialized alongside the rest of a save state.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChannelControlState {
+    /// Volume level.
+    pub volume: c_float,
+    /// Relative pitch / playback rate.
+    pub pitch: c_float,
+    /// Mute state.
+    pub mute: bool,
+    /// Pan and mix levels, as a mix matrix.
+    pub mix_matrix: Option<MixMatrix>,
+    /// 3D position and velocity, if the [`ChannelControl`] is 3D.
+    pub attributes_3d: Option<(Vector, Vector)>,
+    /// Parameters of the [`Dsp`] chain, in DSP index order.
+    pub dsps: Vec<DspState>,
+}
+
+/// A mix matrix mapping input channels (columns) to output speakers (rows).
+///
+/// FMOD's default conversions between speaker modes are available from [`System::get_default_mix_matrix`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MixMatrix {
+    /// Number of input channels (columns).
+    pub in_channels: c_int,
+    /// Number of output channels (rows).
+    pub out_channels: c_int,
+    /// Row-major matrix data, `out_channels * in_channels` long.
+    pub data: Vec<c_float>,
+}
+
+/// The parameters of a single [`Dsp`] in a [`ChannelControlState`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DspState {
+    /// Type of the [`Dsp`]. Parameters are only restored onto a [`Dsp`] of the same type.
+    #[cfg_attr(feature = "serde", serde(with = "dsp_type_serde"))]
+    pub kind: DspType,
+    /// Whether the [`Dsp`] is bypassed.
+    pub bypass: bool,
+    /// Parameter values by index. Data parameters are not captured.
+    pub parameters: Vec<(c_int, DspParameterValue)>,
+}
+
+/// The value of a non-data [`Dsp`] parameter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DspParameterValue {
+    /// Float parameter value.
+    Float(c_float),
+    /// Integer parameter value.
+    Int(c_int),
+    /// Boolean parameter value.
+    Bool(bool),
+}
+
+// DspType has deprecated variants, so serialize it as its FFI value instead of deriving.
+#[cfg(feature = "serde")]
+mod dsp_type_serde {
+    use super::*;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        kind: &DspType,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        FMOD_DSP_TYPE::from(*kind).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<DspType, D::Error> {
+        let raw = FMOD_DSP_TYPE::deserialize(deserializer)?;
+        DspType::try_from(raw).map_err(serde::de::Error::custom)
+    }
+}
+
+impl DspState {
+    /// Captures the type, bypass state and parameters of `dsp`.
+    ///
+    /// Returns [`None`] for [`DspType::Unknown`] (plugin) DSPs, whose parameter layout isn't known.
+    pub fn capture(dsp: Dsp) -> Result<Option<Self>> {
+        let kind = dsp.get_type()?;
+        if kind == DspType::Unknown {
+            return Ok(None);
+        }
+
+        let mut parameters = Vec::new();
+        for index in 0..dsp.get_parameter_count()? {
+            let value = match dsp.get_parameter_info(index)?.kind {
+                DspParameterType::Float { .. } => {
+                    DspParameterValue::Float(dsp.get_parameter(index)?)
+                }
+                DspParameterType::Int { .. } => DspParameterValue::Int(dsp.get_parameter(index)?),
+                DspParameterType::Bool { .. } => DspParameterValue::Bool(dsp.get_parameter(index)?),
+                DspParameterType::Data { .. } => continue,
+            };
+            parameters.push((index, value));
+        }
+
+        Ok(Some(Self {
+            kind,
+            bypass: dsp.get_bypass()?,
+            parameters,
+        }))
+    }
+
+    /// Restores the bypass state and parameters onto `dsp`.
+    ///
+    /// Returns [`Error::DspType`] if `dsp` is not of the same type this state was captured from.
+    pub fn apply(&self, dsp: Dsp) -> Result<()> {
+        if dsp.get_type()? != self.kind {
+            return Err(Error::DspType);
+        }
+        dsp.set_bypass(self.bypass)?;
+        for &(index, value) in &self.parameters {
+            match value {
+                DspParameterValue::Float(value) => dsp.set_parameter(index, value)?,
+                DspParameterValue::Int(value) => dsp.set_parameter(index, value)?,
+                DspParameterValue::Bool(value) => dsp.set_parameter(index, value)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ChannelControlState {
+    /// Captures the mix state of `control`.
+    ///
+    /// Only [`Dsp`]s of a known [`DspType`] are captured.
+    pub fn capture(control: &ChannelControl) -> Result<Self> {
+        let attributes_3d = match control.get_3d_attributes() {
+            Ok(attributes) => Some(attributes),
+            Err(Error::Needs3D) => None,
+            Err(e) => return Err(e),
+        };
+
+        let mut dsps = Vec::new();
+        for index in 0..control.get_dsp_count()? {
+            if let Some(state) = DspState::capture(control.get_dsp(index)?)? {
+                dsps.push(state);
+            }
+        }
+
+        Ok(Self {
+            volume: control.get_volume()?,
+            pitch: control.get_pitch()?,
+            mute: control.get_mute()?,
+            mix_matrix: MixMatrix::capture(control)?,
+            attributes_3d,
+            dsps,
+        })
+    }
+
+    /// Restores this state onto `control`.
+    ///
+    /// Captured [`Dsp`] state is matched up with the [`Dsp`]s of `control` in order, by type;
+    /// [`Dsp`]s with no matching captured state are left untouched.
+    pub fn apply(&self, control: &ChannelControl) -> Result<()> {
+        control.set_volume(self.volume)?;
+        control.set_pitch(self.pitch)?;
+        control.set_mute(self.mute)?;
+        if let Some(matrix) = &self.mix_matrix {
+            matrix.apply(control)?;
+        }
+        if let Some((position, velocity)) = self.attributes_3d {
+            control.set_3d_attributes(Some(position), Some(velocity))?;
+        }
+
+        let mut states = self.dsps.iter().peekable();
+        for index in 0..control.get_dsp_count()? {
+            let Some(state) = states.peek() else {
+                break;
+            };
+            let dsp = control.get_dsp(index)?;
+            if dsp.get_type()? == state.kind {
+                state.apply(dsp)?;
+                states.next();
+            }
+        }
+        Ok(())
+    }
+}
+
+impl MixMatrix {
+    /// The gain from input channel `input` to output speaker `output`, or [`None`] if either is out of range.
+    pub fn gain(&self, output: c_int, input: c_int) -> Option<c_float> {
+        let index = self.index(output, input)?;
+        self.data.get(index).copied()
+    }
+
+    /// Sets the gain from input channel `input` to output speaker `output`.
+    ///
+    /// Returns [`Error::InvalidParam`] if either is out of range.
+    pub fn set_gain(&mut self, output: c_int, input: c_int, gain: c_float) -> Result<()> {
+        let index = self.index(output, input).ok_or(Error::InvalidParam)?;
+        let value = self.data.get_mut(index).ok_or(Error::InvalidParam)?;
+        *value = gain;
+        Ok(())
+    }
+
+    fn index(&self, output: c_int, input: c_int) -> Option<usize> {
+        let in_range =
+            (0..self.out_channels).contains(&output) && (0..self.in_channels).contains(&input);
+        in_range.then(|| (output * self.in_channels + input) as usize)
+    }
+
+    /// Captures the current mix matrix of `control`, or [`None`] if it has none.
+    pub fn capture(control: &ChannelControl) -> Result<Option<Self>> {
+        let mut in_channels = 0;
+        let mut out_channels = 0;
+        unsafe {
+            FMOD_ChannelControl_GetMixMatrix(
+                control.inner.as_ptr(),
+                std::ptr::null_mut(),
+                &raw mut out_channels,
+                &raw mut in_channels,
+                0,
+            )
+            .to_result()?;
+        }
+        if in_channels == 0 || out_channels == 0 {
+            return Ok(None);
+        }
+
+        let mut data = vec![0.0; (in_channels * out_channels) as usize];
+        unsafe {
+            FMOD_ChannelControl_GetMixMatrix(
+                control.inner.as_ptr(),
+                data.as_mut_ptr(),
+                &raw mut out_channels,
+                &raw mut in_channels,
+                in_channels,
+            )
+            .to_result()?;
+        }
+        Ok(Some(Self {
+            in_channels,
+            out_channels,
+            data,
+        }))
+    }
+
+    /// Sets this as the mix matrix of `control`.
+    pub fn apply(&self, control: &ChannelControl) -> Result<()> {
+        if self.data.len() != (self.in_channels * self.out_channels) as usize {
+            return Err(Error::InvalidParam);
+        }
+        let mut data = self.data.clone();
+        unsafe {
+            FMOD_ChannelControl_SetMixMatrix(
+                control.inner.as_ptr(),
+                data.as_mut_ptr(),
+                self.out_channels,
+                self.in_channels,
+                self.in_channels,
+            )
+            .to_result()
+        }
+    }
+}
@@ -0,0 +1,69 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::{c_int, c_ulonglong};
+use std::time::Duration;
+
+use crate::{ChannelControl, Error, Result};
+
+#[cfg(doc)]
+use crate::{ChannelGroup, System};
+
+/// A point on the parent [`ChannelGroup`]'s DSP clock, used by [`ChannelControl::sync_start`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DspTime {
+    /// This many samples (at the mixer's sample rate) after the parent's current DSP clock.
+    Delay(c_ulonglong),
+    /// An absolute parent DSP clock value, such as one returned by an earlier [`ChannelControl::sync_start`].
+    Clock(c_ulonglong),
+}
+
+impl DspTime {
+    /// A [`DspTime::Delay`] of `duration`, at the mixer `sample_rate` (see [`System::get_software_format`]).
+    pub fn from_duration(duration: Duration, sample_rate: c_int) -> Self {
+        let samples = duration.as_nanos() * u128::from(sample_rate.unsigned_abs()) / 1_000_000_000;
+        DspTime::Delay(samples.try_into().unwrap_or(c_ulonglong::MAX))
+    }
+}
+
+impl ChannelControl {
+    /// Starts every control in `controls` on the same sample, at `at` on their parent's DSP clock.
+    ///
+    /// The controls should be paused, such as [`Channel`](crate::Channel)s played with `paused` set to `true`.
+    /// Each one gets its start delay set to the shared clock value and is then unpaused,
+    /// with the DSP engine locked throughout so no mix block can run partway through.
+    /// A [`DspTime::Delay`] should be at least one mix block long, or the start time may already have passed by the time the mixer sees it.
+    ///
+    /// The clock value is taken from the parent of the first control, so every control must have a parent running on the same clock,
+    /// which in practice means they share a parent [`ChannelGroup`].
+    /// Returns [`Error::InvalidParam`] if `controls` is empty or their parent clocks differ.
+    ///
+    /// Returns the parent clock value the controls start at.
+    pub fn sync_start(controls: &[ChannelControl], at: DspTime) -> Result<c_ulonglong> {
+        let Some(first) = controls.first() else {
+            return Err(Error::InvalidParam);
+        };
+        let system = first.get_system()?;
+        let _lock = system.lock_dsp()?;
+
+        let (_, parent_clock) = first.get_dsp_clock()?;
+        for control in &controls[1..] {
+            if control.get_dsp_clock()?.1 != parent_clock {
+                return Err(Error::InvalidParam);
+            }
+        }
+
+        let start = match at {
+            DspTime::Delay(delay) => parent_clock.saturating_add(delay),
+            DspTime::Clock(clock) => clock,
+        };
+        for control in controls {
+            control.set_delay(start, 0, false)?;
+            control.set_paused(false)?;
+        }
+        Ok(start)
+    }
+}
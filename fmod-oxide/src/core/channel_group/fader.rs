@@ -0,0 +1,75 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::c_int;
+
+use crate::{ChannelControl, ChannelGroup, Dsp, DspMeteringInfo, Result};
+
+#[cfg(doc)]
+use crate::InitFlags;
+
+/// Which side of a [`ChannelGroup`]'s fader to meter, see [`ChannelGroup::get_meter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MeterPoint {
+    /// The signal going into the fader, before volume, pan and mute are applied.
+    PreFader,
+    /// The signal coming out of the fader.
+    PostFader,
+}
+
+impl ChannelGroup {
+    /// The fader [`Dsp`], which applies this group's volume, pan and mute.
+    pub fn get_fader(&self) -> Result<Dsp> {
+        self.get_dsp(ChannelControl::DSP_FADER)
+    }
+
+    /// The current index of the fader in the DSP chain.
+    ///
+    /// Index 0 is closest to the output, so DSPs with a lower index are post-fader and DSPs with a higher index are pre-fader.
+    pub fn get_fader_index(&self) -> Result<c_int> {
+        self.get_dsp_index(self.get_fader()?)
+    }
+
+    /// Inserts `dsp` directly before the fader, so it processes the signal before volume, pan and mute are applied.
+    ///
+    /// Use this for effects that should not change with the group's volume, such as compressors feeding a send.
+    pub fn add_dsp_pre_fader(&self, dsp: Dsp) -> Result<()> {
+        // a higher index is further from the output, so the signal reaches it first
+        let index = self.get_fader_index()? + 1;
+        self.add_dsp(index, dsp)
+    }
+
+    /// Inserts `dsp` directly after the fader, so it processes the signal after volume, pan and mute are applied.
+    pub fn add_dsp_post_fader(&self, dsp: Dsp) -> Result<()> {
+        // inserting at the fader's index pushes the fader one step further from the output
+        let index = self.get_fader_index()?;
+        self.add_dsp(index, dsp)
+    }
+
+    /// Turns metering of the fader on or off at `point`, leaving the other point as it was.
+    ///
+    /// [`InitFlags::PROFILE_METER_ALL`] turns on metering everywhere, in which case this isn't needed.
+    pub fn set_meter_enabled(&self, point: MeterPoint, enabled: bool) -> Result<()> {
+        let fader = self.get_fader()?;
+        let (input, output) = fader.get_metering_enabled()?;
+        match point {
+            MeterPoint::PreFader => fader.set_metering_enabled(enabled, output),
+            MeterPoint::PostFader => fader.set_metering_enabled(input, enabled),
+        }
+    }
+
+    /// Retrieves the signal levels at `point`.
+    ///
+    /// Metering must have been enabled there with [`ChannelGroup::set_meter_enabled`] (or [`InitFlags::PROFILE_METER_ALL`]),
+    /// and the levels are only filled in once the mixer has run since.
+    pub fn get_meter(&self, point: MeterPoint) -> Result<DspMeteringInfo> {
+        let (input, output) = self.get_fader()?.get_metering_info()?;
+        Ok(match point {
+            MeterPoint::PreFader => input,
+            MeterPoint::PostFader => output,
+        })
+    }
+}
@@ -8,7 +8,7 @@ use fmod_sys::*;
 use lanyard::Utf8CString;
 use std::ffi::c_int;
 
-use crate::{ChannelGroup, get_string};
+use crate::{ChannelGroup, get_string, get_string_bounded};
 use crate::{FmodResultExt, Result};
 
 #[cfg(doc)]
@@ -28,6 +28,19 @@ impl ChannelGroup {
         }
     }
 
+    /// Retrieves the name set when the group was created, truncated to at most `max_len` bytes.
+    ///
+    /// Unlike [`ChannelGroup::get_name`] this never allocates more than `max_len + 1` bytes, and truncation is not an error.
+    pub fn get_name_with_max_len(&self, max_len: usize) -> Result<Utf8CString> {
+        get_string_bounded(max_len, |name| unsafe {
+            FMOD_ChannelGroup_GetName(
+                self.inner.as_ptr(),
+                name.as_mut_ptr().cast(),
+                name.len() as c_int,
+            )
+        })
+    }
+
     /// Frees the memory for the group.
     ///
     /// Any [`Channel`]s or [`ChannelGroup`]s feeding into this group are moved to the master [`ChannelGroup`].
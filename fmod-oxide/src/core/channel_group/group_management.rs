@@ -7,7 +7,7 @@
 use fmod_sys::*;
 use std::ffi::c_int;
 
-use crate::{ChannelGroup, DspConnection};
+use crate::{ChannelGroup, DspConnection, check_same_system};
 use crate::{FmodResultExt, Result};
 
 impl ChannelGroup {
@@ -17,6 +17,7 @@ impl ChannelGroup {
         group: ChannelGroup,
         propgate_dsp_clock: bool,
     ) -> Result<Option<DspConnection>> {
+        check_same_system(|| self.get_system(), || group.get_system())?;
         let mut dsp_connection = std::ptr::null_mut();
         unsafe {
             FMOD_ChannelGroup_AddGroup(
@@ -10,8 +10,14 @@ use std::{ops::Deref, ptr::NonNull};
 use crate::ChannelControl;
 
 mod channel_management;
+mod fader;
 mod general;
 mod group_management;
+mod stem_player;
+mod tree;
+pub use fader::MeterPoint;
+pub use stem_player::StemPlayer;
+pub use tree::ChannelGroupSpec;
 
 #[cfg(doc)]
 use crate::{Channel, System};
@@ -0,0 +1,284 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::{c_float, c_uint, c_ulonglong};
+use std::time::Duration;
+
+use lanyard::{Utf8CStr, c};
+
+use crate::{
+    Channel, ChannelControl, ChannelGroup, DspTime, Error, Mode, Owned, Result, Sound,
+    SoundBuilder, System, TimeUnit,
+};
+
+/// A volume ramp on the parent clock, from `from` at `start` to `to` at `end`.
+#[derive(Debug, Clone, Copy)]
+struct Ramp {
+    start: c_ulonglong,
+    from: c_float,
+    end: c_ulonglong,
+    to: c_float,
+}
+
+impl Ramp {
+    fn level_at(&self, clock: c_ulonglong) -> c_float {
+        if clock >= self.end || self.end <= self.start {
+            self.to
+        } else if clock <= self.start {
+            self.from
+        } else {
+            #[allow(clippy::cast_precision_loss)]
+            // ramps are short, so the precision lost doesn't matter
+            let t = (clock - self.start) as c_float / (self.end - self.start) as c_float;
+            self.from + (self.to - self.from) * t
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Stem {
+    sound: Owned<Sound>,
+    channel: Option<Channel>,
+    volume: c_float,
+    muted: bool,
+    ramp: Option<Ramp>,
+}
+
+impl Stem {
+    fn target_level(&self) -> c_float {
+        if self.muted { 0.0 } else { self.volume }
+    }
+}
+
+/// Plays several sounds of the same length in lockstep, such as the layers of an interactive music track.
+///
+/// Every stem plays into a [`ChannelGroup`] owned by the player, and all of them are started on the same sample with [`ChannelControl::sync_start`].
+/// Pausing pauses the group, so the stems can't drift apart,
+/// and seeking restarts every stem at the new position together.
+///
+/// Each stem has its own volume and mute state, which can be changed with a ramp to fade layers in and out.
+#[derive(Debug)]
+pub struct StemPlayer {
+    system: System,
+    group: ChannelGroup,
+    stems: Vec<Stem>,
+}
+
+impl StemPlayer {
+    /// Creates a player for `stems`, which it takes ownership of.
+    ///
+    /// The stems should all have the same length and sample rate, or they will drift apart or end at different times.
+    pub fn new(system: System, stems: Vec<Owned<Sound>>) -> Result<Self> {
+        let group = system.create_channel_group(c!("stems"))?;
+        let stems = stems
+            .into_iter()
+            .map(|sound| Stem {
+                sound,
+                channel: None,
+                volume: 1.0,
+                muted: false,
+                ramp: None,
+            })
+            .collect();
+        Ok(Self {
+            system,
+            group,
+            stems,
+        })
+    }
+
+    /// Opens each file in `paths` with `mode` as a stem.
+    ///
+    /// [`Mode::CREATE_STREAM`] is a good choice for long music stems.
+    /// If any file fails to open, the stems opened so far are released and the error is returned.
+    pub fn load(system: System, paths: &[&Utf8CStr], mode: Mode) -> Result<Self> {
+        let stems = paths
+            .iter()
+            .map(|path| {
+                SoundBuilder::open(path)
+                    .with_mode(mode)
+                    .build(system)
+                    .map(Owned::new)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Self::new(system, stems)
+    }
+
+    /// The [`ChannelGroup`] every stem plays into.
+    ///
+    /// Route it into another group with [`ChannelGroup::add_group`], or add effects to the whole track.
+    /// The group is released when the player is dropped.
+    pub fn group(&self) -> ChannelGroup {
+        self.group
+    }
+
+    /// Number of stems.
+    pub fn stem_count(&self) -> usize {
+        self.stems.len()
+    }
+
+    /// The sound of the stem at `index`.
+    pub fn stem_sound(&self, index: usize) -> Option<Sound> {
+        self.stems.get(index).map(|stem| *stem.sound)
+    }
+
+    /// Starts every stem from the beginning.
+    pub fn play(&mut self) -> Result<()> {
+        self.play_from(0)
+    }
+
+    /// Starts every stem from `position`, in milliseconds.
+    ///
+    /// Any stems already playing are stopped first.
+    /// If any stem fails to start, all of them are stopped again.
+    pub fn play_from(&mut self, position: c_uint) -> Result<()> {
+        self.stop()?;
+        let result = self.start(position);
+        if result.is_err() {
+            // don't leave a half started player behind, with some stems paused in the group
+            let _ = self.stop();
+        }
+        result
+    }
+
+    fn start(&mut self, position: c_uint) -> Result<()> {
+        let mut controls = Vec::with_capacity(self.stems.len());
+        for stem in &mut self.stems {
+            let channel = self
+                .system
+                .play_sound(*stem.sound, Some(self.group), true)?;
+            stem.channel = Some(channel);
+            if position > 0 {
+                channel.set_position(position, TimeUnit::MS)?;
+            }
+            controls.push(*channel);
+        }
+        if controls.is_empty() {
+            return Ok(());
+        }
+
+        // two mix blocks is enough for the start to still be in the future when the mixer sees it
+        let (buffer_length, _) = self.system.get_dsp_buffer_size()?;
+        let start = ChannelControl::sync_start(
+            &controls,
+            DspTime::Delay(c_ulonglong::from(buffer_length) * 2),
+        )?;
+        for stem in &mut self.stems {
+            let level = stem.target_level();
+            stem.ramp = Some(Ramp {
+                start,
+                from: level,
+                end: start,
+                to: level,
+            });
+            if let Some(channel) = stem.channel {
+                channel.add_fade_point(start, level)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Moves every stem to `position`, in milliseconds, keeping them in sync.
+    ///
+    /// This restarts the stems, so it works the same while paused.
+    pub fn seek(&mut self, position: c_uint) -> Result<()> {
+        self.play_from(position)
+    }
+
+    /// The playback position of the stems, in milliseconds.
+    pub fn position(&self) -> Result<c_uint> {
+        match self.stems.iter().find_map(|stem| stem.channel) {
+            Some(channel) => channel.get_position(TimeUnit::MS),
+            None => Ok(0),
+        }
+    }
+
+    /// Pauses or unpauses every stem together.
+    pub fn set_paused(&self, paused: bool) -> Result<()> {
+        self.group.set_paused(paused)
+    }
+
+    /// Whether the stems are paused.
+    pub fn get_paused(&self) -> Result<bool> {
+        self.group.get_paused()
+    }
+
+    /// Whether any stem is still playing.
+    pub fn is_playing(&self) -> Result<bool> {
+        self.group.is_playing()
+    }
+
+    /// Stops every stem.
+    pub fn stop(&mut self) -> Result<()> {
+        for stem in &mut self.stems {
+            stem.channel = None;
+            stem.ramp = None;
+        }
+        self.group.stop()
+    }
+
+    /// Fades the stem at `index` to `volume` over `ramp`.
+    ///
+    /// Returns [`Error::InvalidParam`] if there is no stem at `index`.
+    pub fn set_stem_volume(&mut self, index: usize, volume: c_float, ramp: Duration) -> Result<()> {
+        let stem = self.stems.get_mut(index).ok_or(Error::InvalidParam)?;
+        stem.volume = volume;
+        Self::apply_level(self.system, stem, ramp)
+    }
+
+    /// The volume of the stem at `index`, ignoring whether it is muted.
+    pub fn get_stem_volume(&self, index: usize) -> Option<c_float> {
+        self.stems.get(index).map(|stem| stem.volume)
+    }
+
+    /// Fades the stem at `index` out or back in over `ramp`.
+    ///
+    /// Returns [`Error::InvalidParam`] if there is no stem at `index`.
+    pub fn set_stem_muted(&mut self, index: usize, muted: bool, ramp: Duration) -> Result<()> {
+        let stem = self.stems.get_mut(index).ok_or(Error::InvalidParam)?;
+        stem.muted = muted;
+        Self::apply_level(self.system, stem, ramp)
+    }
+
+    /// Whether the stem at `index` is muted.
+    pub fn get_stem_muted(&self, index: usize) -> Option<bool> {
+        self.stems.get(index).map(|stem| stem.muted)
+    }
+
+    fn apply_level(system: System, stem: &mut Stem, ramp: Duration) -> Result<()> {
+        let Some(channel) = stem.channel else {
+            return Ok(());
+        };
+        let (sample_rate, ..) = system.get_software_format()?;
+        let (_, now) = channel.get_dsp_clock()?;
+        // start from wherever the previous ramp has got to, so changing direction mid-ramp doesn't jump
+        let from = stem
+            .ramp
+            .map_or_else(|| stem.target_level(), |ramp| ramp.level_at(now));
+        let (DspTime::Delay(length) | DspTime::Clock(length)) =
+            DspTime::from_duration(ramp, sample_rate);
+        let next = Ramp {
+            start: now,
+            from,
+            end: now.saturating_add(length),
+            to: stem.target_level(),
+        };
+
+        channel.remove_fade_points(now, c_ulonglong::MAX)?;
+        channel.add_fade_point(next.start, next.from)?;
+        channel.add_fade_point(next.end, next.to)?;
+        stem.ramp = Some(next);
+        Ok(())
+    }
+}
+
+impl Drop for StemPlayer {
+    fn drop(&mut self) {
+        let _ = self.group.stop();
+        // the group belongs to this player, copies from StemPlayer::group must not outlive it
+        let _ = unsafe { self.group.release() };
+    }
+}
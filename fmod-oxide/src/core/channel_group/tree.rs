@@ -0,0 +1,128 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use lanyard::{Utf8CStr, Utf8CString};
+use std::collections::{HashMap, HashSet};
+
+use crate::{ChannelGroup, Error, Result, System};
+
+/// A declarative description of a [`ChannelGroup`] and the groups nested under it.
+///
+/// Build the groups with [`System::create_channel_group_tree`].
+///
+/// ```ignore
+/// let spec = ChannelGroupSpec::new(c!("SFX"))
+///     .child(ChannelGroupSpec::new(c!("Footsteps")))
+///     .child(ChannelGroupSpec::new(c!("Weapons")).child(ChannelGroupSpec::new(c!("Explosions"))));
+/// let groups = system.create_channel_group_tree(&spec)?;
+/// let explosions = groups[c!("Explosions")];
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelGroupSpec {
+    /// Name of the group. Names must be unique within a tree.
+    pub name: Utf8CString,
+    /// Groups that feed into this group.
+    pub children: Vec<ChannelGroupSpec>,
+}
+
+impl ChannelGroupSpec {
+    /// Creates a spec for a group with no children.
+    pub fn new(name: &Utf8CStr) -> Self {
+        Self {
+            name: name.to_owned(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Adds a group that feeds into this one.
+    #[must_use]
+    pub fn child(mut self, child: ChannelGroupSpec) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    fn collect_names<'a>(&'a self, names: &mut HashSet<&'a Utf8CStr>) -> bool {
+        names.insert(&*self.name) && self.children.iter().all(|c| c.collect_names(names))
+    }
+}
+
+impl System {
+    /// Creates a hierarchy of [`ChannelGroup`]s from `spec`, returning every created group by name.
+    ///
+    /// The root group outputs to the master [`ChannelGroup`], and every other group outputs to its parent in `spec`.
+    ///
+    /// Returns [`Error::InvalidParam`] without creating anything if a name appears more than once.
+    /// If creating any group fails, the groups created so far are released again.
+    pub fn create_channel_group_tree(
+        &self,
+        spec: &ChannelGroupSpec,
+    ) -> Result<HashMap<Utf8CString, ChannelGroup>> {
+        let mut names = HashSet::new();
+        if !spec.collect_names(&mut names) {
+            return Err(Error::InvalidParam);
+        }
+
+        let mut created = HashMap::with_capacity(names.len());
+        match self.create_channel_group_subtree(spec, None, &mut created) {
+            Ok(()) => Ok(created),
+            Err(e) => {
+                if let Some(root) = created.get(&*spec.name) {
+                    // Safety: the groups were created above and haven't been handed out yet
+                    unsafe { root.release_recursive()? };
+                }
+                Err(e)
+            }
+        }
+    }
+
+    fn create_channel_group_subtree(
+        &self,
+        spec: &ChannelGroupSpec,
+        parent: Option<ChannelGroup>,
+        created: &mut HashMap<Utf8CString, ChannelGroup>,
+    ) -> Result<()> {
+        let group = self.create_channel_group(&spec.name)?;
+        let connected = match parent {
+            Some(parent) => parent.add_group(group, true).map(|_| ()),
+            None => Ok(()),
+        };
+        if let Err(e) = connected {
+            // the group isn't connected to the tree, so releasing the root wouldn't reach it
+            // Safety: the group was just created and hasn't been handed out yet
+            unsafe { group.release()? };
+            return Err(e);
+        }
+        created.insert(spec.name.clone(), group);
+        for child in &spec.children {
+            self.create_channel_group_subtree(child, Some(group), created)?;
+        }
+        Ok(())
+    }
+}
+
+impl ChannelGroup {
+    /// Releases this group and every [`ChannelGroup`] feeding into it, innermost first.
+    ///
+    /// Releasing children first means they are not moved to the master [`ChannelGroup`] before being released themselves,
+    /// unlike calling [`ChannelGroup::release`] on just this group.
+    /// [`crate::Channel`]s playing in any of the groups are still moved to the master [`ChannelGroup`].
+    ///
+    /// # Safety
+    ///
+    /// The same as [`ChannelGroup::release`]: neither this group nor any group under it may be used afterwards.
+    pub unsafe fn release_recursive(&self) -> Result<()> {
+        // releasing a child shifts the indices of the ones after it, so always take the last one
+        loop {
+            let count = self.get_group_count()?;
+            if count == 0 {
+                break;
+            }
+            let child = self.get_group(count - 1)?;
+            unsafe { child.release_recursive()? };
+        }
+        unsafe { self.release() }
+    }
+}
@@ -6,9 +6,15 @@
 
 use fmod_sys::*;
 use lanyard::Utf8CString;
+use std::borrow::Cow;
+use std::ffi::{CStr, c_char, c_int};
+use std::sync::Arc;
+use std::thread::ThreadId;
 
-use crate::{FmodResultExt, Result};
-use std::ffi::{c_char, c_int};
+use crate::{FmodResultExt, Result, System, SystemMap, panic_wrapper};
+
+#[cfg(doc)]
+use crate::SystemBuilder;
 
 /// Specify the destination of log output when using the logging version of FMOD.
 #[derive(PartialEq, Eq, Debug)]
@@ -106,3 +112,145 @@ pub fn initialize(flags: DebugFlags, mode: DebugMode) -> Result<()> {
         },
     }
 }
+
+struct LogContext {
+    tag: Arc<str>,
+    threads: Vec<ThreadId>,
+}
+
+static LOG_CONTEXTS: SystemMap<LogContext> = SystemMap::new();
+
+fn with_log_context(system: System, f: impl FnOnce(&mut LogContext)) {
+    LOG_CONTEXTS.update(system, |context| {
+        f(context.get_or_insert_with(|| LogContext {
+            tag: Arc::from(""),
+            threads: Vec::new(),
+        }));
+    });
+}
+
+pub(crate) fn set_log_tag(system: System, tag: &str) {
+    with_log_context(system, |context| context.tag = Arc::from(tag));
+}
+
+pub(crate) fn forget_log_context(system: System) {
+    LOG_CONTEXTS.remove(system);
+}
+
+impl System {
+    /// Attributes log messages emitted on the current thread to this [`System`].
+    ///
+    /// FMOD doesn't say which system a log message came from.
+    /// When only one tagged system exists every message is attributed to it,
+    /// but with several, messages are only attributed on threads bound with this function.
+    /// Call it from the thread(s) you call into this system from, as that is where FMOD logs API errors.
+    pub fn bind_log_thread(&self) {
+        let thread = std::thread::current().id();
+        LOG_CONTEXTS.with_entries(|contexts| {
+            for (_, context) in contexts {
+                context.threads.retain(|&t| t != thread);
+            }
+        });
+        with_log_context(*self, |context| context.threads.push(thread));
+    }
+}
+
+/// A log message from FMOD, passed to a [`DebugCallback`].
+#[derive(Debug, Clone)]
+pub struct DebugMessage<'a> {
+    /// The kind of message, such as [`DebugFlags::ERROR`].
+    pub flags: DebugFlags,
+    /// Source file the message was emitted from.
+    pub file: Cow<'a, str>,
+    /// Line number the message was emitted from.
+    pub line: c_int,
+    /// Function the message was emitted from.
+    pub function: Cow<'a, str>,
+    /// The message, without a trailing newline.
+    pub message: Cow<'a, str>,
+    /// The [`System`] the message is attributed to, if it could be determined.
+    ///
+    /// See [`System::bind_log_thread`].
+    pub system: Option<System>,
+    /// The tag of [`DebugMessage::system`], as set with [`SystemBuilder::log_tag`].
+    pub tag: Option<Arc<str>>,
+}
+
+impl std::fmt::Display for DebugMessage<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(tag) = self.tag.as_deref().filter(|t| !t.is_empty()) {
+            write!(f, "[{tag}] ")?;
+        }
+        if !self.function.is_empty() {
+            write!(f, "{}: ", self.function)?;
+        }
+        f.write_str(&self.message)
+    }
+}
+
+/// Trait for receiving FMOD's log messages through [`initialize_with_callback`].
+///
+/// No `self` parameter is passed to the callback!
+pub trait DebugCallback {
+    /// Called for every log message.
+    ///
+    /// This can be called from any thread, including FMOD's mixer thread, so it should not block.
+    fn log(message: &DebugMessage<'_>) -> Result<()>;
+}
+
+unsafe fn lossy_str<'a>(ptr: *const c_char) -> Cow<'a, str> {
+    if ptr.is_null() {
+        Cow::Borrowed("")
+    } else {
+        unsafe { CStr::from_ptr(ptr) }.to_string_lossy()
+    }
+}
+
+unsafe extern "C" fn debug_callback_impl<C: DebugCallback>(
+    flags: FMOD_DEBUG_FLAGS,
+    file: *const c_char,
+    line: c_int,
+    function: *const c_char,
+    message: *const c_char,
+) -> FMOD_RESULT {
+    panic_wrapper(|| {
+        let (system, tag) = LOG_CONTEXTS.with_entries(|contexts| {
+            let thread = std::thread::current().id();
+            let context = contexts
+                .iter()
+                .find(|(_, c)| c.threads.contains(&thread))
+                .or(match &*contexts {
+                    [only] => Some(only),
+                    _ => None,
+                });
+            match context {
+                Some((system, context)) => (
+                    Some(unsafe { System::from_ffi(*system as *mut FMOD_SYSTEM) }),
+                    Some(context.tag.clone()),
+                ),
+                None => (None, None),
+            }
+        });
+
+        let message = unsafe { lossy_str(message) };
+        let message = DebugMessage {
+            flags: flags.into(),
+            file: unsafe { lossy_str(file) },
+            line,
+            function: unsafe { lossy_str(function) },
+            message: match message {
+                Cow::Borrowed(m) => Cow::Borrowed(m.trim_end()),
+                Cow::Owned(m) => Cow::Owned(m.trim_end().to_string()),
+            },
+            system,
+            tag,
+        };
+        FMOD_RESULT::from_result(C::log(&message))
+    })
+}
+
+/// Like [`initialize`] with [`DebugMode::Callback`], but with messages decoded into a [`DebugMessage`]
+/// that says which [`System`] they came from.
+pub fn initialize_with_callback<C: DebugCallback>(flags: DebugFlags) -> Result<()> {
+    initialize(flags, DebugMode::Callback(debug_callback_impl::<C>))
+}
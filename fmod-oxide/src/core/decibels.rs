@@ -0,0 +1,144 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::c_float;
+
+use crate::{ChannelControl, Error, Result};
+
+#[cfg(feature = "studio")]
+use crate::studio;
+
+/// A volume level in decibels, relative to a linear volume of 1.
+///
+/// FMOD takes linear volumes, where 1 is the original level and 0 is silence.
+/// Options menus usually show decibels instead, since those match how loud things sound.
+/// Silence is negative infinity, which converts to and from a linear volume of 0.
+/// Formats like JSON can't represent infinities, so with the `serde` feature silence is serialized as a missing value (`null`).
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Decibels(pub c_float);
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Decibels {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        if self.is_silent() {
+            serializer.serialize_none()
+        } else {
+            serializer.serialize_some(&self.0)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Decibels {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        let level: Option<c_float> = serde::Deserialize::deserialize(deserializer)?;
+        Ok(level.map_or(Self::SILENCE, Self))
+    }
+}
+
+impl Decibels {
+    /// Silence, a linear volume of 0.
+    pub const SILENCE: Self = Self(c_float::NEG_INFINITY);
+    /// The original level, a linear volume of 1.
+    pub const UNITY: Self = Self(0.0);
+
+    /// Converts a linear volume to decibels.
+    ///
+    /// Volumes of 0 and below are [`Decibels::SILENCE`].
+    pub fn from_linear(volume: c_float) -> Self {
+        if volume > 0.0 {
+            Self(20.0 * volume.log10())
+        } else {
+            Self::SILENCE
+        }
+    }
+
+    /// Converts to a linear volume, with [`Decibels::SILENCE`] (or anything else at negative infinity) becoming 0.
+    pub fn to_linear(self) -> c_float {
+        if self.is_silent() {
+            0.0
+        } else {
+            10.0_f32.powf(self.0 / 20.0)
+        }
+    }
+
+    /// Whether this is silence.
+    pub fn is_silent(self) -> bool {
+        self.0.is_infinite() && self.0.is_sign_negative()
+    }
+
+    // NaN and positive infinity have no linear volume FMOD would accept
+    pub(crate) fn checked_linear(self) -> Result<c_float> {
+        if self.0.is_nan() || (self.0.is_infinite() && self.0.is_sign_positive()) {
+            return Err(Error::InvalidParam);
+        }
+        Ok(self.to_linear())
+    }
+}
+
+impl ChannelControl {
+    /// Sets the volume level in decibels.
+    ///
+    /// [`Decibels::SILENCE`] sets a volume of 0, which unlike [`ChannelControl::set_mute`] is forgotten by the next volume change.
+    /// Returns [`Error::InvalidParam`] for NaN and positive infinity.
+    pub fn set_volume_db(&self, volume: Decibels) -> Result<()> {
+        self.set_volume(volume.checked_linear()?)
+    }
+
+    /// Retrieves the volume level in decibels, [`Decibels::SILENCE`] if it is 0.
+    ///
+    /// Like [`ChannelControl::get_volume`] this doesn't take the mute state into account.
+    pub fn get_volume_db(&self) -> Result<Decibels> {
+        self.get_volume().map(Decibels::from_linear)
+    }
+}
+
+#[cfg(feature = "studio")]
+impl studio::Bus {
+    /// Sets the volume level in decibels.
+    ///
+    /// See [`studio::Bus::set_volume`] and [`ChannelControl::set_volume_db`].
+    pub fn set_volume_db(&self, volume: Decibels) -> Result<()> {
+        self.set_volume(volume.checked_linear()?)
+    }
+
+    /// Retrieves the volume level, and the final volume level, in decibels.
+    ///
+    /// See [`studio::Bus::get_volume`].
+    pub fn get_volume_db(&self) -> Result<(Decibels, Decibels)> {
+        let (volume, final_volume) = self.get_volume()?;
+        Ok((
+            Decibels::from_linear(volume),
+            Decibels::from_linear(final_volume),
+        ))
+    }
+}
+
+#[cfg(feature = "studio")]
+impl studio::Vca {
+    /// Sets the volume level in decibels.
+    ///
+    /// See [`studio::Vca::set_volume`] and [`ChannelControl::set_volume_db`].
+    pub fn set_volume_db(&self, volume: Decibels) -> Result<()> {
+        self.set_volume(volume.checked_linear()?)
+    }
+
+    /// Retrieves the volume level, and the final volume level, in decibels.
+    ///
+    /// See [`studio::Vca::get_volume`].
+    pub fn get_volume_db(&self) -> Result<(Decibels, Decibels)> {
+        let (volume, final_volume) = self.get_volume()?;
+        Ok((
+            Decibels::from_linear(volume),
+            Decibels::from_linear(final_volume),
+        ))
+    }
+}
@@ -5,22 +5,150 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use fmod_sys::*;
-use std::ffi::c_void;
+use std::ffi::{c_int, c_uint, c_void};
+use std::sync::{Mutex, MutexGuard, PoisonError};
 
 use crate::panic_wrapper;
 
 use super::Dsp;
 use crate::{FmodResultExt, Result};
 
+/// A data parameter that a [`Dsp`] no longer needs, converted from what [`DspCallback::data_parameter_release`] receives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DataParameterInfo {
+    /// The data that was passed to the parameter.
+    pub data: *mut c_void,
+    /// Length of `data` in bytes.
+    pub length: c_uint,
+    /// Index of the parameter.
+    pub index: c_int,
+}
+
+impl From<FMOD_DSP_DATA_PARAMETER_INFO> for DataParameterInfo {
+    fn from(value: FMOD_DSP_DATA_PARAMETER_INFO) -> Self {
+        DataParameterInfo {
+            data: value.data,
+            length: value.length,
+            index: value.index,
+        }
+    }
+}
+
 /// Trait for this particular FMOD callback.
 ///
 /// No `self` parameter is passed to the callback!
+///
+/// Callbacks that are part of a DSP plug-in's description (system register/deregister and mix notifications)
+/// are set through [`DspSystemCallback`] instead.
 pub trait DspCallback {
     /// Called when a DSP's data parameter can be released.
+    ///
+    /// If the [`Dsp`] has data set with [`Dsp::set_parameter_data_owned`],
+    /// pass `info` on to [`Dsp::release_owned_parameter_data`] to free it.
     // I'm not sure how FMOD_DSP_DATA_PARAMETER_INFO works we'll just pass the raw value
     fn data_parameter_release(dsp: Dsp, info: FMOD_DSP_DATA_PARAMETER_INFO) -> Result<()>;
 }
 
+/// A [`DspCallback`] that only frees data set with [`Dsp::set_parameter_data_owned`].
+///
+/// [`Dsp::set_parameter_data_owned`] installs this for you.
+#[derive(Debug, Clone, Copy)]
+pub struct OwnedDataRelease;
+
+impl DspCallback for OwnedDataRelease {
+    fn data_parameter_release(dsp: Dsp, info: FMOD_DSP_DATA_PARAMETER_INFO) -> Result<()> {
+        dsp.release_owned_parameter_data(info.into());
+        Ok(())
+    }
+}
+
+/// When in the mix [`DspSystemCallback::mix`] is called.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MixStage {
+    /// Before the mixer runs.
+    PreMix,
+    /// After the mix has run.
+    PostMix,
+    /// After the mixer's clocks are calculated, but before the main mix runs.
+    MidMix,
+}
+
+/// Callbacks a DSP plug-in receives for the [`crate::System`] it's registered with, rather than for a single [`Dsp`].
+///
+/// These are part of the plug-in's [`FMOD_DSP_DESCRIPTION`], set them with [`DspSystemCallback::install`].
+/// Only the system object and the function table of `state` are valid in these callbacks.
+///
+/// No `self` parameter is passed to the callback!
+#[allow(unused_variables)]
+pub trait DspSystemCallback {
+    /// Called when the plug-in is registered with a system.
+    fn register(state: *mut FMOD_DSP_STATE) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called when the plug-in is unregistered from a system.
+    fn deregister(state: *mut FMOD_DSP_STATE) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called by the mixer once per mix, at every [`MixStage`].
+    fn mix(state: *mut FMOD_DSP_STATE, stage: MixStage) -> Result<()> {
+        Ok(())
+    }
+
+    /// Sets the system callbacks of `description` to forward to this type.
+    fn install(description: &mut FMOD_DSP_DESCRIPTION)
+    where
+        Self: Sized,
+    {
+        description.sys_register = Some(sys_register_impl::<Self>);
+        description.sys_deregister = Some(sys_deregister_impl::<Self>);
+        description.sys_mix = Some(sys_mix_impl::<Self>);
+    }
+}
+
+unsafe extern "C" fn sys_register_impl<C: DspSystemCallback>(
+    state: *mut FMOD_DSP_STATE,
+) -> FMOD_RESULT {
+    panic_wrapper(|| FMOD_RESULT::from_result(C::register(state)))
+}
+
+unsafe extern "C" fn sys_deregister_impl<C: DspSystemCallback>(
+    state: *mut FMOD_DSP_STATE,
+) -> FMOD_RESULT {
+    panic_wrapper(|| FMOD_RESULT::from_result(C::deregister(state)))
+}
+
+unsafe extern "C" fn sys_mix_impl<C: DspSystemCallback>(
+    state: *mut FMOD_DSP_STATE,
+    stage: c_int,
+) -> FMOD_RESULT {
+    panic_wrapper(|| {
+        let stage = match stage {
+            0 => MixStage::PreMix,
+            1 => MixStage::PostMix,
+            2 => MixStage::MidMix,
+            _ => {
+                eprintln!("warning: unknown dsp mix stage {stage}");
+                return FMOD_RESULT::FMOD_OK;
+            }
+        };
+        FMOD_RESULT::from_result(C::mix(state, stage))
+    })
+}
+
+struct OwnedData {
+    dsp: usize,
+    data: Box<[u8]>,
+}
+
+static OWNED_DATA: Mutex<Vec<OwnedData>> = Mutex::new(Vec::new());
+
+fn owned_data() -> MutexGuard<'static, Vec<OwnedData>> {
+    // the list is only ever pushed to or removed from, so a panic can't leave it inconsistent
+    OWNED_DATA.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
 unsafe extern "C" fn callback_impl<C: DspCallback>(
     dsp: *mut FMOD_DSP,
     kind: FMOD_DSP_CALLBACK_TYPE,
@@ -49,4 +177,47 @@ impl Dsp {
     pub fn set_callback<C: DspCallback>(&self) -> Result<()> {
         unsafe { FMOD_DSP_SetCallback(self.inner.as_ptr(), Some(callback_impl::<C>)).to_result() }
     }
+
+    /// Sets a data parameter, keeping `data` alive until the [`Dsp`] no longer needs it.
+    ///
+    /// Some [`Dsp`]s (such as plug-ins that stream from the data) hold on to the pointer passed to a data parameter,
+    /// and signal when it can be freed with [`DspCallback::data_parameter_release`].
+    /// This copies nothing: `data` is kept by the crate and freed in that callback, or when the [`Dsp`] is released.
+    ///
+    /// This installs [`OwnedDataRelease`] as the [`Dsp`]'s callback.
+    /// A custom [`DspCallback`] set afterwards must pass what it receives on to [`Dsp::release_owned_parameter_data`].
+    pub fn set_parameter_data_owned(&self, index: c_int, data: Vec<u8>) -> Result<()> {
+        let data = data.into_boxed_slice();
+        self.set_callback::<OwnedDataRelease>()?;
+        unsafe {
+            FMOD_DSP_SetParameterData(
+                self.inner.as_ptr(),
+                index,
+                data.as_ptr().cast_mut().cast(),
+                data.len() as c_uint,
+            )
+            .to_result()?;
+        }
+        owned_data().push(OwnedData {
+            dsp: self.inner.as_ptr() as usize,
+            data,
+        });
+        Ok(())
+    }
+
+    /// Frees the data described by `info` if it was set with [`Dsp::set_parameter_data_owned`], returning whether it was.
+    pub fn release_owned_parameter_data(&self, info: DataParameterInfo) -> bool {
+        let key = self.inner.as_ptr() as usize;
+        let mut owned = owned_data();
+        let position = owned
+            .iter()
+            .position(|o| o.dsp == key && o.data.as_ptr().cast_mut().cast::<c_void>() == info.data);
+        position.map(|index| owned.swap_remove(index)).is_some()
+    }
+
+    /// Frees every piece of data set on this [`Dsp`] with [`Dsp::set_parameter_data_owned`].
+    pub(crate) fn forget_owned_parameter_data(&self) {
+        let key = self.inner.as_ptr() as usize;
+        owned_data().retain(|o| o.dsp != key);
+    }
 }
@@ -7,7 +7,7 @@
 use fmod_sys::*;
 use std::ffi::c_int;
 
-use crate::{Dsp, DspConnection, DspConnectionType};
+use crate::{Dsp, DspConnection, DspConnectionType, check_same_system};
 use crate::{FmodResultExt, Result};
 
 #[cfg(doc)]
@@ -20,6 +20,7 @@ impl Dsp {
     ///
     /// The returned [`DspConnection`] will remain valid until the units are disconnected.
     pub fn add_input(&self, input: Dsp, kind: DspConnectionType) -> Result<DspConnection> {
+        check_same_system(|| self.get_system(), || input.get_system())?;
         let mut connection = std::ptr::null_mut();
         unsafe {
             FMOD_DSP_AddInput(
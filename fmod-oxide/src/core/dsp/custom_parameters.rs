@@ -0,0 +1,297 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::{CStr, c_char, c_float, c_int};
+
+use fmod_sys::*;
+
+use crate::{FmodResultExt, Result, panic_wrapper};
+
+#[cfg(doc)]
+use crate::System;
+
+/// The parameters of a custom DSP, stored as plain fields of a struct.
+///
+/// Usually derived with `#[derive(DspParameters)]` (with the `derive` feature enabled), which also adds an index constant per parameter:
+///
+/// ```ignore
+/// #[derive(DspParameters)]
+/// struct Gain {
+///     #[dsp(name = "Gain", label = "dB", min = -80.0, max = 10.0, default = 0.0)]
+///     gain: f32,
+///     #[dsp(name = "Invert", default = false)]
+///     invert: bool,
+/// }
+///
+/// assert_eq!(Gain::GAIN, 0);
+/// ```
+///
+/// Plug the parameters into an [`FMOD_DSP_DESCRIPTION`] with [`DspParameterTable`].
+pub trait DspParameters {
+    /// Number of parameters.
+    const COUNT: c_int;
+
+    /// Descriptions of every parameter, in index order. Build these with [`DspParameterTable::float_desc`] and friends.
+    fn descriptions() -> Vec<FMOD_DSP_PARAMETER_DESC>;
+
+    /// Sets the float parameter at `index`, clamped to its range.
+    fn set_float(&mut self, index: c_int, value: c_float) -> Result<()>;
+    /// Retrieves the float parameter at `index`.
+    fn get_float(&self, index: c_int) -> Result<c_float>;
+    /// Sets the int parameter at `index`, clamped to its range.
+    fn set_int(&mut self, index: c_int, value: c_int) -> Result<()>;
+    /// Retrieves the int parameter at `index`.
+    fn get_int(&self, index: c_int) -> Result<c_int>;
+    /// Sets the bool parameter at `index`.
+    fn set_bool(&mut self, index: c_int, value: bool) -> Result<()>;
+    /// Retrieves the bool parameter at `index`.
+    fn get_bool(&self, index: c_int) -> Result<bool>;
+}
+
+/// The [`FMOD_DSP_PARAMETER_DESC`] table for a [`DspParameters`] type, in the layout an [`FMOD_DSP_DESCRIPTION`] expects.
+///
+/// FMOD keeps pointers into the table, so it must outlive every [`crate::Dsp`] created from the description.
+#[derive(Debug)]
+pub struct DspParameterTable {
+    descriptions: Box<[FMOD_DSP_PARAMETER_DESC]>,
+    pointers: Box<[*mut FMOD_DSP_PARAMETER_DESC]>,
+}
+
+#[cfg(not(feature = "thread-unsafe"))]
+unsafe impl Send for DspParameterTable {}
+#[cfg(not(feature = "thread-unsafe"))]
+unsafe impl Sync for DspParameterTable {}
+
+// copies as much of `text` as fits, always leaving room for the nul terminator
+// and never cutting a UTF-8 character in half, which tools would show as garbage
+fn copy_name(text: &CStr) -> [c_char; 16] {
+    let bytes = text.to_bytes();
+    let mut len = bytes.len().min(15);
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        while !text.is_char_boundary(len) {
+            len -= 1;
+        }
+    }
+    let mut buffer = [0; 16];
+    for (dst, &src) in buffer.iter_mut().zip(&bytes[..len]) {
+        *dst = src as c_char;
+    }
+    buffer
+}
+
+fn empty_desc(
+    kind: FMOD_DSP_PARAMETER_TYPE,
+    name: &CStr,
+    label: &CStr,
+    description: &'static CStr,
+) -> FMOD_DSP_PARAMETER_DESC {
+    // every field is either a number or a nullable pointer, so zeroed is a valid starting point
+    let mut desc: FMOD_DSP_PARAMETER_DESC = unsafe { std::mem::zeroed() };
+    desc.type_ = kind;
+    desc.name = copy_name(name);
+    desc.label = copy_name(label);
+    desc.description = description.as_ptr();
+    desc
+}
+
+impl DspParameterTable {
+    /// Builds the table for `P`.
+    pub fn new<P: DspParameters>() -> Self {
+        let mut descriptions = P::descriptions().into_boxed_slice();
+        let pointers = descriptions
+            .iter_mut()
+            .map(std::ptr::from_mut)
+            .collect::<Box<[_]>>();
+        Self {
+            descriptions,
+            pointers,
+        }
+    }
+
+    /// Number of parameters in the table.
+    pub fn len(&self) -> usize {
+        self.descriptions.len()
+    }
+
+    /// Whether the table has no parameters.
+    pub fn is_empty(&self) -> bool {
+        self.descriptions.is_empty()
+    }
+
+    /// Points `description` at this table, and sets its parameter callbacks to forward to `P`.
+    ///
+    /// # Safety
+    ///
+    /// This table must have been created for `P`, and must outlive every [`crate::Dsp`] created from `description`
+    /// (see [`System::create_dsp`]).
+    ///
+    /// The `plugindata` of every DSP created from `description` must point to a valid `P` (usually set in the `create` callback),
+    /// and nothing else may access it while FMOD calls the parameter callbacks.
+    pub unsafe fn install<P: DspParameters>(&mut self, description: &mut FMOD_DSP_DESCRIPTION) {
+        description.numparameters = self.pointers.len() as c_int;
+        description.paramdesc = self.pointers.as_mut_ptr();
+        description.setparameterfloat = Some(set_float::<P>);
+        description.getparameterfloat = Some(get_float::<P>);
+        description.setparameterint = Some(set_int::<P>);
+        description.getparameterint = Some(get_int::<P>);
+        description.setparameterbool = Some(set_bool::<P>);
+        description.getparameterbool = Some(get_bool::<P>);
+    }
+
+    /// Describes a float parameter.
+    ///
+    /// `name` and `label` are cut off at 15 bytes.
+    pub fn float_desc(
+        name: &CStr,
+        label: &CStr,
+        description: &'static CStr,
+        min: c_float,
+        max: c_float,
+        default: c_float,
+    ) -> FMOD_DSP_PARAMETER_DESC {
+        let mut desc = empty_desc(FMOD_DSP_PARAMETER_TYPE_FLOAT, name, label, description);
+        desc.__bindgen_anon_1.floatdesc = FMOD_DSP_PARAMETER_DESC_FLOAT {
+            min,
+            max,
+            defaultval: default,
+            mapping: FMOD_DSP_PARAMETER_FLOAT_MAPPING {
+                type_: FMOD_DSP_PARAMETER_FLOAT_MAPPING_TYPE_AUTO,
+                ..unsafe { std::mem::zeroed() }
+            },
+        };
+        desc
+    }
+
+    /// Describes an int parameter.
+    ///
+    /// `name` and `label` are cut off at 15 bytes.
+    pub fn int_desc(
+        name: &CStr,
+        label: &CStr,
+        description: &'static CStr,
+        min: c_int,
+        max: c_int,
+        default: c_int,
+    ) -> FMOD_DSP_PARAMETER_DESC {
+        let mut desc = empty_desc(FMOD_DSP_PARAMETER_TYPE_INT, name, label, description);
+        desc.__bindgen_anon_1.intdesc = FMOD_DSP_PARAMETER_DESC_INT {
+            min,
+            max,
+            defaultval: default,
+            goestoinf: FMOD_BOOL::FALSE,
+            valuenames: std::ptr::null(),
+        };
+        desc
+    }
+
+    /// Describes a bool parameter.
+    ///
+    /// `name` and `label` are cut off at 15 bytes.
+    pub fn bool_desc(
+        name: &CStr,
+        label: &CStr,
+        description: &'static CStr,
+        default: bool,
+    ) -> FMOD_DSP_PARAMETER_DESC {
+        let mut desc = empty_desc(FMOD_DSP_PARAMETER_TYPE_BOOL, name, label, description);
+        desc.__bindgen_anon_1.booldesc = FMOD_DSP_PARAMETER_DESC_BOOL {
+            defaultval: default.into(),
+            valuenames: std::ptr::null(),
+        };
+        desc
+    }
+}
+
+unsafe fn parameters<'a, P>(state: *mut FMOD_DSP_STATE) -> &'a mut P {
+    unsafe { &mut *(*state).plugindata.cast::<P>() }
+}
+
+unsafe fn clear_value_string(value_string: *mut c_char) {
+    if !value_string.is_null() {
+        unsafe { *value_string = 0 };
+    }
+}
+
+unsafe extern "C" fn set_float<P: DspParameters>(
+    state: *mut FMOD_DSP_STATE,
+    index: c_int,
+    value: c_float,
+) -> FMOD_RESULT {
+    panic_wrapper(|| {
+        FMOD_RESULT::from_result(unsafe { parameters::<P>(state) }.set_float(index, value))
+    })
+}
+
+unsafe extern "C" fn get_float<P: DspParameters>(
+    state: *mut FMOD_DSP_STATE,
+    index: c_int,
+    value: *mut c_float,
+    value_string: *mut c_char,
+) -> FMOD_RESULT {
+    panic_wrapper(|| {
+        let result = unsafe { parameters::<P>(state) }
+            .get_float(index)
+            .map(|v| unsafe {
+                *value = v;
+                clear_value_string(value_string);
+            });
+        FMOD_RESULT::from_result(result)
+    })
+}
+
+unsafe extern "C" fn set_int<P: DspParameters>(
+    state: *mut FMOD_DSP_STATE,
+    index: c_int,
+    value: c_int,
+) -> FMOD_RESULT {
+    panic_wrapper(|| {
+        FMOD_RESULT::from_result(unsafe { parameters::<P>(state) }.set_int(index, value))
+    })
+}
+
+unsafe extern "C" fn get_int<P: DspParameters>(
+    state: *mut FMOD_DSP_STATE,
+    index: c_int,
+    value: *mut c_int,
+    value_string: *mut c_char,
+) -> FMOD_RESULT {
+    panic_wrapper(|| {
+        let result = unsafe { parameters::<P>(state) }
+            .get_int(index)
+            .map(|v| unsafe {
+                *value = v;
+                clear_value_string(value_string);
+            });
+        FMOD_RESULT::from_result(result)
+    })
+}
+
+unsafe extern "C" fn set_bool<P: DspParameters>(
+    state: *mut FMOD_DSP_STATE,
+    index: c_int,
+    value: FMOD_BOOL,
+) -> FMOD_RESULT {
+    panic_wrapper(|| {
+        FMOD_RESULT::from_result(unsafe { parameters::<P>(state) }.set_bool(index, value.into()))
+    })
+}
+
+unsafe extern "C" fn get_bool<P: DspParameters>(
+    state: *mut FMOD_DSP_STATE,
+    index: c_int,
+    value: *mut FMOD_BOOL,
+    value_string: *mut c_char,
+) -> FMOD_RESULT {
+    panic_wrapper(|| {
+        let result = unsafe { parameters::<P>(state) }
+            .get_bool(index)
+            .map(|v| unsafe {
+                *value = v.into();
+                clear_value_string(value_string);
+            });
+        FMOD_RESULT::from_result(result)
+    })
+}
@@ -181,7 +181,7 @@ impl Fft {
 impl ReadableParameter for Fft {
     fn get_parameter(dsp: Dsp, index: c_int) -> Result<Self> {
         let desc = dsp.get_raw_parameter_info(index)?;
-        if !parameter_is(&desc, DspParameterDataType::Attributes3D) {
+        if !parameter_is(&desc, DspParameterDataType::FFT) {
             return Err(Error::InvalidParam);
         }
         let mut raw = MaybeUninit::<FMOD_DSP_PARAMETER_FFT>::uninit();
@@ -8,7 +8,7 @@ use fmod_sys::*;
 use lanyard::{Utf8CStr, Utf8CString};
 use std::ffi::{c_char, c_int, c_uint, c_void};
 
-use crate::{Dsp, DspType, System};
+use crate::{Dsp, DspType, InitFlags, System, check_init_flags};
 use crate::{FmodResultExt, Result};
 
 #[derive(Debug)]
@@ -52,7 +52,9 @@ impl Dsp {
     /// If [`Dsp`] is not removed from the network with `ChannelControl::removeDSP` after being added with `ChannelControl::addDSP`,
     /// it will not release and will instead return [`FMOD_RESULT::FMOD_ERR_DSP_INUSE`].
     pub fn release(&self) -> Result<()> {
-        unsafe { FMOD_DSP_Release(self.inner.as_ptr()).to_result() }
+        unsafe { FMOD_DSP_Release(self.inner.as_ptr()).to_result()? };
+        self.forget_owned_parameter_data();
+        Ok(())
     }
 
     /// Retrieves the pre-defined type of a FMOD registered [`Dsp`] unit.
@@ -96,8 +98,10 @@ impl Dsp {
 
     /// Retrieves statistics on the mixer thread CPU usage for this unit.
     ///
-    /// [`crate::InitFlags::PROFILE_ENABLE`] with [`crate::SystemBuilder::new`] is required to call this function.
+    /// [`InitFlags::PROFILE_ENABLE`] with [`crate::SystemBuilder::build`] is required to call this function.
+    /// With the `init-checks` feature, this returns [`crate::Error::Initialization`] if it wasn't set, instead of reporting zero usage.
     pub fn get_cpu_usage(&self) -> Result<(c_uint, c_uint)> {
+        check_init_flags(|| self.get_system(), InitFlags::PROFILE_ENABLE)?;
         let mut exclusive = 0;
         let mut inclusive = 0;
         unsafe {
@@ -11,6 +11,7 @@ use fmod_sys::*;
 mod callback;
 mod channel_format;
 mod connections;
+mod custom_parameters;
 mod data_parameters;
 pub mod effects;
 mod general;
@@ -19,7 +20,8 @@ mod parameter_traits;
 mod parameters;
 mod processing;
 
-pub use callback::DspCallback;
+pub use callback::{DataParameterInfo, DspCallback, DspSystemCallback, MixStage, OwnedDataRelease};
+pub use custom_parameters::{DspParameterTable, DspParameters};
 pub use data_parameters::*;
 pub use parameter_traits::*;
 
@@ -1,8 +1,8 @@
 use fmod_sys::*;
-use lanyard::{Utf8CStr, Utf8CString};
+use lanyard::Utf8CString;
 use std::ffi::{c_float, c_int};
 
-use crate::{Dsp, DspType};
+use crate::{Dsp, DspType, string_from_buffer};
 use crate::{FmodResultExt, Result};
 
 // FIXME don't want sealed so users can impl their own types, what do?
@@ -67,7 +67,7 @@ impl ReadableParameter for bool {
             )
             .to_result()?;
 
-            let string = Utf8CStr::from_utf8_until_nul(&bytes).unwrap().to_cstring();
+            let string = string_from_buffer(&bytes);
             Ok(string)
         }
     }
@@ -104,7 +104,7 @@ impl ReadableParameter for c_int {
             )
             .to_result()?;
 
-            let string = Utf8CStr::from_utf8_until_nul(&bytes).unwrap().to_cstring();
+            let string = string_from_buffer(&bytes);
             Ok(string)
         }
     }
@@ -141,7 +141,7 @@ impl ReadableParameter for c_float {
             )
             .to_result()?;
 
-            let string = Utf8CStr::from_utf8_until_nul(&bytes).unwrap().to_cstring();
+            let string = string_from_buffer(&bytes);
             Ok(string)
         }
     }
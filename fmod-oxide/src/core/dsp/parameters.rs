@@ -5,16 +5,21 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use fmod_sys::*;
-use lanyard::{Utf8CStr, Utf8CString};
+use lanyard::Utf8CString;
 use std::ffi::c_int;
 
 use crate::{
     Dsp, DspParameterDataType, DspParameterDescription, ReadableParameter, ReadableParameterIndex,
-    WritableParameter, WritableParameterIndex,
+    WritableParameter, WritableParameterIndex, string_from_buffer,
 };
 use crate::{FmodResultExt, Result};
 
 impl Dsp {
+    /// Parameter index that doesn't refer to any parameter.
+    ///
+    /// Useful as a placeholder wherever a parameter index is optional but stored as a plain integer, as FMOD's own structures do.
+    pub const PARAMETER_INDEX_NONE: c_int = -1;
+
     /// Retrieve the index of the first data parameter of a particular data type.
     ///
     /// This function returns [`Ok`] if a parmeter of matching type is found and [`FMOD_RESULT::FMOD_ERR_INVALID_PARAM`] if no matches were found.
@@ -185,7 +190,7 @@ impl Dsp {
             )
             .to_result()?;
 
-            let string = Utf8CStr::from_utf8_until_nul(&bytes).unwrap().to_cstring();
+            let string = string_from_buffer(&bytes);
             Ok(string)
         }
     }
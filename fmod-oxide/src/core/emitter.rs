@@ -0,0 +1,503 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::{c_float, c_uint};
+use std::time::Duration;
+
+use crate::{
+    Attributes3D, Channel, ChannelGroup, Error, Mode, Result, Sound, System, SystemMap, TimeUnit,
+    Vector,
+};
+
+#[cfg(feature = "studio")]
+use crate::studio;
+
+/// What an [`Emitter`] plays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EmitterSource {
+    /// A Studio event, played by creating instances of it.
+    #[cfg(feature = "studio")]
+    Event {
+        /// The system the listeners are read from.
+        system: studio::System,
+        /// The event to play.
+        description: studio::EventDescription,
+    },
+    /// A core sound, played on a [`Channel`].
+    ///
+    /// The sound must have been created with [`crate::Mode::D3`].
+    Sound {
+        /// The system the sound is played on.
+        system: System,
+        /// The sound to play.
+        sound: Sound,
+        /// The group to play the sound on, or the master channel group.
+        channel_group: Option<ChannelGroup>,
+    },
+}
+
+impl EmitterSource {
+    // identifies the source for the max instances count
+    fn key(&self) -> usize {
+        match self {
+            #[cfg(feature = "studio")]
+            EmitterSource::Event { description, .. } => description.inner.as_ptr() as usize,
+            EmitterSource::Sound { sound, .. } => sound.inner.as_ptr() as usize,
+        }
+    }
+
+    // calls `f` with the max instances counts of the system the source plays on
+    fn with_live<R>(&self, f: impl FnOnce(&mut Option<Vec<(usize, usize)>>) -> R) -> R {
+        match *self {
+            #[cfg(feature = "studio")]
+            EmitterSource::Event { system, .. } => LIVE.update(system, f),
+            EmitterSource::Sound { system, .. } => LIVE.update(system, f),
+        }
+    }
+
+    fn is_one_shot(&self) -> Result<bool> {
+        match self {
+            #[cfg(feature = "studio")]
+            EmitterSource::Event { description, .. } => description.is_oneshot(),
+            EmitterSource::Sound { sound, .. } => Ok(!sound
+                .get_mode()?
+                .intersects(Mode::LOOP_NORMAL | Mode::LOOP_BIDI)),
+        }
+    }
+
+    fn max_distance(&self) -> Result<c_float> {
+        match self {
+            #[cfg(feature = "studio")]
+            EmitterSource::Event { description, .. } => Ok(description.get_min_max_distance()?.1),
+            EmitterSource::Sound { sound, .. } => Ok(sound.get_3d_min_max_distance()?.1),
+        }
+    }
+
+    fn nearest_listener(&self, position: Vector) -> Result<c_float> {
+        let mut nearest = c_float::INFINITY;
+        match self {
+            #[cfg(feature = "studio")]
+            EmitterSource::Event { system, .. } => {
+                for listener in 0..system.get_listener_count()? {
+                    // the attenuation position is the listener position unless it was overridden
+                    let (_, attenuation_position) = system.get_listener_attributes(listener)?;
+                    nearest = nearest.min((attenuation_position - position).length());
+                }
+            }
+            EmitterSource::Sound { system, .. } => {
+                for listener in 0..system.get_3d_listener_count()? {
+                    let (listener_position, ..) = system.get_3d_listener_attributes(listener)?;
+                    nearest = nearest.min((listener_position - position).length());
+                }
+            }
+        }
+        Ok(nearest)
+    }
+
+    // where a looping source would be after playing for `elapsed`, or None for sources that don't loop
+    fn catch_up_position(&self, elapsed: Duration) -> Result<Option<c_uint>> {
+        if self.is_one_shot()? {
+            return Ok(None);
+        }
+        let length = match self {
+            #[cfg(feature = "studio")]
+            EmitterSource::Event { description, .. } => description.get_length()?.max(0) as c_uint,
+            EmitterSource::Sound { sound, .. } => sound.get_length(TimeUnit::MS)?,
+        };
+        if length == 0 {
+            return Ok(None);
+        }
+        Ok(Some((elapsed.as_millis() % u128::from(length)) as c_uint))
+    }
+
+    fn play(&self, attributes: Attributes3D, position: Option<c_uint>) -> Result<Playing> {
+        match *self {
+            #[cfg(feature = "studio")]
+            EmitterSource::Event { description, .. } => {
+                let instance = description.create_instance()?;
+                let result = instance
+                    .set_3d_attributes(attributes)
+                    .and_then(|()| instance.start())
+                    .and_then(|()| match position {
+                        Some(position) => {
+                            instance.set_timeline_position(position as std::ffi::c_int)
+                        }
+                        None => Ok(()),
+                    })
+                    // the instance is destroyed once it stops, which is how finished one-shots are noticed
+                    .and_then(|()| instance.release());
+                if let Err(e) = result {
+                    // don't leave a half set up instance playing where the emitter can't see it
+                    let _ = instance.stop(studio::StopMode::Immediate);
+                    let _ = instance.release();
+                    return Err(e);
+                }
+                Ok(Playing::Event(instance))
+            }
+            EmitterSource::Sound {
+                system,
+                sound,
+                channel_group,
+            } => {
+                let channel = system.play_sound(sound, channel_group, true)?;
+                let result = channel
+                    .set_3d_attributes(Some(attributes.position), Some(attributes.velocity))
+                    .and_then(|()| match position {
+                        Some(position) => channel.set_position(position, TimeUnit::MS),
+                        None => Ok(()),
+                    })
+                    .and_then(|()| channel.set_paused(false));
+                if let Err(e) = result {
+                    // the channel is still paused, stop it so it doesn't hold on to a voice
+                    let _ = channel.stop();
+                    return Err(e);
+                }
+                Ok(Playing::Sound(channel))
+            }
+        }
+    }
+}
+
+// a playing event instance or channel, also used by the ambience mixer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Playing {
+    #[cfg(feature = "studio")]
+    Event(studio::EventInstance),
+    Sound(Channel),
+}
+
+impl Playing {
+    pub(crate) fn is_playing(self) -> Result<bool> {
+        let result = match self {
+            #[cfg(feature = "studio")]
+            Playing::Event(instance) => instance
+                .get_playback_state()
+                .map(|state| state != studio::PlaybackState::Stopped),
+            Playing::Sound(channel) => channel.is_playing(),
+        };
+        match result {
+            Err(Error::InvalidHandle | Error::ChannelStolen) => Ok(false),
+            result => result,
+        }
+    }
+
+    fn set_attributes(self, attributes: Attributes3D) -> Result<()> {
+        match self {
+            #[cfg(feature = "studio")]
+            Playing::Event(instance) => instance.set_3d_attributes(attributes),
+            Playing::Sound(channel) => {
+                channel.set_3d_attributes(Some(attributes.position), Some(attributes.velocity))
+            }
+        }
+    }
+
+    pub(crate) fn stop(self) -> Result<()> {
+        let result = match self {
+            #[cfg(feature = "studio")]
+            Playing::Event(instance) => instance.stop(studio::StopMode::AllowFadeout),
+            Playing::Sound(channel) => channel.stop(),
+        };
+        match result {
+            // already gone, which is what stopping was for
+            Err(Error::InvalidHandle | Error::ChannelStolen) => Ok(()),
+            result => result,
+        }
+    }
+}
+
+// how many emitter instances of each source are playing on each system, for max instances
+static LIVE: SystemMap<Vec<(usize, usize)>> = SystemMap::new();
+
+fn acquire_slot(source: &EmitterSource, max_instances: Option<usize>) -> bool {
+    let key = source.key();
+    source.with_live(|live| {
+        let live = live.get_or_insert_with(Vec::new);
+        match live.iter_mut().find(|(s, _)| *s == key) {
+            Some((_, count)) if max_instances.is_some_and(|max| *count >= max) => false,
+            Some((_, count)) => {
+                *count += 1;
+                true
+            }
+            None if max_instances == Some(0) => false,
+            None => {
+                live.push((key, 1));
+                true
+            }
+        }
+    })
+}
+
+fn release_slot(source: &EmitterSource) {
+    let key = source.key();
+    source.with_live(|live| {
+        let Some(counts) = live else {
+            return;
+        };
+        if let Some(index) = counts.iter().position(|&(s, _)| s == key) {
+            counts[index].1 -= 1;
+            if counts[index].1 == 0 {
+                counts.swap_remove(index);
+            }
+        }
+        if counts.is_empty() {
+            *live = None;
+        }
+    });
+}
+
+impl System {
+    pub(crate) fn forget_emitters(&self) {
+        LIVE.remove(*self);
+    }
+}
+
+#[cfg(feature = "studio")]
+impl studio::System {
+    pub(crate) fn forget_emitters(&self) {
+        LIVE.remove(*self);
+    }
+}
+
+/// How an [`Emitter`] avoids thrashing at the edge of its range, and where it starts playing from.
+///
+/// Set with [`Emitter::with_culling`]. The default has no hysteresis and starts sources from the beginning.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CullingPolicy {
+    /// How much further than its range the nearest listener has to be before a playing emitter stops.
+    ///
+    /// Without it an emitter right at the edge of its range starts and stops every few frames as the listener moves about.
+    pub hysteresis: c_float,
+    /// Start looping sources where they would be had they been playing since the emitter's first update, instead of from the beginning.
+    ///
+    /// This is how FMOD treats virtual voices, and keeps a culled ambience from audibly restarting every time the listener returns.
+    /// One-shots always start from the beginning.
+    pub catch_up: bool,
+}
+
+/// A sound source placed in the world, which plays while a listener is near it.
+///
+/// This is the "audio emitter" component most engines have, without tying it to any one engine:
+/// call [`Emitter::update`] once per frame with the emitter's position,
+/// and it starts an instance of its [`EmitterSource`] when the nearest listener comes within range and stops it when the listener leaves.
+///
+/// Far away emitters have no instance at all, so they cost nothing in FMOD.
+/// See [`CullingPolicy`] for how starting and stopping can be smoothed out.
+///
+/// A one-shot that finishes while the listener is in range isn't started again until the listener has left and come back.
+/// A looping source that stops early, such as when FMOD steals its channel, is started again on the next update.
+/// Dropping the emitter stops its instance.
+#[derive(Debug)]
+pub struct Emitter {
+    source: EmitterSource,
+    range: c_float,
+    culling: CullingPolicy,
+    max_instances: Option<usize>,
+    playing: Option<Playing>,
+    active: bool,
+    finished: bool,
+    in_range_for: Duration,
+    clock: Duration,
+}
+
+impl Emitter {
+    /// Creates an emitter for `source`, with the source's max distance as its range.
+    pub fn new(source: EmitterSource) -> Result<Self> {
+        Ok(Self {
+            range: source.max_distance()?,
+            source,
+            culling: CullingPolicy::default(),
+            max_instances: None,
+            playing: None,
+            active: false,
+            finished: false,
+            in_range_for: Duration::ZERO,
+            clock: Duration::ZERO,
+        })
+    }
+
+    /// Creates an emitter for a Studio event.
+    #[cfg(feature = "studio")]
+    pub fn event(system: studio::System, description: studio::EventDescription) -> Result<Self> {
+        Self::new(EmitterSource::Event {
+            system,
+            description,
+        })
+    }
+
+    /// Creates an emitter for a core sound.
+    pub fn sound(
+        system: System,
+        sound: Sound,
+        channel_group: Option<ChannelGroup>,
+    ) -> Result<Self> {
+        Self::new(EmitterSource::Sound {
+            system,
+            sound,
+            channel_group,
+        })
+    }
+
+    /// Sets how close the nearest listener has to be for the emitter to play.
+    #[must_use]
+    pub fn with_range(mut self, range: c_float) -> Self {
+        self.range = range;
+        self
+    }
+
+    /// Sets the [`CullingPolicy`].
+    #[must_use]
+    pub fn with_culling(mut self, culling: CullingPolicy) -> Self {
+        self.culling = culling;
+        self
+    }
+
+    /// Limits how many emitters of the same source may play at once.
+    ///
+    /// Emitters that come into range once the limit is reached wait until another emitter of the source stops.
+    /// This counts only instances started by emitters, FMOD's own max instances setting for an event applies on top of it.
+    #[must_use]
+    pub fn with_max_instances(mut self, max_instances: usize) -> Self {
+        self.max_instances = Some(max_instances);
+        self
+    }
+
+    /// What the emitter plays.
+    pub fn source(&self) -> EmitterSource {
+        self.source
+    }
+
+    /// How close the nearest listener has to be for the emitter to play.
+    pub fn range(&self) -> c_float {
+        self.range
+    }
+
+    /// Sets how close the nearest listener has to be for the emitter to play.
+    pub fn set_range(&mut self, range: c_float) {
+        self.range = range;
+    }
+
+    /// The [`CullingPolicy`].
+    pub fn culling(&self) -> CullingPolicy {
+        self.culling
+    }
+
+    /// Sets the [`CullingPolicy`].
+    pub fn set_culling(&mut self, culling: CullingPolicy) {
+        self.culling = culling;
+    }
+
+    /// Whether the emitter has an instance playing.
+    pub fn is_playing(&self) -> bool {
+        self.playing.is_some()
+    }
+
+    /// How long a listener has been within range, as measured by the `dt`s passed to [`Emitter::update`].
+    ///
+    /// With [`CullingPolicy::hysteresis`] the listener counts as within range until it is past the hysteresis.
+    pub fn in_range_for(&self) -> Duration {
+        self.in_range_for
+    }
+
+    /// The Studio event instance playing, if the source is an event and it is playing.
+    #[cfg(feature = "studio")]
+    pub fn event_instance(&self) -> Option<studio::EventInstance> {
+        match self.playing? {
+            Playing::Event(instance) => Some(instance),
+            Playing::Sound(_) => None,
+        }
+    }
+
+    /// The channel playing, if the source is a sound and it is playing.
+    pub fn channel(&self) -> Option<Channel> {
+        match self.playing? {
+            Playing::Sound(channel) => Some(channel),
+            #[cfg(feature = "studio")]
+            Playing::Event(_) => None,
+        }
+    }
+
+    /// Moves the emitter to `attributes`, and starts or stops its instance depending on how far away the nearest listener is.
+    ///
+    /// `dt` is the time since the last update, which also drives [`CullingPolicy::catch_up`].
+    /// Call this once per frame, after the listener attributes have been set.
+    pub fn update(&mut self, dt: Duration, attributes: Attributes3D) -> Result<()> {
+        self.clock += dt;
+
+        let stopped = match self.playing {
+            Some(playing) => !playing.is_playing()?,
+            None => false,
+        };
+        if stopped {
+            self.forget_playing();
+            // a looping source only stops early, such as when its channel is stolen, so it starts again
+            self.finished = self.source.is_one_shot()?;
+        }
+
+        let distance = self.source.nearest_listener(attributes.position)?;
+        if distance <= self.range {
+            self.active = true;
+        } else if distance > self.range + self.culling.hysteresis {
+            self.active = false;
+        }
+        if self.active {
+            self.in_range_for += dt;
+        } else {
+            self.in_range_for = Duration::ZERO;
+            self.finished = false;
+        }
+
+        match self.playing {
+            Some(playing) if self.active => playing.set_attributes(attributes),
+            Some(_) => self.stop(),
+            None if self.active && !self.finished => self.start(attributes),
+            None => Ok(()),
+        }
+    }
+
+    /// Stops the emitter's instance, if it is playing.
+    ///
+    /// The emitter starts again on the next [`Emitter::update`] if a listener is still within range.
+    pub fn stop(&mut self) -> Result<()> {
+        if let Some(playing) = self.playing {
+            self.forget_playing();
+            playing.stop()?;
+        }
+        Ok(())
+    }
+
+    fn start(&mut self, attributes: Attributes3D) -> Result<()> {
+        let position = if self.culling.catch_up {
+            self.source.catch_up_position(self.clock)?
+        } else {
+            None
+        };
+        if !acquire_slot(&self.source, self.max_instances) {
+            return Ok(());
+        }
+        match self.source.play(attributes, position) {
+            Ok(playing) => {
+                self.playing = Some(playing);
+                Ok(())
+            }
+            Err(e) => {
+                release_slot(&self.source);
+                Err(e)
+            }
+        }
+    }
+
+    fn forget_playing(&mut self) {
+        if self.playing.take().is_some() {
+            release_slot(&self.source);
+        }
+    }
+}
+
+impl Drop for Emitter {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
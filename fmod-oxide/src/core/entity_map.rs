@@ -0,0 +1,274 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering, fence};
+
+use crate::{Channel, ChannelControl, ChannelGroup, Dsp, Error, Result, Sound};
+
+#[cfg(feature = "studio")]
+use crate::studio;
+
+/// A handle type that can be used as a key in an [`AudioEntityMap`].
+pub trait MappedHandle: Copy {
+    /// The address of the object the handle refers to.
+    fn address(self) -> usize;
+}
+
+macro_rules! mapped_handle {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl MappedHandle for $ty {
+                fn address(self) -> usize {
+                    self.inner.as_ptr() as usize
+                }
+            }
+        )*
+    };
+}
+
+mapped_handle!(Channel, ChannelControl, ChannelGroup, Dsp, Sound);
+
+#[cfg(feature = "studio")]
+mapped_handle!(studio::EventInstance, studio::Bus, studio::CommandReplay);
+
+/// A value that can be stored in an [`AudioEntityMap`], such as an entity id from an ECS.
+///
+/// The value has to round trip through a [`u64`] so it can be stored atomically.
+pub trait EntityKey: Copy {
+    /// Packs the value into a [`u64`].
+    fn to_bits(self) -> u64;
+    /// Unpacks a value packed with [`EntityKey::to_bits`].
+    fn from_bits(bits: u64) -> Self;
+}
+
+impl EntityKey for u64 {
+    fn to_bits(self) -> u64 {
+        self
+    }
+
+    fn from_bits(bits: u64) -> Self {
+        bits
+    }
+}
+
+impl EntityKey for u32 {
+    fn to_bits(self) -> u64 {
+        u64::from(self)
+    }
+
+    fn from_bits(bits: u64) -> Self {
+        bits as u32
+    }
+}
+
+impl EntityKey for usize {
+    fn to_bits(self) -> u64 {
+        self as u64
+    }
+
+    fn from_bits(bits: u64) -> Self {
+        bits as usize
+    }
+}
+
+const EMPTY: usize = 0;
+const TOMBSTONE: usize = 1;
+
+// a seqlock: writers make `seq` odd while they update the slot, readers retry if it changed under them.
+// without it a reader could see a handle, then read the key of whatever replaced it after a remove and insert
+#[derive(Debug)]
+struct Slot {
+    seq: AtomicU64,
+    handle: AtomicUsize,
+    key: AtomicU64,
+}
+
+impl Slot {
+    fn read(&self) -> (usize, u64) {
+        loop {
+            let seq = self.seq.load(Ordering::Acquire);
+            if seq & 1 == 0 {
+                let handle = self.handle.load(Ordering::Relaxed);
+                let key = self.key.load(Ordering::Relaxed);
+                fence(Ordering::Acquire);
+                if self.seq.load(Ordering::Relaxed) == seq {
+                    return (handle, key);
+                }
+            }
+            std::hint::spin_loop();
+        }
+    }
+
+    fn write(&self) -> SlotWriter<'_> {
+        loop {
+            let seq = self.seq.load(Ordering::Relaxed);
+            if seq & 1 == 0
+                && self
+                    .seq
+                    .compare_exchange_weak(seq, seq + 1, Ordering::Acquire, Ordering::Relaxed)
+                    .is_ok()
+            {
+                // keeps the writes below from becoming visible before the odd sequence number
+                fence(Ordering::Release);
+                return SlotWriter { slot: self, seq };
+            }
+            std::hint::spin_loop();
+        }
+    }
+}
+
+struct SlotWriter<'a> {
+    slot: &'a Slot,
+    seq: u64,
+}
+
+impl SlotWriter<'_> {
+    fn handle(&self) -> usize {
+        self.slot.handle.load(Ordering::Relaxed)
+    }
+
+    fn key(&self) -> u64 {
+        self.slot.key.load(Ordering::Relaxed)
+    }
+
+    fn set(&self, handle: usize, key: u64) {
+        self.slot.key.store(key, Ordering::Relaxed);
+        self.slot.handle.store(handle, Ordering::Relaxed);
+    }
+}
+
+impl Drop for SlotWriter<'_> {
+    fn drop(&mut self) {
+        self.slot.seq.store(self.seq + 2, Ordering::Release);
+    }
+}
+
+/// Maps FMOD handles to game side keys, readable from FMOD's callback threads without locking.
+///
+/// Event and channel callbacks run on FMOD's threads, so looking up which entity a callback is for
+/// usually means sharing a `Mutex<HashMap>` with the game thread, and risking blocking the mixer or deadlocking on it.
+/// Lookups here are a few atomic loads and never take a lock,
+/// they only retry while another thread is updating the same slot.
+///
+/// The table has a fixed capacity chosen at creation and doesn't grow.
+/// Removed entries leave a tombstone that is reused by later inserts, so churn doesn't wear it out,
+/// but the number of live entries can never exceed the capacity.
+///
+/// Each handle should only be inserted from one thread at a time, usually the game thread that created it;
+/// concurrent inserts of the *same* handle may leave duplicate entries. Lookups and removals are safe from any thread.
+#[derive(Debug)]
+pub struct AudioEntityMap<H, K> {
+    slots: Box<[Slot]>,
+    mask: usize,
+    _marker: PhantomData<fn(H) -> K>,
+}
+
+impl<H: MappedHandle, K: EntityKey> AudioEntityMap<H, K> {
+    /// Creates a map that can hold at least `capacity` entries.
+    pub fn with_capacity(capacity: usize) -> Self {
+        // keep the load factor at or below one half so probe sequences stay short
+        let size = capacity.max(1).saturating_mul(2).next_power_of_two();
+        let slots = (0..size)
+            .map(|_| Slot {
+                seq: AtomicU64::new(0),
+                handle: AtomicUsize::new(EMPTY),
+                key: AtomicU64::new(0),
+            })
+            .collect();
+        Self {
+            slots,
+            mask: size - 1,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Number of entries the map can hold.
+    pub fn capacity(&self) -> usize {
+        self.slots.len() / 2
+    }
+
+    fn start(&self, address: usize) -> usize {
+        // handles are at least 8 byte aligned, so drop the low bits before mixing
+        (address >> 3).wrapping_mul(0x9E37_79B9_7F4A_7C15_u64 as usize) & self.mask
+    }
+
+    fn probe(&self, address: usize) -> impl Iterator<Item = &Slot> {
+        let start = self.start(address);
+        (0..self.slots.len()).map(move |i| &self.slots[(start + i) & self.mask])
+    }
+
+    fn find(&self, address: usize) -> Option<(&Slot, u64)> {
+        for slot in self.probe(address) {
+            match slot.read() {
+                (EMPTY, _) => return None,
+                (handle, key) if handle == address => return Some((slot, key)),
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// Maps `handle` to `key`, replacing any key it was already mapped to.
+    ///
+    /// Returns [`Error::Memory`] if the map is full.
+    pub fn insert(&self, handle: H, key: K) -> Result<()> {
+        let address = handle.address();
+        if let Some((slot, _)) = self.find(address) {
+            let writer = slot.write();
+            // it may have been removed since it was found
+            if writer.handle() == address {
+                writer.set(address, key.to_bits());
+                return Ok(());
+            }
+        }
+
+        for slot in self.probe(address) {
+            let writer = slot.write();
+            if matches!(writer.handle(), EMPTY | TOMBSTONE) {
+                writer.set(address, key.to_bits());
+                return Ok(());
+            }
+        }
+        Err(Error::Memory)
+    }
+
+    /// The key `handle` is mapped to.
+    pub fn get(&self, handle: H) -> Option<K> {
+        self.find(handle.address())
+            .map(|(_, key)| K::from_bits(key))
+    }
+
+    /// Whether `handle` is mapped to a key.
+    pub fn contains(&self, handle: H) -> bool {
+        self.find(handle.address()).is_some()
+    }
+
+    /// Removes `handle`, returning the key it was mapped to.
+    ///
+    /// Call this when the handle is released (for example from an event's destroyed callback),
+    /// as FMOD may reuse the address for a new object.
+    pub fn remove(&self, handle: H) -> Option<K> {
+        let address = handle.address();
+        let (slot, _) = self.find(address)?;
+        let writer = slot.write();
+        if writer.handle() != address {
+            return None;
+        }
+        let key = K::from_bits(writer.key());
+        writer.set(TOMBSTONE, 0);
+        Some(key)
+    }
+
+    /// Removes every entry.
+    ///
+    /// Entries inserted concurrently with this call may or may not survive.
+    pub fn clear(&self) {
+        for slot in &self.slots {
+            slot.write().set(EMPTY, 0);
+        }
+    }
+}
@@ -6,6 +6,7 @@
 
 use crate::{Error, Result};
 use fmod_sys::*;
+use std::ffi::c_int;
 
 #[cfg(doc)]
 use crate::{Channel, ChannelControl, Dsp, Geometry, Sound, System, SystemBuilder, studio};
@@ -79,6 +80,7 @@ use crate::{Channel, ChannelControl, Dsp, Geometry, Sound, System, SystemBuilder
 ///
 /// See the FMOD Studio Mixing Guide for graphical depictions of each speaker mode.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(
     num_enum::TryFromPrimitive,
     num_enum::IntoPrimitive,
@@ -110,8 +112,84 @@ pub enum SpeakerMode {
     SevenPointOneFour = FMOD_SPEAKERMODE_7POINT1POINT4,
 }
 
+impl SpeakerMode {
+    /// Number of speakers (output channels) in this speaker mode.
+    ///
+    /// Returns [`None`] for [`SpeakerMode::Default`] and [`SpeakerMode::Raw`], which don't have a fixed speaker count.
+    /// Use [`System::get_software_format`] to get the resolved count for those.
+    pub fn channel_count(self) -> Option<c_int> {
+        match self {
+            SpeakerMode::Default | SpeakerMode::Raw => None,
+            SpeakerMode::Mono => Some(1),
+            SpeakerMode::Stereo => Some(2),
+            SpeakerMode::Quad => Some(4),
+            SpeakerMode::Surround => Some(5),
+            SpeakerMode::FivePointOne => Some(6),
+            SpeakerMode::SevenPointOne => Some(8),
+            SpeakerMode::SevenPointOneFour => Some(12),
+        }
+    }
+
+    /// The [`Speaker`]s of this speaker mode, in channel order.
+    ///
+    /// Returns an empty slice for [`SpeakerMode::Default`] and [`SpeakerMode::Raw`].
+    pub fn speakers(self) -> &'static [Speaker] {
+        use Speaker::{
+            BackLeft, BackRight, FrontCenter, FrontLeft, FrontRight, LowFrequency, SurroundLeft,
+            SurroundRight, TopBackLeft, TopBackRight, TopFrontLeft, TopFrontRight,
+        };
+        match self {
+            SpeakerMode::Default | SpeakerMode::Raw => &[],
+            // FMOD treats the single mono speaker as front left
+            SpeakerMode::Mono => &[FrontLeft],
+            SpeakerMode::Stereo => &[FrontLeft, FrontRight],
+            SpeakerMode::Quad => &[FrontLeft, FrontRight, SurroundLeft, SurroundRight],
+            SpeakerMode::Surround => &[
+                FrontLeft,
+                FrontRight,
+                FrontCenter,
+                SurroundLeft,
+                SurroundRight,
+            ],
+            SpeakerMode::FivePointOne => &[
+                FrontLeft,
+                FrontRight,
+                FrontCenter,
+                LowFrequency,
+                SurroundLeft,
+                SurroundRight,
+            ],
+            SpeakerMode::SevenPointOne => &[
+                FrontLeft,
+                FrontRight,
+                FrontCenter,
+                LowFrequency,
+                SurroundLeft,
+                SurroundRight,
+                BackLeft,
+                BackRight,
+            ],
+            SpeakerMode::SevenPointOneFour => &[
+                FrontLeft,
+                FrontRight,
+                FrontCenter,
+                LowFrequency,
+                SurroundLeft,
+                SurroundRight,
+                BackLeft,
+                BackRight,
+                TopFrontLeft,
+                TopFrontRight,
+                TopBackLeft,
+                TopBackRight,
+            ],
+        }
+    }
+}
+
 /// Built-in output types that can be used to run the mixer.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(
     num_enum::TryFromPrimitive,
     num_enum::IntoPrimitive,
@@ -231,6 +309,7 @@ pub enum ThreadType {
 
 /// Time types used for position or length.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(
     num_enum::TryFromPrimitive,
     num_enum::IntoPrimitive,
@@ -305,6 +384,16 @@ pub enum Speaker {
     TopBackRight = FMOD_SPEAKER_TOP_BACK_RIGHT,
 }
 
+impl Speaker {
+    /// The [`Speaker`] that output channel `index` of `mode` is played on.
+    ///
+    /// Returns [`None`] if `index` is out of range for `mode`, or if `mode` is [`SpeakerMode::Default`] or [`SpeakerMode::Raw`].
+    pub fn try_from_channel_index(mode: SpeakerMode, index: c_int) -> Option<Speaker> {
+        let index = usize::try_from(index).ok()?;
+        mode.speakers().get(index).copied()
+    }
+}
+
 /// Types of plug-in used to extend functionality.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[derive(
@@ -504,9 +593,9 @@ pub enum DspType {
 #[cfg_attr(target_env = "msvc", repr(i32))]
 #[cfg_attr(not(target_env = "msvc"), repr(u32))]
 pub enum PortType {
-    /// Background music, pass [`FMOD_PORT_INDEX_NONE`] as port index.
+    /// Background music, pass [`PortIndex::NONE`] as port index.
     Music = FMOD_PORT_TYPE_MUSIC,
-    /// Copyright background music, pass [`FMOD_PORT_INDEX_NONE`] as port index.
+    /// Copyright background music, pass [`PortIndex::NONE`] as port index.
     CopyrightMusic = FMOD_PORT_TYPE_COPYRIGHT_MUSIC,
     /// Voice chat, pass platform specific user ID of desired user as port index.
     Voice = FMOD_PORT_TYPE_VOICE,
@@ -516,9 +605,9 @@ pub enum PortType {
     Personal = FMOD_PORT_TYPE_PERSONAL,
     /// Controller vibration, pass platform specific user ID of desired user as port index.
     Vibration = FMOD_PORT_TYPE_VIBRATION,
-    /// Auxiliary output port, pass [`FMOD_PORT_INDEX_NONE`] as port index.
+    /// Auxiliary output port, pass [`PortIndex::NONE`] as port index.
     AUX = FMOD_PORT_TYPE_AUX,
-    /// Passthrough output port, pass [`FMOD_PORT_INDEX_NONE`] as port index.
+    /// Passthrough output port, pass [`PortIndex::NONE`] as port index.
     #[cfg(fmod_eq_2_3)]
     Passthrough = FMOD_PORT_TYPE_PASSTHROUGH,
     /// VR Controller vibration, pass platform specific user ID of desired user as port index.
@@ -526,6 +615,15 @@ pub enum PortType {
     VrVibration = FMOD_PORT_TYPE_VR_VIBRATION,
 }
 
+/// Namespace for special [`FMOD_PORT_INDEX`] values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortIndex {}
+
+impl PortIndex {
+    /// Use when a port index is not required.
+    pub const NONE: FMOD_PORT_INDEX = FMOD_PORT_INDEX_NONE as FMOD_PORT_INDEX;
+}
+
 /// Values specifying behavior when a sound group's max audible value is exceeded.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[derive(
@@ -617,6 +715,7 @@ pub enum DspParameterDataType {
 
 /// Recognized audio formats that can be loaded into a Sound.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(
     num_enum::TryFromPrimitive,
     num_enum::IntoPrimitive,
@@ -696,6 +795,7 @@ pub enum SoundType {
 
 /// These definitions describe the native format of the hardware or software buffer that will be used.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(
     num_enum::TryFromPrimitive,
     num_enum::IntoPrimitive,
@@ -831,6 +931,7 @@ pub enum ChannelOrder {
 
 /// List of interpolation types used for resampling.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(
     num_enum::TryFromPrimitive,
     num_enum::IntoPrimitive,
@@ -0,0 +1,202 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::{c_uint, c_void};
+use std::marker::PhantomData;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use lanyard::{Utf8CStr, Utf8CString};
+
+use crate::{Error, FileBuffer, FileSystem, FileSystemSync, Result};
+
+#[cfg(doc)]
+use crate::{SoundBuilder, System};
+
+/// What happened to a single file opened through a [`Logged`] filesystem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileStats {
+    /// Name FMOD asked for, exactly as it was passed to [`FileSystem::open`].
+    pub name: Utf8CString,
+    /// Number of successful opens.
+    pub opens: u32,
+    /// Number of failed opens.
+    pub open_errors: u32,
+    /// Number of read calls.
+    pub reads: u32,
+    /// Number of seek calls.
+    pub seeks: u32,
+    /// Total bytes read.
+    pub bytes_read: u64,
+    /// The most recent error from any call, other than [`Error::FileEof`].
+    pub last_error: Option<Error>,
+}
+
+#[derive(Debug)]
+struct FileLog {
+    files: Vec<FileStats>,
+    // open handle -> index into files
+    handles: Vec<(usize, usize)>,
+}
+
+static LOG: Mutex<FileLog> = Mutex::new(FileLog {
+    files: Vec::new(),
+    handles: Vec::new(),
+});
+
+fn log() -> MutexGuard<'static, FileLog> {
+    // stats are only ever counted up, so a panic can at worst lose a count
+    LOG.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl FileLog {
+    fn file(&mut self, name: &Utf8CStr) -> usize {
+        if let Some(index) = self.files.iter().position(|f| *f.name == *name) {
+            return index;
+        }
+        self.files.push(FileStats {
+            name: name.to_cstring(),
+            opens: 0,
+            open_errors: 0,
+            reads: 0,
+            seeks: 0,
+            bytes_read: 0,
+            last_error: None,
+        });
+        self.files.len() - 1
+    }
+
+    fn handle(&mut self, handle: *mut c_void) -> Option<&mut FileStats> {
+        let handle = handle as usize;
+        let (_, index) = self.handles.iter().find(|(h, _)| *h == handle)?;
+        self.files.get_mut(*index)
+    }
+
+    fn record_error<T>(stats: &mut FileStats, result: &Result<T>) {
+        match result {
+            Ok(_) | Err(Error::FileEof) => {}
+            Err(e) => stats.last_error = Some(*e),
+        }
+    }
+}
+
+/// Inspects the files opened through [`Logged`] filesystems.
+///
+/// This is meant for tracking down why FMOD can't find a file, such as a bank that is missing from a pak:
+/// install the game's filesystem with [`System::set_filesystem_logged`],
+/// and check [`FileDiag::failed`] for the names FMOD asked for and the errors the filesystem returned.
+///
+/// To check whether the filesystem is at fault at all, load the same file with
+/// [`SoundBuilder::with_ignore_set_filesystem`], which bypasses it and reads from disk directly.
+#[derive(Debug)]
+pub struct FileDiag;
+
+impl FileDiag {
+    /// Stats for every file opened so far, in the order they were first opened.
+    pub fn stats() -> Vec<FileStats> {
+        log().files.clone()
+    }
+
+    /// Stats for files that failed to open at least once.
+    pub fn failed() -> Vec<FileStats> {
+        log()
+            .files
+            .iter()
+            .filter(|f| f.open_errors > 0)
+            .cloned()
+            .collect()
+    }
+
+    /// Stats for `name`, if it has been opened.
+    pub fn file(name: &Utf8CStr) -> Option<FileStats> {
+        log().files.iter().find(|f| *f.name == *name).cloned()
+    }
+
+    /// Forgets all stats. Files that are currently open keep being tracked.
+    pub fn clear() {
+        let mut log = log();
+        let open = log
+            .handles
+            .iter()
+            .map(|&(_, index)| log.files[index].name.clone())
+            .collect::<Vec<_>>();
+        log.files.clear();
+        let handles = std::mem::take(&mut log.handles);
+        for ((handle, _), name) in handles.into_iter().zip(open) {
+            let index = log.file(&name);
+            log.handles.push((handle, index));
+        }
+    }
+}
+
+/// A [`FileSystemSync`] that passes every call on to `F`, and records what happened for [`FileDiag`].
+///
+/// See [`System::set_filesystem_logged`].
+#[derive(Debug)]
+pub struct Logged<F>(PhantomData<F>);
+
+impl<F: FileSystem> FileSystem for Logged<F> {
+    fn open(name: &Utf8CStr, userdata: *mut c_void) -> Result<(*mut c_void, c_uint)> {
+        let result = F::open(name, userdata);
+
+        let mut log = log();
+        let index = log.file(name);
+        let stats = &mut log.files[index];
+        FileLog::record_error(stats, &result);
+        match result {
+            Ok((handle, _)) => {
+                stats.opens += 1;
+                log.handles.push((handle as usize, index));
+            }
+            Err(_) => stats.open_errors += 1,
+        }
+        result
+    }
+
+    fn close(handle: *mut c_void, userdata: *mut c_void) -> Result<()> {
+        let result = F::close(handle, userdata);
+
+        let mut log = log();
+        if let Some(stats) = log.handle(handle) {
+            FileLog::record_error(stats, &result);
+        }
+        log.handles.retain(|(h, _)| *h != handle as usize);
+        result
+    }
+}
+
+impl<F: FileSystemSync> FileSystemSync for Logged<F> {
+    fn read(handle: *mut c_void, userdata: *mut c_void, buffer: FileBuffer<'_>) -> Result<()> {
+        let FileBuffer {
+            buffer, written, ..
+        } = buffer;
+        let result = F::read(
+            handle,
+            userdata,
+            FileBuffer {
+                buffer: &mut *buffer,
+                written: &mut *written,
+                _guard: None,
+            },
+        );
+
+        if let Some(stats) = log().handle(handle) {
+            stats.reads += 1;
+            stats.bytes_read += u64::from(*written);
+            FileLog::record_error(stats, &result);
+        }
+        result
+    }
+
+    fn seek(handle: *mut c_void, userdata: *mut c_void, position: c_uint) -> Result<()> {
+        let result = F::seek(handle, userdata, position);
+
+        if let Some(stats) = log().handle(handle) {
+            stats.seeks += 1;
+            FileLog::record_error(stats, &result);
+        }
+        result
+    }
+}
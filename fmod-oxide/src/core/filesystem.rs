@@ -6,12 +6,14 @@
 
 use fmod_sys::*;
 
-use crate::{FmodResultExt, Result};
+use crate::{Error, FmodResultExt, Result};
 use lanyard::Utf8CStr;
 use std::ffi::{c_char, c_int, c_uint, c_void};
+use std::sync::atomic::{AtomicPtr, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 
 #[cfg(doc)]
-use crate::{Error, System};
+use crate::System;
 
 // I was lost on how to do this for a while, so I took some pointers from https://github.com/CAD97/fmod-rs/blob/main/crates/fmod-rs/src/core/common/file.rs#L181
 // It's not copied verbatim, I made some different design choices (like opting to make handle be a *mut c_void instead)
@@ -40,8 +42,10 @@ pub trait FileSystem {
 /// It's a lot like [`std::io::Cursor`].
 #[derive(Debug)]
 pub struct FileBuffer<'a> {
-    buffer: &'a mut [u8],
-    written: &'a mut u32,
+    pub(crate) buffer: &'a mut [u8],
+    pub(crate) written: &'a mut u32,
+    // keeps an async read from being cancelled while its buffer is being filled
+    pub(crate) _guard: Option<MutexGuard<'a, TokenFlags>>,
 }
 
 impl FileBuffer<'_> {
@@ -94,70 +98,180 @@ pub trait FileSystemSync: FileSystem {
     fn seek(handle: *mut c_void, userdata: *mut c_void, position: c_uint) -> Result<()>;
 }
 
+/// Tells an in-flight [`FileSystemAsync::read`] that FMOD no longer wants the data.
+///
+/// FMOD cancels reads when it is done with a file, such as when a stream is released.
+/// The cancel callback reports the read to FMOD as [`Error::FileDiskEjected`] right away instead of waiting on servicing threads,
+/// so from then on the read's [`AsyncReadInfo`] no longer touches FMOD's memory:
+/// its buffer is empty and [`AsyncReadInfo::complete`] reports nothing.
+///
+/// Get it with [`AsyncReadInfo::token`].
+#[derive(Debug, Clone)]
+pub struct CancellationToken {
+    state: Arc<TokenState>,
+}
+
+#[derive(Debug, Default)]
+struct TokenState {
+    flags: Mutex<TokenFlags>,
+    // kept here rather than in FMOD_ASYNCREADINFO::userdata, which FMOD frees once the read is cancelled
+    userdata: AtomicPtr<c_void>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct TokenFlags {
+    cancelled: bool,
+    reported: bool,
+}
+
+impl CancellationToken {
+    fn new() -> Self {
+        Self {
+            state: Arc::default(),
+        }
+    }
+
+    fn flags(&self) -> MutexGuard<'_, TokenFlags> {
+        // the flags are plain bools, so there's nothing a panic could leave half updated
+        self.state
+            .flags
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Whether FMOD has cancelled the read.
+    pub fn is_cancelled(&self) -> bool {
+        self.flags().cancelled
+    }
+}
+
+// reads FMOD hasn't been told about yet, keyed by their raw read info.
+// whoever reports a read removes it while holding the read's flags, so only one of them ever does
+static PENDING_READS: Mutex<Vec<(usize, CancellationToken)>> = Mutex::new(Vec::new());
+
+fn pending_reads() -> MutexGuard<'static, Vec<(usize, CancellationToken)>> {
+    // reads are only pushed and removed whole
+    PENDING_READS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn pending_read(raw: *mut FMOD_ASYNCREADINFO) -> Option<CancellationToken> {
+    pending_reads()
+        .iter()
+        .find(|(key, _)| *key == raw as usize)
+        .map(|(_, token)| token.clone())
+}
+
+fn forget_read(raw: *mut FMOD_ASYNCREADINFO) {
+    let mut reads = pending_reads();
+    if let Some(index) = reads.iter().position(|(key, _)| *key == raw as usize) {
+        reads.swap_remove(index);
+    }
+}
+
 /// Information about a single asynchronous file operation.
+///
+/// Dropping this without calling [`AsyncReadInfo::complete`] or [`AsyncReadInfo::finish`] abandons the read,
+/// FMOD will cancel it once it's done with the file.
 #[derive(Debug)]
 pub struct AsyncReadInfo {
     raw: *mut FMOD_ASYNCREADINFO,
+    token: CancellationToken,
+    handle: *mut c_void,
+    offset: c_uint,
+    size: c_uint,
+    priority: c_int,
+    // stands in for FMOD's bytesread once the read is cancelled
+    cancelled_written: u32,
 }
 
 impl AsyncReadInfo {
+    unsafe fn new(raw: *mut FMOD_ASYNCREADINFO, token: CancellationToken) -> Self {
+        let info = unsafe { *raw };
+        Self {
+            raw,
+            token,
+            handle: info.handle,
+            offset: info.offset,
+            size: info.sizebytes,
+            priority: info.priority,
+            cancelled_written: 0,
+        }
+    }
+
     /// File handle that was returned in [`FileSystem::open`].
     pub fn handle(&self) -> *mut c_void {
-        unsafe { *self.raw }.handle
+        self.handle
     }
 
     /// Offset within the file where the read operation should occur.
     pub fn offset(&self) -> c_uint {
-        unsafe { *self.raw }.offset
+        self.offset
     }
 
     /// Number of bytes to read.
     pub fn size(&self) -> c_uint {
-        unsafe { *self.raw }.sizebytes
+        self.size
     }
 
     /// Priority hint for how quickly this operation should be serviced where 0 represents low importance and 100 represents extreme importance.
     /// This could be used to prioritize the read order of a file job queue for example.
     /// FMOD decides the importance of the read based on if it could degrade audio or not.
     pub fn priority(&self) -> c_int {
-        unsafe { *self.raw }.priority
+        self.priority
     }
 
     /// User value associated with this async operation, passed to [`FileSystemAsync::cancel`].
     pub fn userdata(&self) -> *mut c_void {
-        unsafe { *self.raw }.userdata
+        self.token.state.userdata.load(Ordering::Acquire)
     }
 
     /// Set the user value associated with this async operation.
-    ///
-    /// # Safety
-    ///
-    /// You cannot call this while a [`AsyncCancelInfo`] with the same raw pointer is live.
-    // FIXME: make this safe somehow?
-    pub unsafe fn set_userdata(&mut self, userdata: *mut c_void) {
-        unsafe { *self.raw }.userdata = userdata;
+    pub fn set_userdata(&mut self, userdata: *mut c_void) {
+        self.token.state.userdata.store(userdata, Ordering::Release);
     }
 
     /// Get the raw pointer associated with this [`AsyncReadInfo`].
+    ///
+    /// FMOD frees it once the read is cancelled, see [`CancellationToken`].
     pub fn raw(&self) -> *mut FMOD_ASYNCREADINFO {
         self.raw
     }
 
     /// Number of bytes currently read.
     pub fn written(&self) -> c_uint {
+        let flags = self.token.flags();
+        if flags.cancelled {
+            return self.cancelled_written;
+        }
         unsafe { *self.raw }.bytesread
     }
 
     /// Get the [`FileBuffer`] associated with this [`AsyncReadInfo`].
-    // Normally this would be really unsafe because FMOD hands out the same *mut FMOD_ASYNCREADINFO to `read()` and `cancel()`.
-    // AsyncCancelInfo doesn't support accessing the buffer, so this should be safe.
+    ///
+    /// FMOD's cancel callback waits while the buffer is alive, so fill it and drop it rather than holding on to it.
+    /// Once the read is cancelled the buffer is empty.
     pub fn buffer(&mut self) -> FileBuffer<'_> {
+        let flags = self.token.flags();
+        if flags.cancelled {
+            return FileBuffer {
+                buffer: &mut [],
+                written: &mut self.cancelled_written,
+                _guard: Some(flags),
+            };
+        }
         let ptr = unsafe { *self.raw }.buffer;
-        let len = self.size();
-
-        let buffer = unsafe { std::slice::from_raw_parts_mut(ptr.cast(), len as usize) };
+        let buffer = unsafe { std::slice::from_raw_parts_mut(ptr.cast(), self.size as usize) };
         let written = &mut unsafe { &mut *self.raw }.bytesread;
-        FileBuffer { buffer, written }
+        FileBuffer {
+            buffer,
+            written,
+            _guard: Some(flags),
+        }
+    }
+
+    /// The token FMOD cancels this read through.
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
     }
 
     /// Signal the async read is done.
@@ -169,12 +283,155 @@ impl AsyncReadInfo {
     /// If you have a [`AsyncCancelInfo`] with the same raw pointer, it is immediately invalid after calling this function.
     // I *really* don't like taking a result like this, but I can't think of another way...
     pub unsafe fn finish(self, result: Result<()>) {
+        self.complete(result);
+    }
+
+    /// Signal the async read is done, unless it has been cancelled.
+    ///
+    /// Unlike [`AsyncReadInfo::finish`] this is safe to call at any time:
+    /// it is coordinated with FMOD's cancel callback through the read's [`CancellationToken`],
+    /// so a cancelled read is never reported to FMOD.
+    ///
+    /// Returns `false` if the read was cancelled.
+    pub fn complete(self, result: Result<()>) -> bool {
+        // hold the flags while reporting, so a cancel can't slip in between checking and reporting
+        let mut flags = self.token.flags();
+        if flags.cancelled || flags.reported {
+            return false;
+        }
         let mut fmod_result = FMOD_RESULT::from_result(result);
-        if fmod_result == FMOD_RESULT::FMOD_OK && self.written() < self.size() {
+        if fmod_result == FMOD_RESULT::FMOD_OK && unsafe { *self.raw }.bytesread < self.size {
             fmod_result = FMOD_RESULT::FMOD_ERR_FILE_EOF;
         }
-        // Should never be null
-        unsafe { (*self.raw).done.unwrap_unchecked()(self.raw, fmod_result) }
+        unsafe { report(self.raw, &mut flags, fmod_result) };
+        true
+    }
+}
+
+// reports the read to FMOD, after which the raw read info must not be touched again
+unsafe fn report(raw: *mut FMOD_ASYNCREADINFO, flags: &mut TokenFlags, result: FMOD_RESULT) {
+    flags.reported = true;
+    forget_read(raw);
+    // Should never be null
+    unsafe { (*raw).done.unwrap_unchecked()(raw, result) }
+}
+
+/// An [`AsyncReadInfo`] that is guaranteed to be reported back to FMOD exactly once.
+///
+/// Create with [`AsyncReadInfo::into_request`]. Fill [`AsyncReadRequest::buffer`], say how much was filled with
+/// [`AsyncReadRequest::bytes_read`], then call [`AsyncReadRequest::complete`].
+///
+/// Dropping a request without completing it prints a warning and reports [`Error::FileBad`],
+/// so FMOD is never left waiting on a read that got lost.
+/// Cancelled requests are never reported, see [`CancellationToken`].
+#[derive(Debug)]
+pub struct AsyncReadRequest {
+    info: Option<AsyncReadInfo>,
+}
+
+/// The buffer of an [`AsyncReadRequest`], see [`AsyncReadRequest::buffer`].
+#[derive(Debug)]
+pub struct AsyncReadBuffer<'a> {
+    buffer: &'a mut [u8],
+    _guard: MutexGuard<'a, TokenFlags>,
+}
+
+impl std::ops::Deref for AsyncReadBuffer<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.buffer
+    }
+}
+
+impl std::ops::DerefMut for AsyncReadBuffer<'_> {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        self.buffer
+    }
+}
+
+impl AsyncReadInfo {
+    /// Wraps this read in an [`AsyncReadRequest`].
+    pub fn into_request(self) -> AsyncReadRequest {
+        AsyncReadRequest { info: Some(self) }
+    }
+}
+
+impl AsyncReadRequest {
+    fn info(&self) -> &AsyncReadInfo {
+        // only taken by complete and drop, which consume the request
+        self.info.as_ref().expect("request was already completed")
+    }
+
+    /// File handle that was returned in [`FileSystem::open`].
+    pub fn handle(&self) -> *mut c_void {
+        self.info().handle()
+    }
+
+    /// Offset within the file where the read should start.
+    pub fn offset(&self) -> c_uint {
+        self.info().offset()
+    }
+
+    /// Number of bytes to read.
+    pub fn size(&self) -> c_uint {
+        self.info().size()
+    }
+
+    /// Priority hint for how quickly this read should be serviced, from 0 (low) to 100 (extreme).
+    pub fn priority(&self) -> c_int {
+        self.info().priority()
+    }
+
+    /// Whether FMOD has cancelled the read, in which case there is no point servicing it.
+    pub fn is_cancelled(&self) -> bool {
+        self.info().token().is_cancelled()
+    }
+
+    /// The buffer to read into, [`AsyncReadRequest::size`] bytes long, or empty once the read is cancelled.
+    ///
+    /// FMOD's cancel callback waits while the buffer is alive, so fill it and drop it rather than holding on to it.
+    pub fn buffer(&mut self) -> AsyncReadBuffer<'_> {
+        let info = self.info();
+        let guard = info.token.flags();
+        let buffer = if guard.cancelled {
+            &mut []
+        } else {
+            let ptr = unsafe { *info.raw }.buffer;
+            unsafe { std::slice::from_raw_parts_mut(ptr.cast(), info.size() as usize) }
+        };
+        AsyncReadBuffer {
+            buffer,
+            _guard: guard,
+        }
+    }
+
+    /// Sets how many bytes of [`AsyncReadRequest::buffer`] were filled, clamped to [`AsyncReadRequest::size`].
+    pub fn bytes_read(&mut self, count: c_uint) {
+        let info = self.info();
+        let count = count.min(info.size());
+        if !info.token.flags().cancelled {
+            unsafe { (*info.raw).bytesread = count };
+        }
+    }
+
+    /// Reports the read to FMOD.
+    ///
+    /// If fewer bytes than requested were read, [`Error::FileEof`] is reported instead of success.
+    /// Returns `false` if the read was cancelled, and so not reported.
+    pub fn complete(mut self, result: Result<()>) -> bool {
+        self.info.take().is_some_and(|info| info.complete(result))
+    }
+}
+
+impl Drop for AsyncReadRequest {
+    fn drop(&mut self) {
+        if let Some(info) = self.info.take() {
+            if !info.token().is_cancelled() {
+                eprintln!("warning: async read request dropped without being completed");
+            }
+            info.complete(Err(Error::FileBad));
+        }
     }
 }
 
@@ -182,6 +439,7 @@ impl AsyncReadInfo {
 #[derive(Debug)]
 pub struct AsyncCancelInfo {
     raw: *mut FMOD_ASYNCREADINFO,
+    userdata: *mut c_void,
 }
 
 impl AsyncCancelInfo {
@@ -207,9 +465,9 @@ impl AsyncCancelInfo {
         unsafe { *self.raw }.priority
     }
 
-    /// User value associated with this async operation, passed to [`FileSystemAsync::cancel`].
+    /// User value associated with this async operation, set with [`AsyncReadInfo::set_userdata`].
     pub fn userdata(&self) -> *mut c_void {
-        unsafe { *self.raw }.userdata
+        self.userdata
     }
 
     /// Get the raw pointer associated with this [`AsyncCancelInfo`].
@@ -244,6 +502,8 @@ pub unsafe trait FileSystemAsync: FileSystem {
     /// The read request can be queued or stored and this callback can return immediately with [`Ok`].
     /// Returning an error at this point will cause FMOD to stop what it was doing and return back to the caller.
     /// If it is from FMOD's stream thread, the stream will typically stop.
+    ///
+    /// [`AsyncReadInfo::token`] is cancelled when FMOD cancels the read, see [`CancellationToken`].
     fn read(info: AsyncReadInfo, userdata: *mut c_void) -> Result<()>;
 
     /// Callback for cancelling a pending asynchronous read.
@@ -251,7 +511,9 @@ pub unsafe trait FileSystemAsync: FileSystem {
     /// This callback is called to stop/release or shut down the resource that is holding the file,
     /// for example: releasing a Sound stream.
     ///
-    /// Before returning from this callback the implementation must ensure that all references to info are relinquished.
+    /// The read's [`CancellationToken`] is cancelled before this is called,
+    /// and once it returns the read is reported to FMOD as cancelled without waiting on the servicing thread.
+    /// Reads still waiting in a queue can simply be dropped from it here.
     fn cancel(info: AsyncCancelInfo, userdata: *mut c_void) -> Result<()>;
 }
 
@@ -292,6 +554,7 @@ pub(crate) unsafe extern "C" fn filesystem_read<F: FileSystemSync>(
         FileBuffer {
             buffer: std::slice::from_raw_parts_mut(buffer.cast(), size_bytes as usize),
             written: &mut *bytes_read,
+            _guard: None,
         }
     };
     if let Err(e) = F::read(handle, userdata, buffer) {
@@ -318,7 +581,24 @@ pub(crate) unsafe extern "C" fn async_filesystem_read<F: FileSystemAsync>(
     raw: *mut FMOD_ASYNCREADINFO,
     userdata: *mut c_void,
 ) -> FMOD_RESULT {
-    let result = F::read(AsyncReadInfo { raw }, userdata);
+    let token = CancellationToken::new();
+    token
+        .state
+        .userdata
+        .store(unsafe { *raw }.userdata, Ordering::Relaxed);
+    pending_reads().push((raw as usize, token.clone()));
+
+    let info = unsafe { AsyncReadInfo::new(raw, token.clone()) };
+    let result = F::read(info, userdata);
+    if result.is_err() {
+        // FMOD neither waits on nor cancels a read that failed to start
+        let mut flags = token.flags();
+        if !flags.reported {
+            flags.cancelled = true;
+            flags.reported = true;
+            forget_read(raw);
+        }
+    }
     FMOD_RESULT::from_result(result)
 }
 
@@ -326,6 +606,25 @@ pub(crate) unsafe extern "C" fn async_filesystem_cancel<F: FileSystemAsync>(
     raw: *mut FMOD_ASYNCREADINFO,
     userdata: *mut c_void,
 ) -> FMOD_RESULT {
-    let result = F::cancel(AsyncCancelInfo { raw }, userdata);
+    // a read that was reported before (or while) FMOD cancelled it has nothing left to cancel
+    let Some(token) = pending_read(raw) else {
+        return FMOD_RESULT::FMOD_OK;
+    };
+    // this only waits for a servicing thread that is filling the buffer right now
+    token.flags().cancelled = true;
+
+    let cancel_info = AsyncCancelInfo {
+        raw,
+        userdata: token.state.userdata.load(Ordering::Acquire),
+    };
+    let result = F::cancel(cancel_info, userdata);
+
+    let mut flags = token.flags();
+    if !flags.reported {
+        flags.reported = true;
+        forget_read(raw);
+        unsafe { (*raw).done.unwrap_unchecked()(raw, FMOD_RESULT::FMOD_ERR_FILE_DISKEJECTED) };
+    }
+    drop(flags);
     FMOD_RESULT::from_result(result)
 }
@@ -6,6 +6,8 @@
 
 use fmod_sys::*;
 
+use crate::SpeakerMode;
+
 bitflags::bitflags! {
   /// Configuration flags used when initializing the System object.
   #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -166,6 +168,7 @@ impl From<ThreadAffinity> for FMOD_THREAD_AFFINITY {
 bitflags::bitflags! {
   /// [`Sound`] description bitfields.
   #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+  #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
   pub struct Mode: FMOD_MODE {
     /// Default for all modes listed below.
     const DEFAULT                   = FMOD_DEFAULT;
@@ -340,6 +343,24 @@ impl From<ChannelMask> for FMOD_CHANNELMASK {
     }
 }
 
+impl ChannelMask {
+    /// The channel mask matching the speakers of `mode`.
+    ///
+    /// Returns [`None`] for [`SpeakerMode::Default`] and [`SpeakerMode::Raw`],
+    /// and for [`SpeakerMode::SevenPointOneFour`] as there are no flags for the height speakers.
+    pub fn from_speaker_mode(mode: SpeakerMode) -> Option<Self> {
+        match mode {
+            SpeakerMode::Default | SpeakerMode::Raw | SpeakerMode::SevenPointOneFour => None,
+            SpeakerMode::Mono => Some(ChannelMask::MONO),
+            SpeakerMode::Stereo => Some(ChannelMask::STEREO),
+            SpeakerMode::Quad => Some(ChannelMask::QUAD),
+            SpeakerMode::Surround => Some(ChannelMask::SURROUND),
+            SpeakerMode::FivePointOne => Some(ChannelMask::_5POINT1),
+            SpeakerMode::SevenPointOne => Some(ChannelMask::_7POINT1),
+        }
+    }
+}
+
 bitflags::bitflags! {
   /// Flags that provide additional information about a particular driver.
   #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
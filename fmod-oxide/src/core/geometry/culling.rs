@@ -0,0 +1,149 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::c_float;
+
+use crate::Result;
+use crate::{Geometry, System, Vector};
+
+#[derive(Debug)]
+struct Culled {
+    geometry: Geometry,
+    radius: c_float,
+    active: Option<bool>,
+}
+
+/// Activates [`Geometry`] objects near the listeners and deactivates the rest.
+///
+/// Occlusion gets slower the more polygons are active, so large levels are usually split into many [`Geometry`] objects
+/// and only the ones near a listener are kept active.
+/// Objects whose bounding sphere is further than the activation range from every listener are deactivated with [`Geometry::set_active`].
+///
+/// Call [`GeometryCuller::update`] once per frame, after setting the listener attributes.
+#[derive(Debug)]
+pub struct GeometryCuller {
+    system: System,
+    range: c_float,
+    geometries: Vec<Culled>,
+}
+
+impl GeometryCuller {
+    /// Creates a culler whose activation range is the max world size set with [`System::set_geometry_settings`].
+    pub fn new(system: System) -> Result<Self> {
+        let range = system.get_geometry_settings()?;
+        Ok(Self::with_range(system, range))
+    }
+
+    /// Creates a culler that keeps objects within `range` of a listener active.
+    pub fn with_range(system: System, range: c_float) -> Self {
+        Self {
+            system,
+            range,
+            geometries: Vec::new(),
+        }
+    }
+
+    /// Sets the activation range.
+    pub fn set_range(&mut self, range: c_float) {
+        self.range = range;
+    }
+
+    /// The activation range.
+    pub fn range(&self) -> c_float {
+        self.range
+    }
+
+    /// Starts managing `geometry`, computing its bounding radius from its polygons and scale.
+    ///
+    /// The radius is only computed once, so add the object after its polygons and scale have been set.
+    pub fn add(&mut self, geometry: Geometry) -> Result<()> {
+        let radius = bounding_radius(geometry)?;
+        self.add_with_radius(geometry, radius);
+        Ok(())
+    }
+
+    /// Starts managing `geometry`, treating it as a sphere of `radius` around its position.
+    pub fn add_with_radius(&mut self, geometry: Geometry, radius: c_float) {
+        self.remove(geometry);
+        self.geometries.push(Culled {
+            geometry,
+            radius,
+            // unknown until the first update, which will then always set it
+            active: None,
+        });
+    }
+
+    /// Stops managing `geometry`. Its active state is left as it is.
+    pub fn remove(&mut self, geometry: Geometry) {
+        self.geometries.retain(|c| c.geometry != geometry);
+    }
+
+    /// Number of objects being managed.
+    pub fn len(&self) -> usize {
+        self.geometries.len()
+    }
+
+    /// Whether no objects are being managed.
+    pub fn is_empty(&self) -> bool {
+        self.geometries.is_empty()
+    }
+
+    /// Number of managed objects that were active after the last update.
+    pub fn active_count(&self) -> usize {
+        self.geometries
+            .iter()
+            .filter(|c| c.active == Some(true))
+            .count()
+    }
+
+    /// Activates objects in range of a listener and deactivates the rest.
+    ///
+    /// [`Geometry::set_active`] is only called for objects whose state changed.
+    /// Returns the number of active objects.
+    pub fn update(&mut self) -> Result<usize> {
+        let listeners = (0..self.system.get_3d_listener_count()?)
+            .map(|listener| {
+                let (position, ..) = self.system.get_3d_listener_attributes(listener)?;
+                Ok(position)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        for culled in &mut self.geometries {
+            let position = culled.geometry.get_position()?;
+            let reach = self.range + culled.radius;
+            let active = listeners
+                .iter()
+                .any(|&listener| distance_squared(position, listener) <= reach * reach);
+            if culled.active != Some(active) {
+                culled.geometry.set_active(active)?;
+                culled.active = Some(active);
+            }
+        }
+        Ok(self.active_count())
+    }
+}
+
+fn distance_squared(a: Vector, b: Vector) -> c_float {
+    let (x, y, z) = (a.x - b.x, a.y - b.y, a.z - b.z);
+    x * x + y * y + z * z
+}
+
+fn bounding_radius(geometry: Geometry) -> Result<c_float> {
+    let scale = geometry.get_scale()?;
+    let mut radius_squared: c_float = 0.0;
+    for polygon in 0..geometry.get_polygon_count()? {
+        for vertex in 0..geometry.get_polygon_vertex_count(polygon)? {
+            let v = geometry.get_polygon_vertex(polygon, vertex)?;
+            let scaled = Vector {
+                x: v.x * scale.x,
+                y: v.y * scale.y,
+                z: v.z * scale.z,
+            };
+            radius_squared = radius_squared.max(distance_squared(scaled, Vector::default()));
+        }
+    }
+    Ok(radius_squared.sqrt())
+}
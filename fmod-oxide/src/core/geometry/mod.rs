@@ -8,9 +8,13 @@ use std::ptr::NonNull;
 
 use fmod_sys::*;
 
+mod culling;
 mod general;
 mod polygons;
 mod spatialization;
+mod template;
+pub use culling::GeometryCuller;
+pub use template::{GeometryTemplate, TemplatePolygon, Transform};
 
 /// An interface that allows the setup and modification of geometry for occlusion.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
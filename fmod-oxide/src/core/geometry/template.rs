@@ -0,0 +1,182 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::{c_float, c_int};
+
+use crate::Result;
+use crate::{Geometry, System, Vector};
+
+/// The position, orientation and scale of a [`Geometry`] object.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Transform {
+    /// Position in world space.
+    pub position: Vector,
+    /// Forwards orientation, must be of unit length and perpendicular to `up`.
+    pub forward: Vector,
+    /// Upwards orientation, must be of unit length and perpendicular to `forward`.
+    pub up: Vector,
+    /// Scale along each axis.
+    pub scale: Vector,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self {
+            position: Vector::default(),
+            forward: Vector {
+                x: 0.0,
+                y: 0.0,
+                z: 1.0,
+            },
+            up: Vector {
+                x: 0.0,
+                y: 1.0,
+                z: 0.0,
+            },
+            scale: Vector {
+                x: 1.0,
+                y: 1.0,
+                z: 1.0,
+            },
+        }
+    }
+}
+
+impl Geometry {
+    /// Sets the position, rotation and scale of the object at once.
+    ///
+    /// See [`Geometry::set_position`], [`Geometry::set_rotation`] and [`Geometry::set_scale`].
+    pub fn set_transform(&self, transform: &Transform) -> Result<()> {
+        self.set_position(transform.position)?;
+        self.set_rotation(transform.forward, transform.up)?;
+        self.set_scale(transform.scale)
+    }
+
+    /// Retrieves the position, rotation and scale of the object.
+    pub fn get_transform(&self) -> Result<Transform> {
+        let (forward, up) = self.get_rotation()?;
+        Ok(Transform {
+            position: self.get_position()?,
+            forward,
+            up,
+            scale: self.get_scale()?,
+        })
+    }
+}
+
+/// A polygon stored in a [`GeometryTemplate`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TemplatePolygon {
+    /// Occlusion factor of the polygon for the direct path, where 0 is no occlusion and 1 is full occlusion.
+    pub direct_occlusion: c_float,
+    /// Occlusion factor of the polygon for the reverb path, where 0 is no occlusion and 1 is full occlusion.
+    pub reverb_occlusion: c_float,
+    /// Whether the polygon occludes from both sides.
+    pub double_sided: bool,
+    /// Vertices of the polygon, in object space.
+    pub vertices: Vec<Vector>,
+}
+
+/// A set of polygons that is stored once and instanced into many [`Geometry`] objects.
+///
+/// Useful when the same occluder shape (a building, a wall segment) appears all over a level:
+/// build the template once from level or navmesh data,
+/// then call [`GeometryTemplate::instantiate`] with each placement's [`Transform`].
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GeometryTemplate {
+    polygons: Vec<TemplatePolygon>,
+}
+
+impl GeometryTemplate {
+    /// Creates an empty template.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Captures the polygons of an existing [`Geometry`] object.
+    pub fn from_geometry(geometry: Geometry) -> Result<Self> {
+        let mut template = Self::new();
+        for index in 0..geometry.get_polygon_count()? {
+            let (direct_occlusion, reverb_occlusion, double_sided) =
+                geometry.get_polygon_attributes(index)?;
+            let vertices = (0..geometry.get_polygon_vertex_count(index)?)
+                .map(|vertex| geometry.get_polygon_vertex(index, vertex))
+                .collect::<Result<_>>()?;
+            template.polygons.push(TemplatePolygon {
+                direct_occlusion,
+                reverb_occlusion,
+                double_sided,
+                vertices,
+            });
+        }
+        Ok(template)
+    }
+
+    /// Adds a polygon to the template.
+    ///
+    /// The same rules as [`Geometry::add_polygon`] apply: the polygon must be convex and all vertices must lay in the same plane.
+    pub fn add_polygon(
+        &mut self,
+        direct_occlusion: c_float,
+        reverb_occlusion: c_float,
+        double_sided: bool,
+        vertices: impl Into<Vec<Vector>>,
+    ) -> &mut Self {
+        self.polygons.push(TemplatePolygon {
+            direct_occlusion,
+            reverb_occlusion,
+            double_sided,
+            vertices: vertices.into(),
+        });
+        self
+    }
+
+    /// The polygons in this template.
+    pub fn polygons(&self) -> &[TemplatePolygon] {
+        &self.polygons
+    }
+
+    /// Number of polygons in this template.
+    pub fn polygon_count(&self) -> c_int {
+        self.polygons.len() as c_int
+    }
+
+    /// Total number of vertices across every polygon in this template.
+    pub fn vertex_count(&self) -> c_int {
+        self.polygons
+            .iter()
+            .map(|p| p.vertices.len())
+            .sum::<usize>() as c_int
+    }
+
+    /// Creates a [`Geometry`] object containing the polygons of this template, placed with `transform`.
+    ///
+    /// The object is sized to fit exactly the polygons of this template.
+    /// If adding a polygon fails the object is released and the error returned.
+    pub fn instantiate(&self, system: System, transform: &Transform) -> Result<Geometry> {
+        let geometry = system.create_geometry(self.polygon_count(), self.vertex_count())?;
+        if let Err(e) = self.fill(geometry, transform) {
+            let _ = geometry.release();
+            return Err(e);
+        }
+        Ok(geometry)
+    }
+
+    fn fill(&self, geometry: Geometry, transform: &Transform) -> Result<()> {
+        for polygon in &self.polygons {
+            geometry.add_polygon(
+                polygon.direct_occlusion,
+                polygon.reverb_occlusion,
+                polygon.double_sided,
+                &polygon.vertices,
+            )?;
+        }
+        geometry.set_transform(transform)
+    }
+}
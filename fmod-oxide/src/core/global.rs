@@ -0,0 +1,111 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::c_int;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard, OnceLock, PoisonError};
+
+use crate::{Error, InitFlags, Result, System, SystemBuilder};
+
+#[cfg(feature = "studio")]
+use crate::studio;
+
+static SYSTEM: OnceLock<System> = OnceLock::new();
+#[cfg(feature = "studio")]
+static STUDIO_SYSTEM: OnceLock<studio::System> = OnceLock::new();
+static SHUT_DOWN: AtomicBool = AtomicBool::new(false);
+static LIFECYCLE: Mutex<()> = Mutex::new(());
+
+fn lifecycle() -> MutexGuard<'static, ()> {
+    // the lock only serializes initialization and shutdown and guards no data, so poisoning doesn't matter
+    LIFECYCLE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn check_uninitialized() -> Result<()> {
+    #[cfg(feature = "studio")]
+    if STUDIO_SYSTEM.get().is_some() {
+        return Err(Error::Initialized);
+    }
+    if SYSTEM.get().is_some() {
+        return Err(Error::Initialized);
+    }
+    Ok(())
+}
+
+/// Builds `builder` into the global [`System`], and returns it.
+///
+/// Only one global system can ever be initialized, later calls (from any thread) return [`Error::Initialized`]
+/// without building `builder`. Calls racing each other are serialized, so exactly one of them builds its system.
+/// Once initialized, [`get`] returns the system from anywhere.
+pub fn init(
+    builder: SystemBuilder,
+    max_channels: c_int,
+    flags: InitFlags,
+) -> Result<&'static System> {
+    let _lifecycle = lifecycle();
+    check_uninitialized()?;
+    let system = builder.build(max_channels, flags)?;
+    Ok(SYSTEM.get_or_init(|| system))
+}
+
+/// Builds `builder` into the global Studio [`studio::System`], and returns it.
+///
+/// Its core system becomes the global [`System`], so [`get`] works as well.
+/// Like [`init`], only one global system can ever be initialized, Studio or not.
+#[cfg(feature = "studio")]
+pub fn init_studio(
+    builder: studio::SystemBuilder,
+    max_channels: c_int,
+    studio_flags: studio::InitFlags,
+    flags: InitFlags,
+) -> Result<&'static studio::System> {
+    let _lifecycle = lifecycle();
+    check_uninitialized()?;
+    let system = builder.build(max_channels, studio_flags, flags)?;
+    let core_system = system.get_core_system()?;
+    SYSTEM.get_or_init(|| core_system);
+    Ok(STUDIO_SYSTEM.get_or_init(|| system))
+}
+
+/// The global [`System`], or [`None`] if it hasn't been initialized or has been shut down.
+pub fn get() -> Option<&'static System> {
+    if SHUT_DOWN.load(Ordering::Acquire) {
+        return None;
+    }
+    SYSTEM.get()
+}
+
+/// The global Studio [`studio::System`], or [`None`] if it hasn't been initialized with [`init_studio`] or has been shut down.
+#[cfg(feature = "studio")]
+pub fn get_studio() -> Option<&'static studio::System> {
+    if SHUT_DOWN.load(Ordering::Acquire) {
+        return None;
+    }
+    STUDIO_SYSTEM.get()
+}
+
+/// Tears down the global system with [`crate::shutdown`], or [`studio::shutdown`] if it is a Studio system.
+///
+/// From the moment this is called [`get`] returns [`None`], even if tearing down fails.
+/// The global system can't be initialized again afterwards. Does nothing if there is no global system.
+///
+/// # Safety
+///
+/// See [`System::release`]. Handles previously returned by [`get`] must not be used anymore.
+pub unsafe fn shutdown() -> Result<()> {
+    let _lifecycle = lifecycle();
+    let Some(&system) = SYSTEM.get() else {
+        return Ok(());
+    };
+    if SHUT_DOWN.swap(true, Ordering::AcqRel) {
+        return Ok(());
+    }
+    #[cfg(feature = "studio")]
+    if let Some(&studio_system) = STUDIO_SYSTEM.get() {
+        return unsafe { studio::shutdown(studio_system) };
+    }
+    unsafe { crate::shutdown(system) }
+}
@@ -6,7 +6,13 @@
 
 use crate::{Error, FmodResultExt, Result};
 use fmod_sys::*;
-use lanyard::{Utf8CStr, Utf8CString};
+use lanyard::Utf8CString;
+use std::ffi::c_int;
+
+/// Upper bound for [`get_string`]'s buffer, so a getter that never nul-terminates can't grow it forever.
+///
+/// Strings that still don't fit are reported as [`Error::Truncated`].
+const MAX_STRING_BUFFER_LEN: usize = 1 << 20;
 
 pub(crate) fn get_string(
     mut string_fn: impl FnMut(&mut [u8]) -> FMOD_RESULT,
@@ -14,44 +20,74 @@ pub(crate) fn get_string(
     // Use stack-based buffer initially.
     let mut buffer = [0; 256];
     // Initial call to get the string.
-    let mut result = string_fn(&mut buffer);
-
-    match result.to_error() {
+    match string_fn(&mut buffer).to_error() {
         // String fit in 256 bytes
-        None => {
-            let string = Utf8CStr::from_utf8_until_nul(&buffer).unwrap().to_cstring();
-            return Ok(string);
-        }
-        // Didn't fit. Try using Vec
-        Some(Error::Truncated) => {}
+        None if !fills_buffer(&buffer) => return Ok(string_from_buffer(&buffer)),
+        // Didn't fit (some getters truncate without reporting it). Try using Vec
+        None | Some(Error::Truncated) => {}
         Some(e) => return Err(e),
     }
 
-    let mut buffer = vec![0u8; 256];
+    let mut buffer = vec![0u8; buffer.len()];
     // If the buffer is too small, resize it and try again.
-    while let FMOD_RESULT::FMOD_ERR_TRUNCATED = result {
+    while buffer.len() < MAX_STRING_BUFFER_LEN {
         buffer.resize(buffer.len() * 2, 0);
-        result = string_fn(&mut buffer);
+        match string_fn(&mut buffer).to_error() {
+            None if !fills_buffer(&buffer) => return Ok(string_from_buffer(&buffer)),
+            None | Some(Error::Truncated) => {}
+            Some(e) => return Err(e),
+        }
     }
 
-    result.to_result()?;
+    // Still didn't fit, report it rather than hand out a silently truncated string.
+    Err(Error::Truncated)
+}
 
-    let string = Utf8CStr::from_utf8_until_nul(&buffer).unwrap().to_cstring();
-    Ok(string)
+/// Like [`get_string`], but reads at most `max_len` bytes (excluding the nul terminator) in a single call.
+///
+/// Truncation is not an error, and a partial UTF-8 sequence left at the end by truncation is dropped.
+/// Returns [`Error::InvalidParam`] if the buffer would be longer than FMOD can take.
+pub(crate) fn get_string_bounded(
+    max_len: usize,
+    mut string_fn: impl FnMut(&mut [u8]) -> FMOD_RESULT,
+) -> Result<Utf8CString> {
+    let len = max_len
+        .checked_add(1)
+        .filter(|&len| len <= c_int::MAX as usize)
+        .ok_or(Error::InvalidParam)?;
+    let mut buffer = vec![0u8; len];
+    match string_fn(&mut buffer).to_error() {
+        None | Some(Error::Truncated) => Ok(string_from_buffer(&buffer)),
+        Some(e) => Err(e),
+    }
 }
 
-pub(crate) fn string_from_utf16_le(utf16: &[u16]) -> String {
-    let iter = utf16.iter().copied().map(u16::from_le);
-    // we use char::decode_utf16 instead of String::from_utf16 because the latter would require us to collect into a Vec<u16> first
-    char::decode_utf16(iter)
-        .map(|r| r.unwrap_or(char::REPLACEMENT_CHARACTER))
-        .collect()
+/// Whether a string written into `buffer` used up all of it, which means it was probably truncated.
+fn fills_buffer(buffer: &[u8]) -> bool {
+    !buffer[..buffer.len() - 1].contains(&0)
 }
 
-pub(crate) fn string_from_utf16_be(utf16: &[u16]) -> String {
-    let iter = utf16.iter().copied().map(u16::from_be);
-    // we use char::decode_utf16 instead of String::from_utf16 because the latter would require us to collect into a Vec<u16> first
-    char::decode_utf16(iter)
-        .map(|r| r.unwrap_or(char::REPLACEMENT_CHARACTER))
-        .collect()
+/// Reads a string out of `buffer` up to the first nul (or the end of the buffer),
+/// dropping any trailing partial UTF-8 sequence left behind by truncation.
+///
+/// Invalid UTF-8 anywhere else is replaced with U+FFFD rather than cutting the string short.
+pub(crate) fn string_from_buffer(buffer: &[u8]) -> Utf8CString {
+    let len = buffer.iter().position(|&b| b == 0).unwrap_or(buffer.len());
+    let string = String::from_utf8_lossy(trim_partial_utf8(&buffer[..len]));
+    // the string was cut at the first nul, so it can't contain any
+    Utf8CString::new(string).unwrap()
+}
+
+// drops the last character of `bytes` if it is a multi-byte sequence that was cut short
+fn trim_partial_utf8(bytes: &[u8]) -> &[u8] {
+    // a sequence is at most 4 bytes, and starts at the last byte that isn't a continuation byte
+    let Some(from_end) = bytes.iter().rev().take(4).position(|&b| b & 0xC0 != 0x80) else {
+        return bytes;
+    };
+    let start = bytes.len() - 1 - from_end;
+    match std::str::from_utf8(&bytes[start..]) {
+        // no error length means the input ended in the middle of a sequence
+        Err(e) if e.error_len().is_none() => &bytes[..start],
+        _ => bytes,
+    }
 }
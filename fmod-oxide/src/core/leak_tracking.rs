@@ -0,0 +1,132 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::backtrace::Backtrace;
+use std::panic::Location;
+use std::sync::Arc;
+
+use crate::{Result, System};
+
+#[cfg(doc)]
+use crate::Owned;
+
+/// Where an [`Owned`] handle that was still alive at shutdown was created.
+///
+/// Returned by [`System::release_checked`] and [`System::outstanding_handles`].
+#[derive(Debug, Clone)]
+pub struct LeakSite {
+    /// The type of the handle, such as `fmod::Sound`.
+    pub type_name: &'static str,
+    /// The call to [`Owned::new`] (or [`From::from`]) that created it.
+    pub location: &'static Location<'static>,
+    /// A backtrace of the creation, in debug builds.
+    ///
+    /// Like [`Backtrace::capture`], this is only filled in when `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE` is set.
+    pub backtrace: Option<Arc<Backtrace>>,
+}
+
+#[cfg(feature = "leak-tracking")]
+mod registry {
+    use std::backtrace::{Backtrace, BacktraceStatus};
+    use std::panic::Location;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+    use super::LeakSite;
+
+    struct Entry {
+        id: u64,
+        // 0 when the owning system isn't known
+        system: usize,
+        site: LeakSite,
+    }
+
+    static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+    static LIVE: Mutex<Vec<Entry>> = Mutex::new(Vec::new());
+
+    fn live() -> MutexGuard<'static, Vec<Entry>> {
+        // entries are pushed and removed in single steps, so a panic can't leave the list inconsistent
+        LIVE.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub(crate) fn track(
+        type_name: &'static str,
+        system: usize,
+        location: &'static Location<'static>,
+    ) -> u64 {
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let backtrace = if cfg!(debug_assertions) {
+            let backtrace = Backtrace::capture();
+            (backtrace.status() == BacktraceStatus::Captured).then(|| Arc::new(backtrace))
+        } else {
+            None
+        };
+        live().push(Entry {
+            id,
+            system,
+            site: LeakSite {
+                type_name,
+                location,
+                backtrace,
+            },
+        });
+        id
+    }
+
+    pub(crate) fn untrack(id: u64) {
+        live().retain(|e| e.id != id);
+    }
+
+    pub(crate) fn outstanding(system: usize) -> Vec<LeakSite> {
+        live()
+            .iter()
+            .filter(|e| e.system == system || e.system == 0)
+            .map(|e| e.site.clone())
+            .collect()
+    }
+}
+
+#[cfg(feature = "leak-tracking")]
+pub(crate) use registry::{track, untrack};
+
+impl System {
+    /// Lists the [`Owned`] handles that are still alive and may belong to this system.
+    ///
+    /// Handles whose system can't be determined (such as [`Reverb3D`](crate::Reverb3D)) are always listed.
+    /// Without the `leak-tracking` feature nothing is tracked, and this always returns an empty list.
+    pub fn outstanding_handles(&self) -> Vec<LeakSite> {
+        #[cfg(feature = "leak-tracking")]
+        {
+            registry::outstanding(self.inner.as_ptr() as usize)
+        }
+        #[cfg(not(feature = "leak-tracking"))]
+        {
+            Vec::new()
+        }
+    }
+
+    /// Like [`System::release`], but refuses to release while [`Owned`] handles are still alive.
+    ///
+    /// Releasing the system invalidates every handle created from it,
+    /// so an [`Owned`] that is dropped afterwards releases freed memory, which usually crashes far away from the actual mistake.
+    /// If [`System::outstanding_handles`] is not empty, the system is left alone and those handles are returned, with where they were created.
+    /// Otherwise the system is released and an empty list is returned.
+    ///
+    /// Only [`Owned`] handles are tracked, and only with the `leak-tracking` feature enabled.
+    /// Plain handles and [`WeakHandle`](crate::WeakHandle)s are [`Copy`], so there is no way to know when they go away;
+    /// they must simply not be used after the system is released.
+    ///
+    /// # Safety
+    ///
+    /// See [`System::release`].
+    pub unsafe fn release_checked(&self) -> Result<Vec<LeakSite>> {
+        let leaks = self.outstanding_handles();
+        if leaks.is_empty() {
+            unsafe { self.release()? };
+        }
+        Ok(leaks)
+    }
+}
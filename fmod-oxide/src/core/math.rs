@@ -0,0 +1,209 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::c_float;
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::{Attributes3D, Error, Result, Vector};
+
+// how far off unit length and perpendicular orientation vectors can be before FMOD rejects them
+const ORIENTATION_TOLERANCE: c_float = 0.01;
+
+impl Vector {
+    /// Creates a new vector.
+    pub const fn new(x: c_float, y: c_float, z: c_float) -> Self {
+        Vector { x, y, z }
+    }
+
+    /// The dot product of `self` and `other`.
+    pub fn dot(self, other: Vector) -> c_float {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    /// The cross product of `self` and `other`.
+    #[must_use]
+    pub fn cross(self, other: Vector) -> Vector {
+        Vector {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
+        }
+    }
+
+    /// The length of this vector.
+    pub fn length(self) -> c_float {
+        self.dot(self).sqrt()
+    }
+
+    /// This vector scaled to unit length, or [`None`] if it is zero or non finite.
+    pub fn try_normalize(self) -> Option<Vector> {
+        let length = self.length();
+        (length > c_float::EPSILON && length.is_finite()).then(|| self * (1.0 / length))
+    }
+
+    /// This vector scaled to unit length. Zero and non finite vectors become zero.
+    #[must_use]
+    pub fn normalize(self) -> Vector {
+        self.try_normalize().unwrap_or_default()
+    }
+
+    /// Interpolates between `self` and `other` by `t`, where 0 is `self` and 1 is `other`.
+    #[must_use]
+    pub fn lerp(self, other: Vector, t: c_float) -> Vector {
+        self + (other - self) * t
+    }
+
+    /// Whether every component is neither NaN nor infinite.
+    pub fn is_finite(self) -> bool {
+        self.x.is_finite() && self.y.is_finite() && self.z.is_finite()
+    }
+}
+
+impl Add for Vector {
+    type Output = Vector;
+
+    fn add(self, rhs: Vector) -> Vector {
+        Vector::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl Sub for Vector {
+    type Output = Vector;
+
+    fn sub(self, rhs: Vector) -> Vector {
+        Vector::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl Mul<c_float> for Vector {
+    type Output = Vector;
+
+    fn mul(self, rhs: c_float) -> Vector {
+        Vector::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl Mul<Vector> for c_float {
+    type Output = Vector;
+
+    fn mul(self, rhs: Vector) -> Vector {
+        rhs * self
+    }
+}
+
+impl Div<c_float> for Vector {
+    type Output = Vector;
+
+    fn div(self, rhs: c_float) -> Vector {
+        Vector::new(self.x / rhs, self.y / rhs, self.z / rhs)
+    }
+}
+
+impl Neg for Vector {
+    type Output = Vector;
+
+    fn neg(self) -> Vector {
+        Vector::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl AddAssign for Vector {
+    fn add_assign(&mut self, rhs: Vector) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Vector {
+    fn sub_assign(&mut self, rhs: Vector) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign<c_float> for Vector {
+    fn mul_assign(&mut self, rhs: c_float) {
+        *self = *self * rhs;
+    }
+}
+
+impl From<[c_float; 3]> for Vector {
+    fn from([x, y, z]: [c_float; 3]) -> Self {
+        Vector { x, y, z }
+    }
+}
+
+impl From<Vector> for [c_float; 3] {
+    fn from(value: Vector) -> Self {
+        [value.x, value.y, value.z]
+    }
+}
+
+impl From<(c_float, c_float, c_float)> for Vector {
+    fn from((x, y, z): (c_float, c_float, c_float)) -> Self {
+        Vector { x, y, z }
+    }
+}
+
+impl Attributes3D {
+    /// Interpolates between `self` and `other` by `t`, where 0 is `self` and 1 is `other`.
+    ///
+    /// Position and velocity are interpolated linearly.
+    /// The orientation is interpolated and then re-orthonormalized,
+    /// falling back to the nearer of the two orientations if the blend is degenerate (such as when they point in opposite directions).
+    #[must_use]
+    pub fn lerp(&self, other: &Attributes3D, t: c_float) -> Attributes3D {
+        let blended = Attributes3D {
+            position: self.position.lerp(other.position, t),
+            velocity: self.velocity.lerp(other.velocity, t),
+            forward: self.forward.lerp(other.forward, t),
+            up: self.up.lerp(other.up, t),
+        };
+        blended.normalize_orientation().unwrap_or_else(|_| {
+            let nearer = if t < 0.5 { self } else { other };
+            Attributes3D {
+                forward: nearer.forward,
+                up: nearer.up,
+                ..blended
+            }
+        })
+    }
+
+    /// Makes `forward` and `up` unit length and perpendicular to each other.
+    ///
+    /// `forward` keeps its direction, and `up` is adjusted to be perpendicular to it.
+    /// Returns [`Error::InvalidVector`] if either vector is zero, non finite, or they are parallel.
+    pub fn normalize_orientation(&self) -> Result<Attributes3D> {
+        let forward = self.forward.try_normalize().ok_or(Error::InvalidVector)?;
+        let up = (self.up - forward * self.up.dot(forward))
+            .try_normalize()
+            .ok_or(Error::InvalidVector)?;
+        Ok(Attributes3D {
+            forward,
+            up,
+            ..*self
+        })
+    }
+
+    /// Checks these attributes the way FMOD does, so bad values can be caught before they are passed to FMOD.
+    ///
+    /// Returns [`Error::InvalidFloat`] if any value is NaN or infinite,
+    /// and [`Error::InvalidVector`] if `forward` and `up` are not unit length and perpendicular.
+    pub fn validated(self) -> Result<Attributes3D> {
+        if ![self.position, self.velocity, self.forward, self.up]
+            .into_iter()
+            .all(Vector::is_finite)
+        {
+            return Err(Error::InvalidFloat);
+        }
+        let unit = |v: Vector| (v.dot(v) - 1.0).abs() <= ORIENTATION_TOLERANCE;
+        if !unit(self.forward)
+            || !unit(self.up)
+            || self.forward.dot(self.up).abs() > ORIENTATION_TOLERANCE
+        {
+            return Err(Error::InvalidVector);
+        }
+        Ok(self)
+    }
+}
@@ -6,7 +6,14 @@
 
 use crate::{FmodResultExt, Result};
 use fmod_sys::*;
-use std::ffi::{c_char, c_int, c_uint, c_void};
+use std::ffi::{c_char, c_float, c_int, c_uint, c_void};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+#[cfg(doc)]
+use crate::System;
+#[cfg(feature = "studio")]
+use crate::studio;
 
 /// How you want FMOD to handle memory.
 #[derive(PartialEq, Eq, Debug)]
@@ -128,3 +135,168 @@ pub fn get_stats(blocking: bool) -> Result<(c_int, c_int)> {
     }
     Ok((current, max))
 }
+
+/// A threshold of a memory budget being crossed, passed to the hook given to [`set_budget`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemoryAlert {
+    /// The budget, in bytes.
+    pub budget: c_int,
+    /// The threshold that was crossed, as a fraction of the budget.
+    pub threshold: c_float,
+    /// Whether usage rose above `threshold`, or fell back below it.
+    pub exceeded: bool,
+    /// Bytes currently allocated by FMOD.
+    pub current: c_int,
+    /// The most bytes FMOD has had allocated at once.
+    pub max: c_int,
+    /// Memory used by each Studio bus, largest first.
+    ///
+    /// Only filled in for alerts raised from [`studio::System::update`],
+    /// and only for systems initialized with [`studio::InitFlags::MEMORY_TRACKING`].
+    #[cfg(feature = "studio")]
+    pub breakdown: Vec<BusMemory>,
+}
+
+/// The memory used by one Studio bus, in a [`MemoryAlert`] or [`studio::MemoryReport`].
+#[cfg(feature = "studio")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BusMemory {
+    /// Path of the bus, or its id if the strings bank isn't loaded.
+    pub name: String,
+    /// Memory used by the bus and everything routed into it.
+    pub usage: studio::MemoryUsage,
+}
+
+struct Budget {
+    bytes: c_int,
+    thresholds: Vec<c_float>,
+    hook: Arc<dyn Fn(&MemoryAlert) + Send + Sync>,
+    // how many thresholds usage was over when last polled
+    level: usize,
+}
+
+static BUDGET: Mutex<Option<Budget>> = Mutex::new(None);
+// lets updates skip the lock and the stats call entirely when there is no budget
+static BUDGET_SET: AtomicBool = AtomicBool::new(false);
+
+fn budget() -> MutexGuard<'static, Option<Budget>> {
+    // the budget is only ever replaced whole, so a panic can't leave it inconsistent
+    BUDGET.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The thresholds [`set_budget`] alerts at: 75%, 90% and 100% of the budget.
+pub const DEFAULT_BUDGET_THRESHOLDS: &[c_float] = &[0.75, 0.9, 1.0];
+
+/// Calls `hook` whenever the memory FMOD has allocated crosses 75%, 90% or 100% of `bytes`, in either direction.
+///
+/// Usage is polled with [`get_stats`] after every [`System::update`] and [`studio::System::update`],
+/// and the hook runs on the thread calling update, right after it returns.
+/// If usage jumps past several thresholds between two updates, the hook is only called for the highest one.
+///
+/// FMOD's memory statistics are global, so there is a single budget covering every system.
+/// Setting a budget replaces the previous one.
+pub fn set_budget(bytes: c_int, hook: impl Fn(&MemoryAlert) + Send + Sync + 'static) {
+    set_budget_with_thresholds(bytes, DEFAULT_BUDGET_THRESHOLDS, hook);
+}
+
+/// Like [`set_budget`], but alerts at `thresholds` (fractions of `bytes`) instead of [`DEFAULT_BUDGET_THRESHOLDS`].
+pub fn set_budget_with_thresholds(
+    bytes: c_int,
+    thresholds: &[c_float],
+    hook: impl Fn(&MemoryAlert) + Send + Sync + 'static,
+) {
+    let mut thresholds = thresholds.to_vec();
+    thresholds.sort_by(c_float::total_cmp);
+    *budget() = Some(Budget {
+        bytes,
+        thresholds,
+        hook: Arc::new(hook),
+        level: 0,
+    });
+    BUDGET_SET.store(true, Ordering::Relaxed);
+}
+
+/// Removes the budget set with [`set_budget`].
+pub fn clear_budget() {
+    *budget() = None;
+    BUDGET_SET.store(false, Ordering::Relaxed);
+}
+
+/// The budget set with [`set_budget`], in bytes.
+pub fn get_budget() -> Option<c_int> {
+    budget().as_ref().map(|budget| budget.bytes)
+}
+
+fn poll_budget_with(fill_breakdown: impl FnOnce(&mut MemoryAlert)) {
+    if !BUDGET_SET.load(Ordering::Relaxed) {
+        return;
+    }
+    // a failed poll shouldn't fail the update it runs after, the next one will try again
+    let Ok((current, max)) = get_stats(false) else {
+        return;
+    };
+
+    let (mut alert, hook) = {
+        let mut budget = budget();
+        let Some(budget) = budget.as_mut() else {
+            return;
+        };
+        let used = current as c_float / budget.bytes as c_float;
+        let level = budget.thresholds.iter().filter(|&&t| used >= t).count();
+        if level == budget.level {
+            return;
+        }
+        let exceeded = level > budget.level;
+        let threshold = if exceeded {
+            budget.thresholds[level - 1]
+        } else {
+            budget.thresholds[level]
+        };
+        budget.level = level;
+        let alert = MemoryAlert {
+            budget: budget.bytes,
+            threshold,
+            exceeded,
+            current,
+            max,
+            #[cfg(feature = "studio")]
+            breakdown: Vec::new(),
+        };
+        (alert, budget.hook.clone())
+    };
+    // called without the lock held, so the hook is free to change the budget
+    fill_breakdown(&mut alert);
+    hook(&alert);
+}
+
+pub(crate) fn poll_budget() {
+    poll_budget_with(|_| {});
+}
+
+#[cfg(feature = "studio")]
+pub(crate) fn poll_budget_studio(system: studio::System) {
+    poll_budget_with(|alert| alert.breakdown = bus_breakdown(system));
+}
+
+#[cfg(feature = "studio")]
+pub(crate) fn bus_breakdown(system: studio::System) -> Vec<BusMemory> {
+    let mut breakdown = Vec::new();
+    let buses = system
+        .get_bank_list()
+        .unwrap_or_default()
+        .into_iter()
+        .flat_map(|bank| bank.get_bus_list().unwrap_or_default());
+    for bus in buses {
+        // without memory tracking there is no usage to report
+        let Ok(usage) = bus.get_memory_usage() else {
+            continue;
+        };
+        let name = match bus.get_path() {
+            Ok(path) => path.into_string(),
+            Err(_) => bus.get_id().map(|id| id.to_string()).unwrap_or_default(),
+        };
+        breakdown.push(BusMemory { name, usage });
+    }
+    breakdown.sort_by(|a, b| b.usage.inclusive.cmp(&a.usage.inclusive));
+    breakdown
+}
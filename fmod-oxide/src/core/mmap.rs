@@ -0,0 +1,155 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::{c_int, c_uint, c_void};
+use std::fs::File;
+use std::io::Write;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::sync::Arc;
+
+use fmod_sys::*;
+use lanyard::Utf8CStr;
+use memmap2::Mmap;
+
+use super::sound_builder::{EMPTY_EXINFO, OwnedData};
+use crate::{Error, FileBuffer, FileSystem, FileSystemSync, Result, SoundBuilder, System};
+
+/// A read-only memory-mapped file.
+///
+/// Open a sound straight from the mapping with [`SoundBuilder::open_mapped`], so FMOD reads from the page cache without copying the file into memory first.
+/// Cloning is cheap, clones share the mapping.
+#[derive(Debug, Clone)]
+pub struct MappedFile {
+    map: Arc<Mmap>,
+}
+
+impl MappedFile {
+    /// Maps the file at `path`.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated while it is mapped, by this process or any other.
+    /// Reading a page past the new end of a truncated file raises `SIGBUS`, and modifications change data that is borrowed as immutable.
+    pub unsafe fn open(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        let file = File::open(path)?;
+        let map = unsafe { Mmap::map(&file)? };
+        Ok(Self { map: Arc::new(map) })
+    }
+
+    /// The contents of the file.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.map
+    }
+}
+
+impl SoundBuilder<'static> {
+    /// Open the sound directly from a memory-mapped file, without copying it.
+    ///
+    /// Like [`SoundBuilder::open_memory_guarded`], the builder and the created sound each keep the mapping alive until the sound is released.
+    /// The OS pages the file in as FMOD decodes it, so a large compressed sample opened with [`crate::Mode::CREATE_COMPRESSED_SAMPLE`]
+    /// is streamed from disk without ever being read into memory in full.
+    pub fn open_mapped(file: MappedFile) -> Self {
+        SoundBuilder {
+            mode: FMOD_OPENMEMORY_POINT,
+            create_sound_ex_info: FMOD_CREATESOUNDEXINFO {
+                length: file.len() as c_uint,
+                ..EMPTY_EXINFO
+            },
+            name_or_data: file.as_ptr().cast(),
+            inclusion_list: None,
+            dls_name: None,
+            encryption_key: None,
+            fsb_guid: None,
+            owned_data: vec![OwnedData::Mapped(file)],
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl Deref for MappedFile {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.map
+    }
+}
+
+struct OpenFile {
+    file: MappedFile,
+    position: usize,
+}
+
+/// A filesystem that serves files from memory maps instead of reading them.
+///
+/// Install with [`MmapFileSystem::install`]. Large streamed FSBs are then paged in by the OS as FMOD reads them,
+/// without going through a read syscall for every block.
+/// FMOD's file callbacks always fill a buffer FMOD owns, so each block is still copied once (where a read syscall would have copied it from the kernel instead).
+/// To avoid the copy entirely, open sounds with [`SoundBuilder::open_mapped`].
+///
+/// Names are treated as paths on the local filesystem.
+#[derive(Debug, Clone, Copy)]
+pub struct MmapFileSystem;
+
+impl MmapFileSystem {
+    /// Makes `system` read every file through memory maps. See [`System::set_filesystem_sync`] for `block_align`.
+    ///
+    /// # Safety
+    ///
+    /// No file FMOD opens may be modified or truncated while it is open, see [`MappedFile::open`].
+    pub unsafe fn install(system: System, block_align: c_int) -> Result<()> {
+        system.set_filesystem_sync::<MmapFiles>(block_align)
+    }
+}
+
+// the callbacks live on a private type, so the filesystem can only be installed through the unsafe `install`
+struct MmapFiles;
+
+impl MmapFiles {
+    fn file<'a>(handle: *mut c_void) -> &'a mut OpenFile {
+        // handles are only ever created by open, and FMOD doesn't use a handle from more than one thread at a time
+        unsafe { &mut *handle.cast::<OpenFile>() }
+    }
+}
+
+impl FileSystem for MmapFiles {
+    fn open(name: &Utf8CStr, _: *mut c_void) -> Result<(*mut c_void, c_uint)> {
+        // SAFETY: `install` requires files not to change while they are open
+        let file = unsafe { MappedFile::open(name.as_str()) }.map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => Error::FileNotFound,
+            _ => Error::FileBad,
+        })?;
+        let size = c_uint::try_from(file.len()).map_err(|_| Error::FileBad)?;
+        let handle = Box::into_raw(Box::new(OpenFile { file, position: 0 }));
+        Ok((handle.cast(), size))
+    }
+
+    fn close(handle: *mut c_void, _: *mut c_void) -> Result<()> {
+        drop(unsafe { Box::from_raw(handle.cast::<OpenFile>()) });
+        Ok(())
+    }
+}
+
+impl FileSystemSync for MmapFiles {
+    fn read(handle: *mut c_void, _: *mut c_void, mut buffer: FileBuffer<'_>) -> Result<()> {
+        let file = Self::file(handle);
+        let remaining = file.file.get(file.position..).unwrap_or_default();
+        let len = remaining.len().min(buffer.capacity());
+        // FileBuffer writes can't fail
+        let _ = buffer.write(&remaining[..len]);
+        file.position += len;
+        if buffer.is_full() {
+            Ok(())
+        } else {
+            Err(Error::FileEof)
+        }
+    }
+
+    fn seek(handle: *mut c_void, _: *mut c_void, position: c_uint) -> Result<()> {
+        Self::file(handle).position = position as usize;
+        Ok(())
+    }
+}
@@ -16,6 +16,9 @@ pub use reverb_3d::*;
 mod channel;
 pub use channel::*;
 
+mod callback_context;
+pub use callback_context::*;
+
 mod channel_control;
 pub use channel_control::*;
 
@@ -37,27 +40,103 @@ pub use dsp_connection::*;
 mod flags;
 pub use flags::*;
 
+mod entity_map;
+pub use entity_map::*;
+
+mod raw_handle;
+pub use raw_handle::*;
+
 mod enums;
 pub use enums::*;
 
+mod math;
 mod reverb_presets;
+mod rolloff;
+pub use rolloff::*;
 mod structs;
 pub use structs::*;
 
 mod sound_builder;
 pub use sound_builder::*;
 
+mod shutdown;
+pub use shutdown::*;
+
+mod sound_spec;
+pub use sound_spec::*;
+
+mod time_scale;
+pub use time_scale::*;
+
+mod leak_tracking;
+pub use leak_tracking::LeakSite;
+
+mod owned;
+pub use owned::*;
+
+mod weak;
+pub use weak::*;
+
+mod visualizer;
+pub use visualizer::*;
+
+mod emitter;
+pub use emitter::*;
+
+mod decibels;
+pub use decibels::*;
+
+mod audio_settings;
+pub use audio_settings::*;
+
+mod automation;
+pub use automation::*;
+
+mod ambience;
+pub use ambience::*;
+
+mod recorder;
+pub use recorder::*;
+
+mod silence;
+pub use silence::*;
+
+mod bounce;
+pub use bounce::*;
+
+pub(crate) mod wav;
+
 /// Low level control over FMOD's debug logging.
 pub mod debug;
 /// Low level control over FMOD's filesystem access.
 pub mod file;
+/// A process wide [`System`], for applications that only ever use one.
+pub mod global;
 /// Low level control over how FMOD allocates memory.
 pub mod memory;
+/// Typed access to the native interface behind FMOD's output.
+pub mod output_handle;
+/// Allocation free formatting of FMOD state, for debug overlays.
+pub mod overlay;
+/// Helpers for retrying calls that FMOD reports as transient.
+pub mod retry;
+/// Helpers for decoding the text encodings FMOD hands out, such as tag data.
+pub mod text;
 /// Low level control over FMOD's threads.
 pub mod thread;
+/// Helpers for routing voice chat audio through FMOD.
+pub mod voice;
 
 mod filesystem;
 pub use filesystem::*;
 
+mod file_diag;
+pub use file_diag::*;
+
+#[cfg(feature = "mmap")]
+mod mmap;
+#[cfg(feature = "mmap")]
+pub use mmap::*;
+
 mod helpers;
 pub(crate) use helpers::*;
@@ -0,0 +1,114 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::c_void;
+use std::marker::{PhantomData, PhantomPinned};
+use std::ptr::NonNull;
+
+use crate::{OutputType, Result, System};
+
+macro_rules! opaque {
+    ($(#[$attr:meta])* $name:ident) => {
+        $(#[$attr])*
+        #[repr(C)]
+        pub struct $name {
+            _data: [u8; 0],
+            // opaque FFI types are !Send, !Sync and !Unpin
+            _marker: PhantomData<(*mut u8, PhantomPinned)>,
+        }
+
+        // the attributes carry the type's cfg, so the impl has to be gated the same way
+        $(#[$attr])*
+        const _: () = {
+            impl std::fmt::Debug for $name {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    f.write_str(stringify!($name))
+                }
+            }
+        };
+    };
+}
+
+opaque! {
+    /// A C stdio `FILE`.
+    StdioFile
+}
+
+opaque! {
+    /// A WASAPI `IAudioClient` COM interface.
+    #[cfg(windows)]
+    IAudioClient
+}
+
+opaque! {
+    /// An ALSA `snd_pcm_t`.
+    #[cfg(target_os = "linux")]
+    SndPcm
+}
+
+opaque! {
+    /// A Core Audio `ComponentInstanceRecord`. `AudioUnit` is a pointer to this type.
+    #[cfg(any(target_os = "macos", target_os = "ios"))]
+    ComponentInstanceRecord
+}
+
+/// The native interface behind FMOD's output, see [`System::get_typed_output_handle`].
+///
+/// The pointee types are opaque stand ins for the platform types,
+/// cast the pointers to the types of whichever bindings (`windows`, `alsa-sys`, `coreaudio-sys`, ...) you are using.
+///
+/// Doing anything with these pointers is unsafe, and they are only valid until the output is closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OutputHandle {
+    /// The file being written to by [`OutputType::WavWriter`] or [`OutputType::WavWriterNRT`].
+    WavWriter(NonNull<StdioFile>),
+    /// The `IAudioClient` used by [`OutputType::WASAPI`].
+    #[cfg(windows)]
+    Wasapi(NonNull<IAudioClient>),
+    /// The PCM handle used by [`OutputType::Alsa`].
+    #[cfg(target_os = "linux")]
+    Alsa(NonNull<SndPcm>),
+    /// The `AudioUnit` used by [`OutputType::CoreAudio`].
+    #[cfg(any(target_os = "macos", target_os = "ios"))]
+    CoreAudio(NonNull<ComponentInstanceRecord>),
+    /// Any other output type that returns a handle.
+    Other {
+        /// The output type the handle belongs to.
+        output_type: OutputType,
+        /// The untyped handle.
+        handle: NonNull<c_void>,
+    },
+}
+
+impl System {
+    /// Retrieves the native interface behind the output, typed according to [`System::get_output_type`].
+    ///
+    /// Returns [`None`] if the output type doesn't expose a handle.
+    ///
+    /// NOTE: Calling this function is safe, but doing anything with the returned pointer is not!!
+    pub fn get_typed_output_handle(&self) -> Result<Option<OutputHandle>> {
+        let Some(handle) = NonNull::new(self.get_output_handle()?) else {
+            return Ok(None);
+        };
+        let handle = match self.get_output_type()? {
+            OutputType::WavWriter | OutputType::WavWriterNRT => {
+                OutputHandle::WavWriter(handle.cast())
+            }
+            #[cfg(windows)]
+            OutputType::WASAPI => OutputHandle::Wasapi(handle.cast()),
+            #[cfg(target_os = "linux")]
+            OutputType::Alsa => OutputHandle::Alsa(handle.cast()),
+            #[cfg(any(target_os = "macos", target_os = "ios"))]
+            OutputType::CoreAudio => OutputHandle::CoreAudio(handle.cast()),
+            output_type => OutputHandle::Other {
+                output_type,
+                handle,
+            },
+        };
+        Ok(Some(handle))
+    }
+}
@@ -0,0 +1,164 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::c_int;
+use std::fmt::{Display, Write};
+
+use lanyard::Utf8CStr;
+
+use crate::Result;
+
+#[cfg(doc)]
+use crate::{CpuUsage, Guid, memory};
+
+/// A fixed size, stack allocated string buffer that can be formatted into without allocating.
+///
+/// Text that doesn't fit is truncated at a character boundary.
+/// One byte is always kept for a nul terminator, so the contents can be handed to C APIs with [`FmtBuf::as_utf8_cstr`].
+///
+/// Every FMOD type with a [`Display`] impl (such as [`Guid`] and [`CpuUsage`]) formats without allocating,
+/// so this can be used to build debug overlay text on platforms where frames must not allocate:
+///
+/// ```ignore
+/// let mut buf = FmtBuf::<64>::new();
+/// let text = buf.format(format_args!("cpu: {}", system.get_cpu_usage()?));
+/// ```
+#[derive(Clone)]
+pub struct FmtBuf<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+    truncated: bool,
+}
+
+impl<const N: usize> FmtBuf<N> {
+    /// Creates an empty buffer.
+    pub const fn new() -> Self {
+        const { assert!(N > 0, "FmtBuf needs room for a nul terminator") };
+        Self {
+            buf: [0; N],
+            len: 0,
+            truncated: false,
+        }
+    }
+
+    /// Empties the buffer.
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.truncated = false;
+        self.buf[0] = 0;
+    }
+
+    /// The text written so far.
+    pub fn as_str(&self) -> &str {
+        // only whole characters are ever copied in, so this is always valid UTF-8
+        std::str::from_utf8(&self.buf[..self.len]).unwrap_or_default()
+    }
+
+    /// The text written so far, as a nul terminated string.
+    pub fn as_utf8_cstr(&self) -> &Utf8CStr {
+        // the text is valid UTF-8 with no interior nuls, and the byte after it is always the nul terminator
+        unsafe { Utf8CStr::from_utf8_with_nul_unchecked(&self.buf[..=self.len]) }
+    }
+
+    /// Whether text was cut off because the buffer was full.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Clears the buffer, formats `args` into it, and returns the result.
+    pub fn format(&mut self, args: std::fmt::Arguments<'_>) -> &Utf8CStr {
+        self.clear();
+        // write_str never fails, it truncates instead
+        let _ = self.write_fmt(args);
+        self.as_utf8_cstr()
+    }
+}
+
+impl<const N: usize> Default for FmtBuf<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> std::fmt::Debug for FmtBuf<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("FmtBuf").field(&self.as_str()).finish()
+    }
+}
+
+impl<const N: usize> Write for FmtBuf<N> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        let available = N - 1 - self.len;
+        let mut take = s.len().min(available);
+        while !s.is_char_boundary(take) {
+            take -= 1;
+        }
+        // an interior nul would cut the C string short, so replace them
+        for (dest, byte) in self.buf[self.len..].iter_mut().zip(s[..take].bytes()) {
+            *dest = if byte == 0 { b' ' } else { byte };
+        }
+        self.len += take;
+        self.buf[self.len] = 0;
+        if take < s.len() {
+            self.truncated = true;
+        }
+        Ok(())
+    }
+}
+
+/// Formats `value` into `buf` without allocating, replacing its previous contents.
+pub fn fmt_into<const N: usize>(buf: &mut FmtBuf<N>, value: &impl Display) -> &Utf8CStr {
+    buf.format(format_args!("{value}"))
+}
+
+/// Displays a size in bytes with a binary unit suffix, such as `1.5 MiB`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Bytes(pub i64);
+
+impl Display for Bytes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+        if self.0.unsigned_abs() < 1024 {
+            return write!(f, "{} B", self.0);
+        }
+        #[allow(clippy::cast_precision_loss)] // only one decimal place is shown
+        let mut value = self.0 as f64 / 1024.0;
+        let mut unit = 0;
+        while value.abs() >= 1024.0 && unit < UNITS.len() - 1 {
+            value /= 1024.0;
+            unit += 1;
+        }
+        write!(f, "{value:.1} {}", UNITS[unit])
+    }
+}
+
+/// FMOD's memory usage, as returned by [`memory::get_stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct MemoryStats {
+    /// Currently allocated memory, in bytes.
+    pub current: c_int,
+    /// Maximum allocated memory since the last system was created, in bytes.
+    pub max: c_int,
+}
+
+impl MemoryStats {
+    /// Retrieves the current memory stats. See [`memory::get_stats`].
+    pub fn get(blocking: bool) -> Result<Self> {
+        let (current, max) = crate::memory::get_stats(blocking)?;
+        Ok(Self { current, max })
+    }
+}
+
+impl Display for MemoryStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} (peak {})",
+            Bytes(self.current.into()),
+            Bytes(self.max.into())
+        )
+    }
+}
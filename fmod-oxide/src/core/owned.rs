@@ -0,0 +1,130 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+
+use crate::{Result, Reverb3D, Sound, System};
+
+/// A handle type that can be released.
+///
+/// FMOD handles are plain pointers that are [`Copy`], so they are never released automatically.
+/// Implementors of this trait can be wrapped in an [`Owned`] to release them when dropped.
+pub trait Release: Copy {
+    /// Releases the object this handle refers to.
+    ///
+    /// The handle (and any copies of it) must not be used afterwards.
+    fn release(&self) -> Result<()>;
+
+    /// The [`System`] the object belongs to, if it can be found.
+    ///
+    /// Used to attribute leaks to a system with the `leak-tracking` feature, see [`System::release_checked`].
+    fn owner(&self) -> Option<System> {
+        None
+    }
+}
+
+impl Release for Reverb3D {
+    fn release(&self) -> Result<()> {
+        Reverb3D::release(self)
+    }
+}
+
+impl Release for Sound {
+    fn release(&self) -> Result<()> {
+        Sound::release(self)
+    }
+
+    fn owner(&self) -> Option<System> {
+        self.get_system().ok()
+    }
+}
+
+/// A handle that is released when dropped.
+///
+/// Errors from releasing on drop are ignored; call [`Owned::release`] to handle them.
+///
+/// Copies of the handle obtained through [`Deref`] are not tracked,
+/// so they must not be used after the [`Owned`] has been dropped.
+///
+/// With the `leak-tracking` feature enabled, every live [`Owned`] is recorded along with where it was created,
+/// so [`System::release_checked`] can report the ones still alive at shutdown.
+#[derive(Debug)]
+pub struct Owned<T: Release> {
+    handle: T,
+    #[cfg(feature = "leak-tracking")]
+    site: u64,
+}
+
+impl<T: Release> Owned<T> {
+    /// Takes ownership of `handle`.
+    #[track_caller]
+    pub fn new(handle: T) -> Self {
+        Self {
+            handle,
+            #[cfg(feature = "leak-tracking")]
+            site: super::leak_tracking::track(
+                std::any::type_name::<T>(),
+                handle
+                    .owner()
+                    .map_or(0, |system| system.inner.as_ptr() as usize),
+                std::panic::Location::caller(),
+            ),
+        }
+    }
+
+    /// Releases the handle now, returning any error.
+    pub fn release(self) -> Result<()> {
+        self.into_inner().release()
+    }
+
+    /// Gives up ownership of the handle without releasing it.
+    pub fn into_inner(self) -> T {
+        let handle = self.handle;
+        #[cfg(feature = "leak-tracking")]
+        super::leak_tracking::untrack(self.site);
+        std::mem::forget(self);
+        handle
+    }
+}
+
+impl<T: Release> Deref for Owned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.handle
+    }
+}
+
+impl<T: Release> From<T> for Owned<T> {
+    #[track_caller]
+    fn from(handle: T) -> Self {
+        Self::new(handle)
+    }
+}
+
+impl<T: Release> Drop for Owned<T> {
+    fn drop(&mut self) {
+        #[cfg(feature = "leak-tracking")]
+        super::leak_tracking::untrack(self.site);
+        let _ = self.handle.release();
+    }
+}
+
+// only the handle matters, not which tracking entry it has
+impl<T: Release + PartialEq> PartialEq for Owned<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.handle == other.handle
+    }
+}
+
+impl<T: Release + Eq> Eq for Owned<T> {}
+
+impl<T: Release + Hash> Hash for Owned<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.handle.hash(state);
+    }
+}
@@ -0,0 +1,92 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use fmod_sys::*;
+
+use crate::{
+    Channel, ChannelControl, ChannelGroup, Dsp, DspConnection, Geometry, Reverb3D, Sound,
+    SoundGroup, SyncPoint, System,
+};
+
+#[cfg(feature = "studio")]
+use crate::studio;
+
+/// A handle type that wraps a raw FMOD pointer, implemented by every core and Studio handle.
+///
+/// This is the same conversion as each type's `from_ffi` and `as_ptr`,
+/// so generic code (and crates building on fmod-oxide, such as editors and plugins) can convert any handle the same way.
+pub trait RawHandle: Copy {
+    /// The FMOD type the handle points to, such as [`FMOD_SOUND`].
+    type Raw;
+
+    /// Converts `self` into its raw representation.
+    fn as_raw(self) -> *mut Self::Raw;
+
+    /// # Safety
+    ///
+    /// `raw` must be a valid pointer either aquired from [`RawHandle::as_raw`] or FMOD.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `raw` is null.
+    unsafe fn from_raw(raw: *mut Self::Raw) -> Self;
+
+    /// Like [`RawHandle::from_raw`], but returns [`None`] if `raw` is null.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be null, or a valid pointer either aquired from [`RawHandle::as_raw`] or FMOD.
+    unsafe fn try_from_raw(raw: *mut Self::Raw) -> Option<Self> {
+        if raw.is_null() {
+            None
+        } else {
+            Some(unsafe { Self::from_raw(raw) })
+        }
+    }
+}
+
+macro_rules! raw_handle {
+    ($($ty:ty => $raw:ty),* $(,)?) => {
+        $(
+            impl RawHandle for $ty {
+                type Raw = $raw;
+
+                fn as_raw(self) -> *mut $raw {
+                    self.as_ptr()
+                }
+
+                unsafe fn from_raw(raw: *mut $raw) -> Self {
+                    unsafe { Self::from_ffi(raw) }
+                }
+            }
+        )*
+    };
+}
+
+raw_handle!(
+    System => FMOD_SYSTEM,
+    Sound => FMOD_SOUND,
+    SyncPoint => FMOD_SYNCPOINT,
+    Channel => FMOD_CHANNEL,
+    ChannelGroup => FMOD_CHANNELGROUP,
+    ChannelControl => FMOD_CHANNELCONTROL,
+    SoundGroup => FMOD_SOUNDGROUP,
+    Dsp => FMOD_DSP,
+    DspConnection => FMOD_DSPCONNECTION,
+    Geometry => FMOD_GEOMETRY,
+    Reverb3D => FMOD_REVERB3D,
+);
+
+#[cfg(feature = "studio")]
+raw_handle!(
+    studio::System => FMOD_STUDIO_SYSTEM,
+    studio::EventDescription => FMOD_STUDIO_EVENTDESCRIPTION,
+    studio::EventInstance => FMOD_STUDIO_EVENTINSTANCE,
+    studio::Bus => FMOD_STUDIO_BUS,
+    studio::Vca => FMOD_STUDIO_VCA,
+    studio::Bank => FMOD_STUDIO_BANK,
+    studio::CommandReplay => FMOD_STUDIO_COMMANDREPLAY,
+);
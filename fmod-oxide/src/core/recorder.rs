@@ -0,0 +1,196 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::{c_int, c_uint};
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use super::wav;
+use crate::{Error, Mode, Result, Sound, SoundBuilder, SoundFormat, System};
+
+/// Records audio from a recording driver into memory, and saves it as a WAV file.
+///
+/// Audio is recorded as PCM16 at the driver's native sample rate and channel count, so FMOD doesn't add a resampler,
+/// and converted to the format set with [`Recorder::with_output_format`] when it is saved.
+/// This makes it suitable for in-game voice memos and for capturing what a tester hears for a bug report.
+///
+/// Call [`Recorder::update`] once per frame, after [`System::update`], to collect what was recorded since the last call,
+/// and save it with [`Recorder::save_wav`] once done. For a quick fixed length capture, see [`Recorder::record_to_wav`].
+/// The driver records into a one second ring buffer, so anything not collected within a second is lost.
+///
+/// Recording stops and the record buffer is released when the recorder is dropped.
+#[derive(Debug)]
+pub struct Recorder {
+    system: System,
+    driver: c_int,
+    sound: Sound,
+    sample_rate: c_int,
+    channels: c_int,
+    buffer_length: c_uint,
+    read_position: c_uint,
+    samples: Vec<i16>,
+    output_format: Option<(c_int, c_int)>,
+}
+
+impl Recorder {
+    /// Starts recording from the recording driver `driver`.
+    ///
+    /// See [`System::get_recording_driver_count`] for enumerating drivers.
+    pub fn new(system: System, driver: c_int) -> Result<Self> {
+        let (_, _, sample_rate, _, channels, _) = system.get_record_driver_info(driver)?;
+        let sample_rate = sample_rate.max(1);
+        let channels = channels.max(1);
+
+        let buffer_length = sample_rate as c_uint;
+        let builder = SoundBuilder::open_user(
+            buffer_length * frame_size(channels),
+            channels,
+            sample_rate,
+            SoundFormat::PCM16,
+        )
+        .with_mode(Mode::LOOP_NORMAL);
+        let sound = system.create_sound(&builder)?;
+        if let Err(e) = system.record_start(driver, sound, true) {
+            let _ = sound.release();
+            return Err(e);
+        }
+
+        Ok(Self {
+            system,
+            driver,
+            sound,
+            sample_rate,
+            channels,
+            buffer_length,
+            read_position: 0,
+            samples: Vec::new(),
+            output_format: None,
+        })
+    }
+
+    /// Converts the recording to `sample_rate` Hz and `channels` channels when it is saved,
+    /// instead of keeping the driver's format.
+    ///
+    /// Channels are mixed down to mono by averaging, and otherwise taken from the recorded channels in order, repeating them if there are fewer.
+    #[must_use]
+    pub fn with_output_format(mut self, sample_rate: c_int, channels: c_int) -> Self {
+        self.output_format = Some((sample_rate, channels));
+        self
+    }
+
+    /// The sample rate audio is recorded at, the driver's native rate.
+    pub fn sample_rate(&self) -> c_int {
+        self.sample_rate
+    }
+
+    /// The number of channels recorded, the driver's native channel count.
+    pub fn channels(&self) -> c_int {
+        self.channels
+    }
+
+    /// Collects the audio recorded since the last call.
+    pub fn update(&mut self) -> Result<()> {
+        let position = self.system.get_record_position(self.driver)?;
+        if position == self.read_position {
+            return Ok(());
+        }
+        let count = (position + self.buffer_length - self.read_position) % self.buffer_length;
+        let frame_size = frame_size(self.channels);
+        // SAFETY: the lock is dropped before the sound can be released
+        let lock = unsafe {
+            self.sound
+                .lock(self.read_position * frame_size, count * frame_size)?
+        };
+        for bytes in [lock.data(), lock.extra().unwrap_or_default()] {
+            self.samples.extend(
+                bytes
+                    .chunks_exact(2)
+                    .map(|sample| i16::from_ne_bytes([sample[0], sample[1]])),
+            );
+        }
+        drop(lock);
+        self.read_position = position;
+        Ok(())
+    }
+
+    /// How much audio has been collected so far.
+    pub fn recorded(&self) -> Duration {
+        let frames = self.samples.len() / self.channels as usize;
+        Duration::from_secs_f64(frames as f64 / f64::from(self.sample_rate))
+    }
+
+    /// The collected audio, as interleaved samples in the driver's format.
+    pub fn samples(&self) -> &[i16] {
+        &self.samples
+    }
+
+    /// Throws away the collected audio, to start a new recording on the same driver.
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Writes the collected audio as a WAV file to `writer`, in the output format.
+    ///
+    /// Returns [`Error::InvalidParam`] if the output format isn't valid, and [`Error::FileBad`] if writing fails.
+    pub fn write_wav(&self, writer: impl Write) -> Result<()> {
+        let (sample_rate, channels) = self
+            .output_format
+            .unwrap_or((self.sample_rate, self.channels));
+        if sample_rate <= 0 || channels <= 0 {
+            return Err(Error::InvalidParam);
+        }
+        let samples = wav::convert_pcm16(
+            &self.samples,
+            (self.sample_rate, self.channels),
+            (sample_rate, channels),
+        );
+        wav::write_pcm16(writer, sample_rate, channels, &samples)
+    }
+
+    /// Saves the collected audio as a WAV file at `path`, in the output format. See [`Recorder::write_wav`].
+    pub fn save_wav(&self, path: impl AsRef<Path>) -> Result<()> {
+        let file = std::fs::File::create(path).map_err(|e| wav::io_error(&e))?;
+        self.write_wav(std::io::BufWriter::new(file))
+    }
+
+    /// Records for `duration`, then saves what was recorded to `path`. See [`Recorder::save_wav`].
+    ///
+    /// This blocks until the recording is complete, calling [`System::update`] itself while it waits,
+    /// so it is meant for tools and debug commands. In a game loop use [`Recorder::update`] and [`Recorder::save_wav`] instead.
+    /// Audio collected before the call is kept at the start of the file.
+    ///
+    /// Returns [`Error::RecordDisconnected`] without saving if the driver stops recording,
+    /// or stops delivering audio for long enough that the recording can't finish in twice `duration` (plus a second of slack).
+    pub fn record_to_wav(&mut self, path: impl AsRef<Path>, duration: Duration) -> Result<()> {
+        let channels = self.channels as usize;
+        let wanted = self.samples.len()
+            + (duration.as_secs_f64() * f64::from(self.sample_rate)) as usize * channels;
+        // an unplugged driver can keep reporting the same record position forever
+        let deadline = Instant::now() + duration * 2 + Duration::from_secs(1);
+        while self.samples.len() < wanted {
+            self.system.update()?;
+            self.update()?;
+            if !self.system.is_recording(self.driver)? || Instant::now() > deadline {
+                return Err(Error::RecordDisconnected);
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        self.samples.truncate(wanted);
+        self.save_wav(path)
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        let _ = self.system.record_stop(self.driver);
+        let _ = self.sound.release();
+    }
+}
+
+fn frame_size(channels: c_int) -> c_uint {
+    channels as c_uint * size_of::<i16>() as c_uint
+}
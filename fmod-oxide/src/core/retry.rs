@@ -0,0 +1,119 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::time::{Duration, Instant};
+
+use crate::{Error, Result, System};
+
+#[cfg(feature = "studio")]
+use crate::studio;
+
+/// A system that can be pumped while waiting for a transient error to clear.
+///
+/// Implemented for [`System`] and [`studio::System`].
+pub trait Update {
+    /// Calls the system's `update` function.
+    fn update(&self) -> Result<()>;
+}
+
+impl Update for System {
+    fn update(&self) -> Result<()> {
+        System::update(self)
+    }
+}
+
+#[cfg(feature = "studio")]
+impl Update for studio::System {
+    fn update(&self) -> Result<()> {
+        studio::System::update(self)
+    }
+}
+
+/// How long to wait between attempts, and for how long to keep trying.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backoff {
+    /// Delay after the first failed attempt.
+    pub initial_delay: Duration,
+    /// Upper bound for the delay between attempts. The delay doubles after each attempt until it reaches this.
+    pub max_delay: Duration,
+    /// Total time after which the last error is returned.
+    pub timeout: Duration,
+}
+
+impl Backoff {
+    /// Creates a backoff with the default delays and the given timeout.
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(50),
+            timeout: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Whether FMOD documents `error` as "try again later".
+fn is_transient(error: Error) -> bool {
+    matches!(error, Error::NotReady | Error::Truncated)
+}
+
+/// Calls `f` until it returns something other than [`Error::NotReady`] or [`Error::Truncated`],
+/// pumping `system` between attempts with an exponential backoff.
+///
+/// If `backoff.timeout` elapses, the last error is returned.
+pub fn retry_transient<T>(
+    system: &impl Update,
+    backoff: Backoff,
+    mut f: impl FnMut() -> Result<T>,
+) -> Result<T> {
+    let start = Instant::now();
+    let mut delay = backoff.initial_delay;
+    loop {
+        match f() {
+            Err(error) if is_transient(error) => {
+                let elapsed = start.elapsed();
+                if elapsed >= backoff.timeout {
+                    return Err(error);
+                }
+                system.update()?;
+                std::thread::sleep(delay.min(backoff.timeout - elapsed));
+                delay = (delay * 2).min(backoff.max_delay);
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Extension combinators for retrying FMOD calls that report transient errors.
+///
+/// ```ignore
+/// use fmod::retry::RetryExt;
+///
+/// let bank = (|| system.get_bank(c!("bank:/Master"))).retry_not_ready(&system, Duration::from_secs(2))?;
+/// ```
+pub trait RetryExt<T>: FnMut() -> Result<T> + Sized {
+    /// Retries `self` while it returns [`Error::NotReady`] or [`Error::Truncated`], pumping `system` in between, until `timeout` elapses.
+    ///
+    /// See [`retry_transient`] for details.
+    fn retry_not_ready(self, system: &impl Update, timeout: Duration) -> Result<T> {
+        retry_transient(system, Backoff::with_timeout(timeout), self)
+    }
+
+    /// Like [`RetryExt::retry_not_ready`], with full control over the [`Backoff`].
+    fn retry_with_backoff(self, system: &impl Update, backoff: Backoff) -> Result<T> {
+        retry_transient(system, backoff, self)
+    }
+}
+
+impl<T, F: FnMut() -> Result<T>> RetryExt<T> for F {}
@@ -158,6 +158,8 @@ impl Reverb3D {
     ///
     /// If you release all [`Reverb3D`] objects and have not added a new [`Reverb3D`] object,
     /// [`crate::System::set_reverb_properties`] should be called to reset the reverb properties.
+    ///
+    /// Dropping an [`Owned<Reverb3D>`](crate::Owned) calls this for you.
     pub fn release(&self) -> Result<()> {
         unsafe { FMOD_Reverb3D_Release(self.inner.as_ptr()).to_result() }
     }
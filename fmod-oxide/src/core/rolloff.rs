@@ -0,0 +1,104 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::c_float;
+
+use crate::Mode;
+
+/// One of FMOD's built in 3D roll-off curves, for computing attenuation outside of FMOD.
+///
+/// See the roll-off flags on [`Mode`] for how each curve behaves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Rolloff {
+    /// [`Mode::INVERSE_ROLLOFF_3D`].
+    #[default]
+    Inverse,
+    /// [`Mode::INVERSE_TAPERED_ROLLOFF_3D`].
+    InverseTapered,
+    /// [`Mode::LINEAR_ROLLOFF_3D`].
+    Linear,
+    /// [`Mode::LINEAR_SQUARE_ROLLOFF_3D`].
+    LinearSquared,
+}
+
+/// A point on an attenuation curve.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct AttenuationPoint {
+    /// Distance from the listener.
+    pub distance: c_float,
+    /// Gain at that distance, from 0 to 1.
+    pub gain: c_float,
+}
+
+impl Rolloff {
+    /// The roll-off curve selected by `mode`, or [`None`] if it uses [`Mode::CUSTOM_ROLLOFF_3D`].
+    ///
+    /// Modes without a roll-off flag use FMOD's default, [`Rolloff::Inverse`].
+    pub fn from_mode(mode: Mode) -> Option<Rolloff> {
+        if mode.contains(Mode::CUSTOM_ROLLOFF_3D) {
+            None
+        } else if mode.contains(Mode::INVERSE_TAPERED_ROLLOFF_3D) {
+            Some(Rolloff::InverseTapered)
+        } else if mode.contains(Mode::LINEAR_SQUARE_ROLLOFF_3D) {
+            Some(Rolloff::LinearSquared)
+        } else if mode.contains(Mode::LINEAR_ROLLOFF_3D) {
+            Some(Rolloff::Linear)
+        } else {
+            Some(Rolloff::Inverse)
+        }
+    }
+
+    /// The [`Mode`] flag that selects this curve.
+    pub fn mode(self) -> Mode {
+        match self {
+            Rolloff::Inverse => Mode::INVERSE_ROLLOFF_3D,
+            Rolloff::InverseTapered => Mode::INVERSE_TAPERED_ROLLOFF_3D,
+            Rolloff::Linear => Mode::LINEAR_ROLLOFF_3D,
+            Rolloff::LinearSquared => Mode::LINEAR_SQUARE_ROLLOFF_3D,
+        }
+    }
+
+    /// The gain this curve gives at `distance`, for a sound with the given minimum and maximum distance.
+    ///
+    /// This assumes a roll-off scale of 1, and doesn't account for geometry occlusion or any other attenuation.
+    pub fn gain(self, distance: c_float, min: c_float, max: c_float) -> c_float {
+        if distance <= min {
+            return 1.0;
+        }
+        let distance = distance.min(max);
+        let inverse = || min / distance;
+        let linear = || {
+            if max > min {
+                1.0 - (distance - min) / (max - min)
+            } else {
+                0.0
+            }
+        };
+        match self {
+            Rolloff::Inverse => inverse(),
+            Rolloff::Linear => linear(),
+            Rolloff::LinearSquared => linear() * linear(),
+            // whichever attenuates more
+            Rolloff::InverseTapered => inverse().min(linear() * linear()),
+        }
+    }
+
+    /// Samples this curve at `samples` evenly spaced distances from 0 to `max`, such as for drawing it in an editor.
+    pub fn preview(self, min: c_float, max: c_float, samples: usize) -> Vec<AttenuationPoint> {
+        #[allow(clippy::cast_precision_loss)] // previews don't have millions of samples
+        let step = max / samples.saturating_sub(1).max(1) as c_float;
+        (0..samples)
+            .map(|i| {
+                #[allow(clippy::cast_precision_loss)]
+                let distance = i as c_float * step;
+                AttenuationPoint {
+                    distance,
+                    gain: self.gain(distance, min, max),
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,28 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::{Result, System};
+
+#[cfg(doc)]
+use crate::{Channel, Owned, Sound, studio};
+
+/// Tears down `system` in the order FMOD recommends.
+///
+/// Every [`Channel`] is stopped through the master channel group, [`System::update`] is called so the stops
+/// (and any end callbacks they fire) are processed, and only then is the system released.
+///
+/// Sounds and other objects created from the system are released along with it,
+/// so any [`Owned`] handles must be dropped first; see [`System::release_checked`].
+/// For a Studio system use [`studio::shutdown`] instead, which also unloads banks.
+///
+/// # Safety
+///
+/// See [`System::release`].
+pub unsafe fn shutdown(system: System) -> Result<()> {
+    system.get_master_channel_group()?.stop()?;
+    system.update()?;
+    unsafe { system.release() }
+}
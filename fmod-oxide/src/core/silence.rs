@@ -0,0 +1,126 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::c_float;
+use std::time::Duration;
+
+use crate::{ChannelControl, Decibels, Error, Result};
+
+#[cfg(feature = "studio")]
+use crate::studio;
+
+/// Reports when a channel or channel group has been effectively silent for a while.
+///
+/// This reads the output metering of the head [`crate::Dsp`], and counts the signal as silent while its peak level,
+/// across all channels, stays below a threshold ([`SilenceDetector::DEFAULT_THRESHOLD`] unless set with [`SilenceDetector::with_threshold`]).
+/// Useful for pausing a music stream that has gone quiet, or for integration tests asserting that nothing is audible.
+///
+/// Call [`SilenceDetector::update`] once per frame, after [`System::update`](crate::System::update).
+/// A channel that has finished playing (or was stolen) counts as silent.
+/// The previous metering state is restored by [`SilenceDetector::release`].
+#[derive(Debug)]
+pub struct SilenceDetector {
+    control: ChannelControl,
+    metering_was_enabled: (bool, bool),
+    threshold: c_float,
+    hold: Duration,
+    silent_for: Duration,
+    peak: c_float,
+}
+
+impl SilenceDetector {
+    /// The default threshold, below which a signal is considered silent.
+    pub const DEFAULT_THRESHOLD: Decibels = Decibels(-60.0);
+
+    /// Starts watching `control`, which is considered silent once it has been below the threshold for `hold`.
+    pub fn new(control: ChannelControl, hold: Duration) -> Result<Self> {
+        let head = control.get_dsp(ChannelControl::DSP_HEAD)?;
+        let metering_was_enabled = head.get_metering_enabled()?;
+        head.set_metering_enabled(metering_was_enabled.0, true)?;
+        Ok(Self {
+            control,
+            metering_was_enabled,
+            threshold: Self::DEFAULT_THRESHOLD.to_linear(),
+            hold,
+            silent_for: Duration::ZERO,
+            peak: 0.0,
+        })
+    }
+
+    /// Starts watching the channel group of a Studio bus. See [`SilenceDetector::new`].
+    ///
+    /// The bus only has a channel group while something is routed into it,
+    /// so lock it with [`studio::Bus::lock_channel_group`] first if it may be idle.
+    #[cfg(feature = "studio")]
+    pub fn for_bus(bus: studio::Bus, hold: Duration) -> Result<Self> {
+        Self::new(*bus.get_channel_group()?, hold)
+    }
+
+    /// Sets the level below which the signal is considered silent.
+    #[must_use]
+    pub fn with_threshold(mut self, threshold: Decibels) -> Self {
+        self.threshold = threshold.to_linear();
+        self
+    }
+
+    /// Reads the current level and advances the silence timer by `dt`, returning whether the signal is now considered silent.
+    ///
+    /// Any sound above the threshold resets the timer.
+    pub fn update(&mut self, dt: Duration) -> Result<bool> {
+        self.peak = match self.read_peak() {
+            Err(Error::InvalidHandle | Error::ChannelStolen) => 0.0,
+            result => result?,
+        };
+        if self.peak < self.threshold {
+            self.silent_for += dt;
+        } else {
+            self.silent_for = Duration::ZERO;
+        }
+        Ok(self.is_silent())
+    }
+
+    fn read_peak(&self) -> Result<c_float> {
+        let (_, output) = self
+            .control
+            .get_dsp(ChannelControl::DSP_HEAD)?
+            .get_metering_info()?;
+        let channels = (output.channel_count.max(0) as usize).min(output.peak_level.len());
+        Ok(output.peak_level[..channels]
+            .iter()
+            .copied()
+            .fold(0.0, c_float::max))
+    }
+
+    /// Whether the signal has been below the threshold for at least the hold time.
+    pub fn is_silent(&self) -> bool {
+        self.silent_for >= self.hold
+    }
+
+    /// How long the signal has been below the threshold.
+    pub fn silent_for(&self) -> Duration {
+        self.silent_for
+    }
+
+    /// The peak level read by the last [`SilenceDetector::update`].
+    pub fn level(&self) -> Decibels {
+        Decibels::from_linear(self.peak)
+    }
+
+    /// Restores the metering state from before the detector was created.
+    ///
+    /// Does nothing if the channel has finished playing in the meantime.
+    pub fn release(self) -> Result<()> {
+        let (input, output) = self.metering_was_enabled;
+        let result = self
+            .control
+            .get_dsp(ChannelControl::DSP_HEAD)
+            .and_then(|head| head.set_metering_enabled(input, output));
+        match result {
+            Err(Error::InvalidHandle | Error::ChannelStolen) => Ok(()),
+            result => result,
+        }
+    }
+}
@@ -0,0 +1,84 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+
+use fmod_sys::FMOD_MODE;
+use lanyard::{Utf8CStr, Utf8CString};
+
+use crate::{Mode, Owned, Result, Sound, SoundBuilder, System};
+
+/// A [`Sound`] shared through a [`SoundCache`]. It is released once every clone has been dropped.
+pub type SharedSound = Arc<Owned<Sound>>;
+
+/// Deduplicates loading sounds by path and [`Mode`].
+///
+/// Asking for a file that is already loaded with the same mode returns the loaded sound instead of opening it again.
+/// The cache only holds weak references, so a sound is released as soon as the last [`SharedSound`] for it is dropped,
+/// and loaded again the next time it is asked for.
+#[derive(Debug)]
+pub struct SoundCache {
+    system: System,
+    sounds: HashMap<(Utf8CString, FMOD_MODE), Weak<Owned<Sound>>>,
+}
+
+impl SoundCache {
+    /// Creates an empty cache that loads sounds with `system`.
+    pub fn new(system: System) -> Self {
+        Self {
+            system,
+            sounds: HashMap::new(),
+        }
+    }
+
+    /// Returns the sound for `path` opened with `mode`, loading it with [`System::create_sound`] if needed.
+    pub fn get(&mut self, path: &Utf8CStr, mode: Mode) -> Result<SharedSound> {
+        self.get_with(path, mode, |builder| builder)
+    }
+
+    /// Like [`SoundCache::get`], but lets `configure` adjust the builder before the sound is loaded.
+    ///
+    /// `configure` is only called on a cache miss. Sounds are keyed by `path` and `mode` alone,
+    /// so anything else set by `configure` should be the same for every call with the same key.
+    pub fn get_with<'a>(
+        &mut self,
+        path: &'a Utf8CStr,
+        mode: Mode,
+        configure: impl FnOnce(SoundBuilder<'a>) -> SoundBuilder<'a>,
+    ) -> Result<SharedSound> {
+        let key = (path.to_cstring(), mode.bits());
+        if let Some(sound) = self.sounds.get(&key).and_then(Weak::upgrade) {
+            return Ok(sound);
+        }
+
+        let builder = configure(SoundBuilder::open(path).with_mode(mode));
+        let sound = Arc::new(Owned::new(self.system.create_sound(&builder)?));
+        self.sounds.retain(|_, sound| sound.strong_count() > 0);
+        self.sounds.insert(key, Arc::downgrade(&sound));
+        Ok(sound)
+    }
+
+    /// Returns the sound for `path` opened with `mode` if it is currently loaded.
+    pub fn get_loaded(&self, path: &Utf8CStr, mode: Mode) -> Option<SharedSound> {
+        self.sounds
+            .get(&(path.to_cstring(), mode.bits()))
+            .and_then(Weak::upgrade)
+    }
+
+    /// Number of sounds currently loaded through this cache.
+    pub fn len(&self) -> usize {
+        self.sounds
+            .values()
+            .filter(|sound| sound.strong_count() > 0)
+            .count()
+    }
+
+    /// Whether no sounds are currently loaded through this cache.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -0,0 +1,139 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::{c_int, c_uint};
+
+use crate::{Channel, ChannelGroup, Error, Mode, Owned, PcmCallback, Result, Sound};
+use crate::{SoundBuilder, SoundFormat, System};
+
+/// Builds a [`SubSoundContainer`]: a [`Mode::OPEN_USER`] parent sound holding a number of user subsounds.
+///
+/// This wraps setting [`SoundBuilder::with_subsound_count`] on a [`SoundBuilder::open_user`] sound,
+/// then checking the sentence to play the subsounds in.
+/// Every subsound shares the same length and format.
+///
+/// Subsound data is provided by a [`PcmCallback`], which is called with the subsound being read as its `sound`,
+/// or written afterwards with [`Sound::lock`] on each [`SubSoundContainer::sub_sound`].
+#[derive(Debug)]
+pub struct SubSoundContainerBuilder {
+    builder: SoundBuilder<'static>,
+    sentence: Vec<c_int>,
+}
+
+/// A [`Mode::OPEN_USER`] parent sound with user subsounds, and a sentence to play them in.
+///
+/// Create with [`SubSoundContainerBuilder`]. The parent sound is released when this is dropped.
+#[derive(Debug)]
+pub struct SubSoundContainer {
+    sound: Owned<Sound>,
+    sentence: Vec<c_int>,
+}
+
+impl SubSoundContainerBuilder {
+    /// Describes `subsound_count` subsounds, each `length` bytes of `format` data.
+    pub const fn new(
+        subsound_count: c_int,
+        length: c_uint,
+        channel_count: c_int,
+        default_frequency: c_int,
+        format: SoundFormat,
+    ) -> Self {
+        Self {
+            builder: SoundBuilder::open_user(length, channel_count, default_frequency, format)
+                .with_subsound_count(subsound_count),
+            sentence: Vec::new(),
+        }
+    }
+
+    /// Adds `mode` flags to the parent sound, see [`SoundBuilder::with_mode`].
+    ///
+    /// Stream subsounds can't play at the same time as each other, so [`Mode::CREATE_STREAM`] prevents sentence playback.
+    #[must_use]
+    pub fn with_mode(mut self, mode: Mode) -> Self {
+        self.builder = self.builder.with_mode(mode);
+        self
+    }
+
+    /// Provides subsound data with `C`, see [`SoundBuilder::with_pcm_callback`].
+    #[must_use]
+    pub fn with_pcm_callback<C: PcmCallback>(mut self) -> Self {
+        self.builder = self.builder.with_pcm_callback::<C>();
+        self
+    }
+
+    /// The order to play subsounds in with [`SubSoundContainer::play`]. Indices may repeat.
+    ///
+    /// Defaults to every subsound once, in order.
+    #[must_use]
+    pub fn with_sentence(mut self, sentence: impl IntoIterator<Item = c_int>) -> Self {
+        self.sentence = sentence.into_iter().collect();
+        self
+    }
+
+    /// Creates the parent sound and checks the sentence against it.
+    ///
+    /// Returns [`Error::InvalidParam`] if the subsound count is not positive or the sentence has an out of range index.
+    /// The sound is released if the sentence is invalid.
+    pub fn build(&self, system: System) -> Result<SubSoundContainer> {
+        let count = self.builder.subsound_count();
+        if count <= 0 {
+            return Err(Error::InvalidParam);
+        }
+        let sound = Owned::new(system.create_sound(&self.builder)?);
+        let sentence = if self.sentence.is_empty() {
+            (0..count).collect()
+        } else {
+            sound.validate_sub_sound_sentence(&self.sentence)?;
+            self.sentence.clone()
+        };
+        Ok(SubSoundContainer { sound, sentence })
+    }
+}
+
+impl SubSoundContainer {
+    /// The parent sound.
+    pub fn sound(&self) -> Sound {
+        *self.sound
+    }
+
+    /// Number of subsounds in the container.
+    pub fn sub_sound_count(&self) -> Result<c_int> {
+        self.sound.get_sub_sound_count()
+    }
+
+    /// The subsound at `index`, see [`Sound::get_sub_sound`].
+    pub fn sub_sound(&self, index: c_int) -> Result<Sound> {
+        self.sound.get_sub_sound(index)
+    }
+
+    /// The order subsounds are played in by [`SubSoundContainer::play`].
+    pub fn sentence(&self) -> &[c_int] {
+        &self.sentence
+    }
+
+    /// Replaces the sentence, after checking it with [`Sound::validate_sub_sound_sentence`].
+    pub fn set_sentence(&mut self, sentence: Vec<c_int>) -> Result<()> {
+        self.sound.validate_sub_sound_sentence(&sentence)?;
+        self.sentence = sentence;
+        Ok(())
+    }
+
+    /// Plays the sentence with [`Sound::play_sub_sound_sentence`], returning a channel per entry.
+    pub fn play(&self, channel_group: Option<ChannelGroup>) -> Result<Vec<Channel>> {
+        self.sound
+            .play_sub_sound_sentence(channel_group, &self.sentence)
+    }
+
+    /// Releases the parent sound, returning any error.
+    pub fn release(self) -> Result<()> {
+        self.sound.release()
+    }
+
+    /// Gives up ownership of the parent sound without releasing it.
+    pub fn into_inner(self) -> Sound {
+        self.sound.into_inner()
+    }
+}
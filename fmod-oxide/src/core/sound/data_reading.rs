@@ -25,6 +25,19 @@ pub struct SoundLock<'a> {
 #[cfg(doc)]
 use crate::{Channel, System};
 
+/// The loading and buffering state of a [`Sound`], as reported by [`Sound::get_stream_health`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamHealth {
+    /// Open state of the sound.
+    pub state: OpenState,
+    /// Filled percentage of a stream's file buffer.
+    pub buffered_percent: c_uint,
+    /// Whether the stream is starving, in which case playback will stutter as old data is replayed.
+    pub starving: bool,
+    /// Whether the disk is currently being accessed for this sound.
+    pub disk_busy: bool,
+}
+
 impl SoundLock<'_> {
     /// The first part of the locked data.
     pub fn data(&self) -> &[u8] {
@@ -112,6 +125,19 @@ impl Sound {
         Ok((open_state, percent_buffered, starving, disk_busy))
     }
 
+    /// Retrieves the open state together with the stream's buffering details.
+    ///
+    /// This is the same information as [`Sound::get_open_state`], in a form that is easier to show in streaming UIs.
+    pub fn get_stream_health(&self) -> Result<StreamHealth> {
+        let (state, buffered_percent, starving, disk_busy) = self.get_open_state()?;
+        Ok(StreamHealth {
+            state,
+            buffered_percent,
+            starving,
+            disk_busy,
+        })
+    }
+
     /// Gives access to a portion or all the sample data of a sound for direct manipulation.
     ///
     /// With this function you get access to the raw audio data.
@@ -7,8 +7,8 @@
 use fmod_sys::*;
 use std::ffi::c_void;
 
+use crate::{CallbackContext, CallbackThread, Sound, System};
 use crate::{FmodResultExt, Result};
-use crate::{Sound, System};
 
 impl Sound {
     /// Frees a sound object.
@@ -18,7 +18,14 @@ impl Sound {
     /// Additionally, if the sound is still playing or has recently been stopped, the release may stall, as the mixer may still be using the sound.
     /// Using `Sound::get_open_state` and checking the open state for `FMOD_OPENSTATE_READY` and `FMOD_OPENSTATE_ERROR` is a good way to avoid stalls.
     pub fn release(&self) -> Result<()> {
-        unsafe { FMOD_Sound_Release(self.inner.as_ptr()).to_result() }
+        CallbackContext::debug_assert_not_in(
+            &[CallbackThread::NonBlocking, CallbackThread::Stream],
+            "Sound::release",
+        );
+        unsafe { FMOD_Sound_Release(self.inner.as_ptr()).to_result()? };
+        self.clear_on_ready();
+        self.forget_owned_memory();
+        Ok(())
     }
 
     /// Sets the user data.
@@ -13,7 +13,7 @@ use crate::{FmodResultExt, Result};
 use fmod_sys::*;
 use lanyard::{Utf8CStr, Utf8CString};
 
-use crate::{Sound, SoundFormat, SoundType, Tag, TimeUnit, get_string};
+use crate::{Sound, SoundFormat, SoundType, Tag, TimeUnit, get_string, get_string_bounded};
 
 impl Sound {
     /// Retrieves the name of a sound.
@@ -29,6 +29,19 @@ impl Sound {
         })
     }
 
+    /// Retrieves the name of a sound, truncated to at most `max_len` bytes.
+    ///
+    /// Unlike [`Sound::get_name`] this never allocates more than `max_len + 1` bytes, and truncation is not an error.
+    pub fn get_name_with_max_len(&self, max_len: usize) -> Result<Utf8CString> {
+        get_string_bounded(max_len, |name| unsafe {
+            FMOD_Sound_GetName(
+                self.inner.as_ptr(),
+                name.as_mut_ptr().cast(),
+                name.len() as c_int,
+            )
+        })
+    }
+
     /// Returns format information about the sound.
     pub fn get_format(&self) -> Result<(SoundType, SoundFormat, c_int, c_int)> {
         let mut kind = 0;
@@ -0,0 +1,155 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::collections::VecDeque;
+use std::ffi::c_int;
+
+use crate::{Mode, OpenState, Result, Sound, SoundBuilder, System};
+
+/// Loads many sounds in the background, such as behind a loading screen.
+///
+/// Builders are queued with [`SoundLoadQueue::push`] and opened with [`Mode::NONBLOCKING`],
+/// spread across the configured non blocking threads.
+/// Call [`SoundLoadQueue::poll`] once per frame to start queued loads and collect the ones that have finished.
+#[derive(Debug)]
+pub struct SoundLoadQueue<'a, K> {
+    system: System,
+    thread_ids: Vec<c_int>,
+    next_thread: usize,
+    max_in_flight: usize,
+    queued: VecDeque<(K, SoundBuilder<'a>)>,
+    loading: Vec<(K, Sound)>,
+    total: usize,
+    finished: usize,
+}
+
+/// How far along a [`SoundLoadQueue`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct LoadProgress {
+    /// Number of sounds that have finished loading, successfully or not.
+    pub finished: usize,
+    /// Number of sounds pushed to the queue in total.
+    pub total: usize,
+}
+
+impl LoadProgress {
+    /// Progress as a fraction between 0 and 1. An empty queue counts as fully loaded.
+    #[allow(clippy::cast_precision_loss)] // nobody is loading 2^24 sounds
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.finished as f32 / self.total as f32
+        }
+    }
+
+    /// Whether every pushed sound has finished loading.
+    pub fn is_complete(&self) -> bool {
+        self.finished == self.total
+    }
+}
+
+impl<'a, K> SoundLoadQueue<'a, K> {
+    /// Creates an empty queue that loads sounds with `system`, on non blocking thread 0.
+    pub fn new(system: System) -> Self {
+        Self {
+            system,
+            thread_ids: vec![0],
+            next_thread: 0,
+            max_in_flight: usize::MAX,
+            queued: VecDeque::new(),
+            loading: Vec::new(),
+            total: 0,
+            finished: 0,
+        }
+    }
+
+    /// Spreads loads across these non blocking thread ids, round robin.
+    ///
+    /// See [`SoundBuilder::with_non_block_thread_id`]. An empty list uses thread 0.
+    #[must_use]
+    pub fn with_thread_ids(mut self, thread_ids: impl IntoIterator<Item = c_int>) -> Self {
+        self.thread_ids = thread_ids.into_iter().collect();
+        if self.thread_ids.is_empty() {
+            self.thread_ids.push(0);
+        }
+        self
+    }
+
+    /// Limits how many sounds are loading at once. The rest wait in the queue.
+    #[must_use]
+    pub fn with_max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = max_in_flight.max(1);
+        self
+    }
+
+    /// Queues `builder` to be loaded. `key` is handed back with the result.
+    ///
+    /// [`Mode::NONBLOCKING`] is added to the builder's mode, and its non blocking thread id is overwritten.
+    pub fn push(&mut self, key: K, builder: SoundBuilder<'a>) {
+        self.queued.push_back((key, builder));
+        self.total += 1;
+    }
+
+    /// The aggregate progress of every sound pushed so far.
+    pub fn progress(&self) -> LoadProgress {
+        LoadProgress {
+            finished: self.finished,
+            total: self.total,
+        }
+    }
+
+    /// Whether every pushed sound has finished loading.
+    pub fn is_empty(&self) -> bool {
+        self.queued.is_empty() && self.loading.is_empty()
+    }
+
+    /// Starts queued loads and returns the sounds that finished since the last poll.
+    ///
+    /// Sounds that failed to open are released and returned as errors.
+    pub fn poll(&mut self) -> Vec<(K, Result<Sound>)> {
+        let mut done = Vec::new();
+
+        while self.loading.len() < self.max_in_flight {
+            let Some((key, builder)) = self.queued.pop_front() else {
+                break;
+            };
+            let thread_id = self.thread_ids[self.next_thread % self.thread_ids.len()];
+            self.next_thread = self.next_thread.wrapping_add(1);
+
+            let builder = builder
+                .with_mode(Mode::NONBLOCKING)
+                .with_non_block_thread_id(thread_id);
+            match self.system.create_sound(&builder) {
+                Ok(sound) => self.loading.push((key, sound)),
+                Err(e) => done.push((key, Err(e))),
+            }
+        }
+
+        let mut index = 0;
+        while index < self.loading.len() {
+            let sound = self.loading[index].1;
+            let result = match sound.get_open_state().map(|state| state.0) {
+                Ok(OpenState::Ready | OpenState::Playing) => Some(Ok(sound)),
+                Ok(OpenState::Error(e)) | Err(e) => {
+                    let _ = sound.release();
+                    Some(Err(e))
+                }
+                Ok(_) => None,
+            };
+            match result {
+                Some(result) => {
+                    let (key, _) = self.loading.swap_remove(index);
+                    done.push((key, result));
+                }
+                None => index += 1,
+            }
+        }
+
+        self.finished += done.len();
+        done
+    }
+}
@@ -8,15 +8,29 @@ use std::ptr::NonNull;
 
 use fmod_sys::*;
 
+mod cache;
+pub use cache::{SharedSound, SoundCache};
+mod container;
+pub use container::{SubSoundContainer, SubSoundContainerBuilder};
 mod data_reading;
-pub use data_reading::SoundLock;
+pub use data_reading::{SoundLock, StreamHealth};
 mod defaults;
 mod general;
 mod information;
+mod load_queue;
+pub use load_queue::{LoadProgress, SoundLoadQueue};
 mod music;
+mod owned_memory;
+mod ready;
+pub use ready::{ReadyNotifier, SoundReady};
 mod relationship;
+mod sentence;
+mod stream_scheduler;
+pub use stream_scheduler::{StreamPriority, StreamScheduler};
 mod synchronization;
 pub use synchronization::SyncPoint;
+mod tag_watcher;
+pub use tag_watcher::TagWatcher;
 
 #[cfg(doc)]
 use crate::System;
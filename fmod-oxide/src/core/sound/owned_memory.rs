@@ -0,0 +1,37 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use crate::Sound;
+
+struct OwnedMemory {
+    sound: usize,
+    _data: Box<dyn Send + Sync>,
+}
+
+static OWNED_MEMORY: Mutex<Vec<OwnedMemory>> = Mutex::new(Vec::new());
+
+fn owned_memory() -> MutexGuard<'static, Vec<OwnedMemory>> {
+    // the list is only ever pushed to or removed from, so a panic can't leave it inconsistent
+    OWNED_MEMORY.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl Sound {
+    /// Keeps `data` alive until this sound is released.
+    pub(crate) fn keep_memory_alive(&self, data: Box<dyn Send + Sync>) {
+        owned_memory().push(OwnedMemory {
+            sound: self.inner.as_ptr() as usize,
+            _data: data,
+        });
+    }
+
+    /// Drops any data kept alive for this sound.
+    pub(crate) fn forget_owned_memory(&self) {
+        let key = self.inner.as_ptr() as usize;
+        owned_memory().retain(|m| m.sound != key);
+    }
+}
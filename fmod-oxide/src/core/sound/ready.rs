@@ -0,0 +1,166 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll, Waker};
+
+use crate::{NonBlockCallback, OpenState, Result, Sound};
+
+#[cfg(doc)]
+use crate::{Channel, Mode, SoundBuilder};
+
+type ReadyHandler = Box<dyn FnMut(Sound, Result<()>) + Send>;
+
+struct Entry {
+    sound: usize,
+    handlers: Vec<ReadyHandler>,
+    wakers: Vec<Waker>,
+    // the result of the last completion the callback has dispatched, if any
+    last: Option<Result<()>>,
+}
+
+static ENTRIES: Mutex<Vec<Entry>> = Mutex::new(Vec::new());
+
+fn entries() -> MutexGuard<'static, Vec<Entry>> {
+    // handlers are run outside the lock, so poisoning can't leave an entry half-updated
+    ENTRIES.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn entry(entries: &mut Vec<Entry>, sound: Sound) -> &mut Entry {
+    let key = sound.inner.as_ptr() as usize;
+    let index = match entries.iter().position(|e| e.sound == key) {
+        Some(index) => index,
+        None => {
+            entries.push(Entry {
+                sound: key,
+                handlers: Vec::new(),
+                wakers: Vec::new(),
+                last: None,
+            });
+            entries.len() - 1
+        }
+    };
+    &mut entries[index]
+}
+
+/// A [`NonBlockCallback`] that forwards completions to [`Sound::on_ready`] handlers and [`Sound::ready`] futures.
+///
+/// Pass it to [`SoundBuilder::with_nonblock_callback`] when creating a sound with [`Mode::NONBLOCKING`].
+#[derive(Debug, Clone, Copy)]
+pub struct ReadyNotifier;
+
+unsafe impl NonBlockCallback for ReadyNotifier {
+    fn call(sound: Sound, result: Result<()>) -> Result<()> {
+        let (mut handlers, wakers) = {
+            let mut entries = entries();
+            let entry = entry(&mut entries, sound);
+            entry.last = Some(result);
+            (
+                std::mem::take(&mut entry.handlers),
+                std::mem::take(&mut entry.wakers),
+            )
+        };
+
+        // run handlers without holding the lock, so they are free to register more
+        for handler in &mut handlers {
+            handler(sound, result);
+        }
+        wakers.into_iter().for_each(Waker::wake);
+
+        let mut entries = entries();
+        let entry = entry(&mut entries, sound);
+        handlers.append(&mut entry.handlers);
+        entry.handlers = handlers;
+        Ok(())
+    }
+}
+
+/// Resolves once a [`Mode::NONBLOCKING`] sound has finished opening or seeking.
+///
+/// Create with [`Sound::ready`].
+#[derive(Debug)]
+pub struct SoundReady {
+    sound: Sound,
+}
+
+impl Future for SoundReady {
+    type Output = Result<()>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // register the waker before checking, so a completion in between isn't missed
+        {
+            let mut entries = entries();
+            let wakers = &mut entry(&mut entries, self.sound).wakers;
+            // polling again with the same waker must not grow the list
+            if !wakers.iter().any(|w| w.will_wake(cx.waker())) {
+                wakers.push(cx.waker().clone());
+            }
+        }
+
+        match self.sound.get_open_state()?.0 {
+            OpenState::Ready | OpenState::Playing => Poll::Ready(Ok(())),
+            OpenState::Error(e) => Poll::Ready(Err(e)),
+            _ => Poll::Pending,
+        }
+    }
+}
+
+impl Sound {
+    /// Calls `handler` whenever this sound finishes a non blocking operation.
+    ///
+    /// This covers the initial open of a [`Mode::NONBLOCKING`] sound,
+    /// as well as later seeks such as [`Channel::set_position`] on a non blocking stream (or restarting it after the first playthrough).
+    /// `handler` receives the result of the operation.
+    ///
+    /// The sound must have been created with [`SoundBuilder::with_nonblock_callback`] using [`ReadyNotifier`], otherwise `handler` is never called.
+    /// If the sound has already finished opening, `handler` is called immediately for that completion, on the calling thread.
+    ///
+    /// `handler` is called from FMOD's async thread, so the same restrictions as [`NonBlockCallback`] apply.
+    pub fn on_ready<F>(&self, mut handler: F) -> Result<()>
+    where
+        F: FnMut(Sound, Result<()>) + Send + 'static,
+    {
+        let result = {
+            let mut entries = entries();
+            // checked under the lock, so a completion is either dispatched by the callback
+            // (which takes the handlers under this lock) or seen here, never both
+            let entry = entry(&mut entries, *self);
+            let finished = match self.get_open_state()?.0 {
+                OpenState::Ready | OpenState::Playing | OpenState::Error(_) => entry.last,
+                _ => None,
+            };
+            let Some(result) = finished else {
+                entry.handlers.push(Box::new(handler));
+                return Ok(());
+            };
+            result
+        };
+
+        // called without the lock, so the handler is free to register more
+        handler(*self, result);
+        entry(&mut entries(), *self)
+            .handlers
+            .push(Box::new(handler));
+        Ok(())
+    }
+
+    /// Removes every handler registered with [`Sound::on_ready`].
+    ///
+    /// [`Sound::release`] calls this for you.
+    pub fn clear_on_ready(&self) {
+        let key = self.inner.as_ptr() as usize;
+        entries().retain(|e| e.sound != key);
+    }
+
+    /// Returns a future that resolves once this sound has finished its current non blocking operation.
+    ///
+    /// Like [`Sound::on_ready`], this requires the sound to be created with [`ReadyNotifier`] as its non blocking callback.
+    pub fn ready(&self) -> SoundReady {
+        SoundReady { sound: *self }
+    }
+}
@@ -9,7 +9,7 @@ use std::ffi::c_int;
 use crate::{FmodResultExt, Result};
 use fmod_sys::*;
 
-use crate::{Sound, SoundGroup};
+use crate::{Sound, SoundGroup, check_same_system};
 
 impl Sound {
     /// Moves the sound from its existing [`SoundGroup`] to the specified sound group.
@@ -17,6 +17,7 @@ impl Sound {
     /// By default, a sound is located in the 'master sound group'.
     /// This can be retrieved with `System::getMasterSoundGroup`.
     pub fn set_sound_group(&self, group: SoundGroup) -> Result<()> {
+        check_same_system(|| self.get_system(), || group.get_system())?;
         unsafe { FMOD_Sound_SetSoundGroup(self.inner.as_ptr(), group.into()).to_result() }
     }
 
@@ -0,0 +1,87 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::{c_int, c_ulonglong};
+
+use crate::{Channel, ChannelGroup, Error, Result, Sound, TimeUnit};
+
+impl Sound {
+    /// Checks that every index in `sentence` refers to a subsound of this sound.
+    ///
+    /// Returns [`Error::Subsounds`] if this sound has no subsounds,
+    /// and [`Error::InvalidParam`] if `sentence` is empty or any index is out of range.
+    pub fn validate_sub_sound_sentence(&self, sentence: &[c_int]) -> Result<()> {
+        let count = self.get_sub_sound_count()?;
+        if count == 0 {
+            return Err(Error::Subsounds);
+        }
+        if sentence.is_empty() || sentence.iter().any(|&index| !(0..count).contains(&index)) {
+            return Err(Error::InvalidParam);
+        }
+        Ok(())
+    }
+
+    /// Plays the subsounds at `sentence` back to back, with no gaps between them.
+    ///
+    /// This is the replacement for `Sound::setSubSoundSentence`, which was removed in FMOD 2.0.
+    /// Each subsound is played on its own [`Channel`] in `channel_group` (or the master group),
+    /// and started sample accurately at the end of the one before it using [`ChannelControl::set_delay`](crate::ChannelControl::set_delay).
+    /// The same index may appear more than once.
+    ///
+    /// The indices are validated with [`Sound::validate_sub_sound_sentence`] before anything is played.
+    /// Stream subsounds can't be played at the same time as each other, so this sound should not be a stream.
+    ///
+    /// Returns the channels in sentence order.
+    pub fn play_sub_sound_sentence(
+        &self,
+        channel_group: Option<ChannelGroup>,
+        sentence: &[c_int],
+    ) -> Result<Vec<Channel>> {
+        self.validate_sub_sound_sentence(sentence)?;
+        let system = self.get_system()?;
+        let group = match channel_group {
+            Some(group) => group,
+            None => system.get_master_channel_group()?,
+        };
+        let (sample_rate, _, _) = system.get_software_format()?;
+        let (buffer_length, _) = system.get_dsp_buffer_size()?;
+
+        let mut parts = Vec::with_capacity(sentence.len());
+        for &index in sentence {
+            let sound = self.get_sub_sound(index)?;
+            let length = sound.get_length(TimeUnit::PCM)?;
+            let (frequency, _) = sound.get_defaults()?;
+            // the parent clock runs at the mixer rate, not the subsound's own rate
+            let length = (f64::from(length) * f64::from(sample_rate) / f64::from(frequency)).round()
+                as c_ulonglong;
+            parts.push((sound, length));
+        }
+
+        let mut channels = Vec::with_capacity(parts.len());
+        let result = (|| {
+            let _lock = system.lock_dsp()?;
+            let (_, parent_clock) = group.get_dsp_clock()?;
+            // two mix blocks is enough for the first start to still be in the future when the mixer sees it
+            let mut start = parent_clock.saturating_add(c_ulonglong::from(buffer_length) * 2);
+            for &(sound, length) in &parts {
+                let channel = system.play_sound(sound, Some(group), true)?;
+                channels.push(channel);
+                channel.set_delay(start, 0, false)?;
+                channel.set_paused(false)?;
+                start = start.saturating_add(length);
+            }
+            Ok(())
+        })();
+
+        if let Err(e) = result {
+            for channel in &channels {
+                let _ = channel.stop();
+            }
+            return Err(e);
+        }
+        Ok(channels)
+    }
+}
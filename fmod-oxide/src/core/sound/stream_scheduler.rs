@@ -0,0 +1,105 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::c_int;
+
+use crate::{SoundBuilder, System};
+
+#[cfg(doc)]
+use crate::{Mode, SystemBuilder};
+
+/// How urgently a [`Mode::NONBLOCKING`] load is needed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum StreamPriority {
+    /// Needed right away, such as a stream that is about to start playing.
+    ///
+    /// High priority loads get a thread to themselves, so they never wait behind bulk loads.
+    High,
+    /// Everything else.
+    #[default]
+    Normal,
+}
+
+/// Assigns [`Mode::NONBLOCKING`] loads to FMOD's non blocking threads.
+///
+/// When more than one thread is available, thread 0 is kept for [`StreamPriority::High`] loads,
+/// and the remaining loads go to whichever other thread has the fewest bytes outstanding.
+///
+/// The scheduler only sees what it is told: call [`StreamScheduler::finished`] once a load completes
+/// so its size stops counting against its thread.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamScheduler {
+    outstanding: Vec<u64>,
+}
+
+impl StreamScheduler {
+    /// Number of non blocking threads FMOD supports. Valid thread ids are `0..MAX_THREADS`.
+    pub const MAX_THREADS: c_int = 5;
+
+    /// Creates a scheduler using the thread count set with [`SystemBuilder::non_block_threads`].
+    pub fn new(system: System) -> Self {
+        Self::with_thread_count(system.get_non_block_thread_count())
+    }
+
+    /// Creates a scheduler for `count` threads, clamped to between 1 and [`Self::MAX_THREADS`].
+    pub fn with_thread_count(count: c_int) -> Self {
+        let count = count.clamp(1, Self::MAX_THREADS) as usize;
+        Self {
+            outstanding: vec![0; count],
+        }
+    }
+
+    /// Number of threads loads are spread across.
+    pub fn thread_count(&self) -> c_int {
+        self.outstanding.len() as c_int
+    }
+
+    /// Picks a thread id for a load of `size` bytes, and counts it as outstanding on that thread.
+    pub fn assign(&mut self, priority: StreamPriority, size: u64) -> c_int {
+        let index = if self.outstanding.len() == 1 || priority == StreamPriority::High {
+            0
+        } else {
+            // min_by_key returns the first minimum, so ties go to the lowest id
+            (1..self.outstanding.len())
+                .min_by_key(|&i| self.outstanding[i])
+                .unwrap_or(0)
+        };
+        self.outstanding[index] = self.outstanding[index].saturating_add(size);
+        index as c_int
+    }
+
+    /// Assigns a thread to `builder` with [`SoundBuilder::with_non_block_thread_id`].
+    ///
+    /// See [`StreamScheduler::assign`].
+    pub fn schedule<'a>(
+        &mut self,
+        builder: SoundBuilder<'a>,
+        priority: StreamPriority,
+        size: u64,
+    ) -> SoundBuilder<'a> {
+        let thread_id = self.assign(priority, size);
+        builder.with_non_block_thread_id(thread_id)
+    }
+
+    /// Marks a load of `size` bytes on `thread_id` as finished.
+    pub fn finished(&mut self, thread_id: c_int, size: u64) {
+        if let Some(outstanding) = usize::try_from(thread_id)
+            .ok()
+            .and_then(|i| self.outstanding.get_mut(i))
+        {
+            *outstanding = outstanding.saturating_sub(size);
+        }
+    }
+
+    /// Bytes assigned to `thread_id` that have not been marked as finished.
+    pub fn outstanding(&self, thread_id: c_int) -> u64 {
+        usize::try_from(thread_id)
+            .ok()
+            .and_then(|i| self.outstanding.get(i))
+            .copied()
+            .unwrap_or(0)
+    }
+}
@@ -0,0 +1,70 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::c_int;
+
+use crate::{Error, Result};
+use crate::{Sound, Tag};
+
+/// Tracks which metadata tags of a [`Sound`] are new or have changed since the last poll.
+///
+/// Create with [`Sound::tag_updates`].
+///
+/// This is mostly useful for internet streams (i.e. shoutcast or icecast),
+/// where tags such as the song name arrive continuously and re-reading every tag each frame is wasteful.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TagWatcher {
+    sound: Sound,
+    tag_count: c_int,
+}
+
+impl Sound {
+    /// Creates a [`TagWatcher`] that yields only new or updated tags each time it is polled.
+    ///
+    /// The first poll yields every tag that has not been retrieved with [`Sound::get_tag`] yet.
+    pub fn tag_updates(&self) -> TagWatcher {
+        TagWatcher {
+            sound: *self,
+            tag_count: 0,
+        }
+    }
+}
+
+impl TagWatcher {
+    /// The [`Sound`] being watched.
+    pub fn sound(&self) -> Sound {
+        self.sound
+    }
+
+    /// Whether there are new or updated tags, without retrieving them.
+    pub fn has_updates(&self) -> Result<bool> {
+        let (tag_count, updated) = self.sound.get_tag_count()?;
+        Ok(updated > 0 || tag_count != self.tag_count)
+    }
+
+    /// Retrieves the tags that are new or have been updated since the last poll.
+    ///
+    /// Returns an empty [`Vec`] without touching the tags if nothing changed.
+    ///
+    /// This uses [`Sound::get_tag`] with an index of -1, so it will skip tags that were already retrieved that way elsewhere.
+    pub fn poll(&mut self) -> Result<Vec<Tag>> {
+        let (tag_count, updated) = self.sound.get_tag_count()?;
+        if updated == 0 && tag_count == self.tag_count {
+            return Ok(Vec::new());
+        }
+        self.tag_count = tag_count;
+
+        let mut tags = Vec::with_capacity(updated.max(0) as usize);
+        loop {
+            match self.sound.get_tag(None, -1) {
+                Ok(tag) => tags.push(tag),
+                Err(Error::TagNotFound) => break,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(tags)
+    }
+}
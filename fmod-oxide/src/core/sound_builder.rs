@@ -1,11 +1,15 @@
-use std::ffi::{c_char, c_int, c_uint, c_void};
+use std::ffi::{CStr, c_char, c_int, c_uint, c_void};
 use std::marker::PhantomData;
+use std::sync::Arc;
 
 use crate::{FmodResultExt, Guid, Result};
 use fmod_sys::*;
 use lanyard::Utf8CStr;
 
-use crate::{ChannelOrder, Mode, SoundFormat, SoundGroup, SoundType, TimeUnit, panic_wrapper};
+use crate::{
+    CallbackContext, CallbackThread, ChannelOrder, Mode, SoundFormat, SoundGroup, SoundType,
+    TimeUnit, panic_wrapper,
+};
 
 use super::{
     FileSystemAsync, FileSystemSync, Sound, System, async_filesystem_cancel, async_filesystem_read,
@@ -16,15 +20,30 @@ use super::{
 use crate::Error;
 
 /// A builder for creating a [`Sound`].
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SoundBuilder<'a> {
     pub(crate) mode: FMOD_MODE,
+    // the pointer fields are kept null, and filled in from the fields below when the sound is created
     pub(crate) create_sound_ex_info: FMOD_CREATESOUNDEXINFO,
     pub(crate) name_or_data: *const c_char,
+    pub(crate) inclusion_list: Option<&'a [c_int]>,
+    pub(crate) dls_name: Option<&'a Utf8CStr>,
+    // may point into owned_data rather than borrowing for 'a, see with_encryption_key_owned
+    pub(crate) encryption_key: Option<&'a Utf8CStr>,
+    pub(crate) fsb_guid: Option<&'a Guid>,
+    pub(crate) owned_data: Vec<OwnedData>,
     pub(crate) _phantom: PhantomData<&'a ()>,
 }
 
-const EMPTY_EXINFO: FMOD_CREATESOUNDEXINFO = unsafe {
+/// Data a [`SoundBuilder`] owns, which FMOD may read after the sound is created.
+#[derive(Debug, Clone)]
+pub(crate) enum OwnedData {
+    Bytes(Arc<[u8]>),
+    #[cfg(feature = "mmap")]
+    Mapped(crate::MappedFile),
+}
+
+pub(crate) const EMPTY_EXINFO: FMOD_CREATESOUNDEXINFO = unsafe {
     FMOD_CREATESOUNDEXINFO {
         cbsize: std::mem::size_of::<FMOD_CREATESOUNDEXINFO>() as c_int,
         ..std::mem::MaybeUninit::zeroed().assume_init()
@@ -72,6 +91,11 @@ impl<'a> SoundBuilder<'a> {
             mode: 0,
             create_sound_ex_info: EMPTY_EXINFO,
             name_or_data: filename.as_ptr(),
+            inclusion_list: None,
+            dls_name: None,
+            encryption_key: None,
+            fsb_guid: None,
+            owned_data: Vec::new(),
             _phantom: PhantomData,
         }
     }
@@ -93,6 +117,11 @@ impl<'a> SoundBuilder<'a> {
                 ..EMPTY_EXINFO
             },
             name_or_data: std::ptr::null(),
+            inclusion_list: None,
+            dls_name: None,
+            encryption_key: None,
+            fsb_guid: None,
+            owned_data: Vec::new(),
             _phantom: PhantomData,
         }
     }
@@ -111,6 +140,33 @@ impl<'a> SoundBuilder<'a> {
                 ..EMPTY_EXINFO
             },
             name_or_data: data.as_ptr().cast(),
+            inclusion_list: None,
+            dls_name: None,
+            encryption_key: None,
+            fsb_guid: None,
+            owned_data: Vec::new(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Open the sound from a buffer of encoded data (such as an Ogg Vorbis or Opus file loaded by an asset system).
+    ///
+    /// Unlike [`Self::open_memory`] this is safe: the builder owns `data`,
+    /// and if the sound is opened with [`Mode::NONBLOCKING`] the data is kept alive until the sound is released.
+    pub fn open_owned_memory(data: Vec<u8>) -> SoundBuilder<'static> {
+        let data: Arc<[u8]> = data.into();
+        SoundBuilder {
+            mode: FMOD_OPENMEMORY,
+            create_sound_ex_info: FMOD_CREATESOUNDEXINFO {
+                length: data.len() as c_uint,
+                ..EMPTY_EXINFO
+            },
+            name_or_data: data.as_ptr().cast(),
+            inclusion_list: None,
+            dls_name: None,
+            encryption_key: None,
+            fsb_guid: None,
+            owned_data: vec![OwnedData::Bytes(data)],
             _phantom: PhantomData,
         }
     }
@@ -121,6 +177,7 @@ impl<'a> SoundBuilder<'a> {
     ///
     /// The slice must remain valid until the sound has been *released*.
     /// Unlike [`Self::open_memory`] this function does not copy the data, so it is even more unsafe!
+    /// [`Self::open_memory_guarded`] is a safe alternative for data in an [`Arc`].
     pub const unsafe fn open_memory_point(data: &'a [u8]) -> Self {
         Self {
             mode: FMOD_OPENMEMORY_POINT,
@@ -129,6 +186,33 @@ impl<'a> SoundBuilder<'a> {
                 ..EMPTY_EXINFO
             },
             name_or_data: data.as_ptr().cast(),
+            inclusion_list: None,
+            dls_name: None,
+            encryption_key: None,
+            fsb_guid: None,
+            owned_data: Vec::new(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Open the sound from a shared buffer without copying it, like [`Self::open_memory_point`] but safe.
+    ///
+    /// The builder holds a clone of `data`, and the created sound holds another until it is released with [`Sound::release`],
+    /// so a buffer shared with an asset cache can be dropped there while FMOD is still playing from it.
+    /// If the sound is never released (or only released along with its [`System`]) the buffer is never freed.
+    pub fn open_memory_guarded(data: Arc<[u8]>) -> SoundBuilder<'static> {
+        SoundBuilder {
+            mode: FMOD_OPENMEMORY_POINT,
+            create_sound_ex_info: FMOD_CREATESOUNDEXINFO {
+                length: data.len() as c_uint,
+                ..EMPTY_EXINFO
+            },
+            name_or_data: data.as_ptr().cast(),
+            inclusion_list: None,
+            dls_name: None,
+            encryption_key: None,
+            fsb_guid: None,
+            owned_data: vec![OwnedData::Bytes(data)],
             _phantom: PhantomData,
         }
     }
@@ -181,10 +265,11 @@ impl<'a> SoundBuilder<'a> {
 
     /// # Safety
     ///
-    /// The [`FMOD_CREATESOUNDEXINFO`] must be valid.
+    /// The [`FMOD_CREATESOUNDEXINFO`] must be valid, and any pointers in it must be valid for `'a`.
     #[must_use]
     pub const unsafe fn with_raw_ex_info(mut self, ex_info: FMOD_CREATESOUNDEXINFO) -> Self {
         self.create_sound_ex_info = ex_info;
+        unsafe { self.take_ex_info_pointers() };
         self
     }
 
@@ -251,27 +336,43 @@ impl<'a> SoundBuilder<'a> {
     }
 
     /// List of subsound indices to load from file.
-    // TODO: check if this is safe
     #[must_use]
     pub const fn with_inclusion_list(mut self, list: &'a [c_int]) -> Self {
-        self.create_sound_ex_info.inclusionlist = list.as_ptr().cast_mut().cast();
-        self.create_sound_ex_info.inclusionlistnum = list.len() as c_int;
+        self.inclusion_list = Some(list);
         self
     }
 
     /// File path for a [`SoundType::DLS`] sample set to use when loading a [`SoundType::MIDI`] file, see below for defaults.
-    // TODO check safety
     #[must_use]
     pub const fn with_dls_name(mut self, dls_name: &'a Utf8CStr) -> Self {
-        self.create_sound_ex_info.dlsname = dls_name.as_ptr();
+        self.dls_name = Some(dls_name);
         self
     }
 
     /// Key for encrypted [`SoundType::FSB`] file, cannot be used in conjunction with [`Self::open_memory_point`].
-    // TODO check safety
     #[must_use]
     pub const fn with_encryption_key(mut self, key: &'a Utf8CStr) -> Self {
-        self.create_sound_ex_info.encryptionkey = key.as_ptr();
+        self.encryption_key = Some(key);
+        self
+    }
+
+    /// Like [`Self::with_encryption_key`], but the builder keeps its own copy of `key`.
+    ///
+    /// This avoids tying the builder's lifetime to the key, which makes it easy to store builders.
+    ///
+    /// FMOD reads the key as a C string, so it is cut off at the first nul byte.
+    #[must_use]
+    pub fn with_encryption_key_owned(mut self, mut key: String) -> Self {
+        if let Some(nul) = key.find('\0') {
+            key.truncate(nul);
+        }
+        key.push('\0');
+        let key: Arc<[u8]> = key.into_bytes().into();
+        // Safety: the key came from a String and is cut off at its first nul byte.
+        // It lives in owned_data, which clones of the builder share, so it outlives every reference to it
+        self.encryption_key =
+            Some(unsafe { &*std::ptr::from_ref(Utf8CStr::from_utf8_with_nul_unchecked(&key)) });
+        self.owned_data.push(OwnedData::Bytes(key));
         self
     }
 
@@ -319,6 +420,10 @@ impl<'a> SoundBuilder<'a> {
     }
 
     /// Ignore [`System::set_filesystem_sync`] and this [`SoundBuilder`]'s file callbacks.
+    ///
+    /// The file is read with the platform native file functions instead.
+    /// When a file can't be found, loading it once with this set tells apart a missing file
+    /// from a custom filesystem that can't find it (see [`crate::FileDiag`]).
     #[must_use]
     pub const fn with_ignore_set_filesystem(mut self, ignore: bool) -> Self {
         self.create_sound_ex_info.ignoresetfilesystem = ignore as _;
@@ -346,11 +451,12 @@ impl<'a> SoundBuilder<'a> {
         self
     }
 
-    /// On input, GUID of already loaded [`SoundType::FSB`] file to reduce disk access, on output, GUID of loaded FSB.
-    // TODO check safety
+    /// GUID of an already loaded [`SoundType::FSB`] file to reduce disk access.
+    ///
+    /// FMOD also writes the GUID of the loaded FSB back, but that is written to a copy and `guid` is left untouched.
     #[must_use]
     pub const fn with_fsb_guid(mut self, guid: &'a Guid) -> Self {
-        self.create_sound_ex_info.fsbguid = std::ptr::from_ref(guid).cast_mut().cast();
+        self.fsb_guid = Some(guid);
         self
     }
 
@@ -363,6 +469,7 @@ impl<'a> SoundBuilder<'a> {
             data_len: c_uint,
         ) -> FMOD_RESULT {
             panic_wrapper(|| {
+                let _context = CallbackContext::enter(CallbackThread::Stream);
                 let result = C::read(unsafe { Sound::from_ffi(sound) }, unsafe {
                     std::slice::from_raw_parts_mut(data.cast(), data_len as _)
                 });
@@ -376,6 +483,7 @@ impl<'a> SoundBuilder<'a> {
             postype: FMOD_TIMEUNIT,
         ) -> FMOD_RESULT {
             panic_wrapper(|| {
+                let _context = CallbackContext::enter(CallbackThread::Stream);
                 let result = C::set_position(
                     unsafe { Sound::from_ffi(sound) },
                     subsound,
@@ -400,6 +508,7 @@ impl<'a> SoundBuilder<'a> {
             result: FMOD_RESULT,
         ) -> FMOD_RESULT {
             panic_wrapper(|| {
+                let _context = CallbackContext::enter(CallbackThread::NonBlocking);
                 let result = C::call(unsafe { Sound::from_ffi(sound) }, result.to_result());
                 FMOD_RESULT::from_result(result)
             })
@@ -410,8 +519,71 @@ impl<'a> SoundBuilder<'a> {
         self
     }
 
-    pub(crate) fn ex_info_is_empty(&self) -> bool {
-        self.create_sound_ex_info == EMPTY_EXINFO
+    /// Copies everything the ex info points to, so the arguments don't borrow from this builder.
+    pub(crate) fn create_sound_args(&self) -> CreateSoundArgs {
+        let mut args = CreateSoundArgs {
+            name_or_data: self.name_or_data,
+            mode: self.mode,
+            ex_info: self.create_sound_ex_info,
+            keep_alive: Vec::new(),
+        };
+        if let Some(list) = self.inclusion_list {
+            let mut list: Box<[c_int]> = list.into();
+            args.ex_info.inclusionlist = list.as_mut_ptr();
+            args.ex_info.inclusionlistnum = list.len() as c_int;
+            args.keep_alive(list);
+        }
+        if let Some(name) = self.dls_name {
+            let name: Box<[u8]> = name.as_bytes_with_nul().into();
+            args.ex_info.dlsname = name.as_ptr().cast();
+            args.keep_alive(name);
+        }
+        if let Some(key) = &self.encryption_key {
+            let key: Box<[u8]> = key.as_bytes_with_nul().into();
+            args.ex_info.encryptionkey = key.as_ptr().cast();
+            args.keep_alive(key);
+        }
+        if let Some(guid) = self.fsb_guid {
+            let mut guid = Box::new(FMOD_GUID::from(*guid));
+            args.ex_info.fsbguid = &raw mut *guid;
+            args.keep_alive(guid);
+        }
+        for data in &self.owned_data {
+            args.keep_alive(data.clone());
+        }
+        args
+    }
+
+    /// Moves the pointer fields of the raw ex info into their typed fields.
+    ///
+    /// # Safety
+    ///
+    /// The pointers must be valid for `'a`.
+    // from_ptr_unchecked isn't const, so the C strings go through CStr
+    const unsafe fn take_ex_info_pointers(&mut self) {
+        let ex_info = &mut self.create_sound_ex_info;
+        if !ex_info.inclusionlist.is_null() {
+            self.inclusion_list = Some(unsafe {
+                std::slice::from_raw_parts(ex_info.inclusionlist, ex_info.inclusionlistnum as usize)
+            });
+        }
+        if !ex_info.dlsname.is_null() {
+            self.dls_name =
+                Some(unsafe { Utf8CStr::from_cstr_unchecked(CStr::from_ptr(ex_info.dlsname)) });
+        }
+        if !ex_info.encryptionkey.is_null() {
+            self.encryption_key = Some(unsafe {
+                Utf8CStr::from_cstr_unchecked(CStr::from_ptr(ex_info.encryptionkey))
+            });
+        }
+        if !ex_info.fsbguid.is_null() {
+            self.fsb_guid = Some(unsafe { &*ex_info.fsbguid.cast::<Guid>() });
+        }
+        ex_info.inclusionlist = std::ptr::null_mut();
+        ex_info.inclusionlistnum = 0;
+        ex_info.dlsname = std::ptr::null();
+        ex_info.encryptionkey = std::ptr::null();
+        ex_info.fsbguid = std::ptr::null_mut();
     }
 
     /// Helper method that forwards to [`System::create_sound`].
@@ -433,8 +605,24 @@ impl<'a> SoundBuilder<'a> {
     }
 
     /// Get the raw ex info of this [`SoundBuilder`].
+    ///
+    /// Pointer fields point into this builder (or data it borrows), so they must not outlive it.
     pub const fn raw_ex_info(&self) -> FMOD_CREATESOUNDEXINFO {
-        self.create_sound_ex_info
+        let mut ex_info = self.create_sound_ex_info;
+        if let Some(list) = self.inclusion_list {
+            ex_info.inclusionlist = list.as_ptr().cast_mut();
+            ex_info.inclusionlistnum = list.len() as c_int;
+        }
+        if let Some(name) = self.dls_name {
+            ex_info.dlsname = name.as_ptr();
+        }
+        if let Some(key) = self.encryption_key {
+            ex_info.encryptionkey = key.as_ptr();
+        }
+        if let Some(guid) = self.fsb_guid {
+            ex_info.fsbguid = std::ptr::from_ref(guid).cast_mut().cast();
+        }
+        ex_info
     }
 
     /// Get the raw name/data/url of this [`SoundBuilder`].
@@ -517,35 +705,18 @@ impl<'a> SoundBuilder<'a> {
     }
 
     /// Get the inclusion list of this [`SoundBuilder`].
-    pub fn inclusion_list(&self) -> Option<&'a [c_int]> {
-        if self.create_sound_ex_info.inclusionlist.is_null() {
-            None
-        } else {
-            Some(unsafe {
-                std::slice::from_raw_parts(
-                    self.create_sound_ex_info.inclusionlist.cast(),
-                    self.create_sound_ex_info.inclusionlistnum as usize,
-                )
-            })
-        }
+    pub const fn inclusion_list(&self) -> Option<&'a [c_int]> {
+        self.inclusion_list
     }
 
     /// Get the DLS name of this [`SoundBuilder`].
-    pub fn dls_name(&self) -> Option<&Utf8CStr> {
-        if self.create_sound_ex_info.dlsname.is_null() {
-            None
-        } else {
-            Some(unsafe { Utf8CStr::from_ptr_unchecked(self.create_sound_ex_info.dlsname) })
-        }
+    pub const fn dls_name(&self) -> Option<&Utf8CStr> {
+        self.dls_name
     }
 
     /// Get the encryption key of this [`SoundBuilder`].
-    pub fn encryption_key(&self) -> Option<&Utf8CStr> {
-        if self.create_sound_ex_info.encryptionkey.is_null() {
-            None
-        } else {
-            Some(unsafe { Utf8CStr::from_ptr_unchecked(self.create_sound_ex_info.encryptionkey) })
-        }
+    pub const fn encryption_key(&self) -> Option<&Utf8CStr> {
+        self.encryption_key
     }
 
     /// Get the max polyphony of this [`SoundBuilder`].
@@ -610,10 +781,9 @@ impl<'a> SoundBuilder<'a> {
 
     /// Get the FSB guid of this [`SoundBuilder`].
     pub const fn fsb_guid(&self) -> Option<Guid> {
-        if self.create_sound_ex_info.fsbguid.is_null() {
-            None
-        } else {
-            Some(unsafe { *(self.create_sound_ex_info.fsbguid.cast()) })
+        match self.fsb_guid {
+            Some(guid) => Some(*guid),
+            None => None,
         }
     }
 }
@@ -634,11 +804,55 @@ impl SoundBuilder<'_> {
         mode: FMOD_MODE,
         create_sound_ex_info: FMOD_CREATESOUNDEXINFO,
     ) -> Self {
-        Self {
+        let mut builder = Self {
             mode,
             create_sound_ex_info,
             name_or_data,
+            inclusion_list: None,
+            dls_name: None,
+            encryption_key: None,
+            fsb_guid: None,
+            owned_data: Vec::new(),
             _phantom: PhantomData,
+        };
+        unsafe { builder.take_ex_info_pointers() };
+        builder
+    }
+}
+
+/// The arguments to `FMOD_System_CreateSound`, with every pointer in the ex info filled in.
+pub(crate) struct CreateSoundArgs {
+    pub(crate) name_or_data: *const c_char,
+    pub(crate) mode: FMOD_MODE,
+    pub(crate) ex_info: FMOD_CREATESOUNDEXINFO,
+    keep_alive: Vec<Box<dyn Send + Sync>>,
+}
+
+impl CreateSoundArgs {
+    /// Keeps `data` alive for as long as FMOD may read the ex info.
+    pub(crate) fn keep_alive(&mut self, data: impl Send + Sync + 'static) {
+        self.keep_alive.push(Box::new(data));
+    }
+
+    pub(crate) fn ex_info_ptr(&mut self) -> *mut FMOD_CREATESOUNDEXINFO {
+        if self.ex_info == EMPTY_EXINFO {
+            std::ptr::null_mut()
+        } else {
+            &raw mut self.ex_info
+        }
+    }
+
+    /// Hands anything the ex info points to over to `sound`, if FMOD may still read it.
+    pub(crate) fn finish(self, sound: Sound) {
+        // without NONBLOCKING FMOD is done with the ex info by the time the sound is created,
+        // unless the sound plays straight from the data it was opened with
+        if !Mode::from_bits_truncate(self.mode)
+            .intersects(Mode::NONBLOCKING | Mode::OPEN_MEMORY_POINT)
+        {
+            return;
+        }
+        for data in self.keep_alive {
+            sound.keep_memory_alive(data);
         }
     }
 }
@@ -9,7 +9,7 @@ use lanyard::Utf8CString;
 use std::ffi::{c_int, c_void};
 
 use crate::{FmodResultExt, Result};
-use crate::{SoundGroup, System, get_string};
+use crate::{SoundGroup, System, get_string, get_string_bounded};
 
 impl SoundGroup {
     /// Retrieves the name of the sound group.
@@ -23,6 +23,19 @@ impl SoundGroup {
         })
     }
 
+    /// Retrieves the name of the sound group, truncated to at most `max_len` bytes.
+    ///
+    /// Unlike [`SoundGroup::get_name`] this never allocates more than `max_len + 1` bytes, and truncation is not an error.
+    pub fn get_name_with_max_len(&self, max_len: usize) -> Result<Utf8CString> {
+        get_string_bounded(max_len, |name| unsafe {
+            FMOD_SoundGroup_GetName(
+                self.inner.as_ptr(),
+                name.as_mut_ptr().cast(),
+                name.len() as c_int,
+            )
+        })
+    }
+
     /// Releases a soundgroup object and returns all sounds back to the master sound group.
     ///
     /// You cannot release the master [`SoundGroup`].
@@ -0,0 +1,123 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::{c_int, c_uint};
+
+use lanyard::Utf8CStr;
+
+use crate::{Mode, SoundBuilder, SoundFormat, SoundType, TimeUnit};
+
+#[cfg(doc)]
+use crate::Sound;
+
+/// A description of how to load a [`Sound`], made up only of plain data.
+///
+/// Unlike [`SoundBuilder`] this holds no pointers or callbacks,
+/// so with the `serde` feature enabled it can be stored in asset files and turned into a builder at load time.
+///
+/// Fields left as [`None`] use FMOD's defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct SoundSpec {
+    /// [`Mode`] flags for the sound.
+    ///
+    /// [`Mode::OPEN_MEMORY`], [`Mode::OPEN_MEMORY_POINT`], [`Mode::OPEN_USER`], and [`Mode::OPEN_RAW`] are ignored, see [`SoundBuilder::with_mode`].
+    pub mode: Mode,
+    /// Ignore the file format and treat the file as raw PCM in this format.
+    pub raw_format: Option<RawFormat>,
+    /// Size of the decoded buffer for [`Mode::CREATE_STREAM`].
+    pub decode_buffer_size: Option<c_uint>,
+    /// Buffer size for reading the file, -1 to disable buffering.
+    pub file_buffer_size: Option<c_int>,
+    /// Initial position to seek to for [`Mode::CREATE_STREAM`].
+    pub initial_seek_position: Option<(c_uint, TimeUnit)>,
+    /// Attempt to load using this type first instead of loading in codec priority order.
+    pub suggested_sound_type: Option<SoundType>,
+}
+
+/// The layout of raw PCM data, used by [`SoundSpec::raw_format`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RawFormat {
+    /// Number of channels in the data.
+    pub channel_count: c_int,
+    /// Default playback rate of the data, in Hz.
+    pub default_frequency: c_int,
+    /// Format of the sample data.
+    pub format: SoundFormat,
+}
+
+impl Default for SoundSpec {
+    fn default() -> Self {
+        Self {
+            mode: Mode::DEFAULT,
+            raw_format: None,
+            decode_buffer_size: None,
+            file_buffer_size: None,
+            initial_seek_position: None,
+            suggested_sound_type: None,
+        }
+    }
+}
+
+impl SoundSpec {
+    /// Creates a [`SoundBuilder`] that opens `filename` as described by this spec.
+    pub fn to_builder<'a>(&self, filename: &'a Utf8CStr) -> SoundBuilder<'a> {
+        self.apply(SoundBuilder::open(filename))
+    }
+
+    /// Applies the settings of this spec to `builder`.
+    ///
+    /// [`Mode`] flags are added to the builder's existing flags.
+    #[must_use]
+    pub fn apply<'a>(&self, mut builder: SoundBuilder<'a>) -> SoundBuilder<'a> {
+        builder = builder.with_mode(self.mode);
+        if let Some(raw) = self.raw_format {
+            builder = builder.with_open_raw(raw.channel_count, raw.default_frequency, raw.format);
+        }
+        if let Some(size) = self.decode_buffer_size {
+            builder = builder.with_decode_buffer_size(size);
+        }
+        if let Some(size) = self.file_buffer_size {
+            builder = builder.with_file_buffer_size(size);
+        }
+        if let Some((position, unit)) = self.initial_seek_position {
+            builder = builder.with_initial_seek_position(position, unit);
+        }
+        if let Some(sound_type) = self.suggested_sound_type {
+            builder = builder.with_suggested_sound_type(sound_type);
+        }
+        builder
+    }
+}
+
+impl SoundBuilder<'_> {
+    /// Extracts the plain data settings of this builder into a [`SoundSpec`].
+    ///
+    /// Settings left at FMOD's defaults (zero) are returned as [`None`].
+    pub fn spec(&self) -> SoundSpec {
+        let mode = self.mode().difference(
+            Mode::OPEN_MEMORY | Mode::OPEN_MEMORY_POINT | Mode::OPEN_USER | Mode::OPEN_RAW,
+        );
+        let raw_format = self.mode().contains(Mode::OPEN_RAW).then(|| RawFormat {
+            channel_count: self.channel_count(),
+            default_frequency: self.default_frequency(),
+            format: self.format(),
+        });
+        let (position, unit) = self.initial_seek_position();
+        let sound_type = self.suggested_sound_type();
+
+        SoundSpec {
+            mode,
+            raw_format,
+            decode_buffer_size: Some(self.decode_buffer_size()).filter(|&size| size != 0),
+            file_buffer_size: Some(self.file_buffer_size()).filter(|&size| size != 0),
+            initial_seek_position: (position != 0).then_some((position, unit)),
+            suggested_sound_type: (sound_type != SoundType::Unknown).then_some(sound_type),
+        }
+    }
+}
@@ -13,14 +13,16 @@ use crate::{FmodResultExt, Result};
 use fmod_sys::*;
 use lanyard::{Utf8CStr, Utf8CString};
 
-use super::{FloatMappingType, Resampler, Speaker};
-use crate::{DspParameterDataType, TagType, string_from_utf16_be, string_from_utf16_le};
+use super::{FloatMappingType, Resampler, Speaker, SpeakerMode};
+use crate::text::{self, Endian};
+use crate::{DspParameterDataType, TagType};
 
 #[cfg(doc)]
 use crate::{Channel, Geometry, Reverb3D, Sound, System, SystemBuilder};
 
 /// Structure describing a globally unique identifier.
-#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Default)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 // force this type to have the exact same layout as FMOD_STUDIO_PARAMETER_ID so we can safely transmute between them.
 #[repr(C)]
 pub struct Guid {
@@ -46,6 +48,126 @@ impl Guid {
     }
 }
 
+impl Guid {
+    /// Creates a GUID from its fields.
+    pub const fn new(
+        data_1: c_uint,
+        data_2: c_ushort,
+        data_3: c_ushort,
+        data_4: [c_uchar; 8],
+    ) -> Self {
+        Guid {
+            data_1,
+            data_2,
+            data_3,
+            data_4,
+        }
+    }
+
+    /// The all zero GUID, which FMOD uses to mean "no GUID".
+    pub const ZERO: Guid = Guid::new(0, 0, 0, [0; 8]);
+
+    /// Whether this is the all zero GUID.
+    pub fn is_zero(&self) -> bool {
+        *self == Guid::ZERO
+    }
+
+    /// The 16 bytes of this GUID in the order they are written out, as in `{00112233-4455-6677-8899-aabbccddeeff}`.
+    ///
+    /// This is independent of platform endianness, so it is suitable for storing GUIDs in files.
+    pub const fn to_bytes(&self) -> [u8; 16] {
+        let [a, b, c, d] = self.data_1.to_be_bytes();
+        let [e, f] = self.data_2.to_be_bytes();
+        let [g, h] = self.data_3.to_be_bytes();
+        let [i, j, k, l, m, n, o, p] = self.data_4;
+        [a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p]
+    }
+
+    /// Creates a GUID from bytes in the order they are written out. The inverse of [`Guid::to_bytes`].
+    pub const fn from_bytes(bytes: [u8; 16]) -> Self {
+        let [a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p] = bytes;
+        Guid {
+            data_1: c_uint::from_be_bytes([a, b, c, d]),
+            data_2: c_ushort::from_be_bytes([e, f]),
+            data_3: c_ushort::from_be_bytes([g, h]),
+            data_4: [i, j, k, l, m, n, o, p],
+        }
+    }
+
+    /// The GUID as it is laid out in memory, which is how FMOD stores it.
+    ///
+    /// Unlike [`Guid::to_bytes`] the first three fields are in native endianness.
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        // Guid is repr(C), 16 bytes, and has no padding
+        unsafe { &*std::ptr::from_ref(self).cast::<[u8; 16]>() }
+    }
+}
+
+/// The error returned when parsing a [`Guid`] with [`str::parse`] fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseGuidError;
+
+impl std::fmt::Display for ParseGuidError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("invalid GUID, expected the form {xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx}")
+    }
+}
+
+impl std::error::Error for ParseGuidError {}
+
+impl std::str::FromStr for Guid {
+    type Err = ParseGuidError;
+
+    /// Parses a GUID of the form `{xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx}`, with or without the braces.
+    ///
+    /// Unlike [`Guid::parse`] this doesn't call into FMOD, so it works without the `studio` feature or an FMOD library present.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s
+            .strip_prefix('{')
+            .and_then(|s| s.strip_suffix('}'))
+            .unwrap_or(s);
+        let bytes = s.as_bytes();
+        if bytes.len() != 36 {
+            return Err(ParseGuidError);
+        }
+
+        let mut digits = [0u8; 32];
+        let mut count = 0;
+        for (index, &b) in bytes.iter().enumerate() {
+            // hyphens separate the groups, and must be exactly here
+            if matches!(index, 8 | 13 | 18 | 23) {
+                if b != b'-' {
+                    return Err(ParseGuidError);
+                }
+                continue;
+            }
+            digits[count] = (b as char).to_digit(16).ok_or(ParseGuidError)? as u8;
+            count += 1;
+        }
+
+        let mut out = [0u8; 16];
+        for (byte, pair) in out.iter_mut().zip(digits.chunks_exact(2)) {
+            *byte = (pair[0] << 4) | pair[1];
+        }
+        Ok(Guid::from_bytes(out))
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl From<uuid::Uuid> for Guid {
+    fn from(value: uuid::Uuid) -> Self {
+        let (data_1, data_2, data_3, data_4) = value.as_fields();
+        Guid::new(data_1, data_2, data_3, *data_4)
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl From<Guid> for uuid::Uuid {
+    fn from(value: Guid) -> Self {
+        uuid::Uuid::from_fields(value.data_1, value.data_2, value.data_3, &value.data_4)
+    }
+}
+
 impl From<FMOD_GUID> for Guid {
     fn from(value: FMOD_GUID) -> Self {
         Guid {
@@ -93,6 +215,7 @@ impl std::fmt::Display for Guid {
 ///
 /// To use a right handed coordinate system specify [`FMOD_INIT_3D_RIGHTHANDED`] from [`FMOD_INITFLAGS`] in [`SystemBuilder::build`].
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct Vector {
     /// X coordinate in 3D space.
@@ -125,6 +248,7 @@ impl From<FMOD_VECTOR> for Vector {
 
 /// Structure describing a position, velocity and orientation.
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct Attributes3D {
     /// Position in world space used for panning and attenuation.
@@ -159,8 +283,29 @@ impl From<Attributes3D> for FMOD_3D_ATTRIBUTES {
     }
 }
 
+/// Output format of the software mixer, see [`System::software_format`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SoftwareFormat {
+    /// Sample rate of the mixer.
+    pub sample_rate: c_int,
+    /// Speaker setup of the mixer.
+    pub speaker_mode: SpeakerMode,
+    /// Number of speakers for [`SpeakerMode::Raw`].
+    pub raw_speakers: c_int,
+}
+
+/// Buffer settings of the software mixer, see [`System::dsp_buffer_size`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DspBufferSize {
+    /// Mixer engine block size, in samples.
+    pub buffer_length: c_uint,
+    /// Number of blocks in the mixer's ring buffer.
+    pub buffer_count: c_int,
+}
+
 /// Performance information for Core API functionality.
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CpuUsage {
     /// DSP mixing engine CPU usage.
     ///
@@ -186,6 +331,21 @@ pub struct CpuUsage {
     pub convolution_2: c_float,
 }
 
+impl std::fmt::Display for CpuUsage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "dsp {:.1}% stream {:.1}% geometry {:.1}% update {:.1}% convolution {:.1}%/{:.1}%",
+            self.dsp,
+            self.stream,
+            self.geometry,
+            self.update,
+            self.convolution_1,
+            self.convolution_2
+        )
+    }
+}
+
 impl From<FMOD_CPU_USAGE> for CpuUsage {
     fn from(value: FMOD_CPU_USAGE) -> Self {
         CpuUsage {
@@ -542,7 +702,6 @@ pub struct Tag {
 
 /// List of tag data / metadata types.
 #[derive(Debug)]
-// FIXME: these strings are most likely null-terminated
 pub enum TagData {
     /// Raw binary data.
     Binary(Vec<u8>),
@@ -600,28 +759,29 @@ impl Tag {
                 FMOD_TAGDATATYPE_STRING => {
                     let ascii =
                         std::slice::from_raw_parts(value.data.cast(), value.datalen as usize);
-                    let string = String::from_utf8_lossy(ascii).into_owned();
+                    let string = text::decode_utf8_lossy(text::trim_nul(ascii)).into_owned();
                     TagData::String(string)
                 }
                 FMOD_TAGDATATYPE_STRING_UTF8 => {
                     let utf8 =
                         std::slice::from_raw_parts(value.data.cast(), value.datalen as usize);
-                    let string = String::from_utf8_lossy(utf8).into_owned();
+                    let string = text::decode_utf8_lossy(text::trim_nul(utf8)).into_owned();
                     TagData::Utf8String(string)
                 }
-                // depending on the architecture rust will optimize this to a no-op
-                // we still need to do this to ensure the correct endianness
-                // ideally we could use String::from_utf16_be_lossy but that is nightly only and the tracking issue has basically no activity
+                // datalen is in bytes, and FMOD doesn't guarantee the data is aligned for u16,
+                // so the text module decodes straight from the byte slice
                 FMOD_TAGDATATYPE_STRING_UTF16 => {
-                    let slice =
+                    let bytes =
                         std::slice::from_raw_parts(value.data.cast(), value.datalen as usize);
-                    let string = string_from_utf16_le(slice);
+                    let (bytes, endian) = text::strip_utf16_bom(bytes, Endian::Little);
+                    let string = text::decode_utf16_lossy(text::trim_nul_utf16(bytes), endian);
                     TagData::Utf16String(string)
                 }
                 FMOD_TAGDATATYPE_STRING_UTF16BE => {
-                    let slice =
+                    let bytes =
                         std::slice::from_raw_parts(value.data.cast(), value.datalen as usize);
-                    let string = string_from_utf16_be(slice);
+                    let (bytes, endian) = text::strip_utf16_bom(bytes, Endian::Big);
+                    let string = text::decode_utf16_lossy(text::trim_nul_utf16(bytes), endian);
                     TagData::Utf16StringBE(string)
                 }
                 _ => panic!("unrecognized tag data type"), // FIXME panic
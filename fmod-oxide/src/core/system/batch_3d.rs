@@ -0,0 +1,60 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::c_int;
+
+use fmod_sys::*;
+
+use crate::{Attributes3D, ChannelControl, System};
+use crate::{FmodResultExt, Result};
+
+#[cfg(doc)]
+use crate::Error;
+
+impl System {
+    /// Sets the position and velocity of many [`ChannelControl`]s at once, such as every emitter in a game world each frame.
+    ///
+    /// The DSP engine is locked once for the whole batch with [`System::lock_dsp`], so the mixer never runs partway through:
+    /// every emitter moves on the same mix block, and the mixer thread isn't woken and blocked again between calls.
+    /// The orientation in each [`Attributes3D`] is ignored, as [`ChannelControl`]s only use it for cones (see [`ChannelControl::set_3d_cone_orientation`]).
+    ///
+    /// Controls that have stopped or been stolen ([`Error::is_invalid_handle`]) are skipped, as emitters commonly outlive their sounds.
+    /// Any other error stops the batch and is returned.
+    /// Returns the number of controls that were updated.
+    ///
+    /// Each control still costs one FMOD call, so the savings are in lock traffic, not in the calls themselves,
+    /// and for a handful of controls calling [`ChannelControl::set_3d_attributes`] directly is just as good.
+    /// The `batch_3d` example in `fmod-examples` times both for 10 to 1000 channels, run it in release mode to see the difference on your platform.
+    /// The lock is held for the whole batch, so keep batches to what is updated in a frame rather than accumulating them.
+    pub fn set_many_3d_attributes(
+        &self,
+        attributes: &[(ChannelControl, Attributes3D)],
+    ) -> Result<c_int> {
+        if attributes.is_empty() {
+            return Ok(0);
+        }
+        let _lock = self.lock_dsp()?;
+
+        let mut updated = 0;
+        for (control, attributes) in attributes {
+            let result = unsafe {
+                // vector is layout compatible with FMOD_VECTOR
+                FMOD_ChannelControl_Set3DAttributes(
+                    control.inner.as_ptr(),
+                    std::ptr::from_ref(&attributes.position).cast(),
+                    std::ptr::from_ref(&attributes.velocity).cast(),
+                )
+                .to_result()
+            };
+            match result {
+                Ok(()) => updated += 1,
+                Err(e) if e.is_invalid_handle() => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(updated)
+    }
+}
@@ -9,13 +9,66 @@ use crate::{InitFlags, OutputType, SpeakerMode, System};
 use fmod_sys::*;
 use std::ffi::{c_int, c_uint, c_void};
 
+/// The kind of application a [`System`] is being set up for, used by [`SystemBuilder::profile`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InitProfile {
+    /// A game on desktop or console. See [`InitFlags::game_default`].
+    Game,
+    /// An editor or other tool. See [`InitFlags::tool_default`].
+    Tool,
+    /// A game on a phone or other low power device. See [`InitFlags::mobile_default`].
+    Mobile,
+}
+
+impl InitProfile {
+    /// The flags recommended for this profile.
+    pub const fn flags(self) -> InitFlags {
+        match self {
+            InitProfile::Game => InitFlags::game_default(),
+            InitProfile::Tool => InitFlags::tool_default(),
+            InitProfile::Mobile => InitFlags::mobile_default(),
+        }
+    }
+}
+
+impl InitFlags {
+    /// Recommended flags for games on desktop and console.
+    ///
+    /// Silent channels become virtual so they stop costing CPU, occlusion and distance filtering get a lowpass filter per channel,
+    /// and the output is clipped instead of wrapping around when the mix is too loud.
+    pub const fn game_default() -> Self {
+        InitFlags::VOL_0_BECOMES_VIRTUAL
+            .union(InitFlags::CHANNEL_LOWPASS)
+            .union(InitFlags::CLIP_OUTPUT)
+    }
+
+    /// Recommended flags for editors and other tools.
+    ///
+    /// Tools usually want to hear exactly what they are doing, so nothing is virtualized,
+    /// and the FMOD profiler can connect to see what is going on.
+    pub const fn tool_default() -> Self {
+        InitFlags::CLIP_OUTPUT.union(InitFlags::PROFILE_ENABLE)
+    }
+
+    /// Recommended flags for phones and other low power devices.
+    ///
+    /// Like [`InitFlags::game_default`], without the per channel lowpass filter to save CPU.
+    pub const fn mobile_default() -> Self {
+        InitFlags::VOL_0_BECOMES_VIRTUAL.union(InitFlags::CLIP_OUTPUT)
+    }
+}
+
 /// A builder for creating and initializing a [`System`].
 ///
 /// Handles setting values that can only be set before initialization for you.
+///
+/// For pre-initialization settings the builder doesn't cover, see [`SystemBuilder::build_with`].
 #[derive(Debug)]
 pub struct SystemBuilder {
     pub(crate) system: *mut FMOD_SYSTEM,
     pub(crate) thread_unsafe: bool,
+    pub(crate) extra_flags: InitFlags,
+    pub(crate) non_block_threads: c_int,
 }
 
 #[cfg(not(feature = "thread-unsafe"))]
@@ -43,6 +96,8 @@ impl SystemBuilder {
         Ok(SystemBuilder {
             system,
             thread_unsafe: false,
+            extra_flags: InitFlags::empty(),
+            non_block_threads: 1,
         })
     }
 
@@ -179,11 +234,45 @@ impl SystemBuilder {
         Ok(self)
     }
 
+    /// Sets a tag that identifies this system in log messages.
+    ///
+    /// The tag is passed to [`debug::DebugCallback`]s as [`debug::DebugMessage::tag`], and prefixed to the message when it is displayed.
+    /// This is mostly useful when running several systems at once.
+    pub fn log_tag(&mut self, tag: &str) -> &mut Self {
+        // the system already exists at this point, so we can tag it right away
+        crate::debug::set_log_tag(unsafe { System::from_ffi(self.system) }, tag);
+        self
+    }
+
+    /// Adds the [`InitFlags`] recommended for `profile` to the flags the system is built with.
+    ///
+    /// See [`InitFlags::game_default`], [`InitFlags::tool_default`] and [`InitFlags::mobile_default`].
+    pub fn profile(&mut self, profile: InitProfile) -> &mut Self {
+        self.extra_flags.insert(profile.flags());
+        self
+    }
+
     /// Initialize the system object and prepare FMOD for playback.
     pub fn build(self, max_channels: c_int, flags: InitFlags) -> Result<System> {
         unsafe { self.build_with_extra_driver_data(max_channels, flags, std::ptr::null_mut()) }
     }
 
+    /// Calls `configure` with the system before it is initialized, then initializes it like [`SystemBuilder::build`].
+    ///
+    /// This is an escape hatch for settings that FMOD only accepts before initialization but that the builder doesn't cover.
+    /// Only functions FMOD documents as usable before `System::init` work on the system passed to `configure`,
+    /// others generally fail with [`crate::Error::Uninitialized`].
+    /// If `configure` returns an error, the system isn't initialized and the error is returned.
+    pub fn build_with(
+        self,
+        max_channels: c_int,
+        flags: InitFlags,
+        configure: impl FnOnce(System) -> Result<()>,
+    ) -> Result<System> {
+        configure(unsafe { System::from_ffi(self.system) })?;
+        self.build(max_channels, flags)
+    }
+
     /// # Safety
     ///
     /// See the FMOD docs explaining driver data for more safety information.
@@ -199,9 +288,17 @@ impl SystemBuilder {
             #[cfg(not(feature = "thread-unsafe"))]
             flags.remove(InitFlags::THREAD_UNSAFE);
         }
-        unsafe {
+        flags.insert(self.extra_flags);
+        let max_channels = self.budgeted_max_channels(max_channels);
+        let system = unsafe {
             FMOD_System_Init(self.system, max_channels, flags.bits(), driver_data).to_result()?;
-            Ok(System::from_ffi(self.system))
+            System::from_ffi(self.system)
+        };
+        system.record_init_flags(flags);
+        system.record_non_block_thread_count(self.non_block_threads);
+        if flags.contains(InitFlags::PROFILE_ENABLE) {
+            system.mark_profiler_enabled();
         }
+        Ok(system)
     }
 }
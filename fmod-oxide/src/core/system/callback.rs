@@ -398,7 +398,7 @@ pub trait SystemCallback {
     }
 }
 
-unsafe extern "C" fn callback_impl<C: SystemCallback>(
+pub(super) unsafe extern "C" fn callback_impl<C: SystemCallback>(
     system: *mut FMOD_SYSTEM,
     callback_type: FMOD_SYSTEM_CALLBACK_TYPE,
     command_data_1: *mut c_void,
@@ -456,6 +456,9 @@ unsafe extern "C" fn callback_impl<C: SystemCallback>(
 }
 
 impl System {
+    /// Sets the callback for the whole [`System`], replacing any previously set callback.
+    ///
+    /// To have several independent callbacks installed at once, use [`System::add_callback`] instead.
     pub fn set_callback<C: SystemCallback>(&self, mask: SystemCallbackMask) -> Result<()> {
         unsafe {
             FMOD_System_SetCallback(self.inner.as_ptr(), Some(callback_impl::<C>), mask.into())
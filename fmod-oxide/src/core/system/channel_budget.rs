@@ -0,0 +1,105 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::c_int;
+
+use super::SystemMap;
+use crate::{Result, System, SystemBuilder};
+
+/// How many channels a [`System`] may play at once, set with [`SystemBuilder::channel_budget`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChannelBudget {
+    /// Maximum number of channels that are actually mixed (software channels).
+    pub real_channels: c_int,
+    /// Maximum number of channels that can exist at once, real or virtual.
+    pub virtual_channels: c_int,
+}
+
+/// The number of channels playing, as returned by [`System::channels_playing`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChannelsPlaying {
+    /// Number of playing channels, real and virtual.
+    pub total: c_int,
+    /// Number of playing channels that are being mixed.
+    pub real: c_int,
+}
+
+impl ChannelsPlaying {
+    /// Number of playing channels that are virtual, and so not being mixed.
+    pub fn virtual_count(&self) -> c_int {
+        self.total - self.real
+    }
+
+    /// How many more channels can start before `budget` runs out and FMOD starts stealing channels.
+    pub fn headroom(&self, budget: ChannelBudget) -> c_int {
+        (budget.virtual_channels - self.total).max(0)
+    }
+
+    /// How many more channels can be mixed before new channels start out virtual.
+    pub fn real_headroom(&self, budget: ChannelBudget) -> c_int {
+        (budget.real_channels - self.real).max(0)
+    }
+
+    /// How many channels are playing beyond `budget`, 0 if it hasn't been exceeded.
+    ///
+    /// A full budget is fine on its own, FMOD steals the least important channel to make room.
+    /// Anything over it means the budget was raised or bypassed after the channels started.
+    pub fn overage(&self, budget: ChannelBudget) -> c_int {
+        (self.total - budget.virtual_channels).max(0)
+    }
+}
+
+static BUDGETS: SystemMap<ChannelBudget> = SystemMap::new();
+
+impl SystemBuilder {
+    /// Sets how many channels the system may mix (`real_channels`) and play in total (`virtual_channels`).
+    ///
+    /// `real_channels` is passed to [`SystemBuilder::software_channels`],
+    /// and `virtual_channels` replaces the `max_channels` passed to [`SystemBuilder::build`].
+    ///
+    /// Use [`ChannelsPlaying::headroom`] and [`ChannelsPlaying::overage`] to check how much of the budget is in use,
+    /// so running out of channels (and FMOD silently stealing them) shows up during development.
+    pub fn channel_budget(
+        &mut self,
+        real_channels: c_int,
+        virtual_channels: c_int,
+    ) -> Result<&mut Self> {
+        self.software_channels(real_channels)?;
+        let budget = ChannelBudget {
+            real_channels,
+            virtual_channels,
+        };
+        BUDGETS.insert(unsafe { System::from_ffi(self.system) }, budget);
+        Ok(self)
+    }
+
+    pub(crate) fn budgeted_max_channels(&self, max_channels: c_int) -> c_int {
+        BUDGETS
+            .get(unsafe { System::from_ffi(self.system) })
+            .map_or(max_channels, |budget| budget.virtual_channels)
+    }
+}
+
+impl System {
+    /// Retrieves the budget set with [`SystemBuilder::channel_budget`], if any.
+    pub fn get_channel_budget(&self) -> Option<ChannelBudget> {
+        BUDGETS.get(*self)
+    }
+
+    /// Retrieves the number of currently playing channels.
+    ///
+    /// This is [`System::get_playing_channels`] with named fields.
+    /// Compare it against [`System::get_channel_budget`] with [`ChannelsPlaying::headroom`] and [`ChannelsPlaying::overage`].
+    pub fn channels_playing(&self) -> Result<ChannelsPlaying> {
+        let (total, real) = self.get_playing_channels()?;
+        Ok(ChannelsPlaying { total, real })
+    }
+
+    pub(crate) fn forget_channel_budget(&self) {
+        BUDGETS.remove(*self);
+    }
+}
@@ -0,0 +1,186 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::{c_float, c_int, c_uint, c_ushort};
+
+use crate::Result;
+use crate::{AdvancedSettings, OutputType, Resampler, SpeakerMode, System, SystemBuilder};
+
+/// The configuration of a [`System`], made up only of plain data.
+///
+/// Capture with [`SystemConfig::capture`] and recreate with [`SystemBuilder::from_config`].
+/// With the `serde` feature enabled this can be attached to bug reports,
+/// or checked in alongside tests so they run with the exact same mixer setup.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SystemConfig {
+    /// Output interface used to run the mixer.
+    pub output_type: OutputType,
+    /// Index of the output driver, for the output type.
+    pub driver: c_int,
+    /// Mixer sample rate, in Hz.
+    pub sample_rate: c_int,
+    /// Speaker setup of the mixer.
+    pub speaker_mode: SpeakerMode,
+    /// Number of speakers for [`SpeakerMode::Raw`].
+    pub raw_speakers: c_int,
+    /// Maximum number of software mixed channels.
+    pub software_channels: c_int,
+    /// Mixer block size, in samples.
+    pub dsp_buffer_length: c_uint,
+    /// Number of mixer blocks in the ring buffer.
+    pub dsp_buffer_count: c_int,
+    /// The plain data parts of the [`AdvancedSettings`].
+    pub advanced: AdvancedConfig,
+}
+
+/// The plain data parts of [`AdvancedSettings`], for a [`SystemConfig`].
+///
+/// The ASIO channel and speaker lists are left out, since they depend on the hardware.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AdvancedConfig {
+    /// See [`AdvancedSettings::max_mpeg_codecs`].
+    pub max_mpeg_codecs: c_int,
+    /// See [`AdvancedSettings::max_adpcm_codecs`].
+    pub max_adpcm_codecs: c_int,
+    /// See [`AdvancedSettings::max_xma_codecs`].
+    pub max_xma_codecs: c_int,
+    /// See [`AdvancedSettings::max_vorbis_codecs`].
+    pub max_vorbis_codecs: c_int,
+    /// See [`AdvancedSettings::max_at9_codecs`].
+    pub max_at9_codecs: c_int,
+    /// See [`AdvancedSettings::max_fadpcm_codecs`].
+    pub max_fadpcm_codecs: c_int,
+    /// See [`AdvancedSettings::max_opus_codecs`].
+    pub max_opus_codecs: c_int,
+    /// See [`AdvancedSettings::vol0_virtual_vol`].
+    pub vol0_virtual_vol: c_float,
+    /// See [`AdvancedSettings::default_decode_buffer_size`].
+    pub default_decode_buffer_size: c_uint,
+    /// See [`AdvancedSettings::profile_port`].
+    pub profile_port: c_ushort,
+    /// See [`AdvancedSettings::geometry_max_fade_time`].
+    pub geometry_max_fade_time: c_uint,
+    /// See [`AdvancedSettings::distance_filter_center_freq`].
+    pub distance_filter_center_freq: c_float,
+    /// See [`AdvancedSettings::reverb_3d_instance`].
+    pub reverb_3d_instance: c_int,
+    /// See [`AdvancedSettings::dsp_buffer_pool_size`].
+    pub dsp_buffer_pool_size: c_int,
+    /// See [`AdvancedSettings::resampler_method`].
+    pub resampler_method: Resampler,
+    /// See [`AdvancedSettings::random_seed`].
+    pub random_seed: c_uint,
+    /// See [`AdvancedSettings::max_convolution_threads`].
+    pub max_convolution_threads: c_int,
+    /// See [`AdvancedSettings::max_spatial_objects`].
+    pub max_spatial_objects: c_int,
+}
+
+impl AdvancedConfig {
+    /// Copies the plain data parts of `settings`.
+    pub fn capture(settings: &AdvancedSettings) -> Self {
+        Self {
+            max_mpeg_codecs: settings.max_mpeg_codecs,
+            max_adpcm_codecs: settings.max_adpcm_codecs,
+            max_xma_codecs: settings.max_xma_codecs,
+            max_vorbis_codecs: settings.max_vorbis_codecs,
+            max_at9_codecs: settings.max_at9_codecs,
+            max_fadpcm_codecs: settings.max_fadpcm_codecs,
+            max_opus_codecs: settings.max_opus_codecs,
+            vol0_virtual_vol: settings.vol0_virtual_vol,
+            default_decode_buffer_size: settings.default_decode_buffer_size,
+            profile_port: settings.profile_port,
+            geometry_max_fade_time: settings.geometry_max_fade_time,
+            distance_filter_center_freq: settings.distance_filter_center_freq,
+            reverb_3d_instance: settings.reverb_3d_instance,
+            dsp_buffer_pool_size: settings.dsp_buffer_pool_size,
+            resampler_method: settings.resampler_method,
+            random_seed: settings.random_seed,
+            max_convolution_threads: settings.max_convolution_threads,
+            max_spatial_objects: settings.max_spatial_objects,
+        }
+    }
+
+    /// Overwrites the matching fields of `settings`, leaving the rest alone.
+    pub fn apply(&self, settings: &mut AdvancedSettings) {
+        settings.max_mpeg_codecs = self.max_mpeg_codecs;
+        settings.max_adpcm_codecs = self.max_adpcm_codecs;
+        settings.max_xma_codecs = self.max_xma_codecs;
+        settings.max_vorbis_codecs = self.max_vorbis_codecs;
+        settings.max_at9_codecs = self.max_at9_codecs;
+        settings.max_fadpcm_codecs = self.max_fadpcm_codecs;
+        settings.max_opus_codecs = self.max_opus_codecs;
+        settings.vol0_virtual_vol = self.vol0_virtual_vol;
+        settings.default_decode_buffer_size = self.default_decode_buffer_size;
+        settings.profile_port = self.profile_port;
+        settings.geometry_max_fade_time = self.geometry_max_fade_time;
+        settings.distance_filter_center_freq = self.distance_filter_center_freq;
+        settings.reverb_3d_instance = self.reverb_3d_instance;
+        settings.dsp_buffer_pool_size = self.dsp_buffer_pool_size;
+        settings.resampler_method = self.resampler_method;
+        settings.random_seed = self.random_seed;
+        settings.max_convolution_threads = self.max_convolution_threads;
+        settings.max_spatial_objects = self.max_spatial_objects;
+    }
+}
+
+impl SystemConfig {
+    /// Records the current configuration of `system`.
+    ///
+    /// Call this after the system has been built, so defaults such as [`SpeakerMode::Default`] have been resolved.
+    pub fn capture(system: &System) -> Result<Self> {
+        let (sample_rate, speaker_mode, raw_speakers) = system.get_software_format()?;
+        let (dsp_buffer_length, dsp_buffer_count) = system.get_dsp_buffer_size()?;
+        Ok(Self {
+            output_type: system.get_output_type()?,
+            driver: system.get_driver()?,
+            sample_rate,
+            speaker_mode,
+            raw_speakers,
+            software_channels: system.get_software_channels()?,
+            dsp_buffer_length,
+            dsp_buffer_count,
+            advanced: AdvancedConfig::capture(&system.get_advanced_settings()?),
+        })
+    }
+}
+
+impl SystemBuilder {
+    /// Creates a [`SystemBuilder`] set up with `config`. See [`SystemBuilder::config`].
+    ///
+    /// # Safety
+    ///
+    /// See [`SystemBuilder::new`] for safety info.
+    pub unsafe fn from_config(config: &SystemConfig) -> Result<Self> {
+        let mut builder = unsafe { Self::new() }?;
+        builder.config(config)?;
+        Ok(builder)
+    }
+
+    /// Applies every setting in `config` to the system being built.
+    ///
+    /// The driver is only selected if the output type has that many drivers on this machine,
+    /// otherwise the default driver is used.
+    pub fn config(&mut self, config: &SystemConfig) -> Result<&mut Self> {
+        // the driver list depends on the output type, so that goes first
+        self.output(config.output_type)?;
+        // the system already exists at this point, so these can be set right away
+        let system = unsafe { System::from_ffi(self.system) };
+        if (0..system.get_driver_count()?).contains(&config.driver) {
+            system.set_driver(config.driver)?;
+        }
+        self.software_format(config.sample_rate, config.speaker_mode, config.raw_speakers)?
+            .software_channels(config.software_channels)?
+            .dsp_buffer_size(config.dsp_buffer_length, config.dsp_buffer_count)?;
+
+        let mut settings = system.get_advanced_settings()?;
+        config.advanced.apply(&mut settings);
+        system.set_advanced_settings(&settings)?;
+        Ok(self)
+    }
+}
@@ -6,17 +6,18 @@
 
 use fmod_sys::*;
 use lanyard::Utf8CStr;
-use std::ffi::c_int;
+use std::ffi::{c_float, c_int};
 
 use crate::{
-    Channel, ChannelGroup, Dsp, DspType, Reverb3D, Sound, SoundBuilder, SoundGroup, System,
+    Channel, ChannelGroup, Dsp, DspType, Reverb3D, ReverbProperties, Sound, SoundBuilder,
+    SoundGroup, System, Vector,
 };
 #[cfg(fmod_gte_2_3_9)]
 use crate::{DspConnection, DspConnectionType};
-use crate::{FmodResultExt, Result};
+use crate::{FmodResultExt, Result, check_same_system};
 
 #[cfg(doc)]
-use crate::Mode;
+use crate::{Error, Mode, Owned};
 
 impl System {
     /// Loads a sound into memory, opens it for streaming or sets it up for callback based sounds.
@@ -46,23 +47,23 @@ impl System {
     /// With [`Mode::OPEN_MEMORY_POINT`], only PCM formats and compressed formats using [`Mode::CREATE_COMPRESSED_SAMPLE`] are supported.
     pub fn create_sound(&self, builder: &SoundBuilder<'_>) -> Result<Sound> {
         let mut sound = std::ptr::null_mut();
-        let mut ex_info = builder.raw_ex_info();
-        let ex_info_ptr = if builder.ex_info_is_empty() {
-            std::ptr::null_mut()
-        } else {
-            &raw mut ex_info
-        };
+        let mut args = builder.create_sound_args();
+        if let Some(key) = self.apply_default_encryption_key(&mut args.ex_info) {
+            args.keep_alive(key);
+        }
         unsafe {
             FMOD_System_CreateSound(
                 self.inner.as_ptr(),
-                builder.name_or_data,
-                builder.mode,
-                ex_info_ptr,
+                args.name_or_data,
+                args.mode,
+                args.ex_info_ptr(),
                 &raw mut sound,
             )
             .to_result()?;
-            Ok(Sound::from_ffi(sound))
         }
+        let sound = unsafe { Sound::from_ffi(sound) };
+        args.finish(sound);
+        Ok(sound)
     }
 
     /// Opens a sound for streaming.
@@ -74,24 +75,23 @@ impl System {
     /// Open multiple streams to have them play concurrently.
     pub fn create_stream(&self, builder: &SoundBuilder<'_>) -> Result<Sound> {
         let mut sound = std::ptr::null_mut();
-
-        let mut ex_info = builder.raw_ex_info();
-        let ex_info_ptr = if builder.ex_info_is_empty() {
-            std::ptr::null_mut()
-        } else {
-            &raw mut ex_info
-        };
+        let mut args = builder.create_sound_args();
+        if let Some(key) = self.apply_default_encryption_key(&mut args.ex_info) {
+            args.keep_alive(key);
+        }
         unsafe {
             FMOD_System_CreateStream(
                 self.inner.as_ptr(),
-                builder.name_or_data,
-                builder.mode,
-                ex_info_ptr,
+                args.name_or_data,
+                args.mode,
+                args.ex_info_ptr(),
                 &raw mut sound,
             )
             .to_result()?;
-            Ok(Sound::from_ffi(sound))
         }
+        let sound = unsafe { Sound::from_ffi(sound) };
+        args.finish(sound);
+        Ok(sound)
     }
 
     /// WARNING: At the moment this function has no guardrails and WILL cause undefined behaviour if used incorrectly.
@@ -160,7 +160,7 @@ impl System {
     /// If a [`ChannelGroup`] has an effect added to it, the effect is processed post-mix from the [`Channel`]s and [`ChannelGroup`]s below it in the mix hierarchy.
     /// See the DSP architecture guide for more information.
     ///
-    /// All [`ChannelGroup`]s will initially output directly to the master [`ChannelGroup`] (See `System::getMasterChannelGroup`).
+    /// All [`ChannelGroup`]s will initially output directly to the master [`ChannelGroup`] (See [`System::get_master_channel_group`]).
     /// [`ChannelGroup`]s can be re-parented this with `ChannelGroup::addGroup`.
     pub fn create_channel_group(&self, name: &Utf8CStr) -> Result<ChannelGroup> {
         let mut channel_group = std::ptr::null_mut();
@@ -236,6 +236,30 @@ impl System {
         }
     }
 
+    /// Creates a 'virtual reverb' object placed at `position`, with the given distances and environmental properties.
+    ///
+    /// This is [`System::create_reverb_3d`] followed by [`Reverb3D::set_3d_attributes`] and [`Reverb3D::set_properties`].
+    /// If either of those fail, the reverb is released again.
+    ///
+    /// Wrap the returned [`Reverb3D`] in an [`Owned`] to release it automatically.
+    pub fn create_reverb_3d_at(
+        &self,
+        position: Vector,
+        min_distance: c_float,
+        max_distance: c_float,
+        properties: ReverbProperties,
+    ) -> Result<Reverb3D> {
+        let reverb = self.create_reverb_3d()?;
+        let result = reverb
+            .set_3d_attributes(Some(position), min_distance, max_distance)
+            .and_then(|()| reverb.set_properties(properties));
+        if let Err(e) = result {
+            reverb.release()?;
+            return Err(e);
+        }
+        Ok(reverb)
+    }
+
     /// Plays a Sound on a Channel.
     ///
     /// When a sound is played, it will use the sound's default frequency and priority. See `Sound::setDefaults`.
@@ -250,12 +274,18 @@ impl System {
     ///
     /// Playing more Sounds than physical Channels allow is handled with virtual voices.
     /// See the white paper on Virtual Voices for more information.
+    ///
+    /// In debug builds (or with the `system-checks` feature), returns [`Error::InvalidParam`] if `sound` or `channel_group` belong to a different [`System`].
     pub fn play_sound(
         &self,
         sound: Sound,
         channel_group: Option<ChannelGroup>,
         paused: bool,
     ) -> Result<Channel> {
+        check_same_system(|| Ok(*self), || sound.get_system())?;
+        if let Some(group) = channel_group {
+            check_same_system(|| Ok(*self), || group.get_system())?;
+        }
         let mut channel = std::ptr::null_mut();
         unsafe {
             FMOD_System_PlaySound(
@@ -285,6 +315,10 @@ impl System {
         channel_group: Option<ChannelGroup>,
         paused: bool,
     ) -> Result<Channel> {
+        check_same_system(|| Ok(*self), || dsp.get_system())?;
+        if let Some(group) = channel_group {
+            check_same_system(|| Ok(*self), || group.get_system())?;
+        }
         let mut channel = std::ptr::null_mut();
         unsafe {
             FMOD_System_PlayDSP(
@@ -333,6 +367,8 @@ impl System {
     /// This is the default [`ChannelGroup`] that [`Channel`]s play on,
     /// unless a different [`ChannelGroup`] is specified with [`System::play_sound`], [`System::play_dsp`] or `Channel::setChannelGroup`.
     /// A master [`ChannelGroup`] can be used to do things like set the 'master volume' for all playing [`Channel`]s. See `ChannelControl::setVolume`.
+    ///
+    /// [`System::masters`] fetches this and the master [`SoundGroup`] together.
     pub fn get_master_channel_group(&self) -> Result<ChannelGroup> {
         let mut channel_group = std::ptr::null_mut();
         unsafe {
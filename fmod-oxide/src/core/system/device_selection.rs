@@ -9,10 +9,10 @@ use lanyard::Utf8CString;
 use std::{ffi::c_int, mem::MaybeUninit};
 
 use crate::{FmodResultExt, Result};
-use crate::{Guid, OutputType, SpeakerMode, System, get_string};
+use crate::{Guid, OutputType, SpeakerMode, System, Vector, get_string};
 
 #[cfg(doc)]
-use crate::SystemBuilder;
+use crate::{ChannelGroup, SystemBuilder, SystemCallback};
 
 impl System {
     #[allow(clippy::doc_markdown)]
@@ -103,4 +103,57 @@ impl System {
         }
         Ok(driver)
     }
+
+    /// Switches output to `driver`, keeping the master mix and listener state.
+    ///
+    /// This is the call a device picker wants: it wraps [`System::set_driver`],
+    /// and afterwards restores the master [`ChannelGroup`]'s volume, mute and paused state and every 3D listener's attributes,
+    /// in case the driver switch reset them.
+    ///
+    /// Sounds, channel groups, DSPs and playing channels all survive the switch, since only the output is restarted.
+    /// Output specific state does not: the output handle (see [`System::get_output_handle`]) is recreated,
+    /// and the output may run at a different sample rate or speaker mode.
+    /// FMOD reports the restart through [`SystemCallback::device_reinitialize`].
+    pub fn switch_output_device(&self, driver: c_int) -> Result<()> {
+        self.switch_output_device_with(None, driver)
+    }
+
+    /// Like [`System::switch_output_device`], but first switches to `output_type` if it differs from the current output type.
+    ///
+    /// Switching output types after initialization is only supported on some platforms, see [`System::set_output`].
+    pub fn switch_output_device_with(
+        &self,
+        output_type: Option<OutputType>,
+        driver: c_int,
+    ) -> Result<()> {
+        let master = self.get_master_channel_group()?;
+        let volume = master.get_volume()?;
+        let mute = master.get_mute()?;
+        let paused = master.get_paused()?;
+        let listeners = (0..self.get_3d_listener_count()?)
+            .map(|listener| self.get_3d_listener_attributes(listener))
+            .collect::<Result<Vec<(Vector, Vector, Vector, Vector)>>>()?;
+
+        if let Some(output_type) = output_type {
+            let current = self.get_output_type()?;
+            if current != output_type {
+                self.set_output(output_type)?;
+            }
+        }
+        self.set_driver(driver)?;
+
+        master.set_volume(volume)?;
+        master.set_mute(mute)?;
+        master.set_paused(paused)?;
+        for (listener, (position, velocity, forward, up)) in (0..).zip(listeners) {
+            self.set_3d_listener_attributes(
+                listener,
+                Some(position),
+                Some(velocity),
+                Some(forward),
+                Some(up),
+            )?;
+        }
+        Ok(())
+    }
 }
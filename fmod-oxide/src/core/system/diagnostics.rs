@@ -0,0 +1,144 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use lanyard::Utf8CString;
+use std::ffi::{c_float, c_int, c_longlong};
+
+use crate::{Channel, ChannelGroup, ChannelsPlaying, CpuUsage, Result, System};
+
+/// A snapshot of everything a [`System`] is doing, taken with [`System::debug_dump`].
+///
+/// Meant to be attached to bug reports: print it with `{:#?}`,
+/// or with the `serde` feature enabled serialize it to JSON (or any other format).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DebugDump {
+    /// Number of playing channels.
+    pub channels_playing: ChannelsPlaying,
+    /// The master [`ChannelGroup`], along with every group and channel under it.
+    pub master_group: ChannelGroupDump,
+    /// CPU usage of the Core engine.
+    pub cpu: CpuUsage,
+    /// Memory used by FMOD, in bytes.
+    pub memory: MemoryDump,
+    /// Bytes read from disk so far.
+    pub file: FileDump,
+}
+
+/// A [`ChannelGroup`] in a [`DebugDump`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChannelGroupDump {
+    /// Name of the group.
+    pub name: String,
+    /// Volume level of the group.
+    pub volume: c_float,
+    /// Whether the group is paused.
+    pub paused: bool,
+    /// Number of DSPs on the group's DSP chain, including the fader.
+    pub dsp_count: c_int,
+    /// Channels playing directly on this group.
+    pub channels: Vec<ChannelDump>,
+    /// Groups that feed into this group.
+    pub groups: Vec<ChannelGroupDump>,
+}
+
+/// A [`Channel`] in a [`DebugDump`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChannelDump {
+    /// Name of the sound being played, if there is one.
+    pub sound: Option<String>,
+    /// Whether the channel is virtual.
+    pub is_virtual: bool,
+    /// Whether the channel is paused.
+    pub paused: bool,
+    /// Volume level of the channel.
+    pub volume: c_float,
+    /// Final volume after 3D, occlusion and group volumes are applied.
+    pub audibility: c_float,
+    /// Number of DSPs on the channel's DSP chain, including the fader.
+    pub dsp_count: c_int,
+}
+
+/// Memory statistics in a [`DebugDump`], see [`crate::memory::get_stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MemoryDump {
+    /// Currently allocated memory.
+    pub current: c_int,
+    /// Maximum allocated memory since the system was created.
+    pub max: c_int,
+}
+
+/// File usage in a [`DebugDump`], see [`System::get_file_usage`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FileDump {
+    /// Bytes read for non-streaming sounds.
+    pub sample_bytes_read: c_longlong,
+    /// Bytes read for streaming sounds.
+    pub stream_bytes_read: c_longlong,
+    /// Bytes read for everything else, such as banks.
+    pub other_bytes_read: c_longlong,
+}
+
+impl System {
+    /// Takes a [`DebugDump`] of the system.
+    ///
+    /// This walks every [`ChannelGroup`] and [`Channel`], so it isn't something to call every frame.
+    pub fn debug_dump(&self) -> Result<DebugDump> {
+        let (current, max) = crate::memory::get_stats(false)?;
+        let (sample_bytes_read, stream_bytes_read, other_bytes_read) = self.get_file_usage()?;
+        Ok(DebugDump {
+            channels_playing: self.channels_playing()?,
+            master_group: ChannelGroupDump::capture(self.get_master_channel_group()?)?,
+            cpu: self.get_cpu_usage()?,
+            memory: MemoryDump { current, max },
+            file: FileDump {
+                sample_bytes_read,
+                stream_bytes_read,
+                other_bytes_read,
+            },
+        })
+    }
+}
+
+impl ChannelGroupDump {
+    fn capture(group: ChannelGroup) -> Result<Self> {
+        let channels = (0..group.get_channel_count()?)
+            .map(|i| ChannelDump::capture(group.get_channel(i)?))
+            .collect::<Result<_>>()?;
+        let groups = (0..group.get_group_count()?)
+            .map(|i| Self::capture(group.get_group(i)?))
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            name: group.get_name()?.into_string(),
+            volume: group.get_volume()?,
+            paused: group.get_paused()?,
+            dsp_count: group.get_dsp_count()?,
+            channels,
+            groups,
+        })
+    }
+}
+
+impl ChannelDump {
+    fn capture(channel: Channel) -> Result<Self> {
+        let sound = channel
+            .get_current_sound()?
+            .map(|sound| sound.get_name().map(Utf8CString::into_string))
+            .transpose()?;
+        Ok(Self {
+            sound,
+            is_virtual: channel.is_virtual()?,
+            paused: channel.get_paused()?,
+            volume: channel.get_volume()?,
+            audibility: channel.get_audibility()?,
+            dsp_count: channel.get_dsp_count()?,
+        })
+    }
+}
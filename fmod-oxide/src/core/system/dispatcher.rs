@@ -0,0 +1,205 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::c_void;
+use std::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use fmod_sys::*;
+
+use super::callback::callback_impl;
+use crate::{FmodResultExt, Result};
+use crate::{System, SystemCallback, SystemCallbackMask};
+
+type RawSystemCallback = unsafe extern "C" fn(
+    *mut FMOD_SYSTEM,
+    FMOD_SYSTEM_CALLBACK_TYPE,
+    *mut c_void,
+    *mut c_void,
+    *mut c_void,
+) -> FMOD_RESULT;
+
+#[derive(Clone, Copy)]
+struct Subscriber {
+    system: usize,
+    id: u64,
+    mask: SystemCallbackMask,
+    callback: RawSystemCallback,
+}
+
+struct Registry {
+    next_id: u64,
+    subscribers: Vec<Subscriber>,
+    // snapshots replaced while a dispatch may still be reading them, boxed because that is what the snapshot pointer points to
+    #[allow(clippy::vec_box)]
+    retired: Vec<Box<Vec<Subscriber>>>,
+}
+
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry {
+    next_id: 0,
+    subscribers: Vec::new(),
+    retired: Vec::new(),
+});
+
+// dispatching reads an immutable copy of the subscribers, so the mixer thread never locks or allocates
+static SNAPSHOT: AtomicPtr<Vec<Subscriber>> = AtomicPtr::new(std::ptr::null_mut());
+static READERS: AtomicUsize = AtomicUsize::new(0);
+
+fn registry() -> MutexGuard<'static, Registry> {
+    // a panicking callback can't leave the registry in an invalid state, so ignore poisoning
+    REGISTRY.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl Registry {
+    fn publish(&mut self) {
+        let snapshot = Box::into_raw(Box::new(self.subscribers.clone()));
+        let old = SNAPSHOT.swap(snapshot, Ordering::SeqCst);
+        if !old.is_null() {
+            self.retired.push(unsafe { Box::from_raw(old) });
+        }
+        // a dispatch still reading a retired snapshot registered itself before the swap,
+        // so once no dispatch is running every retired snapshot is unreachable
+        if READERS.load(Ordering::SeqCst) == 0 {
+            self.retired.clear();
+        }
+    }
+}
+
+struct ReadGuard;
+
+impl ReadGuard {
+    fn snapshot<'a>() -> (Self, &'a [Subscriber]) {
+        READERS.fetch_add(1, Ordering::SeqCst);
+        let snapshot = SNAPSHOT.load(Ordering::SeqCst);
+        // SAFETY: snapshots are only freed while no reader is registered
+        let subscribers = unsafe { snapshot.as_ref() }.map_or(&[][..], Vec::as_slice);
+        (ReadGuard, subscribers)
+    }
+}
+
+impl Drop for ReadGuard {
+    fn drop(&mut self) {
+        READERS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// A handle to a callback registered with [`System::add_callback`].
+///
+/// Pass it to [`System::remove_callback`] to unsubscribe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SystemCallbackHandle {
+    system: usize,
+    id: u64,
+}
+
+unsafe extern "C" fn dispatch_impl(
+    system: *mut FMOD_SYSTEM,
+    callback_type: FMOD_SYSTEM_CALLBACK_TYPE,
+    command_data_1: *mut c_void,
+    command_data_2: *mut c_void,
+    userdata: *mut c_void,
+) -> FMOD_RESULT {
+    let kind = SystemCallbackMask::from(callback_type);
+    // callbacks are free to (un)subscribe, which publishes a new snapshot without touching this one
+    let (_guard, subscribers) = ReadGuard::snapshot();
+    let callbacks = subscribers
+        .iter()
+        .filter(|s| s.system == system as usize && s.mask.intersects(kind))
+        .map(|s| s.callback);
+
+    let mut result = FMOD_RESULT::FMOD_OK;
+    for callback in callbacks {
+        let callback_result = unsafe {
+            callback(
+                system,
+                callback_type,
+                command_data_1,
+                command_data_2,
+                userdata,
+            )
+        };
+        // every subscriber runs, but the first error is what FMOD sees
+        if result == FMOD_RESULT::FMOD_OK {
+            result = callback_result;
+        }
+    }
+    result
+}
+
+impl System {
+    /// Subscribes `C` to the callback types in `mask` without replacing any other subscribers.
+    ///
+    /// Unlike [`System::set_callback`], which installs a single callback for the whole [`System`],
+    /// any number of subscribers can be registered with this function,
+    /// each receiving only the callback types it asked for.
+    /// Subscribers are called in the order they were added.
+    /// If a subscriber returns an error, the remaining subscribers are still called and the first error is returned to FMOD.
+    ///
+    /// Calling [`System::set_callback`] replaces the dispatcher and silences every subscriber until the next call to this function.
+    ///
+    /// Dispatching never locks or allocates, so subscribing to mixer thread callbacks (such as [`SystemCallbackMask::PREMIX`]) is fine.
+    /// Subscribing and unsubscribing take a lock.
+    pub fn add_callback<C: SystemCallback>(
+        &self,
+        mask: SystemCallbackMask,
+    ) -> Result<SystemCallbackHandle> {
+        let mut registry = registry();
+        let id = registry.next_id;
+        registry.next_id += 1;
+        registry.subscribers.push(Subscriber {
+            system: self.inner.as_ptr() as usize,
+            id,
+            mask,
+            callback: callback_impl::<C>,
+        });
+        registry.publish();
+
+        let result = self.install_dispatcher(&registry);
+        if result.is_err() {
+            registry.subscribers.pop();
+            registry.publish();
+        }
+        result.map(|()| SystemCallbackHandle {
+            system: self.inner.as_ptr() as usize,
+            id,
+        })
+    }
+
+    /// Unsubscribes a callback previously registered with [`System::add_callback`].
+    ///
+    /// Removing a handle that has already been removed does nothing.
+    pub fn remove_callback(&self, handle: SystemCallbackHandle) -> Result<()> {
+        let mut registry = registry();
+        registry
+            .subscribers
+            .retain(|s| s.system != handle.system || s.id != handle.id);
+        registry.publish();
+        self.install_dispatcher(&registry)
+    }
+
+    fn install_dispatcher(&self, registry: &Registry) -> Result<()> {
+        let system = self.inner.as_ptr() as usize;
+        let mask = registry
+            .subscribers
+            .iter()
+            .filter(|s| s.system == system)
+            .fold(SystemCallbackMask::empty(), |mask, s| mask | s.mask);
+        let callback = if mask.is_empty() {
+            None
+        } else {
+            Some(dispatch_impl as RawSystemCallback)
+        };
+        unsafe { FMOD_System_SetCallback(self.inner.as_ptr(), callback, mask.into()).to_result() }
+    }
+}
+
+/// Drops every subscriber registered to `system`, called when the [`System`] is released.
+pub(super) fn forget_system(system: System) {
+    let system = system.inner.as_ptr() as usize;
+    let mut registry = registry();
+    registry.subscribers.retain(|s| s.system != system);
+    registry.publish();
+}
@@ -0,0 +1,55 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::Result;
+use crate::{InitFlags, SpeakerMode, System, SystemBuilder};
+
+/// How a surround mix is folded down when the output device has fewer speakers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DownmixPolicy {
+    /// FMOD's standard downmix.
+    #[default]
+    Standard,
+    /// Downmix [`SpeakerMode::FivePointOne`] to stereo with the Dolby Pro Logic II matrix encoder,
+    /// so a Pro Logic II decoder downstream can recover the surround channels.
+    ///
+    /// This sets [`InitFlags::PREFER_DOLBY_DOWNMIX`].
+    DolbyProLogicII,
+}
+
+impl SystemBuilder {
+    /// Sets how the mix is downmixed when the output device has fewer speakers than the software format.
+    ///
+    /// This only matters when the software format is set to a surround mode (see [`SystemBuilder::software_format`]),
+    /// such as a game mixed in 5.1 running on stereo headphones.
+    pub fn downmix_policy(&mut self, policy: DownmixPolicy) -> &mut Self {
+        self.extra_flags.set(
+            InitFlags::PREFER_DOLBY_DOWNMIX,
+            policy == DownmixPolicy::DolbyProLogicII,
+        );
+        self
+    }
+}
+
+impl System {
+    /// Retrieves the speaker mode of the current output device, as negotiated with the device when it was opened.
+    ///
+    /// This can differ from the speaker mode FMOD mixes in (see [`System::get_software_format`]),
+    /// in which case FMOD up or downmixes to it.
+    pub fn get_output_speaker_mode(&self) -> Result<SpeakerMode> {
+        let (_, _, _, speaker_mode, _) = self.get_driver_info(self.get_driver()?)?;
+        Ok(speaker_mode)
+    }
+
+    /// Whether the mix has more channels than the output device, and so is being downmixed.
+    pub fn is_downmixing(&self) -> Result<bool> {
+        let (_, mix_mode, _) = self.get_software_format()?;
+        let output_mode = self.get_output_speaker_mode()?;
+        let mix_channels = self.get_speaker_mode_channels(mix_mode)?;
+        let output_channels = self.get_speaker_mode_channels(output_mode)?;
+        Ok(mix_channels > output_channels)
+    }
+}
@@ -0,0 +1,66 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::sync::Arc;
+
+use fmod_sys::*;
+
+use super::SystemMap;
+use crate::System;
+
+#[cfg(doc)]
+use crate::{Mode, SoundBuilder, SoundType};
+
+// nul terminated
+static DEFAULT_KEYS: SystemMap<Arc<[u8]>> = SystemMap::new();
+
+impl System {
+    /// Sets the key used for encrypted [`SoundType::FSB`] files when a [`SoundBuilder`] does not specify one.
+    ///
+    /// Pass [`None`] to clear the default key.
+    ///
+    /// FMOD reads the key as a C string, so it is cut off at the first nul byte.
+    pub fn set_default_encryption_key(&self, key: Option<&str>) {
+        let Some(key) = key else {
+            DEFAULT_KEYS.remove(*self);
+            return;
+        };
+        let mut bytes = Vec::with_capacity(key.len() + 1);
+        bytes.extend_from_slice(key.as_bytes());
+        bytes.push(0);
+        DEFAULT_KEYS.insert(*self, bytes.into());
+    }
+
+    /// Retrieves the key set with [`System::set_default_encryption_key`].
+    pub fn get_default_encryption_key(&self) -> Option<String> {
+        let key = self.default_encryption_key()?;
+        let key = crate::text::trim_nul(&key);
+        Some(String::from_utf8_lossy(key).into_owned())
+    }
+
+    fn default_encryption_key(&self) -> Option<Arc<[u8]>> {
+        DEFAULT_KEYS.get(*self)
+    }
+
+    /// Points `ex_info` at the default key if it has no key of its own.
+    ///
+    /// The returned key must outlive the sound if it is opened with [`Mode::NONBLOCKING`].
+    pub(crate) fn apply_default_encryption_key(
+        &self,
+        ex_info: &mut FMOD_CREATESOUNDEXINFO,
+    ) -> Option<Arc<[u8]>> {
+        if !ex_info.encryptionkey.is_null() {
+            return None;
+        }
+        let key = self.default_encryption_key()?;
+        ex_info.encryptionkey = key.as_ptr().cast();
+        Some(key)
+    }
+
+    pub(crate) fn forget_default_encryption_key(&self) {
+        self.set_default_encryption_key(None);
+    }
+}
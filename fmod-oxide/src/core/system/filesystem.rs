@@ -7,7 +7,7 @@
 use std::ffi::c_int;
 
 use crate::{
-    FileSystemAsync, FileSystemSync, async_filesystem_cancel, async_filesystem_read,
+    FileSystemAsync, FileSystemSync, Logged, async_filesystem_cancel, async_filesystem_read,
     filesystem_close, filesystem_open, filesystem_read, filesystem_seek,
 };
 use crate::{FmodResultExt, Result};
@@ -16,7 +16,7 @@ use fmod_sys::*;
 use super::System;
 
 #[cfg(doc)]
-use crate::Sound;
+use crate::{FileDiag, Sound, SoundBuilder};
 
 impl System {
     /// Set callbacks to implement all file I/O instead of using the platform native method.
@@ -65,6 +65,13 @@ impl System {
         }
     }
 
+    /// Like [`System::set_filesystem_sync`], but records every file FMOD opens through `F` for [`FileDiag`].
+    ///
+    /// Sounds created with [`SoundBuilder::with_ignore_set_filesystem`] bypass `F`, and so are not recorded.
+    pub fn set_filesystem_logged<F: FileSystemSync>(&self, block_align: c_int) -> Result<()> {
+        self.set_filesystem_sync::<Logged<F>>(block_align)
+    }
+
     /// Set callbacks to implement all file I/O instead of using the platform native method.
     ///
     /// Setting these callbacks have no effect on sounds loaded with [`FMOD_OPENMEMORY`] or [`FMOD_OPENUSER`].
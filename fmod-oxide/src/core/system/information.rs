@@ -11,11 +11,11 @@ use std::{
     os::raw::c_void,
 };
 
-use crate::{CpuUsage, SpeakerMode, System};
+use crate::{CpuUsage, DspBufferSize, MixMatrix, SoftwareFormat, SpeakerMode, System};
 use crate::{FmodResultExt, Result};
 
 #[cfg(doc)]
-use crate::OutputType;
+use crate::{Error, OutputType};
 
 impl System {
     /// Retrieves the FMOD version number.
@@ -64,6 +64,29 @@ impl System {
         Ok(handle)
     }
 
+    /// Retrieves the output format for the software mixer.
+    ///
+    /// This is [`System::get_software_format`] as a struct.
+    pub fn software_format(&self) -> Result<SoftwareFormat> {
+        let (sample_rate, speaker_mode, raw_speakers) = self.get_software_format()?;
+        Ok(SoftwareFormat {
+            sample_rate,
+            speaker_mode,
+            raw_speakers,
+        })
+    }
+
+    /// Retrieves the buffer size settings for the software mixer.
+    ///
+    /// This is [`System::get_dsp_buffer_size`] as a struct.
+    pub fn dsp_buffer_size(&self) -> Result<DspBufferSize> {
+        let (buffer_length, buffer_count) = self.get_dsp_buffer_size()?;
+        Ok(DspBufferSize {
+            buffer_length,
+            buffer_count,
+        })
+    }
+
     /// Retrieves the number of currently playing Channels.
     ///
     /// For differences between real and virtual voices see the Virtual Voices guide for more information.
@@ -114,33 +137,36 @@ impl System {
 
     /// Retrieves the default matrix used to convert from one speaker mode to another.
     ///
-    /// The gain for source channel 's' to target channel 't' is `matrix[t * <number of source channels> + s]`.
+    /// The result has a column per source channel and a row per target channel, see [`MixMatrix::gain`].
+    /// Use it as a starting point for custom upmixing or downmixing, and apply it with [`MixMatrix::apply`].
     ///
-    /// If '`source_mode`' or '`target_mode`' is [`SpeakerMode::Raw`], this function will return [`FMOD_RESULT::FMOD_ERR_INVALID_PARAM`].
-    /// The number of source channels can be found from [`System::get_speaker_mode_channels`].
-    // FIXME: do we take an out slice param?
+    /// If '`source_mode`' or '`target_mode`' is [`SpeakerMode::Raw`], this function will return [`Error::InvalidParam`].
     pub fn get_default_mix_matrix(
         &self,
         source_mode: SpeakerMode,
         target_mode: SpeakerMode,
-    ) -> Result<Vec<f32>> {
-        let source_channels = self.get_speaker_mode_channels(source_mode)?;
-        let target_channels = self.get_speaker_mode_channels(target_mode)?;
-        debug_assert!(source_channels <= FMOD_MAX_CHANNEL_WIDTH as c_int);
-        debug_assert!(target_channels <= FMOD_MAX_CHANNEL_WIDTH as c_int);
-        let mut matrix = vec![0.0; source_channels as usize * target_channels as usize];
+    ) -> Result<MixMatrix> {
+        let in_channels = self.get_speaker_mode_channels(source_mode)?;
+        let out_channels = self.get_speaker_mode_channels(target_mode)?;
+        debug_assert!(in_channels <= FMOD_MAX_CHANNEL_WIDTH as c_int);
+        debug_assert!(out_channels <= FMOD_MAX_CHANNEL_WIDTH as c_int);
+        let mut data = vec![0.0; in_channels as usize * out_channels as usize];
 
         unsafe {
             FMOD_System_GetDefaultMixMatrix(
                 self.inner.as_ptr(),
                 source_mode.into(),
                 target_mode.into(),
-                matrix.as_mut_ptr(),
-                source_channels,
+                data.as_mut_ptr(),
+                in_channels,
             )
             .to_result()?;
         }
-        Ok(matrix)
+        Ok(MixMatrix {
+            in_channels,
+            out_channels,
+            data,
+        })
     }
 
     /// Retrieves the channel count for a given speaker mode.
@@ -0,0 +1,56 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::SystemMap;
+use crate::{Error, InitFlags, Result, System};
+
+#[cfg(doc)]
+use crate::SystemBuilder;
+
+static INIT_FLAGS: SystemMap<InitFlags> = SystemMap::new();
+
+impl System {
+    /// The [`InitFlags`] this system was initialized with.
+    ///
+    /// Returns [`None`] if the system wasn't built by a [`SystemBuilder`] (or [`crate::studio::SystemBuilder`]) from this crate,
+    /// since FMOD has no way to query them.
+    pub fn get_init_flags(&self) -> Option<InitFlags> {
+        INIT_FLAGS.get(*self)
+    }
+
+    pub(crate) fn record_init_flags(&self, flags: InitFlags) {
+        INIT_FLAGS.insert(*self, flags);
+    }
+
+    pub(crate) fn forget_init_flags(&self) {
+        INIT_FLAGS.remove(*self);
+    }
+}
+
+/// Returns [`Error::Initialization`] if `system` was initialized with none of the flags in `any_of`.
+///
+/// Systems that weren't built by this crate are let through, since their flags are unknown.
+pub(crate) fn require_init_flags(system: System, any_of: InitFlags) -> Result<()> {
+    match system.get_init_flags() {
+        Some(flags) if !flags.intersects(any_of) => Err(Error::Initialization),
+        _ => Ok(()),
+    }
+}
+
+/// Like [`require_init_flags`], but only checks with the `init-checks` feature enabled, and `system` isn't called otherwise.
+///
+/// Only use this for functions that FMOD turns into a no-op without the right flags,
+/// not for ones that still do something else without them (like occlusion falling back to volume attenuation).
+#[inline]
+pub(crate) fn check_init_flags(
+    system: impl FnOnce() -> Result<System>,
+    any_of: InitFlags,
+) -> Result<()> {
+    if cfg!(feature = "init-checks") {
+        require_init_flags(system()?, any_of)?;
+    }
+    Ok(())
+}
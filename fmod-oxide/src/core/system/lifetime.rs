@@ -6,7 +6,7 @@
 
 use fmod_sys::*;
 
-use crate::{InitFlags, System, SystemBuilder};
+use crate::{CallbackContext, InitFlags, System, SystemBuilder};
 
 #[cfg(doc)]
 use crate::{Channel, OutputType, Sound};
@@ -35,6 +35,8 @@ impl System {
             Ok(SystemBuilder {
                 system: self.inner.as_ptr(),
                 thread_unsafe: false,
+                extra_flags: InitFlags::empty(),
+                non_block_threads: self.get_non_block_thread_count(),
             })
         }
     }
@@ -47,7 +49,27 @@ impl System {
     ///
     /// [`System::release`] is not thread-safe. Do not call this function simultaneously from multiple threads at once.
     pub unsafe fn release(&self) -> Result<()> {
-        unsafe { FMOD_System_Release(self.inner.as_ptr()).to_result() }
+        CallbackContext::debug_assert_outside("System::release");
+        unsafe { FMOD_System_Release(self.inner.as_ptr()).to_result()? };
+        self.forget_all();
+        Ok(())
+    }
+
+    /// Drops everything the crate tracks for this system, once FMOD has released it.
+    ///
+    /// A new system can be allocated at the same address, so every release path (including [`crate::studio::System::release`]) must call this.
+    pub(crate) fn forget_all(&self) {
+        super::dispatcher::forget_system(*self);
+        crate::debug::forget_log_context(*self);
+        self.forget_default_encryption_key();
+        self.forget_non_block_thread_count();
+        self.forget_profiler();
+        self.forget_init_flags();
+        self.forget_mixer_stalls();
+        self.forget_channel_budget();
+        self.forget_emitters();
+        self.update_timer().forget();
+        crate::thread::forget_threads(*self);
     }
 
     /// Updates the FMOD system.
@@ -67,8 +89,16 @@ impl System {
     ///
     /// If [`InitFlags::STREAM_FROM_UPDATE`]. is used, this function will update the stream engine.
     /// Combining this with the non realtime output will mean smoother captured output.
+    ///
+    /// How long this takes can be measured with [`System::track_update_timing`] and [`System::set_update_budget`].
+    /// This is also where the memory budget set with [`crate::memory::set_budget`] is checked.
     pub fn update(&self) -> Result<()> {
-        unsafe { FMOD_System_Update(self.inner.as_ptr()).to_result() }
+        CallbackContext::debug_assert_outside("System::update");
+        let result = self
+            .update_timer()
+            .time(|| unsafe { FMOD_System_Update(self.inner.as_ptr()).to_result() });
+        crate::memory::poll_budget();
+        result
     }
 
     /// Suspend mixer thread and relinquish usage of audio hardware while maintaining internal state.
@@ -0,0 +1,28 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::{ChannelGroup, Result, SoundGroup, System};
+
+/// The master groups of a [`System`], from [`System::masters`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Masters {
+    /// The master [`ChannelGroup`], see [`System::get_master_channel_group`].
+    pub channel_group: ChannelGroup,
+    /// The master [`SoundGroup`], see [`System::get_master_sound_group`].
+    pub sound_group: SoundGroup,
+}
+
+impl System {
+    /// Retrieves the master [`ChannelGroup`] and master [`SoundGroup`] together.
+    ///
+    /// Both are plain getters, so this is cheap to call wherever they are needed instead of storing them.
+    pub fn masters(&self) -> Result<Masters> {
+        Ok(Masters {
+            channel_group: self.get_master_channel_group()?,
+            sound_group: self.get_master_sound_group()?,
+        })
+    }
+}
@@ -0,0 +1,156 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::collections::VecDeque;
+use std::ffi::c_void;
+use std::time::Instant;
+
+use super::SystemMap;
+use crate::Result;
+use crate::{System, SystemCallback, SystemCallbackHandle, SystemCallbackMask};
+
+/// A way the mixer failed to keep up with the output device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MixerStall {
+    /// Audio was consumed from the ring buffer, but not enough to allow another mix to run.
+    ///
+    /// See [`SystemCallbackMask::BUFFEREDNOMIX`].
+    BufferedNoMix,
+    /// The output device tried to read more samples than were available, so the player heard a glitch.
+    ///
+    /// See [`SystemCallbackMask::OUTPUTUNDERRUN`].
+    OutputUnderrun,
+}
+
+/// A [`MixerStall`], and when it was reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MixerStallEvent {
+    /// What happened.
+    pub kind: MixerStall,
+    /// When the callback for it ran.
+    pub at: Instant,
+}
+
+/// How many of each [`MixerStall`] a [`System`] has reported since [`System::track_mixer_stalls`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct MixerStallCounts {
+    /// Number of [`MixerStall::BufferedNoMix`] reports.
+    pub buffered_no_mix: u64,
+    /// Number of [`MixerStall::OutputUnderrun`] reports.
+    pub output_underrun: u64,
+}
+
+impl MixerStallCounts {
+    /// Total number of stalls of any kind.
+    pub fn total(&self) -> u64 {
+        self.buffered_no_mix.saturating_add(self.output_underrun)
+    }
+}
+
+struct Tracker {
+    handle: SystemCallbackHandle,
+    counts: MixerStallCounts,
+    events: VecDeque<MixerStallEvent>,
+}
+
+static TRACKERS: SystemMap<Tracker> = SystemMap::new();
+
+// oldest events are dropped past this, so an unpolled system can't grow without bound
+const MAX_EVENTS: usize = 256;
+
+fn record(system: System, kind: MixerStall) {
+    TRACKERS.update(system, |tracker| {
+        let Some(tracker) = tracker else {
+            return;
+        };
+        match kind {
+            MixerStall::BufferedNoMix => tracker.counts.buffered_no_mix += 1,
+            MixerStall::OutputUnderrun => tracker.counts.output_underrun += 1,
+        }
+        if tracker.events.len() == MAX_EVENTS {
+            tracker.events.pop_front();
+        }
+        tracker.events.push_back(MixerStallEvent {
+            kind,
+            at: Instant::now(),
+        });
+    });
+}
+
+struct StallRecorder;
+
+impl SystemCallback for StallRecorder {
+    fn buffered_no_mix(system: System, _: *mut c_void) -> Result<()> {
+        record(system, MixerStall::BufferedNoMix);
+        Ok(())
+    }
+
+    fn output_underrun(system: System, _: *mut c_void) -> Result<()> {
+        record(system, MixerStall::OutputUnderrun);
+        Ok(())
+    }
+}
+
+impl System {
+    /// Starts counting [`MixerStall`]s, so audio starvation can be reported from shipped games.
+    ///
+    /// This subscribes to [`SystemCallbackMask::BUFFEREDNOMIX`] and [`SystemCallbackMask::OUTPUTUNDERRUN`] with [`System::add_callback`],
+    /// leaving any other callbacks in place.
+    /// Read the totals with [`System::get_mixer_stall_counts`] and individual reports with [`System::drain_mixer_stalls`].
+    ///
+    /// Calling this again while already tracking does nothing.
+    pub fn track_mixer_stalls(&self) -> Result<()> {
+        if TRACKERS.contains(*self) {
+            return Ok(());
+        }
+        let handle = self.add_callback::<StallRecorder>(
+            SystemCallbackMask::BUFFEREDNOMIX | SystemCallbackMask::OUTPUTUNDERRUN,
+        )?;
+        TRACKERS.insert(
+            *self,
+            Tracker {
+                handle,
+                counts: MixerStallCounts::default(),
+                events: VecDeque::new(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Stops counting [`MixerStall`]s, discarding the counts and any undrained reports.
+    pub fn untrack_mixer_stalls(&self) -> Result<()> {
+        let Some(tracker) = TRACKERS.remove(*self) else {
+            return Ok(());
+        };
+        self.remove_callback(tracker.handle)
+    }
+
+    /// Whether [`System::track_mixer_stalls`] has been called.
+    pub fn is_tracking_mixer_stalls(&self) -> bool {
+        TRACKERS.contains(*self)
+    }
+
+    /// The number of [`MixerStall`]s reported since tracking started, or [`None`] if not tracking.
+    pub fn get_mixer_stall_counts(&self) -> Option<MixerStallCounts> {
+        TRACKERS.update(*self, |tracker| tracker.as_ref().map(|t| t.counts))
+    }
+
+    /// Takes the [`MixerStall`]s reported since the last call, oldest first.
+    ///
+    /// Only the most recent 256 reports are kept; the counts from [`System::get_mixer_stall_counts`] include every report.
+    pub fn drain_mixer_stalls(&self) -> Vec<MixerStallEvent> {
+        TRACKERS.update(*self, |tracker| {
+            tracker
+                .as_mut()
+                .map(|t| t.events.drain(..).collect())
+                .unwrap_or_default()
+        })
+    }
+
+    pub(crate) fn forget_mixer_stalls(&self) {
+        TRACKERS.remove(*self);
+    }
+}
@@ -8,23 +8,56 @@ use std::ptr::NonNull;
 
 use fmod_sys::*;
 
+mod batch_3d;
 mod builder;
 mod callback;
+mod channel_budget;
+mod config;
 mod creation;
 mod device_selection;
+mod diagnostics;
+mod dispatcher;
+mod downmix;
+mod encryption;
 mod filesystem;
 mod general;
 mod geometry;
 mod information;
+mod init_flags;
 mod lifetime;
+mod masters;
+mod mixer_stall;
 mod network;
+mod nonblocking;
+mod ownership;
+mod pause;
 mod plugin;
+mod profiler;
 mod recording;
+mod registry;
 mod runtime_control;
 mod setup;
-pub use builder::SystemBuilder;
+mod spatial;
+mod tuning;
+mod ui;
+mod update_timing;
+pub use builder::{InitProfile, SystemBuilder};
 pub use callback::{ErrorCallbackInfo, Instance, SystemCallback, SystemCallbackMask};
+pub use channel_budget::{ChannelBudget, ChannelsPlaying};
+pub use config::{AdvancedConfig, SystemConfig};
+pub use diagnostics::{ChannelDump, ChannelGroupDump, DebugDump, FileDump, MemoryDump};
+pub use dispatcher::SystemCallbackHandle;
+pub use downmix::DownmixPolicy;
+pub use masters::Masters;
+pub use mixer_stall::{MixerStall, MixerStallCounts, MixerStallEvent};
 pub use setup::RolloffCallback;
+pub use tuning::{OutputTuning, PlatformProfile};
+pub use update_timing::{UpdateBudget, UpdateTimings};
+
+pub(crate) use init_flags::{check_init_flags, require_init_flags};
+pub(crate) use ownership::check_same_system;
+pub(crate) use registry::{RegistryKey, SystemMap};
+pub(crate) use update_timing::UpdateTimer;
 
 /// Management object from which all resources are created and played.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -0,0 +1,44 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::c_int;
+
+use super::SystemMap;
+use crate::{StreamScheduler, System, SystemBuilder};
+
+#[cfg(doc)]
+use crate::SoundBuilder;
+
+static THREAD_COUNTS: SystemMap<c_int> = SystemMap::new();
+
+impl SystemBuilder {
+    /// Sets how many non blocking threads [`StreamScheduler`]s created for this system spread loads across.
+    ///
+    /// FMOD creates a non blocking thread for each distinct id passed to [`SoundBuilder::with_non_block_thread_id`],
+    /// so this effectively caps how many loading threads exist.
+    /// `count` is clamped to between 1 and [`StreamScheduler::MAX_THREADS`]. The default is 1.
+    ///
+    /// The count takes effect once the system is built.
+    pub fn non_block_threads(&mut self, count: c_int) -> &mut Self {
+        self.non_block_threads = count.clamp(1, StreamScheduler::MAX_THREADS);
+        self
+    }
+}
+
+impl System {
+    /// Retrieves the non blocking thread count set with [`SystemBuilder::non_block_threads`].
+    pub fn get_non_block_thread_count(&self) -> c_int {
+        THREAD_COUNTS.get(*self).unwrap_or(1)
+    }
+
+    pub(crate) fn record_non_block_thread_count(&self, count: c_int) {
+        THREAD_COUNTS.insert(*self, count);
+    }
+
+    pub(crate) fn forget_non_block_thread_count(&self) {
+        THREAD_COUNTS.remove(*self);
+    }
+}
@@ -0,0 +1,23 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::{Error, Result, System};
+
+/// Returns [`Error::InvalidParam`] if `a` and `b` belong to different [`System`]s.
+///
+/// FMOD handles don't carry their system, and FMOD doesn't check either: mixing objects across systems corrupts its state silently.
+/// Asking FMOD for each object's system costs a call per object, so this only checks in debug builds or with the `system-checks` feature,
+/// and the closures aren't called otherwise.
+#[inline]
+pub(crate) fn check_same_system(
+    a: impl FnOnce() -> Result<System>,
+    b: impl FnOnce() -> Result<System>,
+) -> Result<()> {
+    if cfg!(any(debug_assertions, feature = "system-checks")) && a()? != b()? {
+        return Err(Error::InvalidParam);
+    }
+    Ok(())
+}
@@ -0,0 +1,59 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::{ChannelGroup, Result, System};
+
+impl System {
+    /// Pauses everything playing through the master [`ChannelGroup`], except for `except` and anything routed into them.
+    ///
+    /// This is the usual pause menu behavior, where the UI (and perhaps music) keeps playing.
+    ///
+    /// The hierarchy is walked from the master [`ChannelGroup`] down.
+    /// Groups that contain none of `except` are paused as a whole,
+    /// while groups that do have the [`crate::Channel`]s and groups under them paused individually.
+    /// Channels started after this call in a group that wasn't paused as a whole will not be paused.
+    ///
+    /// Undo this with [`System::resume_all_except`], passing the same groups.
+    pub fn pause_all_except(&self, except: &[ChannelGroup]) -> Result<()> {
+        set_paused_except(self.get_master_channel_group()?, true, except)
+    }
+
+    /// Unpauses everything that was paused by [`System::pause_all_except`].
+    ///
+    /// `except` should be the same groups that were passed to [`System::pause_all_except`].
+    pub fn resume_all_except(&self, except: &[ChannelGroup]) -> Result<()> {
+        set_paused_except(self.get_master_channel_group()?, false, except)
+    }
+}
+
+fn set_paused_except(group: ChannelGroup, paused: bool, except: &[ChannelGroup]) -> Result<()> {
+    if except.contains(&group) {
+        return Ok(());
+    }
+    if !contains_any(group, except)? {
+        return group.set_paused(paused);
+    }
+
+    // pausing this group would also pause an excepted group, so walk into it instead
+    for index in 0..group.get_channel_count()? {
+        group.get_channel(index)?.set_paused(paused)?;
+    }
+    for index in 0..group.get_group_count()? {
+        set_paused_except(group.get_group(index)?, paused, except)?;
+    }
+    Ok(())
+}
+
+/// Whether `group` is the parent (directly or not) of any of `groups`.
+fn contains_any(group: ChannelGroup, groups: &[ChannelGroup]) -> Result<bool> {
+    for index in 0..group.get_group_count()? {
+        let child = group.get_group(index)?;
+        if groups.contains(&child) || contains_any(child, groups)? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
@@ -0,0 +1,60 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::c_ushort;
+
+use super::SystemMap;
+use crate::Result;
+use crate::{InitFlags, System, SystemBuilder};
+
+#[cfg(doc)]
+use crate::AdvancedSettings;
+
+// systems initialized with InitFlags::PROFILE_ENABLE
+static PROFILED: SystemMap<()> = SystemMap::new();
+
+impl SystemBuilder {
+    /// Lets FMOD Studio or the FMOD Profiler connect to this system.
+    ///
+    /// This adds [`InitFlags::PROFILE_ENABLE`] when the system is built, and if `port` is set,
+    /// listens on it instead of the default port (see [`AdvancedSettings::profile_port`]).
+    pub fn enable_profiler(&mut self, port: Option<c_ushort>) -> Result<&mut Self> {
+        if let Some(port) = port {
+            // the system already exists at this point, so advanced settings can be set right away
+            let system = unsafe { System::from_ffi(self.system) };
+            let mut settings = system.get_advanced_settings()?;
+            settings.profile_port = port;
+            system.set_advanced_settings(&settings)?;
+        }
+        self.extra_flags.insert(InitFlags::PROFILE_ENABLE);
+        Ok(self)
+    }
+}
+
+impl System {
+    /// Whether this system was initialized with [`InitFlags::PROFILE_ENABLE`], so a profiler can connect to it.
+    ///
+    /// FMOD does not report whether a profiler is actually connected, only whether it is listening for one.
+    pub fn is_profiler_enabled(&self) -> bool {
+        PROFILED.contains(*self)
+    }
+
+    /// Retrieves the port FMOD Studio or the FMOD Profiler can connect to, if profiling is enabled.
+    pub fn get_profiler_port(&self) -> Result<Option<c_ushort>> {
+        if !self.is_profiler_enabled() {
+            return Ok(None);
+        }
+        Ok(Some(self.get_advanced_settings()?.profile_port))
+    }
+
+    pub(crate) fn mark_profiler_enabled(&self) {
+        PROFILED.insert(*self, ());
+    }
+
+    pub(crate) fn forget_profiler(&self) {
+        PROFILED.remove(*self);
+    }
+}
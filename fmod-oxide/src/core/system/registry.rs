@@ -0,0 +1,112 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use crate::System;
+
+/// A system the crate keeps state for in a [`SystemMap`].
+pub(crate) trait RegistryKey: Copy {
+    fn registry_key(self) -> usize;
+}
+
+impl RegistryKey for System {
+    fn registry_key(self) -> usize {
+        self.inner.as_ptr() as usize
+    }
+}
+
+#[cfg(feature = "studio")]
+impl RegistryKey for crate::studio::System {
+    fn registry_key(self) -> usize {
+        self.inner.as_ptr() as usize
+    }
+}
+
+/// State the crate keeps for each system, since FMOD has nowhere to store it.
+///
+/// Systems are keyed by address, and a new system can be allocated at the address of a released one,
+/// so every map has to be cleared from [`System::forget_all`] (or the Studio equivalent).
+#[derive(Debug)]
+pub(crate) struct SystemMap<T> {
+    entries: Mutex<Vec<(usize, T)>>,
+}
+
+impl<T> SystemMap<T> {
+    pub(crate) const fn new() -> Self {
+        Self {
+            entries: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn entries(&self) -> MutexGuard<'_, Vec<(usize, T)>> {
+        // entries are only ever inserted, replaced or removed whole, so a panic can't leave the map inconsistent
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Sets the value for `system`, replacing any previous one.
+    pub(crate) fn insert(&self, system: impl RegistryKey, value: T) {
+        let key = system.registry_key();
+        let mut entries = self.entries();
+        match entries.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, existing)) => *existing = value,
+            None => entries.push((key, value)),
+        }
+    }
+
+    /// Removes and returns the value for `system`.
+    pub(crate) fn remove(&self, system: impl RegistryKey) -> Option<T> {
+        let key = system.registry_key();
+        let mut entries = self.entries();
+        let index = entries.iter().position(|(k, _)| *k == key)?;
+        Some(entries.swap_remove(index).1)
+    }
+
+    /// Whether there is a value for `system`.
+    pub(crate) fn contains(&self, system: impl RegistryKey) -> bool {
+        let key = system.registry_key();
+        self.entries().iter().any(|(k, _)| *k == key)
+    }
+
+    /// Calls `f` with the value for `system` while the map is locked, so it can be read, changed, inserted or removed in one step.
+    ///
+    /// `f` must not call back into the same map.
+    pub(crate) fn update<R>(
+        &self,
+        system: impl RegistryKey,
+        f: impl FnOnce(&mut Option<T>) -> R,
+    ) -> R {
+        let key = system.registry_key();
+        let mut entries = self.entries();
+        let mut value = entries
+            .iter()
+            .position(|(k, _)| *k == key)
+            .map(|index| entries.swap_remove(index).1);
+        let result = f(&mut value);
+        if let Some(value) = value {
+            entries.push((key, value));
+        }
+        result
+    }
+
+    /// Calls `f` with every system's value while the map is locked, for lookups that aren't by system.
+    ///
+    /// Keys are the system pointers as `usize`. `f` must not call back into the same map.
+    pub(crate) fn with_entries<R>(&self, f: impl FnOnce(&mut [(usize, T)]) -> R) -> R {
+        f(&mut self.entries())
+    }
+}
+
+impl<T: Clone> SystemMap<T> {
+    /// A copy of the value for `system`.
+    pub(crate) fn get(&self, system: impl RegistryKey) -> Option<T> {
+        let key = system.registry_key();
+        self.entries()
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, value)| value.clone())
+    }
+}
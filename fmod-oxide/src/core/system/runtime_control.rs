@@ -7,7 +7,7 @@
 use fmod_sys::*;
 use std::{ffi::c_int, mem::MaybeUninit};
 
-use crate::{ChannelGroup, PortType, ReverbProperties, System, Vector};
+use crate::{ChannelGroup, PortIndex, PortType, ReverbProperties, System, Vector};
 
 #[cfg(doc)]
 use crate::{Dsp, OutputType};
@@ -146,7 +146,7 @@ impl System {
             FMOD_System_AttachChannelGroupToPort(
                 self.inner.as_ptr(),
                 kind.into(),
-                index.unwrap_or(FMOD_PORT_INDEX_NONE as FMOD_PORT_INDEX),
+                index.unwrap_or(PortIndex::NONE),
                 channel_group.into(),
                 pass_through.into(),
             )
@@ -0,0 +1,89 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::c_int;
+
+use fmod_sys::*;
+
+use crate::{ChannelControl, DspType, Error, OutputType, System, SystemBuilder};
+use crate::{FmodResultExt, Result};
+
+#[cfg(doc)]
+use crate::AdvancedSettings;
+
+// output types that mix to platform spatial objects instead of (or alongside) speakers
+const OBJECT_OUTPUTS: [OutputType; 3] =
+    [OutputType::WinSonic, OutputType::Audio3D, OutputType::Phase];
+
+impl SystemBuilder {
+    /// Sets how many spatial objects this system reserves for object based audio.
+    ///
+    /// See [`AdvancedSettings::max_spatial_objects`]: -1 reserves none, 0 reserves all that are available.
+    ///
+    /// Spatial objects are only available with the [`OutputType::WinSonic`], [`OutputType::Audio3D`], and [`OutputType::Phase`] outputs.
+    /// If a different output has already been selected with [`SystemBuilder::output`], this returns [`Error::Unsupported`],
+    /// so select the output first.
+    pub fn max_spatial_objects(&mut self, count: c_int) -> Result<&mut Self> {
+        if count < -1 {
+            return Err(Error::InvalidParam);
+        }
+        let mut output_type = 0;
+        unsafe {
+            FMOD_System_GetOutput(self.system, &raw mut output_type).to_result()?;
+        }
+        let output_type = OutputType::try_from(output_type)?;
+        if output_type != OutputType::AutoDetect && !OBJECT_OUTPUTS.contains(&output_type) {
+            return Err(Error::Unsupported);
+        }
+
+        // the system already exists at this point, so advanced settings can be set right away
+        let system = unsafe { System::from_ffi(self.system) };
+        let mut settings = system.get_advanced_settings()?;
+        settings.max_spatial_objects = count;
+        system.set_advanced_settings(&settings)?;
+        Ok(self)
+    }
+}
+
+impl System {
+    /// Retrieves how many spatial objects this system was configured to reserve.
+    ///
+    /// See [`SystemBuilder::max_spatial_objects`].
+    pub fn get_max_spatial_objects(&self) -> Result<c_int> {
+        Ok(self.get_advanced_settings()?.max_spatial_objects)
+    }
+
+    /// Whether the current output type supports spatial objects.
+    pub fn supports_spatial_objects(&self) -> Result<bool> {
+        Ok(OBJECT_OUTPUTS.contains(&self.get_output_type()?))
+    }
+
+    /// Counts the spatial objects currently in use, by counting the active [`DspType::ObjectPan`] DSPs
+    /// connected to the master channel group.
+    ///
+    /// This walks the whole DSP graph, so it is meant for debug overlays and budget checks rather than every frame.
+    pub fn get_spatial_objects_in_use(&self) -> Result<c_int> {
+        let master = self.get_master_channel_group()?;
+        let head = master.get_dsp(ChannelControl::DSP_HEAD)?;
+
+        let mut visited = vec![head];
+        let mut pending = vec![head];
+        let mut count = 0;
+        while let Some(dsp) = pending.pop() {
+            if dsp.get_type()? == DspType::ObjectPan && dsp.get_active()? {
+                count += 1;
+            }
+            for index in 0..dsp.get_input_count()? {
+                let (input, _) = dsp.get_input(index)?;
+                if !visited.contains(&input) {
+                    visited.push(input);
+                    pending.push(input);
+                }
+            }
+        }
+        Ok(count)
+    }
+}
@@ -0,0 +1,102 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::{c_int, c_uint};
+
+use crate::{Result, System, SystemBuilder};
+
+/// A class of platform with its own latency and stability tradeoffs, for [`SystemBuilder::tuned_for`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PlatformProfile {
+    /// Desktop and console.
+    ///
+    /// FMOD's own defaults: a DSP buffer of 1024 samples × 4 at the output's preferred rate.
+    Desktop,
+    /// Phones, tablets and other low power devices.
+    ///
+    /// 24 kHz, which is what FMOD picks on most mobile platforms and halves the mixing cost,
+    /// with a DSP buffer of 512 samples × 4 so the shorter buffers of mobile audio APIs don't add latency.
+    Mobile,
+    /// Browsers, with the emscripten build of FMOD.
+    ///
+    /// A DSP buffer of 2048 samples × 2 as FMOD's HTML5 docs recommend, since browsers schedule audio less precisely than native platforms.
+    Web,
+}
+
+impl PlatformProfile {
+    /// The recommended settings for this platform, to override before passing to [`SystemBuilder::tuning`].
+    pub const fn tuning(self) -> OutputTuning {
+        match self {
+            PlatformProfile::Desktop => OutputTuning {
+                sample_rate: None,
+                dsp_buffer_length: 1024,
+                dsp_buffer_count: 4,
+            },
+            PlatformProfile::Mobile => OutputTuning {
+                sample_rate: Some(24_000),
+                dsp_buffer_length: 512,
+                dsp_buffer_count: 4,
+            },
+            PlatformProfile::Web => OutputTuning {
+                sample_rate: None,
+                dsp_buffer_length: 2048,
+                dsp_buffer_count: 2,
+            },
+        }
+    }
+}
+
+/// Mixer sample rate and DSP buffer settings, see [`PlatformProfile::tuning`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OutputTuning {
+    /// The sample rate to mix at, or [`None`] for the one the output prefers.
+    pub sample_rate: Option<c_int>,
+    /// The length of each DSP buffer in samples, see [`SystemBuilder::dsp_buffer_size`].
+    pub dsp_buffer_length: c_uint,
+    /// The number of DSP buffers, see [`SystemBuilder::dsp_buffer_size`].
+    pub dsp_buffer_count: c_int,
+}
+
+impl OutputTuning {
+    /// Sets the sample rate to mix at, or [`None`] for the one the output prefers.
+    #[must_use]
+    pub const fn with_sample_rate(mut self, sample_rate: Option<c_int>) -> Self {
+        self.sample_rate = sample_rate;
+        self
+    }
+
+    /// Sets the DSP buffer length (in samples) and count.
+    #[must_use]
+    pub const fn with_dsp_buffer(mut self, length: c_uint, count: c_int) -> Self {
+        self.dsp_buffer_length = length;
+        self.dsp_buffer_count = count;
+        self
+    }
+}
+
+impl SystemBuilder {
+    /// Applies the recommended [`OutputTuning`] for `profile`.
+    ///
+    /// Settings made afterwards (such as [`SystemBuilder::dsp_buffer_size`]) override it.
+    /// To change part of the tuning up front, use [`PlatformProfile::tuning`] and [`SystemBuilder::tuning`] instead.
+    /// This only affects output settings, see [`SystemBuilder::profile`] for init flags.
+    pub fn tuned_for(&mut self, profile: PlatformProfile) -> Result<&mut Self> {
+        self.tuning(&profile.tuning())
+    }
+
+    /// Applies `tuning`, keeping the speaker mode.
+    pub fn tuning(&mut self, tuning: &OutputTuning) -> Result<&mut Self> {
+        if let Some(sample_rate) = tuning.sample_rate {
+            // the system already exists at this point, so the current format can be read back
+            let system = unsafe { System::from_ffi(self.system) };
+            let (_, speaker_mode, raw_speakers) = system.get_software_format()?;
+            self.software_format(sample_rate, speaker_mode, raw_speakers)?;
+        }
+        self.dsp_buffer_size(tuning.dsp_buffer_length, tuning.dsp_buffer_count)
+    }
+}
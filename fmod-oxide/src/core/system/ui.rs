@@ -0,0 +1,32 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::Result;
+use crate::{Channel, ChannelGroup, Mode, Sound, System, Vector};
+
+impl System {
+    /// Plays `sound` as a UI sound, which stays centered on the listener no matter where the listener moves.
+    ///
+    /// 3D sounds are switched to [`Mode::HEADRELATIVE_3D`] and placed on the listener,
+    /// so they keep their 3D settings (such as their DSP chain) but don't pan or attenuate.
+    /// 2D sounds are played as they are.
+    ///
+    /// The channel starts unpaused on `channel_group`, or the master channel group.
+    pub fn play_ui_sound(
+        &self,
+        sound: Sound,
+        channel_group: Option<ChannelGroup>,
+    ) -> Result<Channel> {
+        // start paused so the sound can't be heard in the wrong place for a mix
+        let channel = self.play_sound(sound, channel_group, true)?;
+        if sound.get_mode()?.contains(Mode::D3) {
+            channel.set_mode(Mode::D3 | Mode::HEADRELATIVE_3D)?;
+            channel.set_3d_attributes(Some(Vector::default()), Some(Vector::default()))?;
+        }
+        channel.set_paused(false)?;
+        Ok(channel)
+    }
+}
@@ -0,0 +1,250 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use super::{RegistryKey, SystemMap};
+use crate::{Error, Result, System};
+
+/// How long the updates of a window of frames took, see [`System::track_update_timing`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UpdateTimings {
+    /// Number of updates measured.
+    pub frames: u32,
+    /// The fastest update.
+    pub min: Duration,
+    /// The average update.
+    pub avg: Duration,
+    /// The slowest update.
+    pub max: Duration,
+}
+
+/// A warning for when a single update takes longer than `limit`, see [`System::set_update_budget`].
+#[derive(Clone)]
+pub struct UpdateBudget {
+    limit: Duration,
+    on_exceeded: Arc<dyn Fn(Duration) + Send + Sync>,
+}
+
+impl UpdateBudget {
+    /// Warns about updates taking longer than `limit`, through the `log` crate with the `log` feature and on stderr otherwise.
+    pub fn new(limit: Duration) -> Self {
+        Self::with_hook(limit, move |elapsed| warn_exceeded(elapsed, limit))
+    }
+
+    /// Calls `on_exceeded` with how long the update took, whenever an update takes longer than `limit`.
+    ///
+    /// The hook runs on the thread calling update, right after it returns.
+    pub fn with_hook(
+        limit: Duration,
+        on_exceeded: impl Fn(Duration) + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            limit,
+            on_exceeded: Arc::new(on_exceeded),
+        }
+    }
+
+    /// The longest an update may take without triggering the hook.
+    pub fn limit(&self) -> Duration {
+        self.limit
+    }
+}
+
+impl std::fmt::Debug for UpdateBudget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UpdateBudget")
+            .field("limit", &self.limit)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "log")]
+fn warn_exceeded(elapsed: Duration, limit: Duration) {
+    log::warn!(target: "fmod", "update took {elapsed:?}, over its budget of {limit:?}");
+}
+
+#[cfg(not(feature = "log"))]
+fn warn_exceeded(elapsed: Duration, limit: Duration) {
+    eprintln!("warning: update took {elapsed:?}, over its budget of {limit:?}");
+}
+
+#[derive(Default)]
+struct Window {
+    frames: u32,
+    min: Duration,
+    max: Duration,
+    total: Duration,
+}
+
+impl Window {
+    fn add(&mut self, elapsed: Duration) {
+        if self.frames == 0 {
+            self.min = elapsed;
+            self.max = elapsed;
+        } else {
+            self.min = self.min.min(elapsed);
+            self.max = self.max.max(elapsed);
+        }
+        self.frames += 1;
+        self.total += elapsed;
+    }
+
+    fn timings(&self) -> UpdateTimings {
+        UpdateTimings {
+            frames: self.frames,
+            min: self.min,
+            avg: self.total / self.frames,
+            max: self.max,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Timer {
+    window_frames: Option<u32>,
+    window: Window,
+    last: Option<UpdateTimings>,
+    budget: Option<UpdateBudget>,
+}
+
+// core and Studio system pointers never overlap, so both share this map
+static TIMERS: SystemMap<Timer> = SystemMap::new();
+// lets updates skip the lock and the clock entirely when nothing is being timed
+static TIMER_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Timing state of the `update` function of a core or Studio system.
+pub(crate) struct UpdateTimer<S>(pub(crate) S);
+
+impl<S: RegistryKey> UpdateTimer<S> {
+    fn modify(&self, f: impl FnOnce(&mut Timer)) {
+        TIMERS.update(self.0, |entry| {
+            let existed = entry.is_some();
+            let timer = entry.get_or_insert_with(Timer::default);
+            f(timer);
+            if timer.window_frames.is_none() && timer.budget.is_none() {
+                *entry = None;
+            }
+            match (existed, entry.is_some()) {
+                (false, true) => {
+                    TIMER_COUNT.fetch_add(1, Ordering::Relaxed);
+                }
+                (true, false) => {
+                    TIMER_COUNT.fetch_sub(1, Ordering::Relaxed);
+                }
+                _ => {}
+            }
+        });
+    }
+
+    pub(crate) fn track(&self, frames: u32) -> Result<()> {
+        if frames == 0 {
+            return Err(Error::InvalidParam);
+        }
+        self.modify(|timer| {
+            timer.window_frames = Some(frames);
+            timer.window = Window::default();
+            timer.last = None;
+        });
+        Ok(())
+    }
+
+    pub(crate) fn untrack(&self) {
+        self.modify(|timer| {
+            timer.window_frames = None;
+            timer.last = None;
+        });
+    }
+
+    pub(crate) fn timings(&self) -> Option<UpdateTimings> {
+        TIMERS.update(self.0, |timer| timer.as_ref()?.last)
+    }
+
+    pub(crate) fn set_budget(&self, budget: Option<UpdateBudget>) {
+        self.modify(|timer| timer.budget = budget);
+    }
+
+    pub(crate) fn budget(&self) -> Option<UpdateBudget> {
+        TIMERS.update(self.0, |timer| timer.as_ref()?.budget.clone())
+    }
+
+    pub(crate) fn forget(&self) {
+        if TIMERS.remove(self.0).is_some() {
+            TIMER_COUNT.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Runs `update`, recording how long it took if anything is being timed.
+    pub(crate) fn time(&self, update: impl FnOnce() -> Result<()>) -> Result<()> {
+        if TIMER_COUNT.load(Ordering::Relaxed) == 0 {
+            return update();
+        }
+        let start = Instant::now();
+        let result = update();
+        let elapsed = start.elapsed();
+
+        let exceeded = TIMERS.update(self.0, |timer| {
+            let timer = timer.as_mut()?;
+            if let Some(frames) = timer.window_frames {
+                timer.window.add(elapsed);
+                if timer.window.frames >= frames {
+                    timer.last = Some(timer.window.timings());
+                    timer.window = Window::default();
+                }
+            }
+            timer
+                .budget
+                .as_ref()
+                .filter(|budget| elapsed > budget.limit)
+                .map(|budget| budget.on_exceeded.clone())
+        });
+        // called without the lock held, so the hook is free to change the budget
+        if let Some(on_exceeded) = exceeded {
+            on_exceeded(elapsed);
+        }
+        result
+    }
+}
+
+impl System {
+    /// Starts measuring how long [`System::update`] takes, in windows of `frames` updates.
+    ///
+    /// Every time a window fills up its [`UpdateTimings`] replace those returned by [`System::get_update_timings`].
+    /// Calling this again restarts the measurement with the new window size.
+    ///
+    /// Returns [`Error::InvalidParam`] if `frames` is 0.
+    pub fn track_update_timing(&self, frames: u32) -> Result<()> {
+        self.update_timer().track(frames)
+    }
+
+    /// Stops measuring how long [`System::update`] takes.
+    pub fn untrack_update_timing(&self) {
+        self.update_timer().untrack();
+    }
+
+    /// The [`UpdateTimings`] of the last full window, or [`None`] if there isn't one yet.
+    pub fn get_update_timings(&self) -> Option<UpdateTimings> {
+        self.update_timer().timings()
+    }
+
+    /// Sets an [`UpdateBudget`] that is checked after every [`System::update`], or removes it with [`None`].
+    ///
+    /// This is independent of [`System::track_update_timing`], a budget can be set without tracking timings and the other way around.
+    pub fn set_update_budget(&self, budget: Option<UpdateBudget>) {
+        self.update_timer().set_budget(budget);
+    }
+
+    /// The [`UpdateBudget`] set with [`System::set_update_budget`].
+    pub fn get_update_budget(&self) -> Option<UpdateBudget> {
+        self.update_timer().budget()
+    }
+
+    pub(crate) fn update_timer(&self) -> UpdateTimer<Self> {
+        UpdateTimer(*self)
+    }
+}
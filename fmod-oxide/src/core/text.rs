@@ -0,0 +1,146 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::borrow::Cow;
+
+#[cfg(doc)]
+use crate::TagData;
+
+/// Byte order of UTF-16 encoded text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endian {
+    /// Little endian, used by [`TagData::Utf16String`] and ASF metadata.
+    Little,
+    /// Big endian, used by [`TagData::Utf16StringBE`].
+    Big,
+}
+
+/// An error returned when decoding UTF-16 text strictly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Utf16Error {
+    /// The data had an odd number of bytes, so the last code unit is incomplete.
+    OddLength,
+    /// A surrogate code unit was not part of a valid surrogate pair.
+    UnpairedSurrogate {
+        /// Index (in code units) of the unpaired surrogate.
+        index: usize,
+        /// The unpaired surrogate.
+        code_unit: u16,
+    },
+}
+
+impl std::fmt::Display for Utf16Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Utf16Error::OddLength => f.write_str("utf-16 data has an odd number of bytes"),
+            Utf16Error::UnpairedSurrogate { index, code_unit } => {
+                write!(f, "unpaired surrogate {code_unit:#06x} at index {index}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Utf16Error {}
+
+fn code_units(bytes: &[u8], endian: Endian) -> impl Iterator<Item = u16> + '_ {
+    // FMOD makes no alignment guarantees for tag data, so read pairs of bytes rather than casting to &[u16]
+    bytes.chunks_exact(2).map(move |pair| match endian {
+        Endian::Little => u16::from_le_bytes([pair[0], pair[1]]),
+        Endian::Big => u16::from_be_bytes([pair[0], pair[1]]),
+    })
+}
+
+/// Returns `bytes` up to the first nul byte, or all of `bytes` if there is none.
+pub fn trim_nul(bytes: &[u8]) -> &[u8] {
+    let len = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    &bytes[..len]
+}
+
+/// Returns `bytes` up to the first nul code unit (two zero bytes at an even offset), or all of `bytes` if there is none.
+pub fn trim_nul_utf16(bytes: &[u8]) -> &[u8] {
+    let len = bytes
+        .chunks_exact(2)
+        .position(|pair| pair == [0, 0])
+        .map_or(bytes.len(), |units| units * 2);
+    &bytes[..len]
+}
+
+/// Splits a leading byte order mark off of UTF-16 data, returning the detected byte order (or `default` if there was no BOM).
+pub fn strip_utf16_bom(bytes: &[u8], default: Endian) -> (&[u8], Endian) {
+    match bytes {
+        [0xFF, 0xFE, rest @ ..] => (rest, Endian::Little),
+        [0xFE, 0xFF, rest @ ..] => (rest, Endian::Big),
+        _ => (bytes, default),
+    }
+}
+
+/// Decodes UTF-16 text, failing on odd length data and unpaired surrogates.
+pub fn decode_utf16(bytes: &[u8], endian: Endian) -> Result<String, Utf16Error> {
+    if !bytes.len().is_multiple_of(2) {
+        return Err(Utf16Error::OddLength);
+    }
+    // fast path for plain ASCII, which is most tag data
+    if let Some(string) = decode_ascii_utf16(bytes, endian) {
+        return Ok(string);
+    }
+
+    let mut string = String::with_capacity(bytes.len() / 2);
+    let mut index = 0;
+    for result in char::decode_utf16(code_units(bytes, endian)) {
+        match result {
+            Ok(c) => {
+                index += c.len_utf16();
+                string.push(c);
+            }
+            Err(e) => {
+                return Err(Utf16Error::UnpairedSurrogate {
+                    index,
+                    code_unit: e.unpaired_surrogate(),
+                });
+            }
+        }
+    }
+    Ok(string)
+}
+
+/// Decodes UTF-16 text, replacing unpaired surrogates and a trailing odd byte with [`char::REPLACEMENT_CHARACTER`].
+pub fn decode_utf16_lossy(bytes: &[u8], endian: Endian) -> String {
+    if let Some(string) = decode_ascii_utf16(bytes, endian) {
+        return string;
+    }
+
+    // we use char::decode_utf16 instead of String::from_utf16 because the latter would require us to collect into a Vec<u16> first
+    let mut string: String = char::decode_utf16(code_units(bytes, endian))
+        .map(|r| r.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect();
+    if !bytes.len().is_multiple_of(2) {
+        string.push(char::REPLACEMENT_CHARACTER);
+    }
+    string
+}
+
+fn decode_ascii_utf16(bytes: &[u8], endian: Endian) -> Option<String> {
+    let (high, low) = match endian {
+        Endian::Little => (1, 0),
+        Endian::Big => (0, 1),
+    };
+    let is_ascii = bytes.len().is_multiple_of(2)
+        && bytes
+            .chunks_exact(2)
+            .all(|pair| pair[high] == 0 && pair[low].is_ascii());
+    is_ascii.then(|| {
+        let ascii = bytes.chunks_exact(2).map(|pair| pair[low]).collect();
+        // Safety: every byte was checked to be ASCII
+        unsafe { String::from_utf8_unchecked(ascii) }
+    })
+}
+
+/// Decodes UTF-8 text, replacing invalid sequences with [`char::REPLACEMENT_CHARACTER`].
+///
+/// Valid UTF-8 is borrowed rather than copied.
+pub fn decode_utf8_lossy(bytes: &[u8]) -> Cow<'_, str> {
+    String::from_utf8_lossy(bytes)
+}
@@ -4,9 +4,13 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use crate::{FmodResultExt, Result};
-use crate::{ThreadAffinity, ThreadType};
+use std::ffi::c_void;
+
+use crate::{Error, FmodResultExt, Result};
+use crate::{System, SystemBuilder, SystemCallback, SystemCallbackHandle, SystemCallbackMask};
+use crate::{SystemMap, ThreadAffinity, ThreadType};
 use fmod_sys::*;
+use lanyard::{Utf8CStr, Utf8CString};
 
 /// Scheduling priority to assign a given thread to.
 pub mod priority {
@@ -114,3 +118,207 @@ pub fn set_attributes(
         FMOD_Thread_SetAttributes(kind.into(), affinity.into(), priority, stack_size).to_result()
     }
 }
+
+const THREAD_TYPES: [ThreadType; 13] = [
+    ThreadType::Mixer,
+    ThreadType::Feeder,
+    ThreadType::Stream,
+    ThreadType::File,
+    ThreadType::NonBlocking,
+    ThreadType::Record,
+    ThreadType::Geometry,
+    ThreadType::Profiler,
+    ThreadType::StudioUpdate,
+    ThreadType::StudioLoadBank,
+    ThreadType::StudioLoadSample,
+    ThreadType::Convolution1,
+    ThreadType::Convolution2,
+];
+
+/// Ready made affinity setups for every [`ThreadType`], applied with [`ThreadAffinityPreset::apply`].
+///
+/// Like [`set_attributes`], presets must be applied before FMOD creates its threads.
+/// Priorities and stack sizes are left at their defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ThreadAffinityPreset {
+    /// FMOD's recommended grouping: the mixer in [`ThreadAffinity::GROUP_A`],
+    /// the Studio update thread in [`ThreadAffinity::GROUP_B`], and everything else in [`ThreadAffinity::GROUP_C`].
+    ///
+    /// What the groups map to is platform specific, see the platform notes in the FMOD documentation.
+    ConsoleDefault,
+    /// Pins the mixer and feeder threads to the given core, and keeps every other thread off it.
+    IsolateMixer(usize),
+    /// Spreads the thread types over all available cores, one core per type, round robin.
+    Spread,
+}
+
+impl ThreadAffinityPreset {
+    /// The affinity this preset gives each thread type.
+    ///
+    /// Returns [`Error::InvalidParam`] if the preset needs more cores than [`std::thread::available_parallelism`] reports.
+    /// Only the first 32 cores are used.
+    pub fn affinities(self) -> Result<Vec<(ThreadType, ThreadAffinity)>> {
+        let cores = std::thread::available_parallelism()
+            .map_or(1, std::num::NonZero::get)
+            .min(32);
+        let core = |index: usize| ThreadAffinity::from_bits_retain(1 << index);
+
+        let affinity_of: Box<dyn Fn(usize, ThreadType) -> ThreadAffinity> = match self {
+            ThreadAffinityPreset::ConsoleDefault => Box::new(|_, kind| match kind {
+                ThreadType::Mixer => ThreadAffinity::GROUP_A,
+                ThreadType::StudioUpdate => ThreadAffinity::GROUP_B,
+                _ => ThreadAffinity::GROUP_C,
+            }),
+            ThreadAffinityPreset::IsolateMixer(mixer_core) => {
+                if cores < 2 || mixer_core >= cores {
+                    return Err(Error::InvalidParam);
+                }
+                let others = (0..cores)
+                    .filter(|&index| index != mixer_core)
+                    .fold(ThreadAffinity::empty(), |mask, index| mask | core(index));
+                Box::new(move |_, kind| match kind {
+                    ThreadType::Mixer | ThreadType::Feeder => core(mixer_core),
+                    _ => others,
+                })
+            }
+            ThreadAffinityPreset::Spread => Box::new(move |index, _| core(index % cores)),
+        };
+
+        Ok(THREAD_TYPES
+            .iter()
+            .enumerate()
+            .map(|(index, &kind)| (kind, affinity_of(index, kind)))
+            .collect())
+    }
+
+    /// Calls [`set_attributes`] for every thread type.
+    pub fn apply(self) -> Result<()> {
+        for (kind, affinity) in self.affinities()? {
+            set_attributes(kind, affinity, priority::DEFAULT, stack_size::DEFAULT)?;
+        }
+        Ok(())
+    }
+}
+
+/// A thread FMOD has created and not yet destroyed, as recorded by [`track_threads`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiveThread {
+    /// What the thread is for, guessed from its name. [`None`] if the name wasn't recognized.
+    pub kind: Option<ThreadType>,
+    /// The native thread handle, such as a `pthread_t` or Windows `HANDLE`.
+    pub handle: *mut c_void,
+    /// The name FMOD gave the thread.
+    pub name: Utf8CString,
+}
+
+struct TrackedThread {
+    handle: usize,
+    name: Utf8CString,
+}
+
+// each system's threads in creation order
+static THREADS: SystemMap<Vec<TrackedThread>> = SystemMap::new();
+
+// FMOD only passes a name to the callbacks, so the type has to be worked out from it
+fn guess_kind(name: &str) -> Option<ThreadType> {
+    const KINDS: [(&str, ThreadType); 13] = [
+        ("convolution1", ThreadType::Convolution1),
+        ("convolution2", ThreadType::Convolution2),
+        ("bank", ThreadType::StudioLoadBank),
+        ("sample", ThreadType::StudioLoadSample),
+        ("studio", ThreadType::StudioUpdate),
+        ("mixer", ThreadType::Mixer),
+        ("feeder", ThreadType::Feeder),
+        ("stream", ThreadType::Stream),
+        ("file", ThreadType::File),
+        ("nonblock", ThreadType::NonBlocking),
+        ("record", ThreadType::Record),
+        ("geometry", ThreadType::Geometry),
+        ("profil", ThreadType::Profiler),
+    ];
+    let name: String = name
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    KINDS
+        .iter()
+        .find(|(needle, _)| name.contains(needle))
+        .map(|&(_, kind)| kind)
+}
+
+struct ThreadTracker;
+
+impl SystemCallback for ThreadTracker {
+    fn thread_created(
+        system: System,
+        handle: *mut c_void,
+        thread_name: &Utf8CStr,
+        _: *mut c_void,
+    ) -> Result<()> {
+        let thread = TrackedThread {
+            handle: handle as usize,
+            name: thread_name.to_cstring(),
+        };
+        THREADS.update(system, |threads| {
+            threads.get_or_insert_with(Vec::new).push(thread);
+        });
+        Ok(())
+    }
+
+    fn thread_destroyed(
+        system: System,
+        handle: *mut c_void,
+        _: &Utf8CStr,
+        _: *mut c_void,
+    ) -> Result<()> {
+        THREADS.update(system, |threads| {
+            if let Some(threads) = threads {
+                threads.retain(|t| t.handle != handle as usize);
+            }
+        });
+        Ok(())
+    }
+}
+
+/// Starts recording the threads `system` creates and destroys, so they can be listed with [`live_threads`].
+///
+/// Most of FMOD's threads are created during initialization, so prefer [`SystemBuilder::track_threads`],
+/// which starts recording before the system is built.
+pub fn track_threads(system: System) -> Result<SystemCallbackHandle> {
+    system.add_callback::<ThreadTracker>(
+        SystemCallbackMask::THREADCREATED | SystemCallbackMask::THREADDESTROYED,
+    )
+}
+
+/// Lists the threads `system` has created and not yet destroyed, in creation order.
+///
+/// Only threads created after [`track_threads`] or [`SystemBuilder::track_threads`] was called are listed.
+pub fn live_threads(system: System) -> Vec<LiveThread> {
+    THREADS.update(system, |threads| {
+        threads
+            .iter()
+            .flatten()
+            .map(|t| LiveThread {
+                kind: guess_kind(t.name.as_str()),
+                handle: t.handle as *mut c_void,
+                name: t.name.clone(),
+            })
+            .collect()
+    })
+}
+
+pub(crate) fn forget_threads(system: System) {
+    THREADS.remove(system);
+}
+
+impl SystemBuilder {
+    /// Records the threads FMOD creates, including the ones created while the system is built.
+    ///
+    /// See [`live_threads`].
+    pub fn track_threads(&mut self) -> Result<&mut Self> {
+        // the system already exists at this point, so the callback can be added right away
+        track_threads(unsafe { System::from_ffi(self.system) })?;
+        Ok(self)
+    }
+}
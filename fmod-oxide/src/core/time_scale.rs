@@ -0,0 +1,219 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::c_float;
+
+use crate::{ChannelControl, ChannelGroup, Error, Result, System};
+
+#[cfg(feature = "studio")]
+use crate::studio;
+
+#[cfg(doc)]
+use crate::DspType;
+
+/// How a [`TimeScale`] changes the sound of the groups it controls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TimeScaleMode {
+    /// Change the playback rate, and the pitch with it, like a tape slowing down.
+    ///
+    /// Works at any scale and costs nothing extra.
+    #[default]
+    Pitch,
+    /// Change the playback rate, and shift the pitch back with a [`DspType::PitchShift`] on each group.
+    ///
+    /// Only scales between [`ChannelControl::MIN_PITCH_PRESERVING_SPEED`] and [`ChannelControl::MAX_PITCH_PRESERVING_SPEED`] (or 0) are supported.
+    /// See [`Channel::set_speed_preserving_pitch`](crate::Channel::set_speed_preserving_pitch) for the quality limits of the DSP.
+    Speed,
+}
+
+/// Applies a gameplay time scale to a selection of [`ChannelGroup`]s, leaving everything else running in real time.
+///
+/// Add the groups carrying game world sounds, and leave out the ones for UI and music,
+/// so that slow motion or a paused game world doesn't affect menus and the soundtrack.
+/// A scale of 0 pauses the groups, which is how a game pause should be applied to audio.
+///
+/// The pitch and paused state of each group are owned by the [`TimeScale`] while it is added, so don't set them elsewhere.
+/// In [`TimeScaleMode::Speed`] the groups should not have a [`DspType::PitchShift`] of their own, as it would be taken over.
+///
+/// Nothing is undone on drop, as the groups may already have been released; call [`TimeScale::reset`] first if needed.
+#[derive(Debug)]
+pub struct TimeScale {
+    system: System,
+    groups: Vec<ChannelGroup>,
+    mode: TimeScaleMode,
+    scale: c_float,
+    base_doppler: Option<c_float>,
+}
+
+impl TimeScale {
+    /// Creates a time scale at 1, with no groups.
+    pub fn new(system: System, mode: TimeScaleMode) -> Self {
+        Self {
+            system,
+            groups: Vec::new(),
+            mode,
+            scale: 1.0,
+            base_doppler: None,
+        }
+    }
+
+    /// Adds `group` and applies the current scale to it.
+    pub fn add_group(&mut self, group: ChannelGroup) -> Result<()> {
+        if !self.groups.contains(&group) {
+            self.apply(&group, self.scale)?;
+            self.groups.push(group);
+        }
+        Ok(())
+    }
+
+    /// Adds the [`ChannelGroup`] of a Studio `bus`.
+    ///
+    /// The bus's group is locked with [`studio::Bus::lock_channel_group`] so that it exists,
+    /// and `system`'s commands are flushed to create it right away.
+    #[cfg(feature = "studio")]
+    pub fn add_bus(&mut self, system: studio::System, bus: studio::Bus) -> Result<()> {
+        bus.lock_channel_group()?;
+        system.flush_commands()?;
+        self.add_group(bus.get_channel_group()?)
+    }
+
+    /// Removes `group`, restoring its pitch and unpausing it.
+    pub fn remove_group(&mut self, group: ChannelGroup) -> Result<()> {
+        if let Some(index) = self.groups.iter().position(|g| *g == group) {
+            self.groups.swap_remove(index);
+            self.restore(&group)?;
+        }
+        Ok(())
+    }
+
+    /// The groups being scaled.
+    pub fn groups(&self) -> &[ChannelGroup] {
+        &self.groups
+    }
+
+    /// The current scale.
+    pub fn scale(&self) -> c_float {
+        self.scale
+    }
+
+    /// The current mode.
+    pub fn mode(&self) -> TimeScaleMode {
+        self.mode
+    }
+
+    /// Whether the groups are paused by a scale of 0.
+    pub fn is_paused(&self) -> bool {
+        self.scale == 0.0
+    }
+
+    /// Sets the scale, where 1 is real time and 0 pauses the groups.
+    ///
+    /// Returns [`Error::InvalidParam`] if `scale` is negative or not finite,
+    /// or out of the supported range in [`TimeScaleMode::Speed`].
+    pub fn set_scale(&mut self, scale: c_float) -> Result<()> {
+        if !scale.is_finite() || scale < 0.0 {
+            return Err(Error::InvalidParam);
+        }
+        if self.mode == TimeScaleMode::Speed
+            && scale != 0.0
+            && !(ChannelControl::MIN_PITCH_PRESERVING_SPEED
+                ..=ChannelControl::MAX_PITCH_PRESERVING_SPEED)
+                .contains(&scale)
+        {
+            return Err(Error::InvalidParam);
+        }
+
+        for group in &self.groups {
+            self.apply(group, scale)?;
+        }
+        if let Some(base) = self.base_doppler {
+            let (_, distance_factor, rolloff_scale) = self.system.get_3d_settings()?;
+            self.system
+                .set_3d_settings(base * scale, distance_factor, rolloff_scale)?;
+        }
+        self.scale = scale;
+        Ok(())
+    }
+
+    /// Switches to `mode`, reapplying the current scale.
+    ///
+    /// Returns [`Error::InvalidParam`] if the current scale is not supported by `mode`, leaving the mode unchanged.
+    pub fn set_mode(&mut self, mode: TimeScaleMode) -> Result<()> {
+        let previous = self.mode;
+        if previous == mode {
+            return Ok(());
+        }
+        self.mode = mode;
+        if let Err(e) = self.set_scale(self.scale) {
+            self.mode = previous;
+            return Err(e);
+        }
+        if previous == TimeScaleMode::Speed {
+            for group in &self.groups {
+                group.compensate_pitch(1.0)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Scales the doppler effect along with time, so sounds moving at game speed keep a believable doppler shift.
+    ///
+    /// Velocities passed to FMOD are normally in game units per game second,
+    /// which move slower in real time when the game is slowed down, so the doppler scale from [`System::get_3d_settings`] is multiplied by the time scale.
+    /// This is a [`System`] wide setting, but UI and music are rarely 3D so they aren't affected in practice.
+    ///
+    /// Disabling restores the doppler scale captured when it was enabled.
+    pub fn set_scale_doppler(&mut self, enabled: bool) -> Result<()> {
+        let (doppler, distance_factor, rolloff_scale) = self.system.get_3d_settings()?;
+        match (enabled, self.base_doppler) {
+            (true, None) => {
+                self.system.set_3d_settings(
+                    doppler * self.scale,
+                    distance_factor,
+                    rolloff_scale,
+                )?;
+                self.base_doppler = Some(doppler);
+            }
+            (false, Some(base)) => {
+                self.system
+                    .set_3d_settings(base, distance_factor, rolloff_scale)?;
+                self.base_doppler = None;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Returns every group and the doppler scale to real time, and removes all groups.
+    pub fn reset(&mut self) -> Result<()> {
+        self.set_scale_doppler(false)?;
+        for group in std::mem::take(&mut self.groups) {
+            self.restore(&group)?;
+        }
+        self.scale = 1.0;
+        Ok(())
+    }
+
+    fn apply(&self, group: &ChannelGroup, scale: c_float) -> Result<()> {
+        if scale == 0.0 {
+            // leave the rate alone so unpausing picks up where it left off
+            return group.set_paused(true);
+        }
+        group.set_pitch(scale)?;
+        if self.mode == TimeScaleMode::Speed {
+            group.compensate_pitch(scale)?;
+        }
+        group.set_paused(false)
+    }
+
+    fn restore(&self, group: &ChannelGroup) -> Result<()> {
+        group.set_pitch(1.0)?;
+        if self.mode == TimeScaleMode::Speed {
+            group.compensate_pitch(1.0)?;
+        }
+        group.set_paused(false)
+    }
+}
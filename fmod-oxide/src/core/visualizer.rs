@@ -0,0 +1,195 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::{c_float, c_int};
+
+use crate::Result;
+use crate::effects::fft;
+use crate::{ChannelControl, ChannelGroup, Dsp, DspType, Fft, System};
+
+/// How FFT bins are grouped into [`Visualizer`] bands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BandScale {
+    /// Every band covers the same number of bins.
+    Linear,
+    /// Bands get wider as frequency increases, which better matches how music is perceived.
+    #[default]
+    Logarithmic,
+}
+
+/// A single frame of visualizer data.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VisualizerFrame<'a> {
+    /// Smoothed magnitude of each band, from lowest to highest frequency.
+    pub bands: &'a [c_float],
+    /// Smoothed peak level across all output channels.
+    pub peak: c_float,
+    /// Smoothed RMS level across all output channels.
+    pub rms: c_float,
+}
+
+/// Taps the master [`ChannelGroup`] with an FFT [`Dsp`] and its metering info to drive spectrum and level visualizers.
+///
+/// Call [`Visualizer::update`] once per frame (after [`System::update`]) to get the latest data.
+/// The FFT [`Dsp`] is removed when [`Visualizer::release`] is called or the visualizer is dropped.
+#[derive(Debug)]
+pub struct Visualizer {
+    master: ChannelGroup,
+    fft: Dsp,
+    head: Dsp,
+    metering_was_enabled: (bool, bool),
+    scale: BandScale,
+    smoothing: c_float,
+    bands: Vec<c_float>,
+    peak: c_float,
+    rms: c_float,
+    released: bool,
+}
+
+impl Visualizer {
+    /// Creates a visualizer on the master [`ChannelGroup`] of `system` that produces `band_count` bands per frame.
+    pub fn new(system: System, band_count: usize) -> Result<Self> {
+        let master = system.get_master_channel_group()?;
+        // the head has to be captured before the FFT is inserted in front of it
+        let head = master.get_dsp(ChannelControl::DSP_HEAD)?;
+        let metering_was_enabled = head.get_metering_enabled()?;
+        let fft = system.create_dsp_by_type(DspType::Fft)?;
+        let result = (|| -> Result<()> {
+            fft.set_parameter(fft::Downmix, fft::DownmixType::Mono)?;
+            fft.set_parameter(fft::Window, fft::WindowType::Hanning)?;
+            head.set_metering_enabled(metering_was_enabled.0, true)?;
+            master.add_dsp(ChannelControl::DSP_HEAD, fft)
+        })();
+        if let Err(e) = result {
+            let _ = master.remove_dsp(fft);
+            let _ = head.set_metering_enabled(metering_was_enabled.0, metering_was_enabled.1);
+            fft.release()?;
+            return Err(e);
+        }
+
+        Ok(Self {
+            master,
+            fft,
+            head,
+            metering_was_enabled,
+            scale: BandScale::default(),
+            smoothing: 0.5,
+            bands: vec![0.0; band_count],
+            peak: 0.0,
+            rms: 0.0,
+            released: false,
+        })
+    }
+
+    /// Sets the FFT window size, which must be a power of 2 between 128 and 32768.
+    ///
+    /// Larger windows give finer frequency resolution at the cost of responsiveness.
+    pub fn set_window_size(&self, window_size: c_int) -> Result<()> {
+        self.fft.set_parameter(fft::WindowSize, window_size)
+    }
+
+    /// Sets how FFT bins are grouped into bands.
+    pub fn set_band_scale(&mut self, scale: BandScale) {
+        self.scale = scale;
+    }
+
+    /// Sets the smoothing factor, from 0.0 (no smoothing) to 1.0 (frozen).
+    ///
+    /// Each frame, values move towards the new measurement by `1.0 - smoothing`.
+    pub fn set_smoothing(&mut self, smoothing: c_float) {
+        self.smoothing = smoothing.clamp(0.0, 1.0);
+    }
+
+    /// Retrieves the FFT [`Dsp`] used by this visualizer.
+    pub fn dsp(&self) -> Dsp {
+        self.fft
+    }
+
+    /// Reads the latest spectrum and metering data and returns the smoothed frame.
+    pub fn update(&mut self) -> Result<VisualizerFrame<'_>> {
+        let spectrum: Fft = self.fft.get_parameter(fft::SpectrumData)?;
+        let (_, output) = self.head.get_metering_info()?;
+
+        let channels = output.channel_count.max(0) as usize;
+        let peak = output.peak_level[..channels]
+            .iter()
+            .copied()
+            .fold(0.0, c_float::max);
+        let rms = output.rms_level[..channels]
+            .iter()
+            .copied()
+            .fold(0.0, c_float::max);
+
+        let factor = 1.0 - self.smoothing;
+        self.peak += (peak - self.peak) * factor;
+        self.rms += (rms - self.rms) * factor;
+
+        if spectrum.channels() > 0 {
+            // the second half of the spectrum mirrors the first
+            let bins = &spectrum.spectrum(0)[..spectrum.spectrum_size() / 2];
+            let band_count = self.bands.len();
+            for (index, band) in self.bands.iter_mut().enumerate() {
+                let (start, end) = band_range(self.scale, index, band_count, bins.len());
+                let bins = &bins[start..end];
+                let magnitude = if bins.is_empty() {
+                    0.0
+                } else {
+                    bins.iter().sum::<c_float>() / bins.len() as c_float
+                };
+                *band += (magnitude - *band) * factor;
+            }
+        }
+
+        Ok(VisualizerFrame {
+            bands: &self.bands,
+            peak: self.peak,
+            rms: self.rms,
+        })
+    }
+
+    /// Removes the FFT [`Dsp`] from the master [`ChannelGroup`], restores the previous metering state, and releases the [`Dsp`].
+    ///
+    /// Dropping the visualizer does the same, but ignores any errors.
+    pub fn release(mut self) -> Result<()> {
+        self.remove()
+    }
+
+    fn remove(&mut self) -> Result<()> {
+        // only tried once, so a failed release isn't repeated when the visualizer drops
+        if std::mem::replace(&mut self.released, true) {
+            return Ok(());
+        }
+        let (input, output) = self.metering_was_enabled;
+        self.master.remove_dsp(self.fft)?;
+        self.head.set_metering_enabled(input, output)?;
+        self.fft.release()
+    }
+}
+
+impl Drop for Visualizer {
+    fn drop(&mut self) {
+        let _ = self.remove();
+    }
+}
+
+fn band_range(
+    scale: BandScale,
+    index: usize,
+    band_count: usize,
+    bin_count: usize,
+) -> (usize, usize) {
+    let edge = |index: usize| match scale {
+        BandScale::Linear => index * bin_count / band_count,
+        BandScale::Logarithmic => {
+            let fraction = index as f64 / band_count as f64;
+            ((bin_count as f64).powf(fraction) - 1.0).round() as usize
+        }
+    };
+    let start = edge(index).min(bin_count);
+    // always cover at least one bin so low bands don't end up empty
+    let end = edge(index + 1).max(start + 1).min(bin_count);
+    (start, end)
+}
@@ -0,0 +1,167 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::{c_int, c_uint};
+
+use crate::{Error, Mode, Result, Sound, SoundBuilder, SoundFormat, System};
+
+/// How much audio each frame delivered by a [`VoiceCapturer`] holds.
+///
+/// Voice codecs such as Opus encode fixed size frames, usually 10ms or 20ms long.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum VoiceFrameDuration {
+    /// 10ms frames.
+    Ms10,
+    /// 20ms frames.
+    #[default]
+    Ms20,
+}
+
+impl VoiceFrameDuration {
+    /// Number of samples in a frame at `sample_rate` Hz.
+    pub fn samples(self, sample_rate: c_int) -> usize {
+        let per_second = match self {
+            VoiceFrameDuration::Ms10 => 100,
+            VoiceFrameDuration::Ms20 => 50,
+        };
+        (sample_rate / per_second).max(1) as usize
+    }
+}
+
+/// Records voice chat audio from a recording driver, ready to be encoded and sent over the network.
+///
+/// Audio is recorded as mono PCM16 at the driver's native rate,
+/// resampled to the requested rate, and cut into fixed size frames.
+/// Call [`VoiceCapturer::update`] once per frame, after [`System::update`], to collect the frames recorded since the last call.
+///
+/// Recording stops and the record buffer is released when the capturer is dropped.
+#[derive(Debug)]
+pub struct VoiceCapturer {
+    system: System,
+    driver: c_int,
+    sound: Sound,
+    buffer_length: c_uint,
+    read_position: c_uint,
+    step: f64,
+    phase: f64,
+    previous: i16,
+    frame_length: usize,
+    pending: Vec<i16>,
+}
+
+impl VoiceCapturer {
+    /// Starts recording from the recording driver `driver`, delivering `sample_rate` Hz frames of `duration`.
+    ///
+    /// See [`System::get_recording_driver_count`] for enumerating drivers.
+    pub fn new(
+        system: System,
+        driver: c_int,
+        sample_rate: c_int,
+        duration: VoiceFrameDuration,
+    ) -> Result<Self> {
+        if sample_rate <= 0 {
+            return Err(Error::InvalidParam);
+        }
+        // record at the native rate; FMOD would otherwise add its own resampler, and latency with it
+        let (_, _, native_rate, _, _, _) = system.get_record_driver_info(driver)?;
+        let native_rate = native_rate.max(1);
+
+        // one second of looping buffer leaves plenty of slack between updates
+        let buffer_length = native_rate as c_uint;
+        let builder = SoundBuilder::open_user(
+            buffer_length * size_of::<i16>() as c_uint,
+            1,
+            native_rate,
+            SoundFormat::PCM16,
+        )
+        .with_mode(Mode::LOOP_NORMAL);
+        let sound = system.create_sound(&builder)?;
+        if let Err(e) = system.record_start(driver, sound, true) {
+            let _ = sound.release();
+            return Err(e);
+        }
+
+        let frame_length = duration.samples(sample_rate);
+        Ok(Self {
+            system,
+            driver,
+            sound,
+            buffer_length,
+            read_position: 0,
+            step: f64::from(native_rate) / f64::from(sample_rate),
+            phase: 0.0,
+            previous: 0,
+            frame_length,
+            pending: Vec::with_capacity(frame_length * 2),
+        })
+    }
+
+    /// The sound recorded into, which is used as a looping ring buffer.
+    pub fn sound(&self) -> Sound {
+        self.sound
+    }
+
+    /// Number of samples in each delivered frame.
+    pub fn frame_length(&self) -> usize {
+        self.frame_length
+    }
+
+    /// Reads audio recorded since the last call, and calls `on_frame` with each complete frame.
+    ///
+    /// Audio that doesn't fill a whole frame is kept for the next call.
+    pub fn update(&mut self, mut on_frame: impl FnMut(&[i16])) -> Result<()> {
+        let position = self.system.get_record_position(self.driver)?;
+        if position != self.read_position {
+            let count = (position + self.buffer_length - self.read_position) % self.buffer_length;
+            let sample_size = size_of::<i16>() as c_uint;
+            // SAFETY: the lock is dropped before the sound can be released
+            let lock = unsafe {
+                self.sound
+                    .lock(self.read_position * sample_size, count * sample_size)?
+            };
+            for bytes in [lock.data(), lock.extra().unwrap_or_default()] {
+                for sample in bytes.chunks_exact(2) {
+                    self.resample(i16::from_ne_bytes([sample[0], sample[1]]));
+                }
+            }
+            drop(lock);
+            self.read_position = position;
+        }
+
+        let mut start = 0;
+        while self.pending.len() - start >= self.frame_length {
+            on_frame(&self.pending[start..start + self.frame_length]);
+            start += self.frame_length;
+        }
+        self.pending.drain(..start);
+        Ok(())
+    }
+
+    /// Collects every complete frame recorded since the last call. See [`VoiceCapturer::update`].
+    pub fn frames(&mut self) -> Result<Vec<Vec<i16>>> {
+        let mut frames = Vec::new();
+        self.update(|frame| frames.push(frame.to_vec()))?;
+        Ok(frames)
+    }
+
+    // linear interpolation between recorded samples, which is plenty for speech
+    fn resample(&mut self, sample: i16) {
+        let (from, to) = (f64::from(self.previous), f64::from(sample));
+        while self.phase < 1.0 {
+            self.pending.push((from + (to - from) * self.phase) as i16);
+            self.phase += self.step;
+        }
+        self.phase -= 1.0;
+        self.previous = sample;
+    }
+}
+
+impl Drop for VoiceCapturer {
+    fn drop(&mut self) {
+        let _ = self.system.record_stop(self.driver);
+        let _ = self.sound.release();
+    }
+}
@@ -0,0 +1,10 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+mod receiver;
+pub use receiver::VoiceReceiver;
+mod capturer;
+pub use capturer::{VoiceCapturer, VoiceFrameDuration};
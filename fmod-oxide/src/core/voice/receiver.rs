@@ -0,0 +1,221 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::collections::VecDeque;
+use std::ffi::{c_int, c_uint};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use crate::{
+    Channel, ChannelGroup, Error, Mode, PcmCallback, Result, Sound, SoundBuilder, SoundFormat,
+    System, TimeUnit,
+};
+
+/// Buffered audio for a single [`VoiceReceiver`], as interleaved PCM16 samples.
+#[derive(Debug)]
+struct JitterBuffer {
+    samples: VecDeque<i16>,
+    target: usize,
+    max: usize,
+    buffering: bool,
+    last_frame: Vec<i16>,
+    underruns: u64,
+}
+
+impl JitterBuffer {
+    fn push(&mut self, pcm: &[i16]) {
+        self.samples.extend(pcm);
+        if self.samples.len() > self.max {
+            // drop the oldest audio to catch back up, keeping whole frames
+            let excess = (self.samples.len() - self.max).next_multiple_of(self.last_frame.len());
+            self.samples.drain(..excess.min(self.samples.len()));
+        }
+    }
+
+    fn read(&mut self, out: &mut [u8]) {
+        if self.buffering && self.samples.len() >= self.target {
+            self.buffering = false;
+        }
+
+        let channel_count = self.last_frame.len();
+        for (index, bytes) in out.chunks_exact_mut(2).enumerate() {
+            let last = &mut self.last_frame[index % channel_count];
+            let next = if self.buffering {
+                None
+            } else {
+                self.samples.pop_front()
+            };
+            match next {
+                Some(sample) => *last = sample,
+                None => {
+                    if !self.buffering {
+                        self.underruns += 1;
+                        self.buffering = true;
+                    }
+                    // fade the last frame out instead of cutting straight to silence, which would click
+                    *last = (i32::from(*last) * 15 / 16) as i16;
+                }
+            }
+            bytes.copy_from_slice(&last.to_ne_bytes());
+        }
+    }
+}
+
+fn lock(buffer: &Mutex<JitterBuffer>) -> MutexGuard<'_, JitterBuffer> {
+    // worst case a panic mid-push leaves a few samples missing, which is no worse than a dropped packet
+    buffer.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+struct ReadVoice;
+
+impl PcmCallback for ReadVoice {
+    fn read(sound: Sound, data: &mut [u8]) -> Result<()> {
+        let userdata = sound.get_userdata()?;
+        if userdata.is_null() {
+            // FMOD prefills the stream while it is being created, before the buffer is attached
+            data.fill(0);
+            return Ok(());
+        }
+        // SAFETY: the userdata is set to the buffer by `VoiceReceiver::with_latency`, which keeps it alive until the sound is released
+        let buffer = unsafe { &*userdata.cast::<Mutex<JitterBuffer>>() };
+        lock(buffer).read(data);
+        Ok(())
+    }
+
+    fn set_position(_: Sound, _: c_int, _: c_uint, _: TimeUnit) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Plays back voice chat audio received over the network.
+///
+/// Decoded PCM16 packets are handed over with [`VoiceReceiver::push`] as they arrive,
+/// and played through a user created stream that reads from a jitter buffer.
+/// Playback only starts once enough audio is buffered to ride out uneven packet arrival.
+/// If the buffer runs dry the last audio is faded out, and buffering starts over.
+///
+/// The stream is released when the receiver is dropped.
+#[derive(Debug)]
+pub struct VoiceReceiver {
+    sound: Sound,
+    sample_rate: c_int,
+    channel_count: c_int,
+    buffer: Arc<Mutex<JitterBuffer>>,
+}
+
+impl VoiceReceiver {
+    /// Buffered audio required before playback starts, used by [`VoiceReceiver::new`].
+    pub const DEFAULT_TARGET_LATENCY: Duration = Duration::from_millis(60);
+    /// Buffered audio above which the oldest audio is dropped, used by [`VoiceReceiver::new`].
+    pub const DEFAULT_MAX_LATENCY: Duration = Duration::from_millis(200);
+
+    /// Creates a receiver for audio at `sample_rate` Hz with `channel_count` interleaved channels.
+    pub fn new(system: System, sample_rate: c_int, channel_count: c_int) -> Result<Self> {
+        Self::with_latency(
+            system,
+            sample_rate,
+            channel_count,
+            Self::DEFAULT_TARGET_LATENCY,
+            Self::DEFAULT_MAX_LATENCY,
+        )
+    }
+
+    /// Like [`VoiceReceiver::new`], with a custom amount of buffering.
+    ///
+    /// `target` is how much audio to buffer before playback (re)starts,
+    /// and `max` how much audio can be buffered before the oldest audio is dropped.
+    pub fn with_latency(
+        system: System,
+        sample_rate: c_int,
+        channel_count: c_int,
+        target: Duration,
+        max: Duration,
+    ) -> Result<Self> {
+        if sample_rate <= 0 || channel_count <= 0 || target > max {
+            return Err(Error::InvalidParam);
+        }
+
+        let samples_per_second = sample_rate as usize * channel_count as usize;
+        let to_samples = |latency: Duration| {
+            let frames = latency.as_millis() as usize * sample_rate as usize / 1000;
+            frames * channel_count as usize
+        };
+        let buffer = Arc::new(Mutex::new(JitterBuffer {
+            samples: VecDeque::with_capacity(to_samples(max)),
+            target: to_samples(target),
+            max: to_samples(max).max(1),
+            buffering: true,
+            last_frame: vec![0; channel_count as usize],
+            underruns: 0,
+        }));
+
+        // the stream loops forever, so its length only needs to be longer than one decode buffer
+        let length = (samples_per_second * size_of::<i16>()) as c_uint;
+        let builder =
+            SoundBuilder::open_user(length, channel_count, sample_rate, SoundFormat::PCM16)
+                .with_mode(Mode::CREATE_STREAM | Mode::LOOP_NORMAL)
+                // read in 10ms blocks so buffered audio reaches the mixer quickly
+                .with_decode_buffer_size((sample_rate / 100).max(1) as c_uint)
+                .with_pcm_callback::<ReadVoice>();
+        let sound = system.create_sound(&builder)?;
+        if let Err(e) = sound.set_userdata(Arc::as_ptr(&buffer).cast_mut().cast()) {
+            sound.release()?;
+            return Err(e);
+        }
+
+        Ok(Self {
+            sound,
+            sample_rate,
+            channel_count,
+            buffer,
+        })
+    }
+
+    /// The stream voice audio is played through.
+    pub fn sound(&self) -> Sound {
+        self.sound
+    }
+
+    /// Starts playing the stream on `channel_group`, or the master channel group.
+    pub fn play(&self, channel_group: Option<ChannelGroup>) -> Result<Channel> {
+        self.sound
+            .get_system()?
+            .play_sound(self.sound, channel_group, false)
+    }
+
+    /// Queues decoded PCM16 audio for playback, interleaved if there is more than one channel.
+    pub fn push(&self, pcm: &[i16]) {
+        lock(&self.buffer).push(pcm);
+    }
+
+    /// Discards all buffered audio, such as when the remote player stops talking.
+    pub fn reset(&self) {
+        let mut buffer = lock(&self.buffer);
+        buffer.samples.clear();
+        buffer.buffering = true;
+    }
+
+    /// How much audio is buffered and waiting to be played.
+    pub fn buffered(&self) -> Duration {
+        let samples = lock(&self.buffer).samples.len() as u64;
+        let frames = samples / self.channel_count as u64;
+        Duration::from_millis(frames * 1000 / self.sample_rate as u64)
+    }
+
+    /// How many times the buffer has run dry during playback.
+    pub fn underruns(&self) -> u64 {
+        lock(&self.buffer).underruns
+    }
+}
+
+impl Drop for VoiceReceiver {
+    fn drop(&mut self) {
+        if self.sound.release().is_err() {
+            // the stream may still read through the userdata, so the buffer has to outlive the sound
+            std::mem::forget(Arc::clone(&self.buffer));
+        }
+    }
+}
@@ -0,0 +1,102 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::c_int;
+use std::io::Write;
+
+use crate::{Error, Result};
+
+pub(crate) fn io_error(error: &std::io::Error) -> Error {
+    match error.kind() {
+        std::io::ErrorKind::NotFound => Error::FileNotFound,
+        _ => Error::FileBad,
+    }
+}
+
+/// Writes interleaved 16 bit PCM `samples` as a canonical 44 byte header RIFF WAVE file.
+pub(crate) fn write_pcm16(
+    mut writer: impl Write,
+    sample_rate: c_int,
+    channels: c_int,
+    samples: &[i16],
+) -> Result<()> {
+    let (Ok(sample_rate), Ok(channels)) = (u32::try_from(sample_rate), u16::try_from(channels))
+    else {
+        return Err(Error::InvalidParam);
+    };
+    if sample_rate == 0 || channels == 0 {
+        return Err(Error::InvalidParam);
+    }
+    // WAV sizes are 32 bit, so anything that doesn't fit can't be written as a single file
+    let data_size = u32::try_from(samples.len() * size_of::<i16>())
+        .ok()
+        .filter(|size| size.checked_add(36).is_some())
+        .ok_or(Error::InvalidParam)?;
+    let block_align = channels
+        .checked_mul(size_of::<i16>() as u16)
+        .ok_or(Error::InvalidParam)?;
+    let byte_rate = sample_rate
+        .checked_mul(u32::from(block_align))
+        .ok_or(Error::InvalidParam)?;
+
+    let mut header = Vec::with_capacity(44);
+    header.extend_from_slice(b"RIFF");
+    header.extend_from_slice(&(36 + data_size).to_le_bytes());
+    header.extend_from_slice(b"WAVE");
+    header.extend_from_slice(b"fmt ");
+    header.extend_from_slice(&16u32.to_le_bytes());
+    header.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    header.extend_from_slice(&channels.to_le_bytes());
+    header.extend_from_slice(&sample_rate.to_le_bytes());
+    header.extend_from_slice(&byte_rate.to_le_bytes());
+    header.extend_from_slice(&block_align.to_le_bytes());
+    header.extend_from_slice(&16u16.to_le_bytes());
+    header.extend_from_slice(b"data");
+    header.extend_from_slice(&data_size.to_le_bytes());
+
+    let result = writer.write_all(&header).and_then(|()| {
+        let data: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+        writer.write_all(&data)?;
+        writer.flush()
+    });
+    result.map_err(|e| io_error(&e))
+}
+
+/// Converts interleaved samples from `(sample_rate, channels)` to another format.
+///
+/// Uses linear interpolation between frames. Mixing down to mono averages the channels, otherwise channels are taken in order, repeating them if there are fewer.
+pub(crate) fn convert_pcm16(samples: &[i16], from: (c_int, c_int), to: (c_int, c_int)) -> Vec<i16> {
+    let (from_rate, from_channels) = (from.0 as usize, from.1 as usize);
+    let (to_rate, to_channels) = (to.0 as usize, to.1 as usize);
+    let frames: Vec<&[i16]> = samples.chunks_exact(from_channels).collect();
+    let Some(&last) = frames.last() else {
+        return Vec::new();
+    };
+
+    let channel = |frame: &[i16], index: usize| -> f64 {
+        if to_channels == 1 {
+            frame.iter().map(|&s| f64::from(s)).sum::<f64>() / from_channels as f64
+        } else {
+            f64::from(frame[index % from_channels])
+        }
+    };
+
+    let out_frames = frames.len() * to_rate / from_rate;
+    let step = from_rate as f64 / to_rate as f64;
+    let mut out = Vec::with_capacity(out_frames * to_channels);
+    for i in 0..out_frames {
+        let position = i as f64 * step;
+        let index = position as usize;
+        let fraction = position - index as f64;
+        let a = frames.get(index).copied().unwrap_or(last);
+        let b = frames.get(index + 1).copied().unwrap_or(last);
+        for c in 0..to_channels {
+            let (a, b) = (channel(a, c), channel(b, c));
+            out.push((a + (b - a) * fraction) as i16);
+        }
+    }
+    out
+}
@@ -0,0 +1,92 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::{Channel, Error};
+
+#[cfg(feature = "studio")]
+use crate::studio;
+
+/// A handle type that can check whether the object it refers to is still alive.
+///
+/// Only implemented for handles FMOD validates itself: Studio handles, checked with FMOD's `IsValid` functions,
+/// and [`Channel`], since FMOD tracks a generation count inside every channel handle.
+/// Other core handles are plain pointers, so calling anything on one that was released reads freed memory,
+/// and there is no safe way to check them.
+pub trait Validity: Copy {
+    /// Whether the handle still refers to a live object.
+    fn is_valid(&self) -> bool;
+}
+
+#[cfg(feature = "studio")]
+macro_rules! studio_validity {
+    ($($ty:ident),* $(,)?) => {
+        $(
+            impl Validity for studio::$ty {
+                fn is_valid(&self) -> bool {
+                    studio::$ty::is_valid(self)
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "studio")]
+studio_validity!(
+    System,
+    Bank,
+    Bus,
+    Vca,
+    EventDescription,
+    EventInstance,
+    CommandReplay,
+);
+
+// FMOD tracks a generation count inside every channel handle,
+// so a handle to a channel that has finished or been stolen reports an error instead of touching a reused channel.
+impl Validity for Channel {
+    fn is_valid(&self) -> bool {
+        !matches!(
+            self.is_playing(),
+            Err(Error::InvalidHandle | Error::ChannelStolen)
+        )
+    }
+}
+
+/// A handle that may outlive the object it refers to.
+///
+/// FMOD handles are plain [`Copy`] pointers, so a handle held by a long-lived game object
+/// can go stale when the object is released elsewhere (an event instance finishing, a channel being stolen, a bank unloading).
+/// Holding a [`WeakHandle`] instead makes the staleness check explicit: get the handle back with [`WeakHandle::upgrade`] before each use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WeakHandle<T> {
+    handle: T,
+}
+
+impl<T: Validity> WeakHandle<T> {
+    /// Wraps `handle`.
+    pub fn new(handle: T) -> Self {
+        Self { handle }
+    }
+
+    /// Whether the object is still alive.
+    pub fn is_valid(&self) -> bool {
+        self.handle.is_valid()
+    }
+
+    /// Returns the handle if the object is still alive.
+    ///
+    /// The handle is only checked at the time of the call,
+    /// so it should be used right away rather than stored.
+    pub fn upgrade(&self) -> Option<T> {
+        self.is_valid().then_some(self.handle)
+    }
+}
+
+impl<T: Validity> From<T> for WeakHandle<T> {
+    fn from(handle: T) -> Self {
+        Self::new(handle)
+    }
+}
@@ -51,6 +51,7 @@
 //!
 //! Currently only `wasm32-unknown-emscripten` works well.
 //! `wasm32-unknown-unknown` also works in some capacity but you have to essentially reimplement parts of libc and emscripten.
+//! The `wasm-stubs` feature provides the libc parts (see the `fmod-wasm-stubs` crate), the emscripten runtime functions are still up to you.
 //!
 //! Unfortunately `wasm-bindgen` doesn't work without patches right now, so your milage may vary
 //!
@@ -156,8 +157,7 @@ pub mod coverage {
 }
 
 mod result;
-pub(crate) use result::FmodResultExt;
-pub use result::{Error, Result};
+pub use result::{Error, ErrorCategory, FmodResultExt, FmodResultLogExt, Result};
 
 // Not really practical to go no_std.
 // FMOD requires libc on pretty much every platform (even webassembly!)
@@ -171,11 +171,17 @@ pub use core::*;
 
 #[doc(no_inline)]
 pub use core::effects::*;
+#[cfg(feature = "derive")]
+pub use fmod_oxide_derive::DspParameters;
 #[doc(no_inline)]
 pub use fmod_sys as sys;
 #[doc(no_inline)]
 pub use lanyard::*;
 
+// nothing is used from it directly, but it has to be linked for its symbols to be exported
+#[cfg(feature = "wasm-stubs")]
+use fmod_wasm_stubs as _;
+
 #[cfg(test)]
 mod tests;
 
@@ -185,6 +191,10 @@ mod tests;
 #[cfg(feature = "studio")]
 pub mod studio;
 
+/// Helpers for the emscripten (HTML5) build of FMOD.
+#[cfg(target_os = "emscripten")]
+pub mod wasm;
+
 /// Current FMOD version number.
 ///
 /// The version is a 32 bit hexadecimal value formatted as 16:8:8, with the upper 16 bits being the product version,
@@ -198,6 +198,150 @@ impl std::fmt::Display for Error {
 
 impl std::error::Error for Error {}
 
+/// A broad grouping of [`Error`]s, for deciding how to handle an error without matching every variant.
+///
+/// See [`Error::category`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ErrorCategory {
+    /// The call may succeed if tried again later, such as [`Error::NotReady`].
+    Transient,
+    /// The handle no longer refers to a live object, such as [`Error::InvalidHandle`] or [`Error::ChannelStolen`].
+    InvalidHandle,
+    /// A file, event, plugin or other named resource does not exist.
+    NotFound,
+    /// The call was made with bad arguments, in the wrong state, or on an object that doesn't support it.
+    Usage,
+    /// A limit was hit, such as running out of memory or channels.
+    Resource,
+    /// Reading a file or network stream failed.
+    Io,
+    /// The output or recording device failed.
+    Device,
+    /// Mismatched library versions or an internal FMOD error. Retrying won't help.
+    Fatal,
+    /// Not really an error, but a signal from a DSP callback, such as [`Error::DspSilence`].
+    Signal,
+    /// FMOD returned a value this crate doesn't know about.
+    Conversion,
+}
+
+impl Error {
+    /// The [`ErrorCategory`] this error belongs to.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Error::NotReady
+            | Error::Truncated
+            | Error::NetWouldBlock
+            | Error::EventLiveUpdateBusy => ErrorCategory::Transient,
+
+            Error::InvalidHandle | Error::ChannelStolen => ErrorCategory::InvalidHandle,
+
+            Error::FileNotFound
+            | Error::EventNotFound
+            | Error::TagNotFound
+            | Error::DspNotFound
+            | Error::PluginMissing
+            | Error::StudioNotLoaded => ErrorCategory::NotFound,
+
+            Error::BadCommand
+            | Error::DspConnection
+            | Error::DspFormat
+            | Error::DspInuse
+            | Error::DspReserved
+            | Error::DspType
+            | Error::Initialization
+            | Error::Initialized
+            | Error::InvalidFloat
+            | Error::InvalidParam
+            | Error::InvalidPosition
+            | Error::InvalidSpeaker
+            | Error::InvalidSyncPoint
+            | Error::InvalidThread
+            | Error::InvalidVector
+            | Error::InvalidString
+            | Error::MemoryCantPoint
+            | Error::Needs3D
+            | Error::NeedsHardWare
+            | Error::ReverbChannelGroup
+            | Error::ReverbInstance
+            | Error::Subsounds
+            | Error::SubsoundAllocated
+            | Error::SubsoundCantMove
+            | Error::Unimplemented
+            | Error::Uninitialized
+            | Error::Unsupported
+            | Error::EventAlreadyLoaded
+            | Error::StudioUninitialized
+            | Error::AlreadyLocked
+            | Error::NotLocked => ErrorCategory::Usage,
+
+            Error::ChannelAlloc
+            | Error::MaxAudible
+            | Error::Memory
+            | Error::PluginResource
+            | Error::TooManyChannels
+            | Error::TooManySamples => ErrorCategory::Resource,
+
+            Error::FileBad
+            | Error::FileCouldNotSeek
+            | Error::FileDiskEjected
+            | Error::FileEof
+            | Error::FileEndOfData
+            | Error::Format
+            | Error::Http
+            | Error::HttpAccess
+            | Error::HttpProxyAuth
+            | Error::HttpServerError
+            | Error::HttpTimeout
+            | Error::NetConnect
+            | Error::NetSocketError
+            | Error::NetUrl => ErrorCategory::Io,
+
+            Error::DMA
+            | Error::OutputAllocated
+            | Error::OutputCreateBuffer
+            | Error::OuputDriverCall
+            | Error::OutputFormat
+            | Error::OutputInit
+            | Error::OutputNoDrivers
+            | Error::Record
+            | Error::RecordDisconnected => ErrorCategory::Device,
+
+            Error::HeaderMismatch
+            | Error::Internal
+            | Error::Plugin
+            | Error::PluginVersion
+            | Error::Version
+            | Error::EventLiveUpdateMismatch
+            | Error::EventLiveUpdateTimeout => ErrorCategory::Fatal,
+
+            Error::DspDontProcess | Error::DspSilence => ErrorCategory::Signal,
+
+            Error::EnumFromPrivitive { .. } => ErrorCategory::Conversion,
+        }
+    }
+
+    /// Whether retrying can't help, because of mismatched library versions or an internal FMOD error.
+    pub fn is_fatal(&self) -> bool {
+        self.category() == ErrorCategory::Fatal
+    }
+
+    /// Whether the call may succeed if tried again later.
+    pub fn is_transient(&self) -> bool {
+        self.category() == ErrorCategory::Transient
+    }
+
+    /// Whether a file, event, plugin or other named resource does not exist.
+    pub fn is_not_found(&self) -> bool {
+        self.category() == ErrorCategory::NotFound
+    }
+
+    /// Whether the handle no longer refers to a live object.
+    pub fn is_invalid_handle(&self) -> bool {
+        self.category() == ErrorCategory::InvalidHandle
+    }
+}
+
 /// Shorthand for [`std::result::Result<T, Error>`]
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -394,11 +538,65 @@ impl From<Error> for FMOD_RESULT {
     }
 }
 
-pub(crate) trait FmodResultExt {
+/// Extension trait for handling errors that shouldn't stop the rest of the game, by logging them.
+///
+/// Audio failing to play is rarely a reason to crash, so a common policy is to log the error and carry on:
+///
+/// ```ignore
+/// use fmod::FmodResultLogExt;
+///
+/// let instance = description.create_instance().ok_or_log("creating footstep event");
+/// ```
+///
+/// With the `log` feature enabled errors are logged with `log::warn!`, otherwise they are printed to stderr.
+pub trait FmodResultLogExt<T> {
+    /// Converts this result into an [`Option`], logging the error along with `context` if there was one.
+    fn ok_or_log(self, context: &str) -> Option<T>;
+
+    /// Logs the error along with `context` if there was one, and discards the result.
+    fn log_err(self, context: &str);
+}
+
+impl<T> FmodResultLogExt<T> for Result<T> {
+    fn ok_or_log(self, context: &str) -> Option<T> {
+        match self {
+            Ok(value) => Some(value),
+            Err(error) => {
+                log_error(context, error);
+                None
+            }
+        }
+    }
+
+    fn log_err(self, context: &str) {
+        let _ = self.ok_or_log(context);
+    }
+}
+
+#[cfg(feature = "log")]
+fn log_error(context: &str, error: Error) {
+    log::warn!(target: "fmod", "{context}: {error}");
+}
+
+#[cfg(not(feature = "log"))]
+fn log_error(context: &str, error: Error) {
+    eprintln!("warning: {context}: {error}");
+}
+
+/// Conversions between [`FMOD_RESULT`] and [`Result`], for code that calls FMOD through `fmod-sys` directly,
+/// such as plugins and callbacks.
+///
+/// The error codes map one to one with [`Error`] through its [`From`] impls, so no information is lost either way.
+pub trait FmodResultExt {
+    /// Converts `FMOD_OK` into `Ok(())`, and anything else into the matching [`Error`].
     fn to_result(self) -> Result<()>;
 
+    /// Converts `FMOD_OK` into [`None`], and anything else into the matching [`Error`].
     fn to_error(self) -> Option<Error>;
 
+    /// Converts `Ok` into `FMOD_OK`, and `Err` into the matching error code, discarding the value.
+    ///
+    /// This is what callbacks return to FMOD.
     fn from_result<T>(result: Result<T>) -> Self;
 }
 
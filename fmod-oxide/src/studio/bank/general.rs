@@ -10,7 +10,7 @@ use std::ffi::c_void;
 use std::mem::MaybeUninit;
 
 use crate::Guid;
-use crate::studio::{Bank, get_string_out_size};
+use crate::studio::{Bank, get_string_out_size, get_string_out_size_bounded};
 use crate::{FmodResultExt, Result};
 
 impl Bank {
@@ -33,6 +33,15 @@ impl Bank {
         })
     }
 
+    /// Retrieves the path, truncated to at most `max_len` bytes.
+    ///
+    /// Unlike [`Self::get_path`] this never allocates more than `max_len + 1` bytes, and truncation is not an error.
+    pub fn get_path_with_max_len(&self, max_len: usize) -> Result<Utf8CString> {
+        get_string_out_size_bounded(max_len, |path, size, ret| unsafe {
+            FMOD_Studio_Bank_GetPath(self.inner.as_ptr(), path, size, ret)
+        })
+    }
+
     /// Checks that the Bank reference is valid.
     pub fn is_valid(&self) -> bool {
         unsafe { FMOD_Studio_Bank_IsValid(self.inner.as_ptr()).into() }
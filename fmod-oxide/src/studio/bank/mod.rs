@@ -11,6 +11,8 @@ use fmod_sys::*;
 mod general;
 mod loading;
 mod lookups; // general lookups that are too small to be their own module
+mod unload;
+pub use unload::{BankUnloadError, LiveInstancePolicy};
 
 /// Banks made in FMOD Studio contain the metadata and audio sample data required for runtime mixing and playback.
 ///
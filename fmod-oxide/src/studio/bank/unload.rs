@@ -0,0 +1,91 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::Error;
+use crate::studio::{Bank, EventInstance, StopMode};
+
+/// What [`Bank::unload_checked`] does with event instances that are still alive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiveInstancePolicy {
+    /// Don't unload the bank, and return [`BankUnloadError::LiveInstances`].
+    Refuse,
+    /// Stop the instances with the given [`StopMode`] and release them, then unload the bank.
+    StopAndRelease(StopMode),
+}
+
+/// An error returned by [`Bank::unload_checked`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BankUnloadError {
+    /// The bank still had live event instances, and was not unloaded.
+    LiveInstances(Vec<EventInstance>),
+    /// An FMOD error occurred.
+    Fmod(Error),
+}
+
+impl std::fmt::Display for BankUnloadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BankUnloadError::LiveInstances(instances) => write!(
+                f,
+                "bank still has {} live event instance(s)",
+                instances.len()
+            ),
+            BankUnloadError::Fmod(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for BankUnloadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BankUnloadError::LiveInstances(_) => None,
+            BankUnloadError::Fmod(error) => Some(error),
+        }
+    }
+}
+
+impl From<Error> for BankUnloadError {
+    fn from(value: Error) -> Self {
+        BankUnloadError::Fmod(value)
+    }
+}
+
+impl Bank {
+    /// Retrieves every event instance created from the events in this bank.
+    pub fn get_live_instances(&self) -> crate::Result<Vec<EventInstance>> {
+        let mut instances = Vec::new();
+        for description in self.get_event_list()? {
+            instances.extend(description.get_instance_list()?);
+        }
+        Ok(instances)
+    }
+
+    /// Unloads the bank, first checking for event instances created from its events.
+    ///
+    /// Unloading a bank invalidates every instance created from it, and handles to those instances are a common source of invalid handle errors.
+    /// With [`LiveInstancePolicy::Refuse`] the bank is left loaded and the offending instances are returned,
+    /// while [`LiveInstancePolicy::StopAndRelease`] stops and releases them before unloading.
+    ///
+    /// Only instances of events that belong to this bank are checked.
+    pub fn unload_checked(self, policy: LiveInstancePolicy) -> Result<(), BankUnloadError> {
+        let instances = self.get_live_instances()?;
+        if !instances.is_empty() {
+            match policy {
+                LiveInstancePolicy::Refuse => {
+                    return Err(BankUnloadError::LiveInstances(instances));
+                }
+                LiveInstancePolicy::StopAndRelease(mode) => {
+                    for instance in instances {
+                        instance.stop(mode)?;
+                        instance.release()?;
+                    }
+                }
+            }
+        }
+        self.unload()?;
+        Ok(())
+    }
+}
@@ -8,10 +8,13 @@ use crate::{FmodResultExt, Result};
 use fmod_sys::*;
 use lanyard::Utf8CStr;
 use std::ffi::{c_char, c_float, c_int, c_uint, c_void};
+use std::marker::PhantomData;
 
 use crate::{
     Guid, panic_wrapper,
-    studio::{Bank, CommandReplay, EventDescription, EventInstance, LoadBankFlags},
+    studio::{
+        Bank, CommandReplay, EventDescription, EventInstance, InstanceFactory, LoadBankFlags,
+    },
 };
 
 /// Trait for this particular FMOD callback.
@@ -49,6 +52,19 @@ unsafe extern "C" fn create_instance_impl<C: CreateInstanceCallback>(
     })
 }
 
+struct CreateWithFactory<F>(PhantomData<F>);
+
+impl<F: InstanceFactory> CreateInstanceCallback for CreateWithFactory<F> {
+    fn create_instance_callback(
+        _: CommandReplay,
+        _: c_int,
+        description: EventDescription,
+        _: *mut c_void,
+    ) -> Result<Option<EventInstance>> {
+        F::create_instance(description)
+    }
+}
+
 /// Trait for this particular FMOD callback.
 ///
 /// No `self` parameter is passed to the callback!
@@ -161,6 +177,13 @@ impl CommandReplay {
         }
     }
 
+    /// Routes instance creation during the replay through `F`.
+    ///
+    /// This is a typed wrapper around [`CommandReplay::set_create_instance_callback`], and replaces any callback set with it.
+    pub fn set_instance_factory<F: InstanceFactory>(&self) -> Result<()> {
+        self.set_create_instance_callback::<CreateWithFactory<F>>()
+    }
+
     /// Sets a callback that is issued each time the replay reaches a new frame.
     pub fn set_frame_callback<C: FrameCallback>(&self) -> Result<()> {
         unsafe {
@@ -12,7 +12,11 @@ mod callback;
 mod general;
 mod playback;
 mod query;
+mod resolver;
+mod run;
 pub use callback::{CreateInstanceCallback, FrameCallback, LoadBankCallback};
+pub use resolver::{BankResolver, BankSource};
+pub use run::{ReplayFrame, ReplayRun, ReplaySpeed};
 
 /// The FMOD Studio command replay system allows API calls in a session to be recorded and later played back for debugging and performance purposes.
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
@@ -0,0 +1,74 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::{c_int, c_void};
+use std::marker::PhantomData;
+
+use lanyard::{Utf8CStr, Utf8CString};
+
+use crate::Guid;
+use crate::Result;
+use crate::studio::{Bank, CommandReplay, LoadBankCallback, LoadBankFlags};
+
+/// Where a [`BankResolver`] found a bank referenced by a replay.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BankSource {
+    /// Load the bank from this local file.
+    File(Utf8CString),
+    /// Load the bank from this data. The data is copied by FMOD.
+    Memory(Vec<u8>),
+    /// Use a bank that has already been loaded.
+    Loaded(Bank),
+    /// Don't load anything. Commands referencing objects in the bank will fail.
+    Skip,
+}
+
+/// Maps the banks recorded in a [`CommandReplay`] to local content.
+///
+/// This lets replays captured on another machine (or from banks loaded with `load_bank_memory` / `load_bank_custom`) be played back.
+/// Install with [`CommandReplay::set_bank_resolver`].
+///
+/// No `self` parameter is passed to the resolver!
+pub trait BankResolver {
+    /// Resolves a recorded bank load.
+    ///
+    /// `filename` is the recorded path for banks loaded from file, and `guid` the bank's GUID when it was recorded.
+    fn resolve(
+        replay: CommandReplay,
+        guid: Option<Guid>,
+        filename: Option<&Utf8CStr>,
+    ) -> Result<BankSource>;
+}
+
+struct ResolveBank<R>(PhantomData<R>);
+
+impl<R: BankResolver> LoadBankCallback for ResolveBank<R> {
+    fn load_bank_callback(
+        replay: CommandReplay,
+        _: c_int,
+        guid: Option<Guid>,
+        filename: Option<&Utf8CStr>,
+        flags: LoadBankFlags,
+        _: *mut c_void,
+    ) -> Result<Option<Bank>> {
+        let system = replay.get_system()?;
+        match R::resolve(replay, guid, filename)? {
+            BankSource::File(path) => system.load_bank_file(&path, flags).map(Some),
+            BankSource::Memory(data) => system.load_bank_memory(&data, flags).map(Some),
+            BankSource::Loaded(bank) => Ok(Some(bank)),
+            BankSource::Skip => Ok(None),
+        }
+    }
+}
+
+impl CommandReplay {
+    /// Routes every recorded bank load through `R`.
+    ///
+    /// This is a typed wrapper around [`CommandReplay::set_load_bank_callback`], and replaces any callback set with it.
+    pub fn set_bank_resolver<R: BankResolver>(&self) -> Result<()> {
+        self.set_load_bank_callback::<ResolveBank<R>>()
+    }
+}
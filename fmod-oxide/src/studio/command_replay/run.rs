@@ -0,0 +1,204 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::collections::VecDeque;
+use std::ffi::{c_float, c_int, c_void};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use fmod_sys::*;
+
+use crate::studio::{CommandReplay, FrameCallback, PlaybackState, System};
+use crate::{FmodResultExt, Result};
+
+/// How fast [`CommandReplay::run_to_completion`] pumps the Studio system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ReplaySpeed {
+    /// Update at the default Studio update period (20ms), like a game would.
+    #[default]
+    RealTime,
+    /// Update in a tight loop without sleeping, for running replays in tests.
+    Unthrottled,
+}
+
+impl ReplaySpeed {
+    fn update_period(self) -> Option<Duration> {
+        match self {
+            ReplaySpeed::RealTime => Some(Duration::from_millis(20)),
+            ReplaySpeed::Unthrottled => None,
+        }
+    }
+}
+
+/// A frame reached during a [`ReplayRun`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReplayFrame {
+    /// Index of the current command.
+    pub command_index: c_int,
+    /// Playback time of the current command, in seconds.
+    pub current_time: c_float,
+}
+
+struct Frames {
+    replay: usize,
+    frames: VecDeque<ReplayFrame>,
+}
+
+static FRAMES: Mutex<Vec<Frames>> = Mutex::new(Vec::new());
+
+fn frames() -> MutexGuard<'static, Vec<Frames>> {
+    // the lists are only ever pushed to or popped from, so a panic can't leave one inconsistent
+    FRAMES.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+struct FrameRecorder;
+
+impl FrameCallback for FrameRecorder {
+    fn frame_callback(
+        replay: CommandReplay,
+        command_index: c_int,
+        current_time: c_float,
+        _: *mut c_void,
+    ) -> Result<()> {
+        let replay = replay.inner.as_ptr() as usize;
+        if let Some(entry) = frames().iter_mut().find(|f| f.replay == replay) {
+            entry.frames.push_back(ReplayFrame {
+                command_index,
+                current_time,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// A running [`CommandReplay`], yielding each frame as it is reached.
+///
+/// Create with [`CommandReplay::run_to_completion`].
+/// Iterating pumps [`System::update`] until the replay stops or is paused.
+/// Dropping the run removes its frame callback, but leaves the replay playing.
+#[derive(Debug)]
+pub struct ReplayRun {
+    replay: CommandReplay,
+    system: System,
+    speed: ReplaySpeed,
+    done: bool,
+}
+
+impl CommandReplay {
+    /// Plays this replay from the start, pumping `system` until it finishes or is paused.
+    ///
+    /// Frames are yielded from the returned [`ReplayRun`] as they are reached,
+    /// which makes captured sessions easy to script as regression tests:
+    ///
+    /// ```ignore
+    /// for frame in replay.run_to_completion(system, ReplaySpeed::Unthrottled)? {
+    ///     let frame = frame?;
+    ///     // check system state at frame.command_index
+    /// }
+    /// ```
+    ///
+    /// This replaces any callback set with [`CommandReplay::set_frame_callback`].
+    pub fn run_to_completion(&self, system: System, speed: ReplaySpeed) -> Result<ReplayRun> {
+        self.run_from_command(system, speed, 0)
+    }
+
+    /// Like [`CommandReplay::run_to_completion`], but seeks to `command_index` after starting.
+    pub fn run_from_command(
+        &self,
+        system: System,
+        speed: ReplaySpeed,
+        command_index: c_int,
+    ) -> Result<ReplayRun> {
+        let replay = self.inner.as_ptr() as usize;
+        {
+            let mut frames = frames();
+            frames.retain(|f| f.replay != replay);
+            frames.push(Frames {
+                replay,
+                frames: VecDeque::new(),
+            });
+        }
+        // construct the run first so its Drop cleans up if starting fails
+        let run = ReplayRun {
+            replay: *self,
+            system,
+            speed,
+            done: false,
+        };
+
+        self.set_frame_callback::<FrameRecorder>()?;
+        self.start()?;
+        if command_index > 0 {
+            self.seek_to_command(command_index)?;
+        }
+        Ok(run)
+    }
+}
+
+impl ReplayRun {
+    /// The replay being run.
+    pub fn replay(&self) -> CommandReplay {
+        self.replay
+    }
+
+    fn pop_frame(&self) -> Option<ReplayFrame> {
+        let replay = self.replay.inner.as_ptr() as usize;
+        frames()
+            .iter_mut()
+            .find(|f| f.replay == replay)?
+            .frames
+            .pop_front()
+    }
+
+    fn is_finished(&self) -> Result<bool> {
+        Ok(self.replay.get_playback_state()? == PlaybackState::Stopped
+            || self.replay.get_paused()?)
+    }
+}
+
+impl Iterator for ReplayRun {
+    type Item = Result<ReplayFrame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(frame) = self.pop_frame() {
+                return Some(Ok(frame));
+            }
+            if self.done {
+                return None;
+            }
+
+            match self.is_finished() {
+                // one more pass to drain frames reached during the last update
+                Ok(true) => self.done = true,
+                Ok(false) => {
+                    if let Err(e) = self.system.update() {
+                        self.done = true;
+                        return Some(Err(e));
+                    }
+                    if let Some(period) = self.speed.update_period() {
+                        std::thread::sleep(period);
+                    }
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+impl Drop for ReplayRun {
+    fn drop(&mut self) {
+        let replay = self.replay.inner.as_ptr() as usize;
+        frames().retain(|f| f.replay != replay);
+        // the replay may already have been released, in which case there's nothing to unset
+        let _ = unsafe {
+            FMOD_Studio_CommandReplay_SetFrameCallback(self.replay.inner.as_ptr(), None).to_result()
+        };
+    }
+}
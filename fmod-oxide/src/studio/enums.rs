@@ -132,6 +132,18 @@ pub enum PlaybackState {
     Stopping = FMOD_STUDIO_PLAYBACK_STOPPING,
 }
 
+impl std::fmt::Display for PlaybackState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            PlaybackState::Playing => "playing",
+            PlaybackState::Sustaining => "sustaining",
+            PlaybackState::Stopped => "stopped",
+            PlaybackState::Starting => "starting",
+            PlaybackState::Stopping => "stopping",
+        })
+    }
+}
+
 /// These definitions describe built-in event properties.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[derive(
@@ -12,7 +12,7 @@ use crate::studio::EventDescription;
 
 #[cfg(doc)]
 use crate::studio::EventInstance;
-use crate::{FmodResultExt, Result};
+use crate::{AttenuationPoint, FmodResultExt, Result, Rolloff};
 
 impl EventDescription {
     /// Retrieves the event's 3D status.
@@ -126,4 +126,24 @@ impl EventDescription {
         }
         Ok(size)
     }
+
+    /// Samples the event's distance attenuation at `samples` evenly spaced distances from 0 to its maximum distance.
+    ///
+    /// Studio doesn't expose which curve an event's spatializer uses, so this assumes the default [`Rolloff::Inverse`] curve.
+    /// Use [`EventDescription::attenuation_preview_with`] for events authored with a different curve.
+    pub fn attenuation_preview(&self, samples: usize) -> Result<Vec<AttenuationPoint>> {
+        self.attenuation_preview_with(Rolloff::Inverse, samples)
+    }
+
+    /// Like [`EventDescription::attenuation_preview`], using the `rolloff` curve.
+    ///
+    /// The points are computed with [`Rolloff::preview`], the same math used for core sounds.
+    pub fn attenuation_preview_with(
+        &self,
+        rolloff: Rolloff,
+        samples: usize,
+    ) -> Result<Vec<AttenuationPoint>> {
+        let (min, max) = self.get_min_max_distance()?;
+        Ok(rolloff.preview(min, max, samples))
+    }
 }
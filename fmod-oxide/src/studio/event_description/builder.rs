@@ -0,0 +1,190 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use fmod_sys::*;
+use lanyard::Utf8CStr;
+use std::ffi::{c_float, c_int, c_void};
+
+use crate::studio::{
+    EventCallbackMask, EventDescription, EventInstance, EventInstanceCallback, ParameterID,
+    event_callback_impl,
+};
+use crate::{Attributes3D, FmodResultExt, Result};
+
+#[derive(Debug, Clone, Copy)]
+enum Parameter<'a> {
+    Name(&'a Utf8CStr),
+    Id(ParameterID),
+}
+
+/// Creates an [`EventInstance`] with its initial state set up in one go.
+///
+/// Create with [`EventDescription::create_instance_with`].
+///
+/// Settings are applied in the order FMOD needs them, regardless of the order the builder methods are called in:
+/// 1. The callback and user data, so no callback fired by the rest of the setup is missed.
+/// 2. Parameters, which are set instantly as the instance hasn't started (seek speed doesn't apply).
+/// 3. 3D attributes, so the first mix is already spatialized at the right position.
+/// 4. Volume and pitch.
+/// 5. Timeline position.
+/// 6. [`EventInstance::start`], then [`EventInstance::release`], if requested.
+///
+/// If any step fails, the instance is released and the error returned.
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct EventInstanceBuilder<'a> {
+    description: EventDescription,
+    callback: Option<(FMOD_STUDIO_EVENT_CALLBACK, EventCallbackMask)>,
+    userdata: *mut c_void,
+    parameters: Vec<(Parameter<'a>, c_float)>,
+    attributes: Option<Attributes3D>,
+    volume: Option<c_float>,
+    pitch: Option<c_float>,
+    timeline_position: Option<c_int>,
+    start: bool,
+    release: bool,
+}
+
+impl EventDescription {
+    /// Creates an instance configured by an [`EventInstanceBuilder`].
+    ///
+    /// ```ignore
+    /// let footstep = description
+    ///     .create_instance_with()
+    ///     .parameter_by_name(c!("Surface"), 2.0)
+    ///     .attributes_3d(attributes)
+    ///     .start()
+    ///     .release()
+    ///     .build()?;
+    /// ```
+    pub fn create_instance_with<'a>(&self) -> EventInstanceBuilder<'a> {
+        EventInstanceBuilder {
+            description: *self,
+            callback: None,
+            userdata: std::ptr::null_mut(),
+            parameters: Vec::new(),
+            attributes: None,
+            volume: None,
+            pitch: None,
+            timeline_position: None,
+            start: false,
+            release: false,
+        }
+    }
+}
+
+impl<'a> EventInstanceBuilder<'a> {
+    /// Sets the initial value of the parameter called `name`.
+    pub fn parameter_by_name(mut self, name: &'a Utf8CStr, value: c_float) -> Self {
+        self.parameters.push((Parameter::Name(name), value));
+        self
+    }
+
+    /// Sets the initial value of the parameter `id`.
+    pub fn parameter_by_id(mut self, id: ParameterID, value: c_float) -> Self {
+        self.parameters.push((Parameter::Id(id), value));
+        self
+    }
+
+    /// Sets the initial 3D attributes.
+    pub fn attributes_3d(mut self, attributes: Attributes3D) -> Self {
+        self.attributes = Some(attributes);
+        self
+    }
+
+    /// Sets the initial volume.
+    pub fn volume(mut self, volume: c_float) -> Self {
+        self.volume = Some(volume);
+        self
+    }
+
+    /// Sets the initial pitch.
+    pub fn pitch(mut self, pitch: c_float) -> Self {
+        self.pitch = Some(pitch);
+        self
+    }
+
+    /// Sets the timeline position to start from, in milliseconds.
+    pub fn timeline_position(mut self, position: c_int) -> Self {
+        self.timeline_position = Some(position);
+        self
+    }
+
+    /// Sets the instance's callback, see [`EventInstance::set_callback`].
+    pub fn callback<C: EventInstanceCallback>(mut self, mask: EventCallbackMask) -> Self {
+        self.callback = Some((Some(event_callback_impl::<C>), mask));
+        self
+    }
+
+    /// Sets the instance's user data, see [`EventInstance::set_userdata`].
+    pub fn userdata(mut self, userdata: *mut c_void) -> Self {
+        self.userdata = userdata;
+        self
+    }
+
+    /// Starts the instance once it is set up.
+    pub fn start(mut self) -> Self {
+        self.start = true;
+        self
+    }
+
+    /// Releases the instance once it is set up (and started), so it is destroyed when it stops.
+    ///
+    /// The returned handle stays valid until then, so it can still be used to stop the instance or move it.
+    pub fn release(mut self) -> Self {
+        self.release = true;
+        self
+    }
+
+    /// Creates the instance and applies the settings.
+    pub fn build(&self) -> Result<EventInstance> {
+        let instance = self.description.create_instance()?;
+        match self.apply(instance) {
+            Ok(()) => Ok(instance),
+            Err(e) => {
+                let _ = instance.release();
+                Err(e)
+            }
+        }
+    }
+
+    fn apply(&self, instance: EventInstance) -> Result<()> {
+        if let Some((callback, mask)) = self.callback {
+            unsafe {
+                FMOD_Studio_EventInstance_SetCallback(instance.as_ptr(), callback, mask.into())
+                    .to_result()?;
+            }
+        }
+        if !self.userdata.is_null() {
+            instance.set_userdata(self.userdata)?;
+        }
+        for &(parameter, value) in &self.parameters {
+            match parameter {
+                Parameter::Name(name) => instance.set_parameter_by_name(name, value, true)?,
+                Parameter::Id(id) => instance.set_parameter_by_id(id, value, true)?,
+            }
+        }
+        if let Some(attributes) = self.attributes {
+            instance.set_3d_attributes(attributes)?;
+        }
+        if let Some(volume) = self.volume {
+            instance.set_volume(volume)?;
+        }
+        if let Some(pitch) = self.pitch {
+            instance.set_pitch(pitch)?;
+        }
+        if let Some(position) = self.timeline_position {
+            instance.set_timeline_position(position)?;
+        }
+        if self.start {
+            instance.start()?;
+        }
+        if self.release {
+            instance.release()?;
+        }
+        Ok(())
+    }
+}
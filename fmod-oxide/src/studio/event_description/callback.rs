@@ -12,6 +12,9 @@ use crate::studio::{
 };
 use crate::{FmodResultExt, Result};
 
+#[cfg(doc)]
+use crate::studio::EventInstance;
+
 impl EventDescription {
     /// Sets the event user data.
     #[allow(clippy::not_unsafe_ptr_arg_deref)] // fmod doesn't dereference the passed in pointer, and the user dereferencing it is unsafe anyway
@@ -34,7 +37,8 @@ impl EventDescription {
     /// Sets the user callback.
     ///
     /// This function sets a user callback which will be assigned to all event instances subsequently created from the event.
-    /// The callback for individual instances can be set with `EventInstance::set_callback`.
+    /// Instances that already exist are left alone; see [`EventDescription::set_callback_for_existing_instances`].
+    /// The callback for individual instances can be set with [`EventInstance::set_callback`].
     pub fn set_callback<C: EventInstanceCallback>(&self, mask: EventCallbackMask) -> Result<()> {
         unsafe {
             FMOD_Studio_EventDescription_SetCallback(
@@ -45,4 +49,24 @@ impl EventDescription {
             .to_result()
         }
     }
+
+    /// Sets `C` as the callback of every instance of this event that currently exists, replacing any callback they had.
+    pub fn set_callback_for_existing_instances<C: EventInstanceCallback>(
+        &self,
+        mask: EventCallbackMask,
+    ) -> Result<()> {
+        for instance in self.get_instance_list()? {
+            instance.set_callback::<C>(mask)?;
+        }
+        Ok(())
+    }
+
+    /// Removes the callback set with [`EventDescription::set_callback`], so new instances are created without one.
+    ///
+    /// Instances that already exist keep their callback.
+    pub fn clear_callback(&self) -> Result<()> {
+        unsafe {
+            FMOD_Studio_EventDescription_SetCallback(self.inner.as_ptr(), None, 0).to_result()
+        }
+    }
 }
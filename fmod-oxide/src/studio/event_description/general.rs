@@ -10,7 +10,7 @@ use fmod_sys::*;
 use lanyard::Utf8CString;
 
 use crate::Guid;
-use crate::studio::{EventDescription, get_string_out_size};
+use crate::studio::{EventDescription, get_string_out_size, get_string_out_size_bounded};
 use crate::{FmodResultExt, Result};
 
 impl EventDescription {
@@ -48,6 +48,15 @@ impl EventDescription {
         })
     }
 
+    /// Retrieves the path, truncated to at most `max_len` bytes.
+    ///
+    /// Unlike [`Self::get_path`] this never allocates more than `max_len + 1` bytes, and truncation is not an error.
+    pub fn get_path_with_max_len(&self, max_len: usize) -> Result<Utf8CString> {
+        get_string_out_size_bounded(max_len, |path, size, ret| unsafe {
+            FMOD_Studio_EventDescription_GetPath(self.inner.as_ptr(), path, size, ret)
+        })
+    }
+
     /// Checks that the [`EventDescription`] reference is valid.
     pub fn is_valid(&self) -> bool {
         unsafe { FMOD_Studio_EventDescription_IsValid(self.inner.as_ptr()).into() }
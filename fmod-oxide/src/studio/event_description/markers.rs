@@ -0,0 +1,120 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::c_int;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use lanyard::Utf8CStr;
+
+use crate::Result;
+use crate::studio::{
+    EventCallbackMask, EventDescription, EventInstance, EventInstanceCallback,
+    TimelineMarkerProperties,
+};
+
+#[cfg(doc)]
+use crate::studio::Bank;
+
+struct MarkerTable {
+    description: usize,
+    // kept sorted by position
+    markers: Vec<TimelineMarkerProperties>,
+}
+
+static MARKERS: Mutex<Vec<MarkerTable>> = Mutex::new(Vec::new());
+
+fn markers() -> MutexGuard<'static, Vec<MarkerTable>> {
+    // tables are only replaced or pushed to whole, so a panic can't leave one inconsistent
+    MARKERS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// An [`EventInstanceCallback`] that records the timeline markers an instance passes into its description's marker table.
+///
+/// FMOD can't list an event's markers up front, so tables either come from build tooling through [`EventDescription::set_markers`],
+/// or are learned by playing the event with this callback set (for example once, silently, at load time).
+/// If the instance needs a callback of its own, call [`EventDescription::record_marker`] from its
+/// [`EventInstanceCallback::timeline_marker`] instead.
+#[derive(Debug, Clone, Copy)]
+pub struct MarkerRecorder;
+
+impl EventInstanceCallback for MarkerRecorder {
+    const MASK: EventCallbackMask = EventCallbackMask::TIMELINE_MARKER;
+
+    fn timeline_marker(
+        event: EventInstance,
+        timeline_props: TimelineMarkerProperties,
+    ) -> Result<()> {
+        event.get_description()?.record_marker(timeline_props);
+        Ok(())
+    }
+}
+
+impl EventDescription {
+    /// Replaces this event's marker table, used by [`EventInstance::skip_to_marker`].
+    pub fn set_markers(&self, table: impl IntoIterator<Item = TimelineMarkerProperties>) {
+        let description = self.inner.as_ptr() as usize;
+        let mut table: Vec<_> = table.into_iter().collect();
+        table.sort_by_key(|marker| marker.position);
+
+        let mut markers = markers();
+        markers.retain(|t| t.description != description);
+        if !table.is_empty() {
+            markers.push(MarkerTable {
+                description,
+                markers: table,
+            });
+        }
+    }
+
+    /// Adds `marker` to this event's marker table, replacing any marker with the same name.
+    pub fn record_marker(&self, marker: TimelineMarkerProperties) {
+        let description = self.inner.as_ptr() as usize;
+        let mut markers = markers();
+        let table = match markers.iter().position(|t| t.description == description) {
+            Some(index) => &mut markers[index].markers,
+            None => {
+                markers.push(MarkerTable {
+                    description,
+                    markers: Vec::new(),
+                });
+                &mut markers.last_mut().unwrap().markers
+            }
+        };
+        table.retain(|m| m.name != marker.name);
+        let index = table.partition_point(|m| m.position <= marker.position);
+        table.insert(index, marker);
+    }
+
+    /// The markers in this event's marker table, in timeline order.
+    pub fn get_markers(&self) -> Vec<TimelineMarkerProperties> {
+        let description = self.inner.as_ptr() as usize;
+        markers()
+            .iter()
+            .find(|t| t.description == description)
+            .map(|t| t.markers.clone())
+            .unwrap_or_default()
+    }
+
+    /// The position of the marker called `name`, in milliseconds, if it is in this event's marker table.
+    pub fn get_marker_position(&self, name: &Utf8CStr) -> Option<c_int> {
+        let description = self.inner.as_ptr() as usize;
+        markers()
+            .iter()
+            .find(|t| t.description == description)?
+            .markers
+            .iter()
+            .find(|m| *m.name == *name)
+            .map(|m| m.position)
+    }
+
+    /// Removes this event's marker table.
+    ///
+    /// Tables aren't removed when a [`Bank`] is unloaded, so clear them if the event may be loaded again from a different build.
+    pub fn clear_markers(&self) {
+        let description = self.inner.as_ptr() as usize;
+        markers().retain(|t| t.description != description);
+    }
+}
@@ -9,13 +9,18 @@ use std::ptr::NonNull;
 use fmod_sys::*;
 
 mod attributes;
+mod builder;
 mod callback;
 mod general;
 mod instance;
+mod markers;
 mod parameter;
 mod sample_data;
 mod user_property;
 
+pub use builder::EventInstanceBuilder;
+pub use markers::MarkerRecorder;
+
 /// The description for an FMOD Studio event.
 ///
 /// Event descriptions belong to banks, and so an event description can only be queried if the relevant bank is loaded.
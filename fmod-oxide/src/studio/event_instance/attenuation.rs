@@ -0,0 +1,69 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::c_float;
+
+use crate::studio::{EventInstance, EventProperty};
+use crate::{Error, Result};
+
+#[cfg(doc)]
+use crate::studio::EventDescription;
+
+/// The 3D attenuation distances of an [`EventInstance`], and whether they come from Studio or an override.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Attenuation {
+    /// Minimum distance currently in effect.
+    pub min_distance: c_float,
+    /// Maximum distance currently in effect.
+    pub max_distance: c_float,
+    /// Whether the minimum distance was overridden with [`EventInstance::override_attenuation`].
+    pub min_overridden: bool,
+    /// Whether the maximum distance was overridden with [`EventInstance::override_attenuation`].
+    pub max_overridden: bool,
+}
+
+impl Attenuation {
+    /// Whether either distance is overridden, rather than the value authored in Studio.
+    pub fn is_overridden(&self) -> bool {
+        self.min_overridden || self.max_overridden
+    }
+}
+
+impl EventInstance {
+    /// Overrides the minimum and maximum distance for 3D attenuation authored in Studio.
+    ///
+    /// This sets [`EventProperty::MinimumDistance`] and [`EventProperty::MaximumDistance`].
+    /// Returns [`Error::InvalidParam`] if `min` is negative or greater than `max`.
+    ///
+    /// An FMOD spatializer or object spatializer may still override these values.
+    pub fn override_attenuation(&self, min: c_float, max: c_float) -> Result<()> {
+        if !(0.0..=max).contains(&min) {
+            return Err(Error::InvalidParam);
+        }
+        self.set_property(EventProperty::MinimumDistance, min)?;
+        self.set_property(EventProperty::MaximumDistance, max)
+    }
+
+    /// Reverts the minimum and maximum distance to the values authored in Studio.
+    pub fn clear_attenuation_override(&self) -> Result<()> {
+        self.set_property(EventProperty::MinimumDistance, -1.0)?;
+        self.set_property(EventProperty::MaximumDistance, -1.0)
+    }
+
+    /// Retrieves the attenuation distances in effect, and whether they are overridden.
+    ///
+    /// The authored values can be retrieved with [`EventDescription::get_min_max_distance`].
+    pub fn get_attenuation(&self) -> Result<Attenuation> {
+        let (min_distance, max_distance) = self.get_min_max_distance()?;
+        // the properties read back as -1 when not overridden
+        Ok(Attenuation {
+            min_distance,
+            max_distance,
+            min_overridden: self.get_property(EventProperty::MinimumDistance)? >= 0.0,
+            max_overridden: self.get_property(EventProperty::MaximumDistance)? >= 0.0,
+        })
+    }
+}
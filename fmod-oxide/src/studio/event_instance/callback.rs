@@ -10,7 +10,7 @@ use lanyard::Utf8CStr;
 use std::ffi::c_void;
 
 use crate::{
-    Sound, panic_wrapper,
+    CallbackContext, CallbackThread, Sound, panic_wrapper,
     studio::{
         EventCallbackMask, EventInstance, PluginInstanceProperties, ProgrammerSoundProperties,
         TimelineBeatProperties, TimelineMarkerProperties, TimelineNestedBeatProperties,
@@ -22,6 +22,19 @@ use crate::{
 /// No `self` parameter is passed to the callback!
 #[allow(unused_variables)]
 pub trait EventInstanceCallback {
+    /// The callback types this implementation handles, to pass to [`EventInstance::set_callback`] as `C::MASK`.
+    ///
+    /// This defaults to [`EventCallbackMask::ALL`], which works for any implementation but means FMOD calls into it for every callback type.
+    /// Implementing the trait through [`event_instance_callback!`](crate::event_instance_callback) sets this to exactly the methods that are implemented,
+    /// so a method can't be implemented and then accidentally left out of the mask.
+    const MASK: EventCallbackMask = EventCallbackMask::ALL;
+
+    /// Mask used to register this callback on events started by start event commands (nested events).
+    ///
+    /// When this is [`Some`], callbacks from those events are forwarded to this type as well, and so on for events they start in turn.
+    /// This only works if [`EventCallbackMask::START_EVENT_COMMAND`] is part of the mask this callback was registered with.
+    const NESTED_EVENT_MASK: Option<EventCallbackMask> = None;
+
     /// Called when an instance is fully created.
     fn created(event: EventInstance) -> Result<()> {
         Ok(())
@@ -61,7 +74,8 @@ pub trait EventInstanceCallback {
         Ok(())
     }
 
-    /// A programmer sound is about to play. FMOD expects the callback to provide an [`Sound`] object for it to use.
+    /// A programmer sound is about to play. FMOD expects the callback to provide an [`Sound`] object for it to use,
+    /// by setting [`ProgrammerSoundProperties::sound`].
     fn create_programmer_sound(
         event: EventInstance,
         sound_props: ProgrammerSoundProperties<'_>,
@@ -124,6 +138,8 @@ pub trait EventInstanceCallback {
     }
 
     /// Called when a new event is started by a start event command.
+    ///
+    /// See [`EventInstanceCallback::NESTED_EVENT_MASK`] to forward the new event's callbacks as well.
     fn start_event_command(event: EventInstance, new_event: EventInstance) -> Result<()> {
         Ok(())
     }
@@ -143,6 +159,7 @@ pub(crate) unsafe extern "C" fn event_callback_impl<C: EventInstanceCallback>(
     parameters: *mut c_void,
 ) -> FMOD_RESULT {
     panic_wrapper(|| {
+        let _context = CallbackContext::enter(CallbackThread::Studio);
         let event = unsafe { EventInstance::from_ffi(event) };
         let result = match kind {
             FMOD_STUDIO_EVENT_CALLBACK_CREATED => C::created(event),
@@ -206,7 +223,11 @@ pub(crate) unsafe extern "C" fn event_callback_impl<C: EventInstanceCallback>(
             FMOD_STUDIO_EVENT_CALLBACK_VIRTUAL_TO_REAL => C::virtual_to_real(event),
             FMOD_STUDIO_EVENT_CALLBACK_START_EVENT_COMMAND => {
                 let new_event = unsafe { EventInstance::from_ffi(parameters.cast()) };
-                C::start_event_command(event, new_event)
+                let forwarded = match C::NESTED_EVENT_MASK {
+                    Some(mask) => new_event.set_callback::<C>(mask),
+                    None => Ok(()),
+                };
+                C::start_event_command(event, new_event).and(forwarded)
             }
             FMOD_STUDIO_EVENT_CALLBACK_NESTED_TIMELINE_BEAT => {
                 let props = unsafe {
@@ -254,3 +275,106 @@ impl EventInstance {
         }
     }
 }
+
+/// Implements [`EventInstanceCallback`], setting [`EventInstanceCallback::MASK`] from the methods that are implemented.
+///
+/// ```ignore
+/// struct Markers;
+///
+/// fmod::event_instance_callback! {
+///     impl EventInstanceCallback for Markers {
+///         fn timeline_marker(event: EventInstance, props: TimelineMarkerProperties) -> Result<()> {
+///             println!("passed marker {}", props.name);
+///             Ok(())
+///         }
+///
+///         fn stopped(event: EventInstance) -> Result<()> {
+///             Ok(())
+///         }
+///     }
+/// }
+///
+/// // registers TIMELINE_MARKER | STOPPED
+/// instance.set_callback::<Markers>(Markers::MASK)?;
+/// ```
+///
+/// Other associated constants (such as [`EventInstanceCallback::NESTED_EVENT_MASK`]) may be given before the methods.
+/// Methods may have doc comments and other attributes, which are kept on the generated methods.
+#[macro_export]
+macro_rules! event_instance_callback {
+    (
+        impl EventInstanceCallback for $ty:ty {
+            $(const $const_name:ident: $const_ty:ty = $const_value:expr;)*
+            $($(#[$meta:meta])* fn $name:ident($($args:tt)*) -> $ret:ty $body:block)*
+        }
+    ) => {
+        impl $crate::studio::EventInstanceCallback for $ty {
+            const MASK: $crate::studio::EventCallbackMask = $crate::studio::EventCallbackMask::empty()
+                $(.union($crate::__event_callback_bit!($name)))*;
+            $(const $const_name: $const_ty = $const_value;)*
+            $($(#[$meta])* fn $name($($args)*) -> $ret $body)*
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __event_callback_bit {
+    (created) => {
+        $crate::studio::EventCallbackMask::CREATED
+    };
+    (destroyed) => {
+        $crate::studio::EventCallbackMask::DESTROYED
+    };
+    (starting) => {
+        $crate::studio::EventCallbackMask::STARTING
+    };
+    (started) => {
+        $crate::studio::EventCallbackMask::STARTED
+    };
+    (restarted) => {
+        $crate::studio::EventCallbackMask::RESTARTED
+    };
+    (stopped) => {
+        $crate::studio::EventCallbackMask::STOPPED
+    };
+    (start_failed) => {
+        $crate::studio::EventCallbackMask::START_FAILED
+    };
+    (create_programmer_sound) => {
+        $crate::studio::EventCallbackMask::CREATE_PROGRAMMER_SOUND
+    };
+    (destroy_programmer_sound) => {
+        $crate::studio::EventCallbackMask::DESTROY_PROGRAMMER_SOUND
+    };
+    (plugin_created) => {
+        $crate::studio::EventCallbackMask::PLUGIN_CREATED
+    };
+    (plugin_destroyed) => {
+        $crate::studio::EventCallbackMask::PLUGIN_DESTROYED
+    };
+    (timeline_marker) => {
+        $crate::studio::EventCallbackMask::TIMELINE_MARKER
+    };
+    (timeline_beat) => {
+        $crate::studio::EventCallbackMask::TIMELINE_BEAT
+    };
+    (sound_played) => {
+        $crate::studio::EventCallbackMask::SOUND_PLAYED
+    };
+    (sound_stopped) => {
+        $crate::studio::EventCallbackMask::SOUND_STOPPED
+    };
+    (real_to_virtual) => {
+        $crate::studio::EventCallbackMask::REAL_TO_VIRTUAL
+    };
+    (virtual_to_real) => {
+        $crate::studio::EventCallbackMask::VIRTUAL_TO_REAL
+    };
+    (start_event_command) => {
+        $crate::studio::EventCallbackMask::START_EVENT_COMMAND
+    };
+    (nested_timeline_beat) => {
+        $crate::studio::EventCallbackMask::NESTED_TIMELINE_BEAT
+    };
+}
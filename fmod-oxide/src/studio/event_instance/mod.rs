@@ -8,17 +8,24 @@ use std::ptr::NonNull;
 
 use fmod_sys::*;
 
+mod attenuation;
 mod attributes_3d;
 mod callback;
 mod core;
 mod general;
+mod parameter_handle;
 mod parameters;
 mod playback;
 mod playback_properties;
 mod profiling;
+mod ramp;
+mod seeking;
 
+pub use attenuation::Attenuation;
 pub use callback::EventInstanceCallback;
 pub(crate) use callback::event_callback_impl;
+pub use parameter_handle::{ParameterHandle, ParameterSmoother, ParameterSmoothing};
+pub(crate) use ramp::{forget_ramps, update_ramps};
 
 /// An instance of an FMOD Studio event.
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
@@ -0,0 +1,193 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::c_float;
+use std::time::Duration;
+
+use lanyard::Utf8CStr;
+
+use crate::studio::{EventInstance, ParameterID};
+use crate::{Error, Result};
+
+#[cfg(doc)]
+use crate::studio::{PlaybackState, System};
+
+/// A parameter of one [`EventInstance`], looked up once so it can be set cheaply every frame.
+///
+/// Parameters can be authored in FMOD Studio with a seek speed, which makes the final value move towards the set value at a fixed rate.
+/// [`ParameterHandle::set_with_seek`] goes through that seek speed and [`ParameterHandle::set_immediate`] skips it.
+/// For parameters authored without one, [`ParameterSmoother`] provides the same kind of movement on the crate side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ParameterHandle {
+    instance: EventInstance,
+    id: ParameterID,
+}
+
+impl ParameterHandle {
+    /// Creates a handle for the parameter `id` of `instance`.
+    pub fn new(instance: EventInstance, id: ParameterID) -> Self {
+        Self { instance, id }
+    }
+
+    /// The instance the parameter belongs to.
+    pub fn instance(&self) -> EventInstance {
+        self.instance
+    }
+
+    /// The parameter's id.
+    pub fn id(&self) -> ParameterID {
+        self.id
+    }
+
+    /// Sets the value, letting the final value move towards it at the parameter's authored seek speed.
+    ///
+    /// Parameters without a seek speed jump straight to `value`, as do all parameters while the event is [`PlaybackState::Stopped`].
+    pub fn set_with_seek(&self, value: c_float) -> Result<()> {
+        self.instance.set_parameter_by_id(self.id, value, false)
+    }
+
+    /// Sets the value, ignoring the parameter's authored seek speed.
+    pub fn set_immediate(&self, value: c_float) -> Result<()> {
+        self.instance.set_parameter_by_id(self.id, value, true)
+    }
+
+    /// Retrieves the value last set, and the final value after seek speed, automation and modulation.
+    ///
+    /// See [`EventInstance::get_parameter_by_id`].
+    pub fn get(&self) -> Result<(c_float, c_float)> {
+        self.instance.get_parameter_by_id(self.id)
+    }
+}
+
+impl EventInstance {
+    /// Looks up the parameter called `name` on this instance's event and returns a [`ParameterHandle`] for it.
+    pub fn parameter(&self, name: &Utf8CStr) -> Result<ParameterHandle> {
+        let description = self
+            .get_description()?
+            .get_parameter_description_by_name(name)?;
+        Ok(ParameterHandle::new(*self, description.id))
+    }
+}
+
+/// How a [`ParameterSmoother`] moves its parameter towards the target value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParameterSmoothing {
+    /// Jump to the target right away.
+    Immediate,
+    /// Let FMOD move to the target at the seek speed authored in FMOD Studio.
+    SeekSpeed,
+    /// Move towards the target by at most `units_per_second`, stepped by [`ParameterSmoother::update`].
+    ///
+    /// For parameters authored without a seek speed.
+    Rate {
+        /// How far the value may move in one second, in the parameter's units.
+        units_per_second: c_float,
+    },
+}
+
+/// Smooths changes to a parameter, whether or not it was authored with a seek speed.
+///
+/// Game code calls [`ParameterSmoother::set_target`] whenever the value changes and [`ParameterSmoother::update`] once per frame,
+/// and [`ParameterSmoothing`] decides whether FMOD or the crate does the smoothing,
+/// so switching a parameter between the two in FMOD Studio only means changing one [`ParameterSmoothing`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParameterSmoother {
+    handle: ParameterHandle,
+    smoothing: ParameterSmoothing,
+    current: c_float,
+    target: c_float,
+}
+
+impl ParameterSmoother {
+    /// Creates a smoother for `handle`, starting from the parameter's current value.
+    ///
+    /// Returns [`Error::InvalidParam`] if a [`ParameterSmoothing::Rate`] isn't positive.
+    pub fn new(handle: ParameterHandle, smoothing: ParameterSmoothing) -> Result<Self> {
+        let valid = match smoothing {
+            ParameterSmoothing::Rate { units_per_second } => units_per_second > 0.0,
+            ParameterSmoothing::Immediate | ParameterSmoothing::SeekSpeed => true,
+        };
+        if !valid {
+            return Err(Error::InvalidParam);
+        }
+        let (current, _) = handle.get()?;
+        Ok(Self {
+            handle,
+            smoothing,
+            current,
+            target: current,
+        })
+    }
+
+    /// The parameter being smoothed.
+    pub fn handle(&self) -> ParameterHandle {
+        self.handle
+    }
+
+    /// The value the parameter is moving towards.
+    pub fn target(&self) -> c_float {
+        self.target
+    }
+
+    /// The value last sent to FMOD.
+    ///
+    /// With [`ParameterSmoothing::SeekSpeed`] this is the target, use [`ParameterHandle::get`] for where FMOD's seek has got to.
+    pub fn current(&self) -> c_float {
+        self.current
+    }
+
+    /// Whether the value sent to FMOD has reached the target.
+    // the target is copied into `current` once it is reached, so comparing exactly is right
+    #[allow(clippy::float_cmp)]
+    pub fn is_settled(&self) -> bool {
+        self.current == self.target
+    }
+
+    /// Sets the value to move towards.
+    ///
+    /// With [`ParameterSmoothing::Immediate`] and [`ParameterSmoothing::SeekSpeed`] this is passed on to FMOD right away.
+    pub fn set_target(&mut self, target: c_float) -> Result<()> {
+        self.target = target;
+        match self.smoothing {
+            ParameterSmoothing::Immediate => self.handle.set_immediate(target)?,
+            ParameterSmoothing::SeekSpeed => self.handle.set_with_seek(target)?,
+            ParameterSmoothing::Rate { .. } => return Ok(()),
+        }
+        self.current = target;
+        Ok(())
+    }
+
+    /// Jumps straight to `value`, whatever the [`ParameterSmoothing`].
+    pub fn snap_to(&mut self, value: c_float) -> Result<()> {
+        self.handle.set_immediate(value)?;
+        self.target = value;
+        self.current = value;
+        Ok(())
+    }
+
+    /// Steps a [`ParameterSmoothing::Rate`] towards the target by `dt` worth of movement.
+    ///
+    /// Does nothing for the other kinds of smoothing, or once the target has been reached,
+    /// so it is fine to call every frame. The new value goes out with the next [`System::update`].
+    pub fn update(&mut self, dt: Duration) -> Result<()> {
+        let ParameterSmoothing::Rate { units_per_second } = self.smoothing else {
+            return Ok(());
+        };
+        if self.is_settled() {
+            return Ok(());
+        }
+        let max_step = units_per_second * dt.as_secs_f32();
+        let delta = self.target - self.current;
+        let next = if delta.abs() <= max_step {
+            self.target
+        } else {
+            self.current + max_step.copysign(delta)
+        };
+        self.handle.set_immediate(next)?;
+        self.current = next;
+        Ok(())
+    }
+}
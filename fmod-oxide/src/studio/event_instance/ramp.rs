@@ -0,0 +1,171 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::c_float;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+use crate::studio::{EventInstance, System};
+use crate::{FmodResultLogExt, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RampProperty {
+    Volume,
+    Pitch,
+}
+
+struct Ramp {
+    // identifies this ramp, so one replaced while the previous step was being applied isn't removed by mistake
+    id: u64,
+    system: usize,
+    instance: usize,
+    property: RampProperty,
+    from: c_float,
+    to: c_float,
+    start: Instant,
+    duration: Duration,
+}
+
+impl Ramp {
+    fn progress(&self, now: Instant) -> c_float {
+        let elapsed = now.saturating_duration_since(self.start);
+        (elapsed.as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+    }
+
+    fn value(&self, t: c_float) -> c_float {
+        match self.property {
+            RampProperty::Volume => self.from + (self.to - self.from) * t,
+            // glide in equal steps of semitones rather than of ratio, which is how pitch is heard
+            RampProperty::Pitch if self.from > 0.0 && self.to > 0.0 => {
+                self.from * (self.to / self.from).powf(t)
+            }
+            RampProperty::Pitch => self.from + (self.to - self.from) * t,
+        }
+    }
+}
+
+impl RampProperty {
+    fn apply(self, instance: EventInstance, value: c_float) -> Result<()> {
+        match self {
+            RampProperty::Volume => instance.set_volume(value),
+            RampProperty::Pitch => instance.set_pitch(value),
+        }
+    }
+}
+
+static RAMPS: Mutex<Vec<Ramp>> = Mutex::new(Vec::new());
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+fn ramps() -> MutexGuard<'static, Vec<Ramp>> {
+    // ramps are only pushed, replaced or removed whole, so a panic can't leave the list inconsistent
+    RAMPS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Advances the ramps of `system`, called from [`System::update`] so the new values go out with that update.
+pub(crate) fn update_ramps(system: System) {
+    let system = system.inner.as_ptr() as usize;
+    let now = Instant::now();
+    // work out the steps under the lock, but make the calls without it,
+    // so other threads starting or cancelling ramps never wait on FMOD
+    let steps: Vec<_> = ramps()
+        .iter()
+        .filter(|ramp| ramp.system == system)
+        .map(|ramp| {
+            let t = ramp.progress(now);
+            (ramp.id, ramp.instance, ramp.property, ramp.value(t), t)
+        })
+        .collect();
+    if steps.is_empty() {
+        return;
+    }
+
+    let mut finished = Vec::new();
+    for (id, instance, property, value, t) in steps {
+        let instance = unsafe { EventInstance::from_ffi(instance as *mut _) };
+        match property.apply(instance, value) {
+            Ok(()) if t < 1.0 => {}
+            Ok(()) => finished.push(id),
+            // the instance has been released, which is the usual way for a ramp to end early
+            Err(e) if e.is_invalid_handle() => finished.push(id),
+            Err(e) => {
+                finished.push(id);
+                Err::<(), _>(e).log_err("event instance ramp");
+            }
+        }
+    }
+    ramps().retain(|ramp| !finished.contains(&ramp.id));
+}
+
+/// Drops every ramp of `system`, once it has been released.
+pub(crate) fn forget_ramps(system: System) {
+    let system = system.inner.as_ptr() as usize;
+    ramps().retain(|ramp| ramp.system != system);
+}
+
+impl EventInstance {
+    fn start_ramp(
+        &self,
+        property: RampProperty,
+        from: c_float,
+        to: c_float,
+        duration: Duration,
+    ) -> Result<()> {
+        self.cancel_ramp(property);
+        if duration.is_zero() {
+            return property.apply(*self, to);
+        }
+        let system = self.get_system()?;
+        ramps().push(Ramp {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            system: system.inner.as_ptr() as usize,
+            instance: self.inner.as_ptr() as usize,
+            property,
+            from,
+            to,
+            start: Instant::now(),
+            duration,
+        });
+        Ok(())
+    }
+
+    fn cancel_ramp(&self, property: RampProperty) {
+        let instance = self.inner.as_ptr() as usize;
+        ramps().retain(|r| !(r.instance == instance && r.property == property));
+    }
+
+    /// Fades the volume set with [`EventInstance::set_volume`] to `target` over `duration`, linearly.
+    ///
+    /// The Studio API has no per-instance fades, so the crate steps the volume on every [`System::update`] until `duration` has passed.
+    /// Steps are as fine as the update rate, which is plenty for fades of more than a few frames.
+    /// The fade starts from the current volume, so starting a new fade (or calling this again mid-fade) carries on smoothly.
+    /// Calling [`EventInstance::set_volume`] directly doesn't cancel the fade; use [`EventInstance::cancel_fades`].
+    pub fn fade_volume_to(&self, target: c_float, duration: Duration) -> Result<()> {
+        let (volume, _) = self.get_volume()?;
+        self.start_ramp(RampProperty::Volume, volume, target, duration)
+    }
+
+    /// Glides the pitch set with [`EventInstance::set_pitch`] to `target` over `duration`.
+    ///
+    /// The glide is even in musical terms (semitones per second) rather than linear in the multiplier.
+    /// Like [`EventInstance::fade_volume_to`], it is stepped on every [`System::update`].
+    pub fn glide_pitch_to(&self, target: c_float, duration: Duration) -> Result<()> {
+        let (pitch, _) = self.get_pitch()?;
+        self.start_ramp(RampProperty::Pitch, pitch, target, duration)
+    }
+
+    /// Stops any fade or glide started on this instance, leaving the volume and pitch where they are.
+    pub fn cancel_fades(&self) {
+        let instance = self.inner.as_ptr() as usize;
+        ramps().retain(|r| r.instance != instance);
+    }
+
+    /// Whether a fade or glide is in progress on this instance.
+    pub fn is_fading(&self) -> bool {
+        let instance = self.inner.as_ptr() as usize;
+        ramps().iter().any(|r| r.instance == instance)
+    }
+}
@@ -0,0 +1,52 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::c_int;
+use std::time::Duration;
+
+use lanyard::Utf8CStr;
+
+use crate::studio::EventInstance;
+use crate::{Error, Result};
+
+#[cfg(doc)]
+use crate::studio::{EventDescription, MarkerRecorder};
+
+impl EventInstance {
+    /// Moves the timeline cursor to `position`, clamped to the length of the event.
+    ///
+    /// The length comes from [`EventDescription::get_length`], so scrubbing past the end lands on the end rather than overshooting.
+    /// Events without a timeline length (0) are only clamped to what fits in milliseconds.
+    ///
+    /// Returns the position that was set, in milliseconds.
+    pub fn set_timeline_position_clamped(&self, position: Duration) -> Result<c_int> {
+        let length = self.get_description()?.get_length()?;
+        let position = c_int::try_from(position.as_millis()).unwrap_or(c_int::MAX);
+        let position = if length > 0 {
+            position.min(length)
+        } else {
+            position
+        };
+        self.set_timeline_position(position)?;
+        Ok(position)
+    }
+
+    /// Moves the timeline cursor to the marker called `name`.
+    ///
+    /// The marker is looked up in the description's marker table (see [`EventDescription::set_markers`] and [`MarkerRecorder`]),
+    /// as FMOD has no way to look up markers by name.
+    /// Returns [`Error::EventNotFound`] if the table has no such marker.
+    ///
+    /// Returns the position that was set, in milliseconds.
+    pub fn skip_to_marker(&self, name: &Utf8CStr) -> Result<c_int> {
+        let position = self
+            .get_description()?
+            .get_marker_position(name)
+            .ok_or(Error::EventNotFound)?;
+        self.set_timeline_position(position)?;
+        Ok(position)
+    }
+}
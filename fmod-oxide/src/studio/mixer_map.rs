@@ -0,0 +1,160 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use lanyard::{Utf8CStr, Utf8CString};
+
+use crate::Error;
+use crate::studio::{Bus, System, Vca};
+
+/// Whether a [`MixerMap`] path names a [`Bus`] or a [`Vca`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MixerKind {
+    /// A bus, such as `bus:/SFX`.
+    Bus,
+    /// A VCA, such as `vca:/Music`.
+    Vca,
+}
+
+/// A path registered with a [`MixerMap`] that could not be resolved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingMixerPath {
+    /// Whether the path was registered as a bus or a VCA.
+    pub kind: MixerKind,
+    /// The path that could not be resolved.
+    pub path: Utf8CString,
+    /// The error FMOD returned.
+    pub error: Error,
+}
+
+/// An error returned by [`MixerMap::resolve`], listing every path that could not be resolved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MixerMapError {
+    /// The paths that could not be resolved.
+    pub missing: Vec<MissingMixerPath>,
+}
+
+impl std::fmt::Display for MixerMapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "failed to resolve mixer path(s):")?;
+        for missing in &self.missing {
+            write!(f, " {} ({})", missing.path, missing.error)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for MixerMapError {}
+
+/// Resolves a fixed set of bus and VCA paths to handles once, and hands them out by key.
+///
+/// Register paths with [`MixerMap::bus`] and [`MixerMap::vca`], then call [`MixerMap::resolve`] after loading banks.
+/// Keys can be any hashable type, such as an enum of the mixer groups a game uses, or plain strings.
+///
+/// ```ignore
+/// let mut mixer = MixerMap::new();
+/// mixer.bus(Mix::Sfx, c!("bus:/SFX")).vca(Mix::Music, c!("vca:/Music"));
+/// mixer.resolve(system)?;
+/// mixer.get_bus(&Mix::Sfx).unwrap().set_volume(0.5)?;
+/// ```
+#[derive(Debug)]
+pub struct MixerMap<K> {
+    paths: Vec<(K, MixerKind, Utf8CString)>,
+    buses: HashMap<K, Bus>,
+    vcas: HashMap<K, Vca>,
+}
+
+impl<K> Default for MixerMap<K> {
+    fn default() -> Self {
+        Self {
+            paths: Vec::new(),
+            buses: HashMap::new(),
+            vcas: HashMap::new(),
+        }
+    }
+}
+
+impl<K: Eq + Hash + Clone> MixerMap<K> {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the bus at `path` under `key`.
+    pub fn bus(&mut self, key: K, path: &Utf8CStr) -> &mut Self {
+        self.paths.push((key, MixerKind::Bus, path.to_cstring()));
+        self
+    }
+
+    /// Registers the VCA at `path` under `key`.
+    pub fn vca(&mut self, key: K, path: &Utf8CStr) -> &mut Self {
+        self.paths.push((key, MixerKind::Vca, path.to_cstring()));
+        self
+    }
+
+    /// Looks up every registered path in `system`, replacing any previously resolved handles.
+    ///
+    /// Call this after loading the banks that contain the buses and VCAs.
+    /// Paths that resolve are available even if others fail, and every failure is listed in the returned error.
+    pub fn resolve(&mut self, system: System) -> Result<(), MixerMapError> {
+        self.buses.clear();
+        self.vcas.clear();
+        let mut missing = Vec::new();
+        for (key, kind, path) in &self.paths {
+            let result = match kind {
+                MixerKind::Bus => system.get_bus(path).map(|bus| {
+                    self.buses.insert(key.clone(), bus);
+                }),
+                MixerKind::Vca => system.get_vca(path).map(|vca| {
+                    self.vcas.insert(key.clone(), vca);
+                }),
+            };
+            if let Err(error) = result {
+                missing.push(MissingMixerPath {
+                    kind: *kind,
+                    path: path.clone(),
+                    error,
+                });
+            }
+        }
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(MixerMapError { missing })
+        }
+    }
+
+    /// The bus registered under `key`, if it has been resolved.
+    pub fn get_bus<Q>(&self, key: &Q) -> Option<Bus>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.buses.get(key).copied()
+    }
+
+    /// The VCA registered under `key`, if it has been resolved.
+    pub fn get_vca<Q>(&self, key: &Q) -> Option<Vca>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.vcas.get(key).copied()
+    }
+
+    /// Iterates over every resolved bus.
+    pub fn buses(&self) -> impl Iterator<Item = (&K, Bus)> {
+        self.buses.iter().map(|(key, bus)| (key, *bus))
+    }
+
+    /// Iterates over every resolved VCA.
+    pub fn vcas(&self) -> impl Iterator<Item = (&K, Vca)> {
+        self.vcas.iter().map(|(key, vca)| (key, *vca))
+    }
+}
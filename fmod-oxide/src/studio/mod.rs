@@ -40,6 +40,18 @@ pub use event_instance::*;
 mod vca;
 pub use vca::*;
 
+mod mixer_map;
+pub use mixer_map::*;
+
+mod shutdown;
+pub use shutdown::*;
+
+mod preloader;
+pub use preloader::*;
+
+/// Test doubles for unit testing Studio callback handlers without a real event.
+pub mod testing;
+
 fn get_string_out_size(
     mut get_fn: impl FnMut(*mut c_char, c_int, *mut c_int) -> fmod_sys::FMOD_RESULT,
 ) -> Result<Utf8CString> {
@@ -67,6 +79,19 @@ fn get_string_out_size(
     Ok(string)
 }
 
+fn get_string_out_size_bounded(
+    max_len: usize,
+    mut get_fn: impl FnMut(*mut c_char, c_int, *mut c_int) -> fmod_sys::FMOD_RESULT,
+) -> Result<Utf8CString> {
+    crate::get_string_bounded(max_len, |buffer| {
+        get_fn(
+            buffer.as_mut_ptr().cast(),
+            buffer.len() as c_int,
+            std::ptr::null_mut(),
+        )
+    })
+}
+
 /// The required memory alignment of banks in user memory.
 ///
 /// When using [`System::load_bank_pointer`] you must align the past slice to this alignment.
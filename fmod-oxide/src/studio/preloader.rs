@@ -0,0 +1,155 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::studio::{Bank, EventDescription, LoadingState};
+use crate::{Error, LoadProgress, Result};
+
+#[cfg(doc)]
+use crate::studio::System;
+
+/// Something whose sample data a [`Preloader`] loads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PreloadTarget {
+    /// The sample data of one event, and the events it references.
+    Event(EventDescription),
+    /// The sample data of every event in a bank.
+    Bank(Bank),
+}
+
+impl From<EventDescription> for PreloadTarget {
+    fn from(value: EventDescription) -> Self {
+        PreloadTarget::Event(value)
+    }
+}
+
+impl From<Bank> for PreloadTarget {
+    fn from(value: Bank) -> Self {
+        PreloadTarget::Bank(value)
+    }
+}
+
+impl PreloadTarget {
+    fn load(self) -> Result<()> {
+        match self {
+            PreloadTarget::Event(event) => event.load_sample_data(),
+            PreloadTarget::Bank(bank) => bank.load_sample_data(),
+        }
+    }
+
+    fn unload(self) -> Result<()> {
+        let result = match self {
+            PreloadTarget::Event(event) => event.unload_sample_data(),
+            PreloadTarget::Bank(bank) => bank.unload_sample_data(),
+        };
+        match result {
+            // the bank was unloaded already, which took the sample data with it
+            Err(Error::InvalidHandle) => Ok(()),
+            result => result,
+        }
+    }
+
+    /// The sample data loading state.
+    pub fn get_sample_loading_state(self) -> Result<LoadingState> {
+        match self {
+            PreloadTarget::Event(event) => event.get_sample_loading_state(),
+            PreloadTarget::Bank(bank) => bank.get_sample_loading_state(),
+        }
+    }
+}
+
+/// Keeps the sample data of one set of events and banks loaded, such as everything the current level plays.
+///
+/// This is FMOD's recommended way of managing sample data: load it ahead of time for everything that is about to play,
+/// so events don't wait on disk when they start, and unload it once it won't be played any more.
+/// Calling [`Preloader::preload`] with the next set loads what is new and unloads what is no longer needed,
+/// leaving sample data shared by both sets alone.
+///
+/// Loading happens asynchronously, poll [`Preloader::progress`] (for example from a loading screen) until it is complete.
+/// The sample data isn't unloaded when the preloader is dropped, call [`Preloader::unload_all`] for that.
+#[derive(Debug, Default)]
+pub struct Preloader {
+    targets: Vec<PreloadTarget>,
+}
+
+impl Preloader {
+    /// Creates a preloader with nothing loaded.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Makes `targets` the loaded set, loading the sample data of new targets and unloading that of the previous ones.
+    ///
+    /// Sample data is loaded and unloaded asynchronously, with the next [`System::update`].
+    /// Sample data of an event is only actually unloaded once all its instances are released.
+    ///
+    /// If loading a target fails, the error is returned right away and the previous targets stay loaded,
+    /// along with the new targets loaded so far.
+    pub fn preload<I>(&mut self, targets: I) -> Result<()>
+    where
+        I: IntoIterator,
+        I::Item: Into<PreloadTarget>,
+    {
+        let mut next: Vec<PreloadTarget> = Vec::new();
+        for target in targets.into_iter().map(Into::into) {
+            if !next.contains(&target) {
+                next.push(target);
+            }
+        }
+
+        for &target in &next {
+            if !self.targets.contains(&target) {
+                target.load()?;
+                self.targets.push(target);
+            }
+        }
+
+        let previous = std::mem::take(&mut self.targets);
+        let mut result = Ok(());
+        for target in previous {
+            if next.contains(&target) {
+                self.targets.push(target);
+            } else if let Err(e) = target.unload() {
+                // keep it around so unloading it can be retried
+                self.targets.push(target);
+                result = Err(e);
+            }
+        }
+        result
+    }
+
+    /// The currently loaded set.
+    pub fn targets(&self) -> &[PreloadTarget] {
+        &self.targets
+    }
+
+    /// The loading state of every target in the loaded set.
+    pub fn states(&self) -> Result<Vec<(PreloadTarget, LoadingState)>> {
+        self.targets
+            .iter()
+            .map(|&target| Ok((target, target.get_sample_loading_state()?)))
+            .collect()
+    }
+
+    /// How many targets have finished loading, successfully or not.
+    ///
+    /// Targets that failed have a [`LoadingState::Error`] in [`Preloader::states`].
+    pub fn progress(&self) -> Result<LoadProgress> {
+        let finished = self
+            .states()?
+            .into_iter()
+            .filter(|(_, state)| matches!(state, LoadingState::Loaded | LoadingState::Error(_)))
+            .count();
+        Ok(LoadProgress {
+            finished,
+            total: self.targets.len(),
+        })
+    }
+
+    /// Unloads the sample data of the whole loaded set.
+    pub fn unload_all(&mut self) -> Result<()> {
+        self.preload(std::iter::empty::<PreloadTarget>())
+    }
+}
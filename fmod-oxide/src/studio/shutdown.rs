@@ -0,0 +1,38 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use lanyard::c;
+
+use crate::Result;
+use crate::studio::{StopMode, System};
+
+#[cfg(doc)]
+use crate::studio::{Bank, EventInstance};
+
+/// Tears down a Studio `system` in the order FMOD recommends.
+///
+/// 1. Every [`EventInstance`] is stopped immediately through the master bus.
+/// 2. [`System::flush_commands`] waits for the stops and any non blocking [`Bank`] loads to finish.
+/// 3. [`System::flush_sample_loading`] waits for sample data loads to finish.
+/// 4. Every [`Bank`] is unloaded, and the unloads are flushed.
+/// 5. The system is released, which releases its core system too.
+///
+/// If no bank with the master bus is loaded there is nothing to stop, so step 1 is skipped.
+///
+/// # Safety
+///
+/// See [`System::release`].
+pub unsafe fn shutdown(system: System) -> Result<()> {
+    // the master bus only exists once the master bank is loaded
+    if let Ok(master) = system.get_bus(c!("bus:/")) {
+        master.stop_all_events(StopMode::Immediate)?;
+    }
+    system.flush_commands()?;
+    system.flush_sample_loading()?;
+    system.unload_all_banks()?;
+    system.flush_commands()?;
+    unsafe { system.release() }
+}
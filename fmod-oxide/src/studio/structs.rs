@@ -30,6 +30,19 @@ pub struct MemoryUsage {
     pub sample_data: c_int,
 }
 
+impl std::fmt::Display for MemoryUsage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use crate::overlay::Bytes;
+        write!(
+            f,
+            "exclusive {} inclusive {} sample data {}",
+            Bytes(self.exclusive.into()),
+            Bytes(self.inclusive.into()),
+            Bytes(self.sample_data.into())
+        )
+    }
+}
+
 impl From<FMOD_STUDIO_MEMORY_USAGE> for MemoryUsage {
     fn from(value: FMOD_STUDIO_MEMORY_USAGE) -> Self {
         MemoryUsage {
@@ -357,12 +370,19 @@ impl From<BufferUsage> for FMOD_STUDIO_BUFFER_USAGE {
 
 /// Performance information for Studio API functionality.
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CpuUsage {
     /// `System::update` CPU usage.
     /// Percentage of main thread, or main thread if the System was created with `SYNCHRONOUS_UPDATE`.
     pub update: c_float,
 }
 
+impl std::fmt::Display for CpuUsage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "update {:.1}%", self.update)
+    }
+}
+
 impl From<FMOD_STUDIO_CPU_USAGE> for CpuUsage {
     fn from(value: FMOD_STUDIO_CPU_USAGE) -> Self {
         CpuUsage {
@@ -409,7 +429,7 @@ impl From<&SoundInfo<'_>> for FMOD_STUDIO_SOUND_INFO {
         FMOD_STUDIO_SOUND_INFO {
             name_or_data: value.builder.name_or_data,
             mode: value.builder.mode,
-            exinfo: value.builder.create_sound_ex_info,
+            exinfo: value.builder.raw_ex_info(),
             subsoundindex: value.subsound_index,
         }
     }
@@ -486,7 +506,7 @@ pub struct ProgrammerSoundProperties<'prop> {
 }
 
 /// Describes a DSP plug-in instance.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PluginInstanceProperties {
     /// Name of the plug-in effect or sound (set in FMOD Studio).
     pub name: Utf8CString,
@@ -5,12 +5,41 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use std::ffi::{c_int, c_void};
+use std::time::Duration;
 
 use fmod_sys::*;
 
 use crate::studio::{AdvancedSettings, InitFlags, System};
 use crate::{FmodResultExt, Result};
 
+/// How the Studio system processes its command queue, set with [`SystemBuilder::update_mode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UpdateMode {
+    /// Commands are processed on FMOD's Studio update thread, every `period`.
+    ///
+    /// [`System::update`] only submits the commands issued since the last update, so it is cheap to call from the game thread.
+    /// Changes take effect on the next tick of the update thread, so up to `period` later.
+    /// The period is quantized to a multiple of the mixer block length.
+    Async {
+        /// How often the update thread runs. FMOD's default is 20ms.
+        period: Duration,
+    },
+    /// Commands are processed during [`System::update`], on the thread that calls it.
+    ///
+    /// There is no update thread, so changes take effect as soon as [`System::update`] is called,
+    /// but that call also pays for all of Studio's processing.
+    /// FMOD expects every Studio call to then come from that same thread.
+    Synchronous,
+}
+
+impl Default for UpdateMode {
+    fn default() -> Self {
+        UpdateMode::Async {
+            period: Duration::from_millis(20),
+        }
+    }
+}
+
 /// A builder for creating and initializing a [`System`].
 ///
 /// Handles setting values that can only be set before initialization for you.
@@ -20,6 +49,7 @@ pub struct SystemBuilder {
     system: *mut FMOD_STUDIO_SYSTEM,
     core_builder: crate::SystemBuilder,
     sync_update: bool,
+    pub(crate) memory_tracking: bool,
 }
 
 #[cfg(not(feature = "thread-unsafe"))]
@@ -47,8 +77,11 @@ impl SystemBuilder {
             core_builder: crate::SystemBuilder {
                 system: core_system,
                 thread_unsafe: false,
+                extra_flags: crate::InitFlags::empty(),
+                non_block_threads: 1,
             },
             sync_update: false,
+            memory_tracking: false,
         })
     }
 
@@ -68,6 +101,44 @@ impl SystemBuilder {
         self.sync_update = true;
     }
 
+    /// Sets whether Studio runs its own update thread, and how often.
+    ///
+    /// This pairs [`InitFlags::SYNCHRONOUS_UPDATE`] with [`AdvancedSettings::studio_update_period`],
+    /// which only has an effect in async mode.
+    ///
+    /// # Safety
+    ///
+    /// Setting [`UpdateMode::Synchronous`] has the same safety requirements as [`SystemBuilder::synchronous_update`].
+    #[cfg(not(feature = "thread-unsafe"))]
+    pub unsafe fn update_mode(&mut self, mode: UpdateMode) -> Result<&mut Self> {
+        self.set_update_mode(mode)
+    }
+
+    /// Sets whether Studio runs its own update thread, and how often.
+    ///
+    /// This pairs [`InitFlags::SYNCHRONOUS_UPDATE`] with [`AdvancedSettings::studio_update_period`],
+    /// which only has an effect in async mode.
+    #[cfg(feature = "thread-unsafe")]
+    pub fn update_mode(&mut self, mode: UpdateMode) -> Result<&mut Self> {
+        self.set_update_mode(mode)
+    }
+
+    fn set_update_mode(&mut self, mode: UpdateMode) -> Result<&mut Self> {
+        match mode {
+            UpdateMode::Async { period } => {
+                // the system already exists at this point, so advanced settings can be read back and modified
+                let system = unsafe { System::from_ffi(self.system) };
+                let mut settings = system.get_advanced_settings()?;
+                settings.studio_update_period =
+                    c_int::try_from(period.as_millis()).unwrap_or(c_int::MAX);
+                self.settings(&settings)?;
+                self.sync_update = false;
+            }
+            UpdateMode::Synchronous => self.sync_update = true,
+        }
+        Ok(self)
+    }
+
     /// Sets advanced settings.
     pub fn settings(&mut self, settings: &AdvancedSettings) -> Result<&mut Self> {
         let mut settings = settings.into();
@@ -98,6 +169,22 @@ impl SystemBuilder {
         }
     }
 
+    /// Calls `configure` with the Studio system before it is initialized, then initializes it like [`SystemBuilder::build`].
+    ///
+    /// This is an escape hatch for settings that FMOD only accepts before initialization but that the builder doesn't cover.
+    /// The core system is available through [`System::get_core_system`].
+    /// If `configure` returns an error, the system isn't initialized and the error is returned.
+    pub fn build_with(
+        self,
+        max_channels: c_int,
+        studio_flags: InitFlags,
+        flags: crate::InitFlags,
+        configure: impl FnOnce(System) -> Result<()>,
+    ) -> Result<System> {
+        configure(unsafe { System::from_ffi(self.system) })?;
+        self.build(max_channels, studio_flags, flags)
+    }
+
     /// Returns the FMOD core `SystemBuilder`.
     ///
     /// This function only returns a `&mut` reference to prevent building the core `System` as building the studio `System` will handle that for you.
@@ -112,7 +199,7 @@ impl SystemBuilder {
         self,
         max_channels: c_int,
         mut studio_flags: InitFlags,
-        flags: crate::InitFlags,
+        mut flags: crate::InitFlags,
         driver_data: *mut c_void,
     ) -> Result<System> {
         if self.sync_update {
@@ -121,6 +208,15 @@ impl SystemBuilder {
             #[cfg(not(feature = "thread-unsafe"))]
             studio_flags.remove(InitFlags::SYNCHRONOUS_UPDATE);
         }
+        if self.memory_tracking {
+            studio_flags.insert(InitFlags::MEMORY_TRACKING);
+        }
+        // Studio memory tracking turns on core memory tracking, record that so the flags reflect it
+        if studio_flags.contains(InitFlags::MEMORY_TRACKING) {
+            flags.insert(crate::InitFlags::MEMORY_TRACKING);
+        }
+        flags.insert(self.core_builder.extra_flags);
+        let max_channels = self.core_builder.budgeted_max_channels(max_channels);
         unsafe {
             FMOD_Studio_System_Initialize(
                 self.system,
@@ -130,7 +226,13 @@ impl SystemBuilder {
                 driver_data,
             )
             .to_result()?;
-            Ok(System::from_ffi(self.system))
         }
+        let core_system = unsafe { crate::System::from_ffi(self.core_builder.system) };
+        core_system.record_init_flags(flags);
+        core_system.record_non_block_thread_count(self.core_builder.non_block_threads);
+        if flags.contains(crate::InitFlags::PROFILE_ENABLE) {
+            core_system.mark_profiler_enabled();
+        }
+        unsafe { Ok(System::from_ffi(self.system)) }
     }
 }
@@ -7,11 +7,11 @@
 use fmod_sys::*;
 use std::ffi::c_void;
 
-use crate::{FmodResultExt, Result};
 use crate::{
-    panic_wrapper,
+    CallbackContext, CallbackThread, panic_wrapper,
     studio::{Bank, System, SystemCallbackMask},
 };
+use crate::{FmodResultExt, Result};
 
 /// Trait for this particular FMOD callback.
 ///
@@ -53,6 +53,7 @@ unsafe extern "C" fn callback_impl<C: SystemCallback>(
     userdata: *mut c_void,
 ) -> FMOD_RESULT {
     panic_wrapper(|| {
+        let _context = CallbackContext::enter(CallbackThread::Studio);
         let system = unsafe { System::from_ffi(system) };
 
         let result = match kind {
@@ -0,0 +1,86 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use lanyard::Utf8CString;
+use std::ffi::c_int;
+
+use crate::Result;
+use crate::studio::{Bank, CpuUsage, EventDescription, System};
+
+/// A snapshot of everything a Studio [`System`] is doing, taken with [`System::debug_dump`].
+///
+/// Like [`crate::DebugDump`], which it includes for the core system.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DebugDump {
+    /// Dump of the core system.
+    pub core: crate::DebugDump,
+    /// CPU usage of the Studio engine.
+    pub cpu: CpuUsage,
+    /// Every loaded bank.
+    pub banks: Vec<BankDump>,
+}
+
+/// A [`Bank`] in a [`DebugDump`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BankDump {
+    /// Path of the bank, or [`None`] if the strings bank isn't loaded.
+    pub path: Option<String>,
+    /// Every event in the bank.
+    pub events: Vec<EventDump>,
+}
+
+/// An [`EventDescription`] in a [`DebugDump`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EventDump {
+    /// Path of the event, or [`None`] if the strings bank isn't loaded.
+    pub path: Option<String>,
+    /// Number of instances of the event.
+    pub instance_count: c_int,
+}
+
+impl System {
+    /// Takes a [`DebugDump`] of the system and its core system.
+    ///
+    /// This walks every bank, event, [`crate::ChannelGroup`] and [`crate::Channel`], so it isn't something to call every frame.
+    pub fn debug_dump(&self) -> Result<DebugDump> {
+        let banks = self
+            .get_bank_list()?
+            .into_iter()
+            .map(BankDump::capture)
+            .collect::<Result<_>>()?;
+        Ok(DebugDump {
+            core: self.get_core_system()?.debug_dump()?,
+            cpu: self.get_cpu_usage()?.0,
+            banks,
+        })
+    }
+}
+
+impl BankDump {
+    fn capture(bank: Bank) -> Result<Self> {
+        let events = bank
+            .get_event_list()?
+            .into_iter()
+            .map(EventDump::capture)
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            path: bank.get_path().ok().map(Utf8CString::into_string),
+            events,
+        })
+    }
+}
+
+impl EventDump {
+    fn capture(event: EventDescription) -> Result<Self> {
+        Ok(Self {
+            path: event.get_path().ok().map(Utf8CString::into_string),
+            instance_count: event.instance_count()?,
+        })
+    }
+}
@@ -0,0 +1,69 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::studio::{EventDescription, EventInstance, System};
+use crate::{Result, SystemMap};
+
+#[cfg(doc)]
+use crate::studio::CommandReplay;
+
+/// Creates [`EventInstance`]s for [`System::create_instance`] and replays.
+///
+/// Routing instance creation through a factory lets an instance pool hand out recycled instances,
+/// or lets tests substitute their own instances.
+/// Install with [`System::set_instance_factory`] or [`CommandReplay::set_instance_factory`].
+///
+/// This is not a hook on [`EventDescription::create_instance`], which always creates a new instance.
+/// Only code that creates instances through [`System::create_instance`] goes through the factory,
+/// and factories are free to call [`EventDescription::create_instance`] themselves.
+///
+/// No `self` parameter is passed to the factory!
+pub trait InstanceFactory {
+    /// Creates (or reuses) an instance of `description`.
+    ///
+    /// Returning [`None`] skips creating the instance.
+    /// In a replay, subsequent commands for a skipped instance are ignored.
+    fn create_instance(description: EventDescription) -> Result<Option<EventInstance>>;
+}
+
+/// An [`InstanceFactory`] that always creates a new instance with [`EventDescription::create_instance`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct DefaultInstanceFactory;
+
+impl InstanceFactory for DefaultInstanceFactory {
+    fn create_instance(description: EventDescription) -> Result<Option<EventInstance>> {
+        description.create_instance().map(Some)
+    }
+}
+
+type FactoryFn = fn(EventDescription) -> Result<Option<EventInstance>>;
+
+static FACTORIES: SystemMap<FactoryFn> = SystemMap::new();
+
+impl System {
+    /// Routes [`System::create_instance`] for this system through `F`.
+    ///
+    /// Instances created directly with [`EventDescription::create_instance`] don't go through `F`.
+    pub fn set_instance_factory<F: InstanceFactory>(&self) {
+        FACTORIES.insert(*self, F::create_instance);
+    }
+
+    /// Removes the factory set with [`System::set_instance_factory`], returning to [`DefaultInstanceFactory`].
+    pub fn clear_instance_factory(&self) {
+        FACTORIES.remove(*self);
+    }
+
+    /// Creates an instance of `description` through this system's [`InstanceFactory`].
+    ///
+    /// Without a factory set this is the same as [`EventDescription::create_instance`].
+    pub fn create_instance(&self, description: EventDescription) -> Result<Option<EventInstance>> {
+        // copy the fn pointer out so the factory runs without the lock held
+        let create = FACTORIES
+            .get(*self)
+            .unwrap_or(DefaultInstanceFactory::create_instance);
+        create(description)
+    }
+}
@@ -7,7 +7,10 @@
 use fmod_sys::*;
 
 use crate::studio::{InitFlags, System, SystemBuilder};
-use crate::{FmodResultExt, Result};
+use crate::{CallbackContext, FmodResultExt, Result};
+
+#[cfg(doc)]
+use crate::studio::EventInstance;
 
 impl System {
     /// A convenience function over [`SystemBuilder`] with sane defaults.
@@ -32,7 +35,26 @@ impl System {
     ///
     /// This function is not safe to be called at the same time across multiple threads.
     pub unsafe fn release(&self) -> Result<()> {
-        unsafe { FMOD_Studio_System_Release(self.inner.as_ptr()).to_result() }
+        CallbackContext::debug_assert_outside("studio::System::release");
+        // releasing the Studio system releases its core system too, which has to be forgotten like a core release would
+        let core_system = self.get_core_system().ok();
+        unsafe { FMOD_Studio_System_Release(self.inner.as_ptr()).to_result()? };
+        if let Some(core_system) = core_system {
+            core_system.forget_all();
+        }
+        self.forget_all();
+        Ok(())
+    }
+
+    /// Drops everything the crate tracks for this Studio system, once FMOD has released it.
+    ///
+    /// The core system's state is dropped separately, with [`crate::System::forget_all`].
+    pub(crate) fn forget_all(&self) {
+        self.clear_instance_factory();
+        self.discard_batch();
+        crate::studio::forget_ramps(*self);
+        self.update_timer().forget();
+        self.forget_emitters();
     }
 
     /// Update the FMOD Studio System.
@@ -44,14 +66,26 @@ impl System {
     ///
     /// When Studio is initialized with [`InitFlags::SYNCHRONOUS_UPDATE`] queued commands will be processed immediately when calling this function, the scheduling and update logic for the Studio system are executed and all callbacks are fired.
     /// This may block the calling thread for a substantial amount of time.
+    ///
+    /// Fades started with [`EventInstance::fade_volume_to`] and [`EventInstance::glide_pitch_to`] are stepped first, so their new values go out with this update.
+    ///
+    /// How long this takes can be measured with [`System::track_update_timing`] and [`System::set_update_budget`].
+    /// This is also where the memory budget set with [`crate::memory::set_budget`] is checked.
     pub fn update(&self) -> Result<()> {
-        unsafe { FMOD_Studio_System_Update(self.inner.as_ptr()) }.to_result()
+        CallbackContext::debug_assert_outside("studio::System::update");
+        crate::studio::update_ramps(*self);
+        let result = self
+            .update_timer()
+            .time(|| unsafe { FMOD_Studio_System_Update(self.inner.as_ptr()) }.to_result());
+        crate::memory::poll_budget_studio(*self);
+        result
     }
 
     /// This function blocks the calling thread until all pending commands have been executed and all non-blocking bank loads have been completed.
     ///
     /// This is equivalent to calling [`System::update`] and then sleeping until the asynchronous thread has finished executing all pending commands.
     pub fn flush_commands(&self) -> Result<()> {
+        CallbackContext::debug_assert_outside("studio::System::flush_commands");
         unsafe { FMOD_Studio_System_FlushCommands(self.inner.as_ptr()) }.to_result()
     }
 
@@ -59,6 +93,7 @@ impl System {
     ///
     /// This function may stall for a long time if other threads are continuing to issue calls to load and unload sample data, e.g. by creating new event instances.
     pub fn flush_sample_loading(&self) -> Result<()> {
+        CallbackContext::debug_assert_outside("studio::System::flush_sample_loading");
         unsafe { FMOD_Studio_System_FlushSampleLoading(self.inner.as_ptr()) }.to_result()
     }
 }
@@ -0,0 +1,132 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use lanyard::Utf8CString;
+use std::ffi::c_int;
+
+use crate::Result;
+use crate::memory::BusMemory;
+use crate::studio::{EventDescription, InitFlags, MemoryUsage, System, SystemBuilder};
+
+/// Where a Studio [`System`]'s memory goes, taken with [`System::memory_report`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryReport {
+    /// Whether the system was initialized with [`InitFlags::MEMORY_TRACKING`],
+    /// or [`None`] if it wasn't built by this crate's [`SystemBuilder`] so that isn't known.
+    ///
+    /// Without it FMOD doesn't track memory per object, and every usage in the report is 0.
+    pub tracking_enabled: Option<bool>,
+    /// Bytes currently allocated by FMOD, see [`crate::memory::get_stats`].
+    pub current: c_int,
+    /// The most bytes FMOD has had allocated at once.
+    pub max: c_int,
+    /// Memory used by the whole system.
+    pub system: MemoryUsage,
+    /// Memory used by each bus, largest first.
+    pub buses: Vec<BusMemory>,
+    /// Memory used by each event with instances, largest first.
+    pub events: Vec<EventMemory>,
+}
+
+/// The memory used by the instances of one event, in a [`MemoryReport`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventMemory {
+    /// Path of the event, or [`None`] if the strings bank isn't loaded.
+    pub path: Option<String>,
+    /// Number of instances of the event.
+    pub instance_count: c_int,
+    /// The memory of all instances added up.
+    ///
+    /// Instances of an event share their sample data, so `sample_data` is that of a single instance.
+    pub usage: MemoryUsage,
+}
+
+impl SystemBuilder {
+    /// Initializes the system with [`InitFlags::MEMORY_TRACKING`] (and the core [`crate::InitFlags::MEMORY_TRACKING`] it implies),
+    /// so [`System::memory_report`] and the `get_memory_usage` functions have something to report.
+    ///
+    /// Tracking costs a little memory and CPU, and FMOD only reports usage with the logging libraries,
+    /// see the `force-debug` feature of `fmod-audio-sys`.
+    pub fn memory_tracking(&mut self) -> &mut Self {
+        self.memory_tracking = true;
+        self
+    }
+}
+
+impl System {
+    /// Collects the memory usage of the system, its buses and the instances of each event.
+    ///
+    /// Memory usage is only tracked when the system was initialized with [`InitFlags::MEMORY_TRACKING`],
+    /// see [`SystemBuilder::memory_tracking`].
+    /// If it is known not to have been, this warns (through the `log` crate with the `log` feature and on stderr otherwise)
+    /// since the report will be all zeros, and [`MemoryReport::tracking_enabled`] is `Some(false)`.
+    pub fn memory_report(&self) -> Result<MemoryReport> {
+        let tracking_enabled = self
+            .get_core_system()?
+            .get_init_flags()
+            .map(|flags| flags.contains(crate::InitFlags::MEMORY_TRACKING));
+        if tracking_enabled == Some(false) {
+            warn_untracked();
+        }
+
+        let (current, max) = crate::memory::get_stats(false)?;
+        let mut events = Vec::new();
+        for bank in self.get_bank_list()? {
+            for event in bank.get_event_list()? {
+                if event.instance_count()? > 0 {
+                    events.push(EventMemory::capture(event)?);
+                }
+            }
+        }
+        events.sort_by(|a, b| b.usage.inclusive.cmp(&a.usage.inclusive));
+
+        Ok(MemoryReport {
+            tracking_enabled,
+            current,
+            max,
+            system: self.get_memory_usage()?,
+            buses: crate::memory::bus_breakdown(*self),
+            events,
+        })
+    }
+}
+
+impl EventMemory {
+    fn capture(event: EventDescription) -> Result<Self> {
+        let instances = event.get_instance_list()?;
+        let mut usage = MemoryUsage {
+            exclusive: 0,
+            inclusive: 0,
+            sample_data: 0,
+        };
+        for instance in &instances {
+            let instance_usage = instance.get_memory_usage()?;
+            usage.exclusive += instance_usage.exclusive;
+            usage.inclusive += instance_usage.inclusive;
+            usage.sample_data = usage.sample_data.max(instance_usage.sample_data);
+        }
+        Ok(Self {
+            path: event.get_path().ok().map(Utf8CString::into_string),
+            instance_count: instances.len() as c_int,
+            usage,
+        })
+    }
+}
+
+#[cfg(feature = "log")]
+fn warn_untracked() {
+    log::warn!(
+        target: "fmod",
+        "memory report requested, but the system wasn't initialized with memory tracking so every usage is 0"
+    );
+}
+
+#[cfg(not(feature = "log"))]
+fn warn_untracked() {
+    eprintln!(
+        "warning: memory report requested, but the system wasn't initialized with memory tracking so every usage is 0"
+    );
+}
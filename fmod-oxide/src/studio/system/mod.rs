@@ -9,20 +9,31 @@ use std::ptr::NonNull;
 use fmod_sys::*;
 
 mod bank;
+mod batch;
 mod builder;
 mod callback;
 mod command_replay;
+mod diagnostics;
 mod general;
+mod instance_factory;
 mod lifecycle;
 mod listener;
+mod memory_report;
 mod misc;
 mod parameter;
+mod pause;
 mod plugins;
 mod profiling; // things too small to really make their own module
+mod ui;
+mod update_timing;
 
 pub use bank::LoadBankUserdata;
-pub use builder::SystemBuilder;
+pub use batch::BatchCommand;
+pub use builder::{SystemBuilder, UpdateMode};
 pub use callback::SystemCallback;
+pub use diagnostics::{BankDump, DebugDump, EventDump};
+pub use instance_factory::{DefaultInstanceFactory, InstanceFactory};
+pub use memory_report::{EventMemory, MemoryReport};
 
 /// The main system object for FMOD Studio.
 ///
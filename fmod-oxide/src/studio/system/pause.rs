@@ -0,0 +1,60 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::Result;
+use crate::studio::{Bus, System};
+
+impl System {
+    /// Pauses everything except `except` (and the events and buses routed into them), such as a UI bus in a pause menu.
+    ///
+    /// This locks the [`crate::ChannelGroup`]s of `except` and flushes commands so they exist,
+    /// then walks the core mixer hierarchy using [`crate::System::pause_all_except`].
+    ///
+    /// The pause is applied to core [`crate::ChannelGroup`]s, not through [`Bus::set_paused`],
+    /// so groups that Studio destroys and recreates while idle will not stay paused.
+    /// Undo this with [`System::resume_all_buses_except`], passing the same buses.
+    pub fn pause_all_buses_except(&self, except: &[Bus]) -> Result<()> {
+        self.set_paused_except(true, except)
+    }
+
+    /// Unpauses everything that was paused by [`System::pause_all_buses_except`].
+    pub fn resume_all_buses_except(&self, except: &[Bus]) -> Result<()> {
+        self.set_paused_except(false, except)
+    }
+
+    fn set_paused_except(&self, paused: bool, except: &[Bus]) -> Result<()> {
+        let mut locked = 0;
+        let result = except
+            .iter()
+            .try_for_each(|bus| {
+                bus.lock_channel_group()?;
+                locked += 1;
+                Ok(())
+            })
+            .and_then(|()| self.flush_commands())
+            .and_then(|()| {
+                let groups = except
+                    .iter()
+                    .map(Bus::get_channel_group)
+                    .collect::<Result<Vec<_>>>()?;
+                let core = self.get_core_system()?;
+                if paused {
+                    core.pause_all_except(&groups)
+                } else {
+                    core.resume_all_except(&groups)
+                }
+            });
+        // unlock every bus that was locked even if one fails, so none is left locked
+        let mut unlocked = Ok(());
+        for bus in &except[..locked] {
+            let result = bus.unlock_channel_group();
+            if unlocked.is_ok() {
+                unlocked = result;
+            }
+        }
+        result.and(unlocked)
+    }
+}
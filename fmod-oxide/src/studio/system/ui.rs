@@ -0,0 +1,39 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::c_int;
+
+use crate::Result;
+use crate::studio::{EventDescription, EventInstance, System};
+
+impl System {
+    /// Plays an instance of `description` as a UI sound.
+    ///
+    /// Studio events have no head relative mode, so 3D events are placed on listener 0 when they start.
+    /// A 3D event that plays for a while will drift as the listener moves,
+    /// unless it is kept in place with [`EventInstance::attach_to_listener`] every frame;
+    /// authoring UI events as 2D in Studio avoids the problem entirely.
+    ///
+    /// The instance is released once it stops, like a fire and forget sound.
+    pub fn play_ui(&self, description: EventDescription) -> Result<EventInstance> {
+        let instance = description.create_instance()?;
+        // the instance is only destroyed once it stops, so it can be released right away
+        instance.release()?;
+        if description.is_3d()? {
+            instance.attach_to_listener(*self, 0)?;
+        }
+        instance.start()?;
+        Ok(instance)
+    }
+}
+
+impl EventInstance {
+    /// Moves this instance onto `listener`, so it is heard without panning or attenuation.
+    pub fn attach_to_listener(&self, system: System, listener: c_int) -> Result<()> {
+        let (attributes, _) = system.get_listener_attributes(listener)?;
+        self.set_3d_attributes(attributes)
+    }
+}
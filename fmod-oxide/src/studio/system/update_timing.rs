@@ -0,0 +1,47 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::studio::System;
+use crate::{Result, UpdateBudget, UpdateTimer, UpdateTimings};
+
+#[cfg(doc)]
+use crate::Error;
+
+impl System {
+    /// Starts measuring how long [`System::update`] takes, in windows of `frames` updates.
+    ///
+    /// Like [`crate::System::track_update_timing`], but for Studio updates,
+    /// which is where command queue flushes (and with [`crate::studio::UpdateMode::Synchronous`] all of Studio's processing) show up.
+    ///
+    /// Returns [`Error::InvalidParam`] if `frames` is 0.
+    pub fn track_update_timing(&self, frames: u32) -> Result<()> {
+        self.update_timer().track(frames)
+    }
+
+    /// Stops measuring how long [`System::update`] takes.
+    pub fn untrack_update_timing(&self) {
+        self.update_timer().untrack();
+    }
+
+    /// The [`UpdateTimings`] of the last full window, or [`None`] if there isn't one yet.
+    pub fn get_update_timings(&self) -> Option<UpdateTimings> {
+        self.update_timer().timings()
+    }
+
+    /// Sets an [`UpdateBudget`] that is checked after every [`System::update`], or removes it with [`None`].
+    pub fn set_update_budget(&self, budget: Option<UpdateBudget>) {
+        self.update_timer().set_budget(budget);
+    }
+
+    /// The [`UpdateBudget`] set with [`System::set_update_budget`].
+    pub fn get_update_budget(&self) -> Option<UpdateBudget> {
+        self.update_timer().budget()
+    }
+
+    pub(crate) fn update_timer(&self) -> UpdateTimer<Self> {
+        UpdateTimer(*self)
+    }
+}
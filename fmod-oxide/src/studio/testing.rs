@@ -0,0 +1,226 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use lanyard::Utf8CStr;
+use std::ffi::c_int;
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+use crate::studio::{
+    EventCallbackMask, EventInstance, EventInstanceCallback, ProgrammerSoundProperties,
+    TimelineBeatProperties, TimelineMarkerProperties, TimelineNestedBeatProperties,
+};
+use crate::{CallbackContext, CallbackThread, Result, Sound};
+
+/// An [`EventInstance`] handle that doesn't belong to any event, for callbacks synthesized by an [`EventCallbackHarness`].
+///
+/// It is only meant to be compared and passed around.
+/// Studio validates handles, so calling FMOD with it should fail with [`crate::Error::InvalidHandle`],
+/// but code under test shouldn't rely on that.
+pub fn placeholder_instance() -> EventInstance {
+    EventInstance {
+        inner: NonNull::dangling(),
+    }
+}
+
+fn placeholder_sound() -> Sound {
+    Sound {
+        inner: NonNull::dangling(),
+    }
+}
+
+/// What an [`EventInstanceCallback::create_programmer_sound`] handed back to an [`EventCallbackHarness`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProgrammerSound {
+    /// The sound the callback provided, [`None`] if it didn't provide one.
+    pub sound: Option<Sound>,
+    /// The subsound index the callback set, `-1` if it didn't set one.
+    pub subsound_index: c_int,
+}
+
+/// Invokes the methods of an [`EventInstanceCallback`] the way FMOD would, without a real event playing.
+///
+/// This makes game-side callback handlers testable in isolation: fire a marker, a beat or a programmer sound request
+/// at the handler and check what it did.
+/// Like FMOD, the harness only calls methods whose callback type is in `C::MASK`, and returns [`Ok`] without calling anything otherwise,
+/// so a handler missing from its mask shows up in tests.
+/// While a method runs, [`CallbackContext::current`] reports [`CallbackThread::Studio`], as in a real callback.
+///
+/// Unlike a real callback, panics aren't caught and reach the test.
+///
+/// ```ignore
+/// let harness = EventCallbackHarness::<Markers>::new();
+/// harness.timeline_marker(c!("chorus"), 12_000)?;
+/// ```
+#[derive(Debug)]
+pub struct EventCallbackHarness<C> {
+    instance: EventInstance,
+    callback: PhantomData<fn() -> C>,
+}
+
+impl<C: EventInstanceCallback> Default for EventCallbackHarness<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: EventInstanceCallback> EventCallbackHarness<C> {
+    /// Creates a harness that passes [`placeholder_instance`] as the event.
+    pub fn new() -> Self {
+        Self::with_instance(placeholder_instance())
+    }
+
+    /// Creates a harness that passes `instance` as the event, such as an instance of a test bank.
+    pub fn with_instance(instance: EventInstance) -> Self {
+        Self {
+            instance,
+            callback: PhantomData,
+        }
+    }
+
+    /// The event passed to the callback.
+    pub fn instance(&self) -> EventInstance {
+        self.instance
+    }
+
+    fn invoke(
+        &self,
+        kind: EventCallbackMask,
+        f: impl FnOnce(EventInstance) -> Result<()>,
+    ) -> Result<()> {
+        if !C::MASK.contains(kind) {
+            return Ok(());
+        }
+        let _context = CallbackContext::enter(CallbackThread::Studio);
+        f(self.instance)
+    }
+
+    /// Invokes [`EventInstanceCallback::created`].
+    pub fn created(&self) -> Result<()> {
+        self.invoke(EventCallbackMask::CREATED, C::created)
+    }
+
+    /// Invokes [`EventInstanceCallback::destroyed`].
+    pub fn destroyed(&self) -> Result<()> {
+        self.invoke(EventCallbackMask::DESTROYED, C::destroyed)
+    }
+
+    /// Invokes [`EventInstanceCallback::starting`].
+    pub fn starting(&self) -> Result<()> {
+        self.invoke(EventCallbackMask::STARTING, C::starting)
+    }
+
+    /// Invokes [`EventInstanceCallback::started`].
+    pub fn started(&self) -> Result<()> {
+        self.invoke(EventCallbackMask::STARTED, C::started)
+    }
+
+    /// Invokes [`EventInstanceCallback::restarted`].
+    pub fn restarted(&self) -> Result<()> {
+        self.invoke(EventCallbackMask::RESTARTED, C::restarted)
+    }
+
+    /// Invokes [`EventInstanceCallback::stopped`].
+    pub fn stopped(&self) -> Result<()> {
+        self.invoke(EventCallbackMask::STOPPED, C::stopped)
+    }
+
+    /// Invokes [`EventInstanceCallback::start_failed`].
+    pub fn start_failed(&self) -> Result<()> {
+        self.invoke(EventCallbackMask::START_FAILED, C::start_failed)
+    }
+
+    /// Invokes [`EventInstanceCallback::real_to_virtual`].
+    pub fn real_to_virtual(&self) -> Result<()> {
+        self.invoke(EventCallbackMask::REAL_TO_VIRTUAL, C::real_to_virtual)
+    }
+
+    /// Invokes [`EventInstanceCallback::virtual_to_real`].
+    pub fn virtual_to_real(&self) -> Result<()> {
+        self.invoke(EventCallbackMask::VIRTUAL_TO_REAL, C::virtual_to_real)
+    }
+
+    /// Invokes [`EventInstanceCallback::timeline_marker`] for the marker `name`, `position` milliseconds into the timeline.
+    pub fn timeline_marker(&self, name: &Utf8CStr, position: c_int) -> Result<()> {
+        let props = TimelineMarkerProperties {
+            name: name.to_cstring(),
+            position,
+        };
+        self.invoke(EventCallbackMask::TIMELINE_MARKER, |event| {
+            C::timeline_marker(event, props)
+        })
+    }
+
+    /// Invokes [`EventInstanceCallback::timeline_beat`].
+    pub fn timeline_beat(&self, props: TimelineBeatProperties) -> Result<()> {
+        self.invoke(EventCallbackMask::TIMELINE_BEAT, |event| {
+            C::timeline_beat(event, props)
+        })
+    }
+
+    /// Invokes [`EventInstanceCallback::nested_timeline_beat`].
+    pub fn nested_timeline_beat(&self, props: TimelineNestedBeatProperties) -> Result<()> {
+        self.invoke(EventCallbackMask::NESTED_TIMELINE_BEAT, |event| {
+            C::nested_timeline_beat(event, props)
+        })
+    }
+
+    /// Invokes [`EventInstanceCallback::create_programmer_sound`] for the programmer instrument `name`,
+    /// returning the sound and subsound index the callback provided.
+    ///
+    /// If the callback type isn't in `C::MASK` nothing is provided.
+    pub fn create_programmer_sound(&self, name: &Utf8CStr) -> Result<ProgrammerSound> {
+        // stands in for the null sound FMOD passes, and is never handed back
+        let placeholder = placeholder_sound();
+        let mut sound = placeholder;
+        let mut subsound_index = -1;
+        self.invoke(EventCallbackMask::CREATE_PROGRAMMER_SOUND, |event| {
+            let props = ProgrammerSoundProperties {
+                name: name.to_cstring(),
+                sound: &mut sound,
+                subsound_index: &mut subsound_index,
+            };
+            C::create_programmer_sound(event, props)
+        })?;
+        Ok(ProgrammerSound {
+            sound: (sound != placeholder).then_some(sound),
+            subsound_index,
+        })
+    }
+
+    /// Invokes [`EventInstanceCallback::destroy_programmer_sound`] for the programmer instrument `name`,
+    /// with the sound that [`EventCallbackHarness::create_programmer_sound`] returned.
+    pub fn destroy_programmer_sound(
+        &self,
+        name: &Utf8CStr,
+        provided: ProgrammerSound,
+    ) -> Result<()> {
+        let mut sound = provided.sound.unwrap_or_else(placeholder_sound);
+        let mut subsound_index = provided.subsound_index;
+        self.invoke(EventCallbackMask::DESTROY_PROGRAMMER_SOUND, |event| {
+            let props = ProgrammerSoundProperties {
+                name: name.to_cstring(),
+                sound: &mut sound,
+                subsound_index: &mut subsound_index,
+            };
+            C::destroy_programmer_sound(event, props)
+        })
+    }
+
+    /// Invokes [`EventInstanceCallback::sound_played`].
+    pub fn sound_played(&self, sound: Sound) -> Result<()> {
+        self.invoke(EventCallbackMask::SOUND_PLAYED, |event| {
+            C::sound_played(event, sound)
+        })
+    }
+
+    /// Invokes [`EventInstanceCallback::sound_stopped`].
+    pub fn sound_stopped(&self, sound: Sound) -> Result<()> {
+        self.invoke(EventCallbackMask::SOUND_STOPPED, |event| {
+            C::sound_stopped(event, sound)
+        })
+    }
+}
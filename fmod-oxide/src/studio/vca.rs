@@ -12,7 +12,7 @@ use lanyard::Utf8CString;
 use crate::Guid;
 use crate::{FmodResultExt, Result};
 
-use super::get_string_out_size;
+use super::{get_string_out_size, get_string_out_size_bounded};
 
 /// Represents a global mixer VCA.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -96,6 +96,15 @@ impl Vca {
         })
     }
 
+    /// Retrieves the path, truncated to at most `max_len` bytes.
+    ///
+    /// Unlike [`Self::get_path`] this never allocates more than `max_len + 1` bytes, and truncation is not an error.
+    pub fn get_path_with_max_len(&self, max_len: usize) -> Result<Utf8CString> {
+        get_string_out_size_bounded(max_len, |path, size, ret| unsafe {
+            FMOD_Studio_VCA_GetPath(self.inner.as_ptr(), path, size, ret)
+        })
+    }
+
     /// Checks that the VCA reference is valid.
     pub fn is_valid(&self) -> bool {
         unsafe { FMOD_Studio_VCA_IsValid(self.inner.as_ptr()).into() }
@@ -1,5 +1,6 @@
 use crate as fmod;
 use std::sync::LazyLock;
+use std::time::Duration;
 
 pub static SYSTEM: LazyLock<fmod::studio::System> = LazyLock::new(|| {
     fn try_make_system() -> fmod::Result<fmod::studio::System> {
@@ -32,3 +33,325 @@ fn get_core() -> fmod::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn nonblocking_sound_ready() -> fmod::Result<()> {
+    let core = SYSTEM.get_core_system()?;
+    let sound = fmod::SoundBuilder::open_user(44100, 1, 44100, fmod::SoundFormat::PCM16)
+        .with_mode(fmod::Mode::NONBLOCKING)
+        .with_nonblock_callback::<fmod::ReadyNotifier>()
+        .build(core)?;
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    sound.on_ready(move |_, result| {
+        let _ = sender.send(result);
+    })?;
+    receiver
+        .recv_timeout(Duration::from_secs(5))
+        .expect("sound never finished opening")?;
+
+    sound.release()
+}
+
+#[test]
+fn vector_math() {
+    let x = fmod::Vector::new(1.0, 0.0, 0.0);
+    let y = fmod::Vector::new(0.0, 1.0, 0.0);
+    assert_eq!(x.cross(y), fmod::Vector::new(0.0, 0.0, 1.0));
+    assert_eq!(x.dot(y), 0.0);
+    assert_eq!((x * 3.0 - y).length(), 10.0_f32.sqrt());
+    assert_eq!(-x + x, fmod::Vector::default());
+    assert_eq!(fmod::Vector::default().try_normalize(), None);
+
+    let attributes = fmod::Attributes3D {
+        forward: x * 2.0,
+        up: x + y,
+        ..Default::default()
+    };
+    assert_eq!(attributes.validated(), Err(fmod::Error::InvalidVector));
+    let normalized = attributes.normalize_orientation().unwrap();
+    assert_eq!((normalized.forward, normalized.up), (x, y));
+    assert_eq!(normalized.validated(), Ok(normalized));
+}
+
+#[test]
+fn decibels_conversion() {
+    assert_eq!(fmod::Decibels::UNITY.to_linear(), 1.0);
+    assert_eq!(fmod::Decibels::SILENCE.to_linear(), 0.0);
+    assert!(fmod::Decibels::from_linear(0.0).is_silent());
+    assert!(fmod::Decibels::from_linear(-1.0).is_silent());
+    assert!((fmod::Decibels::from_linear(0.5).0 + 6.0206).abs() < 0.001);
+    assert!((fmod::Decibels(-6.0206).to_linear() - 0.5).abs() < 0.001);
+}
+
+#[test]
+fn automation_curve_evaluate() -> fmod::Result<()> {
+    let curve = fmod::AutomationCurve::new([
+        fmod::Keyframe::new(2.0, 1.0).with_interpolation(fmod::Interpolation::Step),
+        fmod::Keyframe::new(0.0, 0.0),
+        fmod::Keyframe::new(4.0, 0.0),
+    ])?;
+    assert_eq!(curve.duration(), 4.0);
+    assert_eq!(curve.evaluate(-1.0), 0.0);
+    assert_eq!(curve.evaluate(1.0), 0.5);
+    assert_eq!(curve.evaluate(3.0), 1.0);
+    assert_eq!(curve.evaluate(5.0), 0.0);
+    assert!(fmod::AutomationCurve::new([]).is_err());
+    Ok(())
+}
+
+#[test]
+fn callback_harness_respects_mask() -> fmod::Result<()> {
+    use fmod::studio::{EventInstance, TimelineMarkerProperties};
+    use std::sync::atomic::{AtomicI32, Ordering};
+
+    static LAST_MARKER: AtomicI32 = AtomicI32::new(0);
+    struct Markers;
+    fmod::event_instance_callback! {
+        impl EventInstanceCallback for Markers {
+            /// Records where the last marker was.
+            #[inline]
+            fn timeline_marker(_event: EventInstance, props: TimelineMarkerProperties) -> fmod::Result<()> {
+                assert!(fmod::CallbackContext::current().is_some());
+                LAST_MARKER.store(props.position, Ordering::Relaxed);
+                Ok(())
+            }
+        }
+    }
+
+    let harness = fmod::studio::testing::EventCallbackHarness::<Markers>::new();
+    harness.timeline_marker(fmod::c!("chorus"), 1200)?;
+    assert_eq!(LAST_MARKER.load(Ordering::Relaxed), 1200);
+    // not in the mask, so nothing is provided
+    let provided = harness.create_programmer_sound(fmod::c!("voice"))?;
+    assert_eq!(provided.sound, None);
+    assert_eq!(provided.subsound_index, -1);
+    Ok(())
+}
+
+#[test]
+fn guid_round_trip() {
+    let text = "{00112233-4455-6677-8899-aabbccddeeff}";
+    let guid: fmod::Guid = text.parse().unwrap();
+    assert_eq!(guid.to_string(), text);
+    assert_eq!(guid.to_bytes()[..4], [0x00, 0x11, 0x22, 0x33]);
+    assert_eq!(fmod::Guid::from_bytes(guid.to_bytes()), guid);
+    assert!(
+        "00112233-4455-6677-8899_aabbccddeeff"
+            .parse::<fmod::Guid>()
+            .is_err()
+    );
+}
+
+#[test]
+fn entity_map_insert_remove() {
+    let map = fmod::AudioEntityMap::<fmod::Channel, u32>::with_capacity(4);
+    // the map only compares addresses, so these never need to be real channels
+    let channels: Vec<_> = (1..=4)
+        .map(|i| unsafe { fmod::Channel::from_ffi((i * 16) as *mut _) })
+        .collect();
+    for (key, &channel) in channels.iter().enumerate() {
+        map.insert(channel, key as u32).unwrap();
+    }
+    assert_eq!(map.get(channels[2]), Some(2));
+    assert_eq!(map.remove(channels[2]), Some(2));
+    assert_eq!(map.get(channels[2]), None);
+    map.insert(channels[3], 7).unwrap();
+    assert_eq!(map.get(channels[3]), Some(7));
+    map.insert(channels[2], 9).unwrap();
+    assert_eq!(map.get(channels[2]), Some(9));
+}
+
+#[test]
+fn raw_handle_round_trip() {
+    use fmod::RawHandle;
+
+    // nothing is dereferenced, so the pointer never needs to be a real sound
+    let raw = 64 as *mut fmod::sys::FMOD_SOUND;
+    let sound = unsafe { fmod::Sound::from_raw(raw) };
+    assert_eq!(sound.as_raw(), sound.as_ptr());
+    assert_eq!(sound.as_raw(), raw);
+    assert!(unsafe { fmod::Sound::try_from_raw(std::ptr::null_mut()) }.is_none());
+}
+
+#[test]
+fn default_mix_matrix() -> fmod::Result<()> {
+    let core = SYSTEM.get_core_system()?;
+    let mut matrix =
+        core.get_default_mix_matrix(fmod::SpeakerMode::Stereo, fmod::SpeakerMode::Quad)?;
+    assert_eq!((matrix.in_channels, matrix.out_channels), (2, 4));
+    assert_eq!(matrix.data.len(), 8);
+    assert_eq!(matrix.gain(4, 0), None);
+    matrix.set_gain(3, 1, 0.5)?;
+    assert_eq!(matrix.gain(3, 1), Some(0.5));
+    Ok(())
+}
+
+#[test]
+fn wav_header_is_canonical() -> fmod::Result<()> {
+    let mut bytes = Vec::new();
+    fmod::core::wav::write_pcm16(&mut bytes, 48000, 2, &[1, -1])?;
+    let mut expected = Vec::new();
+    expected.extend_from_slice(b"RIFF");
+    expected.extend_from_slice(&40u32.to_le_bytes());
+    expected.extend_from_slice(b"WAVEfmt ");
+    expected.extend_from_slice(&16u32.to_le_bytes());
+    expected.extend_from_slice(&1u16.to_le_bytes());
+    expected.extend_from_slice(&2u16.to_le_bytes());
+    expected.extend_from_slice(&48000u32.to_le_bytes());
+    expected.extend_from_slice(&192_000u32.to_le_bytes());
+    expected.extend_from_slice(&4u16.to_le_bytes());
+    expected.extend_from_slice(&16u16.to_le_bytes());
+    expected.extend_from_slice(b"data");
+    expected.extend_from_slice(&4u32.to_le_bytes());
+    expected.extend_from_slice(&[1, 0, 0xff, 0xff]);
+    assert_eq!(bytes, expected);
+
+    assert_eq!(
+        fmod::core::wav::write_pcm16(&mut Vec::new(), 48000, 0, &[]),
+        Err(fmod::Error::InvalidParam)
+    );
+    assert_eq!(
+        fmod::core::wav::write_pcm16(&mut Vec::new(), std::ffi::c_int::MAX, 2, &[]),
+        Err(fmod::Error::InvalidParam)
+    );
+    Ok(())
+}
+
+#[test]
+fn pcm16_resample_and_downmix() {
+    use fmod::core::wav::convert_pcm16;
+
+    // stereo to mono averages the channels
+    let mono = convert_pcm16(&[100, 300, -200, 0], (48000, 2), (48000, 1));
+    assert_eq!(mono, [200, -100]);
+    // mono to stereo repeats the channel
+    let stereo = convert_pcm16(&[5, 7], (48000, 1), (48000, 2));
+    assert_eq!(stereo, [5, 5, 7, 7]);
+    // doubling the rate interpolates between frames
+    let upsampled = convert_pcm16(&[0, 100], (24000, 1), (48000, 1));
+    assert_eq!(upsampled, [0, 50, 100, 100]);
+    // halving it keeps every other frame
+    let downsampled = convert_pcm16(&[0, 10, 20, 30], (48000, 1), (24000, 1));
+    assert_eq!(downsampled, [0, 20]);
+    assert!(convert_pcm16(&[], (48000, 2), (44100, 2)).is_empty());
+}
+
+#[test]
+fn panner_mix_matrix_is_out_by_in() {
+    // more outputs than inputs, so transposed dimensions would show up as misplaced gains
+    let matrix = fmod::Panner::stereo(-1.0).mix_matrix(2, 12);
+    assert_eq!((matrix.in_channels, matrix.out_channels), (2, 12));
+    assert_eq!(matrix.data.len(), 24);
+    assert_eq!(matrix.gain(0, 0), Some(1.0));
+    assert_eq!(matrix.gain(0, 1), Some(0.0));
+    assert!(matrix.gain(1, 1).unwrap().abs() < 1e-6);
+    assert_eq!(matrix.gain(11, 1), Some(0.0));
+    assert_eq!(matrix.gain(0, 2), None);
+
+    let mono = fmod::Panner::surround([(fmod::Speaker::TopBackRight, 0.5)]).mix_matrix(1, 12);
+    assert_eq!(mono.gain(11, 0), Some(0.5));
+    assert_eq!(mono.gain(0, 0), Some(0.0));
+}
+
+#[test]
+fn channels_playing_reports_overage() {
+    let budget = fmod::ChannelBudget {
+        real_channels: 32,
+        virtual_channels: 64,
+    };
+    let full = fmod::ChannelsPlaying {
+        total: 64,
+        real: 32,
+    };
+    assert_eq!((full.headroom(budget), full.overage(budget)), (0, 0));
+    let over = fmod::ChannelsPlaying {
+        total: 70,
+        real: 32,
+    };
+    assert_eq!((over.headroom(budget), over.overage(budget)), (0, 6));
+}
+
+#[test]
+fn entity_map_lookups_never_see_a_reused_slot() {
+    // one slot pair, so removing one handle and inserting the other keeps reusing the same slot
+    let map = fmod::AudioEntityMap::<fmod::Channel, u64>::with_capacity(1);
+    let a = unsafe { fmod::Channel::from_ffi(16 as *mut _) };
+    let b = unsafe { fmod::Channel::from_ffi(32 as *mut _) };
+    let done = std::sync::atomic::AtomicBool::new(false);
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            for _ in 0..100_000 {
+                map.insert(a, 1).unwrap();
+                map.remove(a);
+                map.insert(b, 2).unwrap();
+                map.remove(b);
+            }
+            done.store(true, std::sync::atomic::Ordering::Release);
+        });
+        while !done.load(std::sync::atomic::Ordering::Acquire) {
+            assert!(matches!(map.get(a), None | Some(1)));
+            assert!(matches!(map.get(b), None | Some(2)));
+        }
+    });
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn automation_curve_deserializes_through_new() {
+    let curve: fmod::AutomationCurve = serde_json::from_str(
+        r#"{"keyframes": [
+            {"time": 2.0, "value": 1.0, "interpolation": "Linear"},
+            {"time": 0.0, "value": 0.0, "interpolation": "Linear"}
+        ], "looping": true}"#,
+    )
+    .unwrap();
+    assert_eq!(curve.keyframes()[0].time, 0.0);
+    assert_eq!(curve.keyframes()[1].time, 2.0);
+    assert!(curve.is_looping());
+
+    let round_trip: fmod::AutomationCurve =
+        serde_json::from_str(&serde_json::to_string(&curve).unwrap()).unwrap();
+    assert_eq!(round_trip, curve);
+
+    let empty = serde_json::from_str::<fmod::AutomationCurve>(r#"{"keyframes": []}"#);
+    assert!(empty.is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn decibels_json_round_trip() {
+    for level in [
+        fmod::Decibels::SILENCE,
+        fmod::Decibels::UNITY,
+        fmod::Decibels(-6.0),
+    ] {
+        let json = serde_json::to_string(&level).unwrap();
+        assert_eq!(
+            serde_json::from_str::<fmod::Decibels>(&json).unwrap(),
+            level
+        );
+    }
+    assert_eq!(
+        serde_json::to_string(&fmod::Decibels::SILENCE).unwrap(),
+        "null"
+    );
+}
+
+#[test]
+fn dsp_parameter_names_are_cut_on_a_char_boundary() {
+    // 14 bytes of ascii and a two byte character, which doesn't fit in the 15 bytes FMOD allows
+    let desc = fmod::DspParameterTable::float_desc(c"aaaaaaaaaaaaaaä", c"", c"", 0.0, 1.0, 0.5);
+    let name = unsafe { std::ffi::CStr::from_ptr(desc.name.as_ptr()) };
+    assert_eq!(name.to_str(), Ok("aaaaaaaaaaaaaa"));
+}
+
+#[test]
+fn strings_from_buffers_keep_text_after_invalid_bytes() {
+    // a cut off "ä" at the end is dropped, but text after an invalid byte in the middle is kept
+    assert_eq!(fmod::string_from_buffer(b"ab\xC3").as_str(), "ab");
+    assert_eq!(
+        fmod::string_from_buffer(b"a\xFFb\0c").as_str(),
+        "a\u{FFFD}b"
+    );
+}
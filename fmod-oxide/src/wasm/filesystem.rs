@@ -0,0 +1,160 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::{c_char, c_int, c_uint, c_void};
+use std::future::Future;
+use std::io;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll, Waker};
+
+use lanyard::Utf8CStr;
+
+use crate::{Error, Result};
+
+#[cfg(doc)]
+use crate::studio;
+
+type OnLoad = unsafe extern "C" fn(c_uint, *mut c_void, *const c_char);
+type OnStatus = unsafe extern "C" fn(c_uint, *mut c_void, c_int);
+
+unsafe extern "C" {
+    fn emscripten_async_wget2(
+        url: *const c_char,
+        file: *const c_char,
+        request_type: *const c_char,
+        param: *const c_char,
+        arg: *mut c_void,
+        onload: Option<OnLoad>,
+        onerror: Option<OnStatus>,
+        onprogress: Option<OnStatus>,
+    ) -> c_int;
+}
+
+/// Writes `data` into the emscripten filesystem at `path`, creating any missing parent directories.
+///
+/// Use this for banks that were fetched some other way (embedded with `include_bytes!`, downloaded by your own JS, ...),
+/// so they can be loaded with [`studio::System::load_bank_file`] like on every other platform.
+pub fn write_file(path: impl AsRef<Path>, data: &[u8]) -> io::Result<()> {
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, data)
+}
+
+#[derive(Default)]
+struct State {
+    result: Option<Result<()>>,
+    waker: Option<Waker>,
+}
+
+impl std::fmt::Debug for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("State")
+            .field("result", &self.result)
+            .finish_non_exhaustive()
+    }
+}
+
+type Shared = Mutex<State>;
+
+fn lock(shared: &Shared) -> MutexGuard<'_, State> {
+    // the state is only ever replaced wholesale, so a panic can't leave it inconsistent
+    shared.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+// emscripten calls exactly one of `onload` and `onerror`, which takes back the reference given to it
+fn finish(arg: *mut c_void, result: Result<()>) {
+    let shared = unsafe { Arc::from_raw(arg.cast_const().cast::<Shared>()) };
+    let waker = {
+        let mut state = lock(&shared);
+        state.result = Some(result);
+        state.waker.take()
+    };
+    if let Some(waker) = waker {
+        waker.wake();
+    }
+}
+
+unsafe extern "C" fn on_load(_: c_uint, arg: *mut c_void, _: *const c_char) {
+    finish(arg, Ok(()));
+}
+
+unsafe extern "C" fn on_error(_: c_uint, arg: *mut c_void, status: c_int) {
+    let error = match status {
+        401 | 403 | 407 => Error::HttpAccess,
+        404 | 410 => Error::FileNotFound,
+        408 => Error::HttpTimeout,
+        500..=599 => Error::HttpServerError,
+        _ => Error::Http,
+    };
+    finish(arg, Err(error));
+}
+
+/// Downloads `url` into the emscripten filesystem at `path`.
+///
+/// Await the returned future before loading the file, after which `path` can be passed to [`studio::System::load_bank_file`]
+/// (or [`crate::SoundBuilder::open`]) as normal.
+/// This replaces the `FS.createPreloadedFile` JS glue FMOD's HTML5 examples use.
+///
+/// ```ignore
+/// fmod::wasm::preload(c!("banks/Master.bank"), c!("/banks/Master.bank")).await?;
+/// let bank = system.load_bank_file(c!("/banks/Master.bank"), LoadBankFlags::NORMAL)?;
+/// ```
+///
+/// The download is driven by the browser, so the future only completes while control returns to the browser event loop
+/// (i.e. from inside `emscripten_set_main_loop` or an async executor that yields to it).
+/// It fails with [`Error::FileNotFound`], [`Error::HttpAccess`], [`Error::HttpTimeout`], [`Error::HttpServerError`] or [`Error::Http`],
+/// depending on the HTTP status.
+pub fn preload(url: &Utf8CStr, path: &Utf8CStr) -> Preload {
+    let shared = Arc::new(Mutex::new(State::default()));
+
+    let parent = Path::new(path.as_str()).parent();
+    if parent.is_some_and(|parent| std::fs::create_dir_all(parent).is_err()) {
+        lock(&shared).result = Some(Err(Error::FileBad));
+        return Preload { shared };
+    }
+
+    let arg = Arc::into_raw(shared.clone()).cast_mut().cast();
+    unsafe {
+        emscripten_async_wget2(
+            url.as_ptr(),
+            path.as_ptr(),
+            c"GET".as_ptr(),
+            c"".as_ptr(),
+            arg,
+            Some(on_load),
+            Some(on_error),
+            None,
+        );
+    }
+    Preload { shared }
+}
+
+/// Resolves once a download started by [`preload`] has been written to the emscripten filesystem.
+///
+/// Dropping this does not cancel the download.
+#[derive(Debug)]
+pub struct Preload {
+    shared: Arc<Shared>,
+}
+
+impl Future for Preload {
+    type Output = Result<()>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = lock(&self.shared);
+        match state.result {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
@@ -0,0 +1,13 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+mod filesystem;
+mod output;
+mod threading;
+
+pub use filesystem::{Preload, preload, write_file};
+pub use output::WebOutput;
+pub use threading::THREADS_AVAILABLE;
@@ -0,0 +1,146 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::ffi::{c_char, c_int, c_void};
+
+use fmod_sys::*;
+
+use crate::{Error, OutputType, Result, System, SystemBuilder};
+
+type EventCallback = unsafe extern "C" fn(c_int, *const c_void, *mut c_void) -> bool;
+
+unsafe extern "C" {
+    fn emscripten_set_click_callback_on_thread(
+        target: *const c_char,
+        user_data: *mut c_void,
+        use_capture: bool,
+        callback: Option<EventCallback>,
+        thread: usize,
+    ) -> c_int;
+    fn emscripten_set_keydown_callback_on_thread(
+        target: *const c_char,
+        user_data: *mut c_void,
+        use_capture: bool,
+        callback: Option<EventCallback>,
+        thread: usize,
+    ) -> c_int;
+    fn emscripten_set_touchend_callback_on_thread(
+        target: *const c_char,
+        user_data: *mut c_void,
+        use_capture: bool,
+        callback: Option<EventCallback>,
+        thread: usize,
+    ) -> c_int;
+}
+
+// EMSCRIPTEN_EVENT_TARGET_DOCUMENT and EM_CALLBACK_THREAD_CONTEXT_CALLING_THREAD, which are macros in html5.h
+const TARGET_DOCUMENT: *const c_char = std::ptr::without_provenance(1);
+const CALLING_THREAD: usize = 2;
+
+/// The Web Audio node FMOD outputs through on HTML5.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WebOutput {
+    /// An `AudioWorkletNode`, which mixes off the main thread. See [`OutputType::AudioWorklet`].
+    ///
+    /// This is FMOD's default when the browser supports it.
+    AudioWorklet,
+    /// A `ScriptProcessorNode`, which is deprecated but available everywhere. See [`OutputType::WebAudio`].
+    ///
+    /// Prefer this if the page can't be served from a secure context, which audio worklets require.
+    ScriptProcessor,
+}
+
+impl From<WebOutput> for OutputType {
+    fn from(value: WebOutput) -> Self {
+        match value {
+            WebOutput::AudioWorklet => OutputType::AudioWorklet,
+            WebOutput::ScriptProcessor => OutputType::WebAudio,
+        }
+    }
+}
+
+impl SystemBuilder {
+    /// Picks the Web Audio node to output through, instead of letting FMOD choose.
+    pub fn web_output(&mut self, output: WebOutput) -> Result<&mut Self> {
+        self.output(output.into())
+    }
+}
+
+// registers (or with `None`, removes) `callback` for every event browsers count as a user gesture
+fn set_gesture_callbacks(system: *mut c_void, callback: Option<EventCallback>) -> Result<()> {
+    let results = unsafe {
+        [
+            emscripten_set_click_callback_on_thread(
+                TARGET_DOCUMENT,
+                system,
+                true,
+                callback,
+                CALLING_THREAD,
+            ),
+            emscripten_set_keydown_callback_on_thread(
+                TARGET_DOCUMENT,
+                system,
+                true,
+                callback,
+                CALLING_THREAD,
+            ),
+            emscripten_set_touchend_callback_on_thread(
+                TARGET_DOCUMENT,
+                system,
+                true,
+                callback,
+                CALLING_THREAD,
+            ),
+        ]
+    };
+    // EMSCRIPTEN_RESULT_SUCCESS is 0, everything else means the event isn't supported here
+    if results.iter().all(|&r| r == 0) {
+        Ok(())
+    } else {
+        Err(Error::Unsupported)
+    }
+}
+
+unsafe extern "C" fn on_gesture(_: c_int, _: *const c_void, system: *mut c_void) -> bool {
+    let system = unsafe { System::from_ffi(system.cast::<FMOD_SYSTEM>()) };
+    // once the context is running it stays running, so the first gesture is the only one that matters
+    if system.resume_audio_context().is_ok() {
+        let _ = set_gesture_callbacks(system.inner.as_ptr().cast(), None);
+    }
+    // let the page handle the event as usual
+    false
+}
+
+impl System {
+    /// Which [`WebOutput`] FMOD is using, or [`None`] if the output isn't Web Audio (such as [`OutputType::NoSound`]).
+    pub fn get_web_output(&self) -> Result<Option<WebOutput>> {
+        Ok(match self.get_output_type()? {
+            OutputType::AudioWorklet => Some(WebOutput::AudioWorklet),
+            OutputType::WebAudio => Some(WebOutput::ScriptProcessor),
+            _ => None,
+        })
+    }
+
+    /// Resumes the browser's `AudioContext`.
+    ///
+    /// Browsers block audio until the user interacts with the page, so the context FMOD creates during init starts out suspended and the game is silent.
+    /// Call this from inside a click, key or touch handler to unblock it, or use [`System::resume_audio_context_on_gesture`].
+    /// Calling it outside of a user gesture does nothing.
+    ///
+    /// This is [`System::suspend_mixer`] followed by [`System::resume_mixer`], which is how FMOD recreates the context.
+    pub fn resume_audio_context(&self) -> Result<()> {
+        self.suspend_mixer()?;
+        self.resume_mixer()
+    }
+
+    /// Calls [`System::resume_audio_context`] on the first click, key press or touch anywhere on the page.
+    ///
+    /// The handlers are removed again once the context has been resumed, and don't stop the page from seeing the event.
+    /// Fails with [`Error::Unsupported`] if the handlers couldn't be registered.
+    pub fn resume_audio_context_on_gesture(&self) -> Result<()> {
+        set_gesture_callbacks(self.inner.as_ptr().cast(), Some(on_gesture))
+    }
+}
@@ -0,0 +1,65 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::{InitFlags, PlatformProfile, Result, SystemBuilder};
+
+#[cfg(doc)]
+use crate::{Mode, System};
+
+/// Whether this build can use threads, i.e. was compiled with `-C target-feature=+atomics` and linked with `-pthread`.
+///
+/// Without threads FMOD can't run its mixer, streaming, non blocking loading or Studio update threads,
+/// so everything that would normally happen on them has to be driven from [`System::update`].
+/// [`SystemBuilder::web_defaults`] sets that up.
+pub const THREADS_AVAILABLE: bool = cfg!(target_feature = "atomics");
+
+impl InitFlags {
+    /// Recommended flags for the web.
+    ///
+    /// Like [`InitFlags::mobile_default`], and without [`THREADS_AVAILABLE`] also [`InitFlags::MIX_FROM_UPDATE`],
+    /// so output modes without a browser callback (such as [`crate::OutputType::NoSound`]) mix during [`System::update`].
+    pub const fn web_default() -> Self {
+        if THREADS_AVAILABLE {
+            InitFlags::mobile_default()
+        } else {
+            InitFlags::mobile_default().union(InitFlags::MIX_FROM_UPDATE)
+        }
+    }
+}
+
+impl SystemBuilder {
+    /// Sets up the system for the emscripten build of FMOD.
+    ///
+    /// This adds [`InitFlags::web_default`] to the flags the system is built with,
+    /// and applies the tuning of [`PlatformProfile::Web`].
+    /// Call [`SystemBuilder::dsp_buffer_size`] afterwards to pick something else.
+    ///
+    /// Without [`THREADS_AVAILABLE`] FMOD has no loading thread either,
+    /// so [`Mode::NONBLOCKING`] sounds only make progress during [`System::update`], which must be called every frame.
+    pub fn web_defaults(&mut self) -> Result<&mut Self> {
+        self.extra_flags.insert(InitFlags::web_default());
+        self.tuning(&PlatformProfile::Web.tuning())
+    }
+}
+
+#[cfg(feature = "studio")]
+impl crate::studio::SystemBuilder {
+    /// Sets up the Studio system and its core system for the emscripten build of FMOD.
+    ///
+    /// The core system gets [`SystemBuilder::web_defaults`].
+    /// Without [`THREADS_AVAILABLE`] Studio can't run its update thread, so this also switches to [`crate::studio::UpdateMode::Synchronous`].
+    pub fn web_defaults(&mut self) -> Result<&mut Self> {
+        self.core_builder().web_defaults()?;
+        // without threads there is nothing else that could touch Studio, so synchronous updates are always sound
+        #[cfg(all(not(target_feature = "atomics"), not(feature = "thread-unsafe")))]
+        unsafe {
+            self.synchronous_update();
+        }
+        #[cfg(all(not(target_feature = "atomics"), feature = "thread-unsafe"))]
+        self.synchronous_update();
+        Ok(self)
+    }
+}
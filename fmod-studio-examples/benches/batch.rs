@@ -0,0 +1,121 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+// Compares a frame of redundant setter calls made directly against the same calls made through
+// studio::System::begin_batch/end_batch. Run with `cargo bench -p fmod-studio-examples`.
+//
+// Each frame several gameplay systems write the same parameter and volume on every instance,
+// which is the case batching coalesces. The frame includes System::update and flush_commands,
+// so the cost of Studio working through the command buffer is measured too, not just the calls.
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use fmod::c;
+use fmod::studio::{BatchCommand, EventInstance, ParameterID, System};
+use fmod_studio_examples::media_path_for;
+
+const INSTANCE_COUNTS: [usize; 3] = [10, 100, 500];
+const WRITERS: usize = 4;
+
+fn setup() -> fmod::Result<(System, fmod::studio::EventDescription, ParameterID)> {
+    let builder = unsafe {
+        // Safety: we call this before calling any other functions, and only one system is created per benchmark
+        fmod::studio::SystemBuilder::new()?
+    };
+    let system = builder.build(
+        1024,
+        fmod::studio::InitFlags::NORMAL,
+        fmod::InitFlags::NORMAL,
+    )?;
+    for bank in ["Master.bank", "Master.strings.bank", "SFX.bank"] {
+        system.load_bank_file(&media_path_for(bank), fmod::studio::LoadBankFlags::NORMAL)?;
+    }
+    let description = system.get_event(c!("event:/Character/Player Footsteps"))?;
+    let surface = description
+        .get_parameter_description_by_name(c!("Surface"))?
+        .id;
+    Ok((system, description, surface))
+}
+
+fn commands(instances: &[EventInstance], surface: ParameterID, writer: usize) -> Vec<BatchCommand> {
+    instances
+        .iter()
+        .flat_map(|&instance| {
+            [
+                BatchCommand::EventParameter {
+                    instance,
+                    id: surface,
+                    value: writer as f32,
+                    ignore_seek_speed: false,
+                },
+                BatchCommand::EventVolume {
+                    instance,
+                    volume: 1.0 / (writer + 1) as f32,
+                },
+            ]
+        })
+        .collect()
+}
+
+fn frame(system: System, commands: &[Vec<BatchCommand>], batched: bool) -> fmod::Result<()> {
+    if batched {
+        system.begin_batch();
+    }
+    for command in commands.iter().flatten() {
+        system.queue(*command)?;
+    }
+    if batched {
+        system.end_batch()?;
+    }
+    system.update()?;
+    system.flush_commands()
+}
+
+fn bench_batch(criterion: &mut Criterion) {
+    let (system, description, surface) = setup().expect("failed to set up the studio system");
+
+    let mut group = criterion.benchmark_group("studio_setter_frame");
+    for count in INSTANCE_COUNTS {
+        let instances = (0..count)
+            .map(|_| description.create_instance())
+            .collect::<Result<Vec<_>, _>>()
+            .expect("failed to create instances");
+        for instance in &instances {
+            instance.start().expect("failed to start instance");
+        }
+        let commands = (0..WRITERS)
+            .map(|writer| commands(&instances, surface, writer))
+            .collect::<Vec<_>>();
+
+        group.bench_with_input(
+            BenchmarkId::new("direct", count),
+            &commands,
+            |b, commands| {
+                b.iter(|| frame(system, commands, false).expect("frame failed"));
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("batched", count),
+            &commands,
+            |b, commands| {
+                b.iter(|| frame(system, commands, true).expect("frame failed"));
+            },
+        );
+
+        for instance in instances {
+            instance
+                .stop(fmod::studio::StopMode::Immediate)
+                .and_then(|()| instance.release())
+                .expect("failed to release instance");
+        }
+    }
+    group.finish();
+
+    // Safety: nothing created by the system is used after this
+    unsafe { system.release() }.expect("failed to release the studio system");
+}
+
+criterion_group!(benches, bench_batch);
+criterion_main!(benches);
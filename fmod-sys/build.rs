@@ -1,4 +1,7 @@
-use std::{fs, path::PathBuf};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
 #[derive(Debug, Default)]
 pub struct VersionCallbacks;
@@ -91,6 +94,87 @@ fn find_fmod_directory() -> PathBuf {
     );
 }
 
+// the headers each API family is generated from, and which must exist in the SDK for its feature
+#[cfg(feature = "core")]
+const CORE_HEADERS: &[&str] = &[
+    "core/inc/fmod.h",
+    "core/inc/fmod_common.h",
+    "core/inc/fmod_errors.h",
+    "core/inc/fmod_dsp.h",
+    "core/inc/fmod_dsp_effects.h",
+];
+#[cfg(feature = "studio")]
+const STUDIO_HEADERS: &[&str] = &[
+    "studio/inc/fmod_studio.h",
+    "studio/inc/fmod_studio_common.h",
+];
+#[cfg(feature = "fsbank")]
+const FSBANK_HEADERS: &[&str] = &["fsbank/inc/fsbank.h", "fsbank/inc/fsbank_errors.h"];
+#[cfg(feature = "output-dev")]
+const OUTPUT_DEV_HEADERS: &[&str] = &["core/inc/fmod_output.h", "core/inc/fmod_codec.h"];
+
+// Fails the build up front if the SDK doesn't ship `feature`'s headers,
+// instead of leaving it to a confusing missing symbol error at link time.
+fn require_headers(api_dir: &Path, feature: &str, headers: &[&str]) {
+    for header in headers {
+        let path = api_dir.join(header);
+        if !path.exists() {
+            panic!(
+                "the `{feature}` feature needs {}, which this FMOD SDK doesn't include; disable the feature or install an SDK with that API",
+                path.display()
+            );
+        }
+    }
+}
+
+// Generates bindings only for items declared in `headers` (and whatever those items refer to),
+// so the bindings of a feature don't drag in declarations from headers that belong to another one.
+fn allowlist_headers(mut bindgen: bindgen::Builder, headers: &[&str]) -> bindgen::Builder {
+    for header in headers {
+        let file_name = header.rsplit('/').next().unwrap();
+        bindgen = bindgen.allowlist_file(format!(r".*[/\\]{}", file_name.replace('.', r"\.")));
+    }
+    bindgen
+}
+
+// A library to link to. Its name can be overridden through `name_var`,
+// for SDKs that ship renamed libraries or to pick the logging build without `force-debug`.
+#[cfg(feature = "link-fmod")]
+struct FmodLib {
+    name: String,
+    dir: PathBuf,
+}
+
+#[cfg(feature = "link-fmod")]
+impl FmodLib {
+    fn new(name_var: &str, default_name: String, dir: PathBuf) -> Self {
+        println!("cargo:rerun-if-env-changed={name_var}");
+        let name = std::env::var(name_var).unwrap_or(default_name);
+        Self { name, dir }
+    }
+
+    // The files needed to load the library at runtime.
+    // On Linux that includes the versioned names, the binary asks for the soname (libfmod.so.<n>) rather than libfmod.so.
+    fn runtime_files(&self, is_windows: bool, is_macos: bool) -> Vec<PathBuf> {
+        let file_names = if is_windows {
+            vec![format!("{}.dll", self.name)]
+        } else if is_macos {
+            vec![format!("lib{}.dylib", self.name)]
+        } else {
+            let prefix = format!("lib{}.so", self.name);
+            fs::read_dir(&self.dir)
+                .expect("failed to read fmod lib dir")
+                .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+                .filter(|file_name| file_name.starts_with(&prefix))
+                .collect()
+        };
+        file_names
+            .into_iter()
+            .map(|file_name| self.dir.join(file_name))
+            .collect()
+    }
+}
+
 fn main() {
     // skip generating bindings in docs.rs, as we use the packaged "documentation.rs" instead
     if std::env::var("DOCS_RS").is_ok() {
@@ -151,9 +235,25 @@ fn main() {
 
     assert!(api_dir.exists(), "fmod api dir does not exist");
 
+    #[cfg(feature = "core")]
+    require_headers(&api_dir, "core", CORE_HEADERS);
+    #[cfg(feature = "studio")]
+    require_headers(&api_dir, "studio", STUDIO_HEADERS);
+    #[cfg(feature = "fsbank")]
+    require_headers(&api_dir, "fsbank", FSBANK_HEADERS);
+    #[cfg(feature = "output-dev")]
+    require_headers(&api_dir, "output-dev", OUTPUT_DEV_HEADERS);
+    #[cfg(feature = "fsbank")]
+    if build_is_wasm {
+        panic!(
+            "the `fsbank` feature is not available on wasm, FMOD doesn't ship the FSBank API for HTML5"
+        );
+    }
+
     let api_dir_display = api_dir.display();
     println!("cargo:rerun-if-changed={api_dir_display}/core/inc");
     println!("cargo:rerun-if-changed={api_dir_display}/studio/inc");
+    println!("cargo:rerun-if-changed={api_dir_display}/fsbank/inc");
 
     let mut bindgen = bindgen::builder()
         .parse_callbacks(Box::new(bindgen::CargoCallbacks::new()))
@@ -170,12 +270,28 @@ fn main() {
         .prepend_enum_name(false) // fmod already does this
         .header("src/wrapper.h");
 
+    #[cfg(feature = "core")]
+    {
+        bindgen = allowlist_headers(bindgen, CORE_HEADERS)
+            .allowlist_file(r".*[/\\]channel_control\.h")
+            .clang_arg("-DFMOD_CORE_ENABLED");
+    }
     #[cfg(feature = "studio")]
     {
-        bindgen = bindgen
+        bindgen = allowlist_headers(bindgen, STUDIO_HEADERS)
             .clang_arg(format!("-I{api_dir_display}/studio/inc"))
             .clang_arg("-DFMOD_STUDIO_ENABLED");
     }
+    #[cfg(feature = "fsbank")]
+    {
+        bindgen = allowlist_headers(bindgen, FSBANK_HEADERS)
+            .clang_arg(format!("-I{api_dir_display}/fsbank/inc"))
+            .clang_arg("-DFMOD_FSBANK_ENABLED");
+    }
+    #[cfg(feature = "output-dev")]
+    {
+        bindgen = allowlist_headers(bindgen, OUTPUT_DEV_HEADERS);
+    }
     let include_debug = cfg!(any(debug_assertions, feature = "force-debug"));
     let debug_char = if include_debug { "L" } else { "" };
 
@@ -183,30 +299,8 @@ fn main() {
         bindgen = bindgen.clang_arg("-fvisibility=default")
     }
 
-    // On macOS the fmod library uses @rpath to find the dylib and the following doesn't work:
-    // println!("cargo:rustc-link-args='-rpath {api_dir_display}/core/lib'");
-    // Therefore, as workaround, copy the libraries to OUT_DIR before the build.
-    // Note: you will probably have to run `xattr -d com.apple.quarantine` on all the `.dylib`s
-    // in the fmod installation folder.
-    #[cfg(feature = "link-fmod")]
-    if build_is_macos {
-        let corelib = format!("libfmod{debug_char}.dylib");
-        fs::copy(
-            api_dir.join("core").join("lib").join(&corelib),
-            out_dir.join(&corelib),
-        )
-        .expect("failed to copy core lib");
-
-        let studiolib = format!("libfmodstudio{debug_char}.dylib");
-        fs::copy(
-            api_dir.join("studio").join("lib").join(&studiolib),
-            out_dir.join(&studiolib),
-        )
-        .expect("failed to copy studio lib");
-    }
-
     // due to some weird shenanigans I can't figure out how to turn off, the linker searches for lib<library name> instead of just accepting the library name
-    #[cfg(feature = "link-fmod")]
+    #[cfg(all(feature = "link-fmod", feature = "studio"))]
     if build_is_wasm {
         let old_lib_path = format!("studio/lib/upstream/w32/fmodstudio{debug_char}_wasm.a");
         let new_lib_path = format!("studio/lib/upstream/w32/libfmodstudio{debug_char}_wasm.a");
@@ -232,11 +326,15 @@ fn main() {
     if build_is_wasm {
         // studio includes core on this platform, so no need to link against it
         println!("cargo:rustc-link-search={api_dir_display}/studio/lib/upstream/w32");
-    } else if build_is_macos {
-        println!("cargo:rustc-link-search={api_dir_display}/core/lib");
-        println!("cargo:rustc-link-search={api_dir_display}/studio/lib");
+        // the html5 libraries are always static
+        #[cfg(all(feature = "core", not(feature = "studio")))]
+        println!("cargo:rustc-link-lib=static=fmod{debug_char}_wasm");
+        #[cfg(feature = "studio")]
+        println!("cargo:rustc-link-lib=static=fmodstudio{debug_char}_wasm");
     } else {
-        let target_arch = if build_is_x86_64 && !build_is_windows {
+        let lib_subdir = if build_is_macos {
+            ""
+        } else if build_is_x86_64 && !build_is_windows {
             "x86_64"
         } else if build_is_x86_64 {
             "x64"
@@ -249,26 +347,87 @@ fn main() {
         } else {
             todo!()
         };
-        println!("cargo:rustc-link-search={api_dir_display}/core/lib/{target_arch}");
-        println!("cargo:rustc-link-search={api_dir_display}/studio/lib/{target_arch}");
-    }
 
-    #[cfg(feature = "link-fmod")]
-    if build_is_wasm {
-        #[cfg(not(feature = "studio"))]
-        // studio includes core on this platform, so no need to link against it
-        println!("cargo:rustc-link-lib=fmod{debug_char}_wasm");
-        #[cfg(feature = "studio")]
-        // studio includes core on this platform, so no need to link against it
-        println!("cargo:rustc-link-lib=fmodstudio{debug_char}_wasm");
-    } else if build_is_windows {
-        println!("cargo:rustc-link-lib=fmod{debug_char}_vc");
-        #[cfg(feature = "studio")]
-        println!("cargo:rustc-link-lib=fmodstudio{debug_char}_vc");
-    } else {
-        println!("cargo:rustc-link-lib=fmod{debug_char}");
+        #[allow(unused_mut)] // empty if no API family is enabled
+        let mut libs: Vec<FmodLib> = Vec::new();
+        #[cfg(feature = "core")]
+        libs.push(FmodLib::new(
+            "FMOD_SYS_CORE_LIB_NAME",
+            format!("fmod{debug_char}"),
+            api_dir.join("core/lib").join(lib_subdir),
+        ));
         #[cfg(feature = "studio")]
-        println!("cargo:rustc-link-lib=fmodstudio{debug_char}");
+        libs.push(FmodLib::new(
+            "FMOD_SYS_STUDIO_LIB_NAME",
+            format!("fmodstudio{debug_char}"),
+            api_dir.join("studio/lib").join(lib_subdir),
+        ));
+        // fsbank has no logging build
+        #[cfg(feature = "fsbank")]
+        libs.push(FmodLib::new(
+            "FMOD_SYS_FSBANK_LIB_NAME",
+            "fsbank".to_string(),
+            api_dir.join("fsbank/lib").join(lib_subdir),
+        ));
+
+        let static_link = cfg!(feature = "static-link");
+        if static_link && build_is_windows {
+            panic!(
+                "the `static-link` feature is not available on Windows, FMOD only ships import libraries for its DLLs there"
+            );
+        }
+
+        for lib in &libs {
+            println!("cargo:rustc-link-search=native={}", lib.dir.display());
+            if static_link {
+                let archive = lib.dir.join(format!("lib{}.a", lib.name));
+                if !archive.exists() {
+                    panic!(
+                        "the `static-link` feature needs {}, which this FMOD SDK doesn't include; FMOD only ships static libraries for some platforms",
+                        archive.display()
+                    );
+                }
+                println!("cargo:rustc-link-lib=static={}", lib.name);
+            } else if build_is_windows {
+                println!("cargo:rustc-link-lib=dylib={}_vc", lib.name);
+            } else {
+                println!("cargo:rustc-link-lib=dylib={}", lib.name);
+            }
+        }
+
+        let runtime_files: Vec<PathBuf> = if static_link {
+            Vec::new()
+        } else {
+            libs.iter()
+                .flat_map(|lib| lib.runtime_files(build_is_windows, build_is_macos))
+                .collect()
+        };
+
+        // On macOS the fmod library uses @rpath to find the dylib and the following doesn't work:
+        // println!("cargo:rustc-link-args='-rpath {api_dir_display}/core/lib'");
+        // Therefore, as workaround, copy the libraries to OUT_DIR before the build.
+        // Note: you will probably have to run `xattr -d com.apple.quarantine` on all the `.dylib`s
+        // in the fmod installation folder.
+        //
+        // Cargo adds link search paths inside the target directory to the library search path of `cargo run` and `cargo test`,
+        // so the same copy is what makes the libraries found at runtime on other platforms.
+        if !static_link && (build_is_macos || cfg!(feature = "copy-runtime-libs")) {
+            for file in &runtime_files {
+                let file_name = file.file_name().unwrap();
+                fs::copy(file, out_dir.join(file_name)).unwrap_or_else(|e| {
+                    panic!("failed to copy {}: {e}", file_name.to_string_lossy())
+                });
+            }
+            println!("cargo:rustc-link-search=native={}", out_dir.display());
+        }
+
+        // for fmod_sys::copy_runtime_libs, which puts them next to the binaries when asked to
+        let runtime_files = std::env::join_paths(&runtime_files)
+            .expect("fmod library paths can't contain the path separator");
+        println!(
+            "cargo::rustc-env=FMOD_SYS_RUNTIME_LIBS={}",
+            runtime_files.to_string_lossy()
+        );
     }
 
     let bindings = bindgen.generate().expect("failed to generate bindings");
@@ -278,19 +437,23 @@ fn main() {
         .write_to_file(out_path)
         .expect("failed to write bindings");
 
-    let version_number = DETECTED_VERSION.load(std::sync::atomic::Ordering::Relaxed);
-    if version_number == i64::MAX {
-        panic!("Failed to determine FMOD version!");
-    }
+    // FMOD_VERSION lives in the core headers, without them there is no version to report
+    #[cfg(feature = "core")]
+    {
+        let version_number = DETECTED_VERSION.load(std::sync::atomic::Ordering::Relaxed);
+        if version_number == i64::MAX {
+            panic!("Failed to determine FMOD version!");
+        }
 
-    let minor = version_number & 0xFF;
-    let major = (version_number >> 8) & 0xFF;
-    let product = version_number >> 16;
+        let minor = version_number & 0xFF;
+        let major = (version_number >> 8) & 0xFF;
+        let product = version_number >> 16;
 
-    println!("cargo::metadata=version_number={version_number}");
-    println!("cargo::metadata=minor={minor}");
-    println!("cargo::metadata=minor={major}");
-    println!("cargo::metadata=minor={product}");
+        println!("cargo::metadata=version_number={version_number}");
+        println!("cargo::metadata=minor={minor}");
+        println!("cargo::metadata=minor={major}");
+        println!("cargo::metadata=minor={product}");
+    }
 
     println!("cargo::rustc-env=FMOD_DIR={}", fmod_dir.display());
     println!("cargo::rustc-env=FMOD_API_DIR={}", api_dir.display());
@@ -301,34 +464,39 @@ fn main() {
         .write_to_file(docs_path)
         .expect("failed to write docs");
 
-    println!("cargo:rerun-if-changed=src/channel_control.cpp");
-    println!("cargo:rerun-if-changed=src/channel_control.h");
+    // the ChannelControl wrapper is part of the core bindings
+    #[cfg(feature = "core")]
+    {
+        println!("cargo:rerun-if-changed=src/channel_control.cpp");
+        println!("cargo:rerun-if-changed=src/channel_control.h");
 
-    // wrapper does not use the stdlib
-    let mut build = cc::Build::new();
+        // wrapper does not use the stdlib
+        let mut build = cc::Build::new();
 
-    build
-        .cpp(true)
-        .cpp_link_stdlib(None)
-        .cpp_set_stdlib(None)
-        .include(format!("{api_dir_display}/core/inc"))
-        .file("src/channel_control.cpp");
+        build
+            .cpp(true)
+            .cpp_link_stdlib(None)
+            .cpp_set_stdlib(None)
+            .include(format!("{api_dir_display}/core/inc"))
+            .file("src/channel_control.cpp");
 
-    if build_is_emscripten {
-        build.flag_if_supported("-Wunused-command-line-argument"); // why is this raised?
-    }
+        if build_is_emscripten {
+            build.flag_if_supported("-Wunused-command-line-argument"); // why is this raised?
+        }
 
-    if build_is_windows {
-        let target = if build_is_x86_64 {
-            "x86_64-pc-windows-msvc"
-        } else if build_is_x86 {
-            "i686-pc-windows-msvc"
-        } else {
-            todo!()
-        };
-        let tool = cc::windows_registry::find_tool(target, "cl.exe").expect("failed to find cl");
-        build.compiler(tool.path());
-    }
+        if build_is_windows {
+            let target = if build_is_x86_64 {
+                "x86_64-pc-windows-msvc"
+            } else if build_is_x86 {
+                "i686-pc-windows-msvc"
+            } else {
+                todo!()
+            };
+            let tool =
+                cc::windows_registry::find_tool(target, "cl.exe").expect("failed to find cl");
+            build.compiler(tool.path());
+        }
 
-    build.compile("channel_control_wrapper");
+        build.compile("channel_control_wrapper");
+    }
 }
@@ -3,7 +3,10 @@
 //! Due to licensing restrictions, the FMOD API cannot be distributed with this crate.
 //! docs.rs documention is provided for the FMOD API but the actual API is not available without the FMOD library.
 //!
-//! Currently, this crate provides both core and studio bindings (no fsbank bindings).
+//! The bindings are split by API family into the `core`, `studio`, `fsbank` and `output-dev` features,
+//! so a crate that only needs the Core API doesn't generate or link anything from Studio.
+//! Enabling a feature whose headers are missing from the installed FMOD SDK fails the build script with a message naming the feature,
+//! rather than leaving it to the linker to complain about missing symbols.
 //!
 //! See [`fmod-oxide`](https://github.com/melody-rs/fmod-oxide/blob/main/README.md)'s README.md for more information on how this crate links to FMOD.
 //!
@@ -24,23 +27,42 @@ include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
 pub const INSTALL_DIR: &str = env!("FMOD_DIR");
 pub const API_DIR: &str = env!("FMOD_API_DIR");
 
+/// Copies the FMOD shared libraries this crate links to into `dest`, so binaries placed there find them at runtime.
+///
+/// Call this from a build or packaging step (such as an `xtask`) when distributing a binary.
+/// Does nothing when FMOD is linked statically or not linked by this crate.
+pub fn copy_runtime_libs(dest: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+    let dest = dest.as_ref();
+    let files = option_env!("FMOD_SYS_RUNTIME_LIBS").unwrap_or_default();
+    for file in std::env::split_paths(files).filter(|file| !file.as_os_str().is_empty()) {
+        if let Some(file_name) = file.file_name() {
+            std::fs::copy(&file, dest.join(file_name))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "core")]
 impl From<FMOD_BOOL> for bool {
     fn from(val: FMOD_BOOL) -> Self {
         val.0 > 0
     }
 }
 
+#[cfg(feature = "core")]
 impl From<bool> for FMOD_BOOL {
     fn from(value: bool) -> Self {
         Self(value as _)
     }
 }
 
+#[cfg(feature = "core")]
 impl FMOD_BOOL {
     pub const FALSE: Self = Self(0);
     pub const TRUE: Self = Self(1);
 }
 
+#[cfg(feature = "core")]
 #[allow(non_snake_case)]
 pub const fn error_code_to_str(result: FMOD_RESULT) -> &'static str {
     match result {
@@ -5,6 +5,7 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 // Core
+#ifdef FMOD_CORE_ENABLED
 #include <fmod.h>
 #include <fmod_common.h>
 #include <fmod_errors.h>
@@ -17,9 +18,16 @@
 #include <fmod_output.h>
 
 #include "channel_control.h"
+#endif
 
 // Studio
 #ifdef FMOD_STUDIO_ENABLED
 #include <fmod_studio.h>
 #include <fmod_studio_common.h>
+#endif
+
+// FSBank
+#ifdef FMOD_FSBANK_ENABLED
+#include <fsbank.h>
+#include <fsbank_errors.h>
 #endif
\ No newline at end of file
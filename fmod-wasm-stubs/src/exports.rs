@@ -0,0 +1,106 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! The C symbols themselves. Everything here forwards to the implementations in the other modules.
+
+use core::ffi::{c_char, c_int, c_void};
+
+use crate::{memory, sort, string};
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn malloc(size: usize) -> *mut c_void {
+    unsafe { memory::malloc(size) }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn calloc(count: usize, size: usize) -> *mut c_void {
+    unsafe { memory::calloc(count, size) }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn realloc(data: *mut c_void, size: usize) -> *mut c_void {
+    unsafe { memory::realloc(data, size) }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn free(data: *mut c_void) {
+    unsafe { memory::free(data) }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn aligned_alloc(align: usize, size: usize) -> *mut c_void {
+    unsafe { memory::aligned_alloc(align, size) }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn posix_memalign(out: *mut *mut c_void, align: usize, size: usize) -> c_int {
+    unsafe { memory::posix_memalign(out, align, size) }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn strlen(string: *const c_char) -> usize {
+    unsafe { string::strlen(string) }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn strnlen(string: *const c_char, max: usize) -> usize {
+    unsafe { string::strnlen(string, max) }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn strcmp(a: *const c_char, b: *const c_char) -> c_int {
+    unsafe { string::strcmp(a, b) }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn strncmp(a: *const c_char, b: *const c_char, max: usize) -> c_int {
+    unsafe { string::strncmp(a, b, max) }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn strchr(string: *const c_char, needle: c_int) -> *mut c_char {
+    unsafe { string::strchr(string, needle) }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn strrchr(string: *const c_char, needle: c_int) -> *mut c_char {
+    unsafe { string::strrchr(string, needle) }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn strstr(haystack: *const c_char, needle: *const c_char) -> *mut c_char {
+    unsafe { string::strstr(haystack, needle) }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn strcpy(dst: *mut c_char, src: *const c_char) -> *mut c_char {
+    unsafe { string::strcpy(dst, src) }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn strncpy(dst: *mut c_char, src: *const c_char, max: usize) -> *mut c_char {
+    unsafe { string::strncpy(dst, src, max) }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn strcat(dst: *mut c_char, src: *const c_char) -> *mut c_char {
+    unsafe { string::strcat(dst, src) }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn qsort(
+    base: *mut c_void,
+    count: usize,
+    size: usize,
+    compare: sort::Compare,
+) {
+    unsafe { sort::qsort(base, count, size, compare) }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn abort() -> ! {
+    core::arch::wasm32::unreachable()
+}
@@ -0,0 +1,191 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+#![warn(rust_2018_idioms, clippy::pedantic, missing_docs)]
+#![allow(
+    clippy::missing_safety_doc,
+    clippy::cast_sign_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_possible_wrap
+)]
+#![cfg_attr(not(test), no_std)]
+
+//! The libc functions FMOD expects to link against on `wasm32-unknown-unknown`.
+//!
+//! FMOD's HTML5 libraries are built with emscripten, which provides libc.
+//! `wasm32-unknown-unknown` has no libc, so every project using that target used to carry its own copy of these.
+//!
+//! The functions are only exported on `wasm32-unknown-unknown`, every other target already has a libc and this crate does nothing there.
+//! Enable fmod-oxide's `wasm-stubs` feature rather than depending on this crate directly, it makes sure the crate is actually linked.
+//!
+//! Provided functions:
+//! - Memory: `malloc`, `calloc`, `realloc`, `free`, `aligned_alloc`, `posix_memalign`
+//! - Strings: `strlen`, `strnlen`, `strcmp`, `strncmp`, `strchr`, `strrchr`, `strstr`, `strcpy`, `strncpy`, `strcat`
+//! - Other: `qsort`, `abort`
+//!
+//! `memcpy`, `memmove`, `memset` and `memcmp` come from Rust's `compiler_builtins`.
+//! Emscripten's own JavaScript runtime functions are not provided, those have to come from your JS glue.
+
+#[cfg(any(test, all(target_arch = "wasm32", target_os = "unknown")))]
+extern crate alloc;
+
+// the implementations are built on the host too so they can be tested there
+#[cfg(any(test, all(target_arch = "wasm32", target_os = "unknown")))]
+mod memory;
+#[cfg(any(test, all(target_arch = "wasm32", target_os = "unknown")))]
+mod sort;
+#[cfg(any(test, all(target_arch = "wasm32", target_os = "unknown")))]
+mod string;
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+mod exports;
+
+#[cfg(test)]
+mod tests {
+    use core::ffi::{c_char, c_int, c_void};
+    use core::ptr;
+
+    use crate::{memory, sort, string};
+
+    #[test]
+    fn malloc_roundtrip() {
+        unsafe {
+            let ptr = memory::malloc(24).cast::<u8>();
+            assert!(!ptr.is_null());
+            assert_eq!(ptr as usize % memory::MIN_ALIGN, 0);
+            ptr.write_bytes(0xAB, 24);
+            memory::free(ptr.cast());
+        }
+    }
+
+    #[test]
+    fn free_null() {
+        unsafe { memory::free(ptr::null_mut()) };
+    }
+
+    #[test]
+    fn calloc_zeroes() {
+        unsafe {
+            let ptr = memory::calloc(8, 4).cast::<u32>();
+            assert!(!ptr.is_null());
+            assert!(core::slice::from_raw_parts(ptr, 8).iter().all(|&v| v == 0));
+            memory::free(ptr.cast());
+        }
+    }
+
+    #[test]
+    fn calloc_overflow() {
+        unsafe { assert!(memory::calloc(usize::MAX, 2).is_null()) };
+    }
+
+    #[test]
+    fn realloc_keeps_contents() {
+        unsafe {
+            let ptr = memory::malloc(4).cast::<u8>();
+            ptr.copy_from_nonoverlapping([1, 2, 3, 4].as_ptr(), 4);
+            let ptr = memory::realloc(ptr.cast(), 4096).cast::<u8>();
+            assert!(!ptr.is_null());
+            assert_eq!(core::slice::from_raw_parts(ptr, 4), [1, 2, 3, 4]);
+            memory::free(ptr.cast());
+        }
+    }
+
+    #[test]
+    fn realloc_null_allocates() {
+        unsafe {
+            let ptr = memory::realloc(ptr::null_mut(), 16);
+            assert!(!ptr.is_null());
+            memory::free(ptr);
+        }
+    }
+
+    #[test]
+    fn aligned() {
+        unsafe {
+            let ptr = memory::aligned_alloc(256, 100);
+            assert_eq!(ptr as usize % 256, 0);
+            let ptr = memory::realloc(ptr, 1000);
+            assert_eq!(ptr as usize % 256, 0);
+            memory::free(ptr);
+
+            let mut out = ptr::null_mut();
+            assert_eq!(memory::posix_memalign(&raw mut out, 64, 10), 0);
+            assert_eq!(out as usize % 64, 0);
+            memory::free(out);
+
+            assert_ne!(memory::posix_memalign(&raw mut out, 3, 10), 0);
+        }
+    }
+
+    #[test]
+    fn string_functions() {
+        let hello = c"hello".as_ptr();
+        let help = c"help".as_ptr();
+        unsafe {
+            assert_eq!(string::strlen(hello), 5);
+            assert_eq!(string::strnlen(hello, 3), 3);
+            assert!(string::strcmp(hello, help) < 0);
+            assert_eq!(string::strcmp(hello, hello), 0);
+            assert_eq!(string::strncmp(hello, help, 3), 0);
+            assert_eq!(
+                string::strchr(hello, c_int::from(b'l')),
+                hello.add(2).cast_mut()
+            );
+            assert_eq!(
+                string::strrchr(hello, c_int::from(b'l')),
+                hello.add(3).cast_mut()
+            );
+            assert_eq!(string::strchr(hello, 0), hello.add(5).cast_mut());
+            assert!(string::strchr(hello, c_int::from(b'z')).is_null());
+            assert_eq!(
+                string::strstr(hello, c"llo".as_ptr()),
+                hello.add(2).cast_mut()
+            );
+            assert_eq!(string::strstr(hello, c"".as_ptr()), hello.cast_mut());
+            assert!(string::strstr(hello, c"lol".as_ptr()).is_null());
+        }
+    }
+
+    #[test]
+    fn string_copies() {
+        let mut buffer = [1 as c_char; 12];
+        unsafe {
+            string::strcpy(buffer.as_mut_ptr(), c"abc".as_ptr());
+            string::strcat(buffer.as_mut_ptr(), c"def".as_ptr());
+            assert_eq!(string::strcmp(buffer.as_ptr(), c"abcdef".as_ptr()), 0);
+
+            string::strncpy(buffer.as_mut_ptr(), c"xy".as_ptr(), 5);
+            assert_eq!(
+                &buffer[..6],
+                &[b'x' as c_char, b'y' as c_char, 0, 0, 0, b'f' as c_char]
+            );
+        }
+    }
+
+    unsafe extern "C" fn compare(a: *const c_void, b: *const c_void) -> c_int {
+        let (a, b) = unsafe { (*a.cast::<i32>(), *b.cast::<i32>()) };
+        a.cmp(&b) as c_int
+    }
+
+    #[test]
+    fn qsort() {
+        let mut values = [5, -3, 9, 0, 5, 12, -40, 7];
+        unsafe {
+            sort::qsort(
+                values.as_mut_ptr().cast(),
+                values.len(),
+                size_of::<i32>(),
+                Some(compare),
+            );
+        }
+        assert_eq!(values, [-40, -3, 0, 5, 5, 7, 9, 12]);
+    }
+
+    #[test]
+    fn qsort_empty() {
+        unsafe { sort::qsort(ptr::null_mut(), 0, 4, Some(compare)) };
+    }
+}
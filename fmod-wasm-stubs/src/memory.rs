@@ -0,0 +1,126 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use alloc::alloc::{Layout, alloc, alloc_zeroed, dealloc, realloc as grow};
+use core::ffi::{c_int, c_void};
+use core::ptr;
+
+// what malloc guarantees in C, enough for any scalar type
+pub(crate) const MIN_ALIGN: usize = 16;
+
+const EINVAL: c_int = 28;
+const ENOMEM: c_int = 48;
+
+// Every allocation is prefixed with a header that is `align` bytes long,
+// the last two words of which hold the requested size and the alignment, so `free` can rebuild the layout.
+//
+// | padding | size | align | data ...
+//                          ^ returned pointer
+const HEADER_WORDS: usize = 2;
+
+fn layout(size: usize, align: usize) -> Option<Layout> {
+    Layout::from_size_align(size.checked_add(align)?, align).ok()
+}
+
+// the header always starts at a multiple of MIN_ALIGN, so it is aligned for usize
+#[allow(clippy::cast_ptr_alignment)]
+unsafe fn finish(base: *mut u8, size: usize, align: usize) -> *mut c_void {
+    if base.is_null() {
+        return ptr::null_mut();
+    }
+    unsafe {
+        let data = base.add(align);
+        let header = data.cast::<usize>().sub(HEADER_WORDS);
+        header.write(size);
+        header.add(1).write(align);
+        data.cast()
+    }
+}
+
+// returns the base pointer and layout of a pointer returned by `finish`
+#[allow(clippy::cast_ptr_alignment)]
+unsafe fn header(data: *mut c_void) -> (*mut u8, usize, usize) {
+    unsafe {
+        let header = data.cast::<usize>().sub(HEADER_WORDS);
+        let size = header.read();
+        let align = header.add(1).read();
+        (data.cast::<u8>().sub(align), size, align)
+    }
+}
+
+unsafe fn allocate(size: usize, align: usize, zeroed: bool) -> *mut c_void {
+    let align = align.max(MIN_ALIGN);
+    let Some(layout) = layout(size, align) else {
+        return ptr::null_mut();
+    };
+    unsafe {
+        let base = if zeroed {
+            alloc_zeroed(layout)
+        } else {
+            alloc(layout)
+        };
+        finish(base, size, align)
+    }
+}
+
+pub(crate) unsafe fn malloc(size: usize) -> *mut c_void {
+    unsafe { allocate(size, MIN_ALIGN, false) }
+}
+
+pub(crate) unsafe fn calloc(count: usize, size: usize) -> *mut c_void {
+    let Some(size) = count.checked_mul(size) else {
+        return ptr::null_mut();
+    };
+    unsafe { allocate(size, MIN_ALIGN, true) }
+}
+
+pub(crate) unsafe fn aligned_alloc(align: usize, size: usize) -> *mut c_void {
+    if !align.is_power_of_two() {
+        return ptr::null_mut();
+    }
+    unsafe { allocate(size, align, false) }
+}
+
+pub(crate) unsafe fn posix_memalign(out: *mut *mut c_void, align: usize, size: usize) -> c_int {
+    if !align.is_power_of_two() || !align.is_multiple_of(size_of::<*mut c_void>()) {
+        return EINVAL;
+    }
+    let data = unsafe { allocate(size, align, false) };
+    if data.is_null() {
+        return ENOMEM;
+    }
+    unsafe { out.write(data) };
+    0
+}
+
+pub(crate) unsafe fn realloc(data: *mut c_void, new_size: usize) -> *mut c_void {
+    if data.is_null() {
+        return unsafe { malloc(new_size) };
+    }
+    if new_size == 0 {
+        unsafe { free(data) };
+        return ptr::null_mut();
+    }
+    unsafe {
+        let (base, size, align) = header(data);
+        let Some(new_layout) = layout(new_size, align) else {
+            return ptr::null_mut();
+        };
+        let old_layout = Layout::from_size_align_unchecked(size + align, align);
+        let base = grow(base, old_layout, new_layout.size());
+        finish(base, new_size, align)
+    }
+}
+
+pub(crate) unsafe fn free(data: *mut c_void) {
+    if data.is_null() {
+        return;
+    }
+    unsafe {
+        let (base, size, align) = header(data);
+        dealloc(base, Layout::from_size_align_unchecked(size + align, align));
+    }
+}
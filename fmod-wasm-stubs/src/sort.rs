@@ -0,0 +1,36 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use alloc::vec::Vec;
+use core::ffi::{c_int, c_void};
+
+pub(crate) type Compare = Option<unsafe extern "C" fn(*const c_void, *const c_void) -> c_int>;
+
+// Sorts a list of indices with the comparator, then writes the elements back in that order.
+// This is simpler than swapping elements of an unknown size in place, and the lists FMOD sorts are small.
+pub(crate) unsafe fn qsort(base: *mut c_void, count: usize, size: usize, compare: Compare) {
+    let Some(compare) = compare else {
+        return;
+    };
+    if count < 2 || size == 0 {
+        return;
+    }
+    let base = base.cast::<u8>();
+    let total = count * size;
+    let copy = unsafe { core::slice::from_raw_parts(base, total) }.to_vec();
+    let element = |i: usize| copy[i * size..].as_ptr().cast::<c_void>();
+
+    let mut order = (0..count).collect::<Vec<_>>();
+    // stable, so equal elements keep their order
+    order.sort_by(|&a, &b| unsafe { compare(element(a), element(b)) }.cmp(&0));
+
+    for (to, from) in order.into_iter().enumerate() {
+        unsafe {
+            base.add(to * size)
+                .copy_from_nonoverlapping(copy[from * size..].as_ptr(), size);
+        }
+    }
+}
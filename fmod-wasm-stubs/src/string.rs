@@ -0,0 +1,97 @@
+// Copyright (c) 2024 Melody Madeline Lyons
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use core::ffi::{c_char, c_int};
+use core::ptr;
+
+pub(crate) unsafe fn strlen(string: *const c_char) -> usize {
+    let mut len = 0;
+    while unsafe { *string.add(len) } != 0 {
+        len += 1;
+    }
+    len
+}
+
+pub(crate) unsafe fn strnlen(string: *const c_char, max: usize) -> usize {
+    let mut len = 0;
+    while len < max && unsafe { *string.add(len) } != 0 {
+        len += 1;
+    }
+    len
+}
+
+pub(crate) unsafe fn strncmp(a: *const c_char, b: *const c_char, max: usize) -> c_int {
+    for i in 0..max {
+        // C compares as unsigned char
+        let (x, y) = unsafe { (*a.add(i) as u8, *b.add(i) as u8) };
+        if x != y || x == 0 {
+            return c_int::from(x) - c_int::from(y);
+        }
+    }
+    0
+}
+
+pub(crate) unsafe fn strcmp(a: *const c_char, b: *const c_char) -> c_int {
+    unsafe { strncmp(a, b, usize::MAX) }
+}
+
+pub(crate) unsafe fn strchr(string: *const c_char, needle: c_int) -> *mut c_char {
+    let needle = needle as c_char;
+    let mut cursor = string;
+    loop {
+        let c = unsafe { *cursor };
+        if c == needle {
+            return cursor.cast_mut();
+        }
+        if c == 0 {
+            return ptr::null_mut();
+        }
+        cursor = unsafe { cursor.add(1) };
+    }
+}
+
+pub(crate) unsafe fn strrchr(string: *const c_char, needle: c_int) -> *mut c_char {
+    let needle = needle as c_char;
+    // the terminator counts as part of the string, so searching for 0 finds it
+    let len = unsafe { strlen(string) };
+    (0..=len)
+        .rev()
+        .map(|i| unsafe { string.add(i) })
+        .find(|&c| unsafe { *c } == needle)
+        .map_or(ptr::null_mut(), <*const c_char>::cast_mut)
+}
+
+pub(crate) unsafe fn strstr(haystack: *const c_char, needle: *const c_char) -> *mut c_char {
+    let needle_len = unsafe { strlen(needle) };
+    let haystack_len = unsafe { strlen(haystack) };
+    if needle_len > haystack_len {
+        return ptr::null_mut();
+    }
+    (0..=haystack_len - needle_len)
+        .map(|i| unsafe { haystack.add(i) })
+        .find(|&start| unsafe { strncmp(start, needle, needle_len) } == 0)
+        .map_or(ptr::null_mut(), <*const c_char>::cast_mut)
+}
+
+pub(crate) unsafe fn strcpy(dst: *mut c_char, src: *const c_char) -> *mut c_char {
+    unsafe { dst.copy_from_nonoverlapping(src, strlen(src) + 1) };
+    dst
+}
+
+pub(crate) unsafe fn strncpy(dst: *mut c_char, src: *const c_char, max: usize) -> *mut c_char {
+    let len = unsafe { strnlen(src, max) };
+    unsafe {
+        dst.copy_from_nonoverlapping(src, len);
+        // strncpy pads the rest of the buffer with nul bytes
+        dst.add(len).write_bytes(0, max - len);
+    }
+    dst
+}
+
+pub(crate) unsafe fn strcat(dst: *mut c_char, src: *const c_char) -> *mut c_char {
+    unsafe { strcpy(dst.add(strlen(dst)), src) };
+    dst
+}